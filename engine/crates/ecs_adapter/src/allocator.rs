@@ -48,6 +48,42 @@ impl EntityAllocator {
         true
     }
 
+    /// True if `claim(id)` would succeed: the slot is free and `id` is not
+    /// older than the slot's current generation.
+    pub fn can_claim(&self, id: EntityId) -> bool {
+        let idx = id.index as usize;
+        idx >= self.alive.len() || (!self.alive[idx] && id.generation >= self.generations[idx])
+    }
+
+    /// Mark a specific EntityId as alive (for merging entities from a
+    /// partial snapshot into a live world). Returns false if the slot is
+    /// taken or `id` is older than the slot's current generation.
+    pub fn claim(&mut self, id: EntityId) -> bool {
+        if !self.can_claim(id) {
+            return false;
+        }
+        let idx = id.index as usize;
+        while self.alive.len() <= idx {
+            let new_index = self.alive.len() as u32;
+            self.generations.push(0);
+            self.alive.push(false);
+            if new_index != id.index {
+                self.free_indices.push(new_index);
+            }
+        }
+        self.next_index = self.next_index.max(id.index + 1);
+        self.free_indices.retain(|&i| i != id.index);
+        self.generations[idx] = id.generation;
+        self.alive[idx] = true;
+        true
+    }
+
+    /// Generation of the live entity at `index`, if any.
+    pub fn live_generation(&self, index: u32) -> Option<u32> {
+        let idx = index as usize;
+        (idx < self.alive.len() && self.alive[idx]).then(|| self.generations[idx])
+    }
+
     pub fn is_alive(&self, id: EntityId) -> bool {
         let idx = id.index as usize;
         idx < self.alive.len() && self.alive[idx] && self.generations[idx] == id.generation
@@ -94,6 +130,25 @@ mod tests {
         assert!(!alloc.is_alive(a));
     }
 
+    #[test]
+    fn claim_specific_ids() {
+        let mut alloc = EntityAllocator::new();
+        let far = EntityId::new(3, 2);
+        assert!(alloc.claim(far));
+        assert!(alloc.is_alive(far));
+        assert!(!alloc.claim(far));
+
+        // Skipped slots are handed out by allocate, never the claimed one.
+        let ids: Vec<_> = (0..4).map(|_| alloc.allocate()).collect();
+        assert!(ids.iter().all(|id| id.index != 3));
+        assert_eq!(ids[3].index, 4);
+
+        // A freed slot can't be claimed with an older generation.
+        assert!(alloc.deallocate(far));
+        assert!(!alloc.claim(EntityId::new(3, 1)));
+        assert!(alloc.claim(far));
+    }
+
     #[test]
     fn double_deallocate_returns_false() {
         let mut alloc = EntityAllocator::new();
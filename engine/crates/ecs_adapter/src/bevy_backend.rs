@@ -57,6 +57,18 @@ impl EcsAdapter {
         &mut self.allocator
     }
 
+    /// Map an EntityId to its bevy entity, validating the generation.
+    /// A handle whose index now holds a newer entity yields `StaleEntity`.
+    fn resolve(&self, eid: EntityId) -> Result<Entity, EcsError> {
+        if let Some(bevy_entity) = self.mapping.get_bevy(&eid) {
+            return Ok(bevy_entity);
+        }
+        match self.allocator.live_generation(eid.index) {
+            Some(generation) if generation != eid.generation => Err(EcsError::StaleEntity(eid)),
+            _ => Err(EcsError::EntityNotFound(eid)),
+        }
+    }
+
     /// Spawn a new entity, returning a stable EntityId.
     pub fn spawn_entity(&mut self) -> EntityId {
         let eid = self.allocator.allocate();
@@ -76,11 +88,24 @@ impl EcsAdapter {
         Ok(())
     }
 
+    /// Spawn an entity with a specific EntityId the allocator does not yet
+    /// track, marking it alive first (for merging partial snapshots).
+    pub fn claim_entity(&mut self, eid: EntityId) -> Result<(), EcsError> {
+        if !self.allocator.claim(eid) {
+            return Err(EcsError::StaleEntity(eid));
+        }
+        self.spawn_entity_with_id(eid)
+    }
+
+    /// Whether `eid` names a spawned entity. Generation-checked: a stale
+    /// handle whose index was reused by a newer entity is not alive.
+    pub fn is_alive(&self, eid: EntityId) -> bool {
+        self.mapping.get_bevy(&eid).is_some()
+    }
+
     /// Despawn an entity.
     pub fn despawn_entity(&mut self, eid: EntityId) -> Result<(), EcsError> {
-        if !self.allocator.is_alive(eid) {
-            return Err(EcsError::EntityNotFound(eid));
-        }
+        self.resolve(eid)?;
         let bevy_entity = self
             .mapping
             .remove_by_eid(&eid)
@@ -93,10 +118,7 @@ impl EcsAdapter {
 
     /// Get a component reference for an entity.
     pub fn get_component<C: Component>(&self, eid: EntityId) -> Result<&C, EcsError> {
-        let bevy_entity = self
-            .mapping
-            .get_bevy(&eid)
-            .ok_or(EcsError::EntityNotFound(eid))?;
+        let bevy_entity = self.resolve(eid)?;
         self.world
             .entity(bevy_entity)
             .get::<C>()
@@ -105,20 +127,22 @@ impl EcsAdapter {
 
     /// Set (insert or overwrite) a component on an entity.
     pub fn set_component<C: Component>(&mut self, eid: EntityId, component: C) -> Result<(), EcsError> {
-        let bevy_entity = self
-            .mapping
-            .get_bevy(&eid)
-            .ok_or(EcsError::EntityNotFound(eid))?;
+        let bevy_entity = self.resolve(eid)?;
         self.world.entity_mut(bevy_entity).insert(component);
         Ok(())
     }
 
+    /// Set several components on an entity in a single world access.
+    /// Accepts any tuple of components, e.g. `(Name(..), Health(..), Attack(..))`.
+    pub fn set_components<B: Bundle>(&mut self, eid: EntityId, bundle: B) -> Result<(), EcsError> {
+        let bevy_entity = self.resolve(eid)?;
+        self.world.entity_mut(bevy_entity).insert(bundle);
+        Ok(())
+    }
+
     /// Remove a component from an entity.
     pub fn remove_component<C: Component>(&mut self, eid: EntityId) -> Result<(), EcsError> {
-        let bevy_entity = self
-            .mapping
-            .get_bevy(&eid)
-            .ok_or(EcsError::EntityNotFound(eid))?;
+        let bevy_entity = self.resolve(eid)?;
         self.world.entity_mut(bevy_entity).remove::<C>();
         Ok(())
     }
@@ -131,7 +155,9 @@ impl EcsAdapter {
             .unwrap_or(false)
     }
 
-    /// Collect all alive EntityIds that have a specific component.
+    /// Collect all alive EntityIds that have a specific component, sorted by
+    /// `(index, generation)`. The mapping is a HashMap, so the sort is what
+    /// makes the order deterministic.
     pub fn entities_with<C: Component>(&self) -> Vec<EntityId> {
         let mut result = Vec::new();
         // Iterate our mapping and check which ones have the component
@@ -144,6 +170,57 @@ impl EcsAdapter {
         result
     }
 
+    /// Collect all alive EntityIds that have both components A and B (sorted).
+    pub fn entities_with2<A: Component, B: Component>(&self) -> Vec<EntityId> {
+        let mut result = Vec::new();
+        for (&eid, &bevy_entity) in &self.mapping.to_bevy {
+            let entity = self.world.entity(bevy_entity);
+            if entity.contains::<A>() && entity.contains::<B>() {
+                result.push(eid);
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Collect all alive EntityIds that have components A, B and C (sorted).
+    pub fn entities_with3<A: Component, B: Component, C: Component>(&self) -> Vec<EntityId> {
+        let mut result = Vec::new();
+        for (&eid, &bevy_entity) in &self.mapping.to_bevy {
+            let entity = self.world.entity(bevy_entity);
+            if entity.contains::<A>() && entity.contains::<B>() && entity.contains::<C>() {
+                result.push(eid);
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Collect all alive EntityIds whose component C was added or overwritten
+    /// since the last `clear_change_tracking` call (sorted).
+    pub fn changed_this_tick<C: Component>(&self) -> Vec<EntityId> {
+        let mut result = Vec::new();
+        for (&eid, &bevy_entity) in &self.mapping.to_bevy {
+            let changed = self
+                .world
+                .entity(bevy_entity)
+                .get_ref::<C>()
+                .map(|c| c.is_changed())
+                .unwrap_or(false);
+            if changed {
+                result.push(eid);
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Reset change tracking. Call once per tick after consumers
+    /// (e.g. delta broadcast) have read `changed_this_tick`.
+    pub fn clear_change_tracking(&mut self) {
+        self.world.clear_trackers();
+    }
+
     /// Number of alive entities.
     pub fn entity_count(&self) -> usize {
         self.allocator.alive_count()
@@ -170,6 +247,12 @@ mod tests {
     #[derive(Component, Debug, PartialEq)]
     struct Health(pub i32);
 
+    #[derive(Component, Debug, PartialEq)]
+    struct Name(pub String);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Attack(pub i32);
+
     #[test]
     fn spawn_and_despawn() {
         let mut ecs = EcsAdapter::new();
@@ -215,4 +298,158 @@ mod tests {
         assert!(with_health.contains(&e1));
         assert!(with_health.contains(&e2));
     }
+
+    #[test]
+    fn entities_with_sorted_by_index_then_generation() {
+        let mut ecs = EcsAdapter::new();
+        let e0 = ecs.spawn_entity();
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        ecs.despawn_entity(e0).unwrap();
+        let recycled = ecs.spawn_entity();
+        assert_eq!(recycled.index, e0.index);
+        assert!(recycled.generation > e0.generation);
+
+        // Insert in an order unrelated to the ids
+        ecs.set_component(e2, Health(1)).unwrap();
+        ecs.set_component(recycled, Health(2)).unwrap();
+        ecs.set_component(e1, Health(3)).unwrap();
+
+        let expected = vec![recycled, e1, e2];
+        assert_eq!(ecs.entities_with::<Health>(), expected);
+        assert_eq!(ecs.all_entities(), expected);
+    }
+
+    #[test]
+    fn entities_with_multiple_components() {
+        let mut ecs = EcsAdapter::new();
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        let e3 = ecs.spawn_entity();
+        let e4 = ecs.spawn_entity();
+
+        ecs.set_components(e1, (Health(10), Name("a".into()), Attack(1))).unwrap();
+        ecs.set_components(e2, (Health(20), Name("b".into()))).unwrap();
+        ecs.set_components(e3, (Name("c".into()), Attack(3))).unwrap();
+        ecs.set_component(e4, Health(40)).unwrap();
+
+        assert_eq!(ecs.entities_with2::<Health, Name>(), vec![e1, e2]);
+        assert_eq!(ecs.entities_with2::<Name, Attack>(), vec![e1, e3]);
+        assert_eq!(ecs.entities_with2::<Health, Attack>(), vec![e1]);
+        assert_eq!(ecs.entities_with3::<Health, Name, Attack>(), vec![e1]);
+
+        ecs.despawn_entity(e1).unwrap();
+        assert!(ecs.entities_with3::<Health, Name, Attack>().is_empty());
+        assert_eq!(ecs.entities_with2::<Health, Name>(), vec![e2]);
+    }
+
+    #[test]
+    fn changed_this_tick_tracks_only_mutated_entities() {
+        let mut ecs = EcsAdapter::new();
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        let e3 = ecs.spawn_entity();
+        ecs.set_component(e1, Health(10)).unwrap();
+        ecs.set_component(e2, Health(20)).unwrap();
+        ecs.set_component(e3, Health(30)).unwrap();
+
+        // Newly inserted components count as changed
+        assert_eq!(ecs.changed_this_tick::<Health>(), vec![e1, e2, e3]);
+
+        ecs.clear_change_tracking();
+        assert!(ecs.changed_this_tick::<Health>().is_empty());
+
+        ecs.set_component(e2, Health(15)).unwrap();
+        ecs.set_component(e3, Name("other".into())).unwrap();
+        assert_eq!(ecs.changed_this_tick::<Health>(), vec![e2]);
+        assert_eq!(ecs.changed_this_tick::<Name>(), vec![e3]);
+
+        ecs.clear_change_tracking();
+        assert!(ecs.changed_this_tick::<Health>().is_empty());
+        assert!(ecs.changed_this_tick::<Name>().is_empty());
+    }
+
+    #[test]
+    fn set_components_bundle() {
+        let mut ecs = EcsAdapter::new();
+        let e = ecs.spawn_entity();
+
+        ecs.set_components(e, (Name("Hero".to_string()), Health(100), Attack(7)))
+            .unwrap();
+        assert_eq!(ecs.get_component::<Name>(e).unwrap().0, "Hero");
+        assert_eq!(ecs.get_component::<Health>(e).unwrap().0, 100);
+        assert_eq!(ecs.get_component::<Attack>(e).unwrap().0, 7);
+
+        // Overwrites existing components like set_component
+        ecs.set_components(e, (Health(40),)).unwrap();
+        assert_eq!(ecs.get_component::<Health>(e).unwrap().0, 40);
+
+        let dead = ecs.spawn_entity();
+        ecs.despawn_entity(dead).unwrap();
+        assert!(ecs.set_components(dead, (Health(1), Attack(1))).is_err());
+    }
+
+    #[test]
+    fn set_components_moves_entity_once() {
+        // Each single insert moves the entity to a new archetype, creating
+        // the intermediate {Name} and {Name, Health} archetypes on the way.
+        // A bundle moves it straight to {Name, Health, Attack}.
+        let mut single = EcsAdapter::new();
+        let e = single.spawn_entity();
+        let before = single.world.archetypes().len();
+        single.set_component(e, Name("Hero".to_string())).unwrap();
+        single.set_component(e, Health(100)).unwrap();
+        single.set_component(e, Attack(7)).unwrap();
+        assert_eq!(single.world.archetypes().len() - before, 3);
+
+        let mut batched = EcsAdapter::new();
+        let e = batched.spawn_entity();
+        let before = batched.world.archetypes().len();
+        batched
+            .set_components(e, (Name("Hero".to_string()), Health(100), Attack(7)))
+            .unwrap();
+        assert_eq!(batched.world.archetypes().len() - before, 1);
+    }
+
+    #[test]
+    fn is_alive_tracks_spawn_and_despawn() {
+        let mut ecs = EcsAdapter::new();
+        let e = ecs.spawn_entity();
+        assert!(ecs.is_alive(e));
+
+        ecs.despawn_entity(e).unwrap();
+        assert!(!ecs.is_alive(e));
+        assert!(!ecs.is_alive(EntityId::new(99, 0)));
+    }
+
+    #[test]
+    fn stale_handle_after_index_reuse() {
+        #[derive(Component, Debug, PartialEq)]
+        struct Tag(u32);
+
+        let mut ecs = EcsAdapter::new();
+        let old = ecs.spawn_entity();
+        ecs.set_component(old, Tag(1)).unwrap();
+        ecs.despawn_entity(old).unwrap();
+
+        let new = ecs.spawn_entity();
+        assert_eq!(new.index, old.index);
+        assert_ne!(new.generation, old.generation);
+        ecs.set_component(new, Tag(2)).unwrap();
+
+        assert!(matches!(ecs.get_component::<Tag>(old), Err(EcsError::StaleEntity(_))));
+        assert!(matches!(ecs.set_component(old, Tag(3)), Err(EcsError::StaleEntity(_))));
+        assert!(matches!(ecs.remove_component::<Tag>(old), Err(EcsError::StaleEntity(_))));
+        assert!(matches!(ecs.despawn_entity(old), Err(EcsError::StaleEntity(_))));
+        assert!(!ecs.has_component::<Tag>(old));
+        assert!(!ecs.is_alive(old));
+        assert!(ecs.is_alive(new));
+
+        // The live entity is untouched.
+        assert_eq!(ecs.get_component::<Tag>(new).unwrap(), &Tag(2));
+
+        // Never-allocated ids are simply not found.
+        let unknown = EntityId::new(99, 0);
+        assert!(matches!(ecs.get_component::<Tag>(unknown), Err(EcsError::EntityNotFound(_))));
+    }
 }
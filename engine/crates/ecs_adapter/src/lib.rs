@@ -2,10 +2,13 @@ pub mod types;
 pub mod allocator;
 pub mod bevy_backend;
 pub mod error;
+pub mod tags;
 
 pub use types::{EntityId, ComponentId, AreaId, EventId};
 pub use allocator::EntityAllocator;
 pub use bevy_backend::EcsAdapter;
 pub use error::EcsError;
+pub use tags::Tags;
 
+pub use bevy_ecs::bundle::Bundle;
 pub use bevy_ecs::component::Component;
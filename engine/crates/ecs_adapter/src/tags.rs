@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bevy_backend::EcsAdapter;
+use crate::error::EcsError;
+use crate::types::EntityId;
+
+/// Ad-hoc string tags (e.g. "quest_giver", "aggressive") so content doesn't
+/// need a new component per marker. Removed from the entity when it empties.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags(pub BTreeSet<String>);
+
+impl Tags {
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+}
+
+impl EcsAdapter {
+    /// Add a tag to an entity. Returns false if it was already tagged.
+    pub fn add_tag(&mut self, eid: EntityId, tag: &str) -> Result<bool, EcsError> {
+        let mut tags = self.get_component::<Tags>(eid).cloned().unwrap_or_default();
+        if !tags.0.insert(tag.to_string()) {
+            return Ok(false);
+        }
+        self.set_component(eid, tags)?;
+        Ok(true)
+    }
+
+    /// Remove a tag from an entity. Returns false if the tag wasn't present.
+    pub fn remove_tag(&mut self, eid: EntityId, tag: &str) -> Result<bool, EcsError> {
+        let mut tags = match self.get_component::<Tags>(eid) {
+            Ok(tags) if tags.contains(tag) => tags.clone(),
+            Ok(_) | Err(EcsError::ComponentNotFound(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        tags.0.remove(tag);
+        if tags.0.is_empty() {
+            self.remove_component::<Tags>(eid)?;
+        } else {
+            self.set_component(eid, tags)?;
+        }
+        Ok(true)
+    }
+
+    /// Whether the entity carries the tag. Unknown entities have no tags.
+    pub fn has_tag(&self, eid: EntityId, tag: &str) -> bool {
+        self.get_component::<Tags>(eid)
+            .map(|tags| tags.contains(tag))
+            .unwrap_or(false)
+    }
+
+    /// All alive entities carrying the tag, sorted. Scans the entities that
+    /// have a `Tags` component.
+    pub fn entities_with_tag(&self, tag: &str) -> Vec<EntityId> {
+        self.entities_with::<Tags>()
+            .into_iter()
+            .filter(|&eid| self.has_tag(eid, tag))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_query_remove_tags() {
+        let mut ecs = EcsAdapter::new();
+        let a = ecs.spawn_entity();
+        let b = ecs.spawn_entity();
+        let c = ecs.spawn_entity();
+
+        assert!(ecs.add_tag(a, "aggressive").unwrap());
+        assert!(!ecs.add_tag(a, "aggressive").unwrap());
+        ecs.add_tag(a, "quest_giver").unwrap();
+        ecs.add_tag(c, "aggressive").unwrap();
+
+        assert!(ecs.has_tag(a, "quest_giver"));
+        assert!(!ecs.has_tag(b, "aggressive"));
+        assert_eq!(ecs.entities_with_tag("aggressive"), vec![a, c]);
+        assert_eq!(ecs.entities_with_tag("quest_giver"), vec![a]);
+        assert!(ecs.entities_with_tag("missing").is_empty());
+
+        assert!(ecs.remove_tag(a, "aggressive").unwrap());
+        assert!(!ecs.remove_tag(a, "aggressive").unwrap());
+        assert!(!ecs.remove_tag(b, "aggressive").unwrap());
+        assert_eq!(ecs.entities_with_tag("aggressive"), vec![c]);
+        assert!(ecs.has_tag(a, "quest_giver"));
+    }
+
+    #[test]
+    fn last_tag_removal_drops_component() {
+        let mut ecs = EcsAdapter::new();
+        let e = ecs.spawn_entity();
+        ecs.add_tag(e, "boss").unwrap();
+        assert!(ecs.has_component::<Tags>(e));
+        ecs.remove_tag(e, "boss").unwrap();
+        assert!(!ecs.has_component::<Tags>(e));
+    }
+
+    #[test]
+    fn tags_on_dead_entity_error() {
+        let mut ecs = EcsAdapter::new();
+        let e = ecs.spawn_entity();
+        ecs.despawn_entity(e).unwrap();
+        assert!(ecs.add_tag(e, "ghost").is_err());
+        assert!(!ecs.has_tag(e, "ghost"));
+    }
+}
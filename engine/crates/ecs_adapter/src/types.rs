@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Ordered by `(index, generation)` — the canonical iteration order of every
+/// entity list the adapter returns. Note that `to_u64` puts the generation in
+/// the high bits, so the numeric order of packed ids can differ.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EntityId {
     pub index: u32,
@@ -0,0 +1,112 @@
+use ecs_adapter::{EcsAdapter, EntityId};
+use space::SpaceModel;
+
+/// Which cleanups `despawn_with_space` actually performed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DespawnOutcome {
+    /// The entity was placed in the space and has been removed from it.
+    pub removed_from_space: bool,
+    /// The entity was alive in the ECS and has been despawned.
+    pub despawned: bool,
+}
+
+impl DespawnOutcome {
+    /// True if neither the space nor the ECS knew the entity.
+    pub fn is_noop(&self) -> bool {
+        !self.removed_from_space && !self.despawned
+    }
+}
+
+/// Remove an entity from the space, then despawn it from the ECS.
+///
+/// Both steps always run, so an entity that is missing from one side is
+/// still cleaned out of the other and the spatial index cannot leak.
+pub fn despawn_with_space<S: SpaceModel + ?Sized>(
+    ecs: &mut EcsAdapter,
+    space: &mut S,
+    entity: EntityId,
+) -> DespawnOutcome {
+    let removed_from_space = space.remove_entity(entity).is_ok();
+    let despawned = ecs.despawn_entity(entity).is_ok();
+    DespawnOutcome {
+        removed_from_space,
+        despawned,
+    }
+}
+
+/// `despawn_with_space`, then `on_despawn(entity)` so game state keyed by
+/// the entity (parties, follow links, ...) goes with it. The hook is skipped
+/// when neither the space nor the ECS knew the entity.
+pub fn despawn_with_space_and<S, F>(
+    ecs: &mut EcsAdapter,
+    space: &mut S,
+    entity: EntityId,
+    on_despawn: F,
+) -> DespawnOutcome
+where
+    S: SpaceModel + ?Sized,
+    F: FnOnce(EntityId),
+{
+    let outcome = despawn_with_space(ecs, space, entity);
+    if !outcome.is_noop() {
+        on_despawn(entity);
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space::room_graph::RoomExits;
+    use space::RoomGraphSpace;
+
+    #[test]
+    fn despawn_clears_ecs_and_space() {
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let room = EntityId::new(100, 0);
+        space.register_room(room, RoomExits::default());
+
+        let entity = ecs.spawn_entity();
+        space.place_entity(entity, room).unwrap();
+
+        let outcome = despawn_with_space(&mut ecs, &mut space, entity);
+        assert_eq!(
+            outcome,
+            DespawnOutcome {
+                removed_from_space: true,
+                despawned: true
+            }
+        );
+        assert!(!ecs.allocator().is_alive(entity));
+        assert!(space.entity_room(entity).is_none());
+        assert!(space.room_occupants(room).is_empty());
+
+        // A second call finds nothing to clean up.
+        assert!(despawn_with_space(&mut ecs, &mut space, entity).is_noop());
+    }
+
+    #[test]
+    fn despawn_unplaced_entity_still_despawns() {
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let entity = ecs.spawn_entity();
+
+        let outcome = despawn_with_space(&mut ecs, &mut space, entity);
+        assert!(!outcome.removed_from_space);
+        assert!(outcome.despawned);
+        assert!(!ecs.allocator().is_alive(entity));
+    }
+
+    #[test]
+    fn despawn_hook_runs_once_per_entity() {
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let entity = ecs.spawn_entity();
+        let mut seen = Vec::new();
+
+        despawn_with_space_and(&mut ecs, &mut space, entity, |e| seen.push(e));
+        despawn_with_space_and(&mut ecs, &mut space, entity, |e| seen.push(e));
+        assert_eq!(seen, vec![entity]);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod command;
+pub mod despawn;
 pub mod events;
 pub mod tick;
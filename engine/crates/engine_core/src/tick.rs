@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use ecs_adapter::{ComponentId, EcsAdapter, EntityId, EventId};
@@ -5,6 +7,7 @@ use plugin_abi::WasmCommand;
 use space::SpaceModel;
 
 use crate::command::{CommandStream, EngineCommand};
+use crate::despawn::despawn_with_space;
 use crate::events::EventBus;
 
 /// Tick loop configuration.
@@ -14,6 +17,12 @@ pub struct TickConfig {
     pub tps: u32,
     /// Maximum ticks to run (0 = unlimited).
     pub max_ticks: u64,
+    /// Maximum back-to-back steps `run` executes to catch up after an overrun.
+    /// Any further backlog is dropped (reported as skipped) to avoid a spiral of death.
+    pub max_catchup: u32,
+    /// Pace `run` to `tps` in wall time. When false, ticks are stepped
+    /// back-to-back with no sleep (deterministic tests, offline simulation).
+    pub realtime: bool,
 }
 
 impl Default for TickConfig {
@@ -21,6 +30,8 @@ impl Default for TickConfig {
         Self {
             tps: 30,
             max_ticks: 0,
+            max_catchup: 5,
+            realtime: true,
         }
     }
 }
@@ -31,6 +42,132 @@ impl TickConfig {
     }
 }
 
+/// Steps scheduled by [`FixedTimestep::advance`] for one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUp {
+    /// Steps to run now (0 = not yet time for the next tick).
+    pub steps: u32,
+    /// Ticks dropped because the backlog exceeded `max_catchup`.
+    pub skipped: u64,
+}
+
+/// Fixed-timestep accumulator: converts elapsed wall time into a number of
+/// simulation steps so the game clock does not drift when a tick overruns.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    tick_duration: Duration,
+    max_catchup: u32,
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    /// The first `advance` call always schedules one step.
+    pub fn new(tick_duration: Duration, max_catchup: u32) -> Self {
+        Self {
+            tick_duration,
+            max_catchup: max_catchup.max(1),
+            accumulator: tick_duration,
+        }
+    }
+
+    /// Add elapsed wall time and return how many steps are due.
+    pub fn advance(&mut self, elapsed: Duration) -> CatchUp {
+        self.accumulator += elapsed;
+        if self.tick_duration.is_zero() {
+            self.accumulator = Duration::ZERO;
+            return CatchUp { steps: 1, skipped: 0 };
+        }
+
+        let due = (self.accumulator.as_nanos() / self.tick_duration.as_nanos()) as u64;
+        let steps = due.min(self.max_catchup as u64);
+        let skipped = due - steps;
+        self.accumulator -= self.tick_duration * due as u32;
+
+        CatchUp {
+            steps: steps as u32,
+            skipped,
+        }
+    }
+
+    /// Wall time remaining until the next step is due.
+    pub fn time_until_next(&self) -> Duration {
+        self.tick_duration.saturating_sub(self.accumulator)
+    }
+}
+
+/// One tick handed out by [`TickPacer::next_tick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacedTick {
+    /// Runs back-to-back with the previous tick to catch up after an overrun.
+    pub compressed: bool,
+    /// Ticks dropped just before this one because the backlog exceeded `max_catchup`.
+    pub skipped: u64,
+}
+
+/// Wall-clock schedule for a tick loop. `TickLoop::run` and the servers'
+/// own tick threads call `next_tick` once per iteration, so every loop
+/// catches up after an overrun (and honours `realtime`) the same way.
+#[derive(Debug, Clone)]
+pub struct TickPacer {
+    timestep: FixedTimestep,
+    realtime: bool,
+    last: Instant,
+    /// Catch-up steps still owed from the last `advance`.
+    owed: u32,
+}
+
+impl TickPacer {
+    pub fn new(config: &TickConfig) -> Self {
+        Self {
+            timestep: FixedTimestep::new(config.tick_duration(), config.max_catchup),
+            realtime: config.realtime,
+            last: Instant::now(),
+            owed: 0,
+        }
+    }
+
+    /// Block until the next tick is due. Owed catch-up ticks return
+    /// immediately; with `realtime` disabled this never sleeps.
+    pub fn next_tick(&mut self) -> PacedTick {
+        if !self.realtime {
+            return PacedTick { compressed: false, skipped: 0 };
+        }
+        if self.owed > 0 {
+            self.owed -= 1;
+            return PacedTick { compressed: true, skipped: 0 };
+        }
+        loop {
+            let now = Instant::now();
+            let plan = self.timestep.advance(now - self.last);
+            self.last = now;
+            if plan.steps > 0 {
+                self.owed = plan.steps - 1;
+                return PacedTick { compressed: false, skipped: plan.skipped };
+            }
+            std::thread::sleep(self.timestep.time_until_next());
+        }
+    }
+}
+
+/// Shared pause switch for a [`TickLoop`]. Clones control the same loop, so
+/// one can be handed to another thread (admin command, signal handler).
+#[derive(Debug, Clone, Default)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 /// The main simulation tick loop combining all subsystems.
 pub struct TickLoop<S: SpaceModel> {
     pub ecs: EcsAdapter,
@@ -41,6 +178,10 @@ pub struct TickLoop<S: SpaceModel> {
     pub current_tick: u64,
     /// Optional WASM plugin runtime. None = no plugins (Phase 0 compatible).
     pub plugin_runtime: Option<plugin_runtime::PluginRuntime>,
+    /// While set, `run` and `step_if_running` leave the simulation frozen.
+    pause: PauseHandle,
+    /// Root of all per-tick randomness; see `set_master_seed`.
+    master_seed: Option<u64>,
 }
 
 impl<S: SpaceModel> TickLoop<S> {
@@ -53,6 +194,8 @@ impl<S: SpaceModel> TickLoop<S> {
             config,
             current_tick: 0,
             plugin_runtime: None,
+            pause: PauseHandle::default(),
+            master_seed: None,
         }
     }
 
@@ -64,14 +207,56 @@ impl<S: SpaceModel> TickLoop<S> {
         }
     }
 
+    /// Make the run reproducible from one number: every tick derives a seed
+    /// from it (see `tick_seed`), which is handed to the plugin runtime for
+    /// `host_random_seed`. Servers also pass it to the script engine so Lua's
+    /// `math.random` follows the same sequence. Without a master seed plugins
+    /// fall back to the tick number.
+    pub fn set_master_seed(&mut self, seed: u64) {
+        self.master_seed = Some(seed);
+    }
+
+    pub fn master_seed(&self) -> Option<u64> {
+        self.master_seed
+    }
+
+    /// Seed for the tick about to run (`current_tick`), or None when no
+    /// master seed is set.
+    pub fn tick_seed(&self) -> Option<u64> {
+        self.master_seed
+            .map(|seed| mix_seed(seed, self.current_tick))
+    }
+
+    /// Handle for pausing/resuming this loop from elsewhere.
+    pub fn pause_handle(&self) -> PauseHandle {
+        self.pause.clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// `step` unless paused. Server loops call this so network input and
+    /// output keep flowing while the simulation is frozen.
+    pub fn step_if_running(&mut self) -> Option<observability::TickMetrics> {
+        if self.is_paused() {
+            return None;
+        }
+        Some(self.step())
+    }
+
     /// Execute a single tick: plugins → resolve commands → apply → drain events → metrics.
     pub fn step(&mut self) -> observability::TickMetrics {
         let start = Instant::now();
 
         // 1. Run WASM plugins (if present) → collect WasmCommands → convert to EngineCommands
         let wasm_start = Instant::now();
+        let mut plugin_conflicts = 0;
+        let tick_seed = self.tick_seed();
         if let Some(ref mut runtime) = self.plugin_runtime {
+            runtime.set_tick_seed(tick_seed);
             let wasm_cmds = runtime.run_tick(self.current_tick);
+            plugin_conflicts = runtime.last_tick_conflicts().len();
             for wasm_cmd in wasm_cmds {
                 if let Some(engine_cmd) = convert_wasm_to_engine(wasm_cmd) {
                     self.commands.push(engine_cmd);
@@ -104,29 +289,39 @@ impl<S: SpaceModel> TickLoop<S> {
             command_count,
             entity_count: self.ecs.entity_count(),
             wasm_duration_us: wasm_duration.as_micros(),
+            compressed: false,
+            skipped_ticks: 0,
+            plugin_conflicts,
         }
     }
 
     /// Run the tick loop for configured number of ticks (or until max_ticks).
+    /// Overrunning ticks are caught up by stepping back-to-back (up to
+    /// `max_catchup` per frame) instead of letting the game clock drift.
+    /// Time spent paused is not caught up after resuming.
+    /// With `realtime` disabled, ticks run back-to-back and the loop never
+    /// sleeps between them. Pacing is shared with the server tick threads
+    /// through [`TickPacer`].
     pub fn run(&mut self) -> Vec<observability::TickMetrics> {
         let mut all_metrics = Vec::new();
-        let tick_duration = self.config.tick_duration();
+        let mut pacer = TickPacer::new(&self.config);
 
-        loop {
-            if self.config.max_ticks > 0 && self.current_tick >= self.config.max_ticks {
-                break;
+        while self.config.max_ticks == 0 || self.current_tick < self.config.max_ticks {
+            let paced = pacer.next_tick();
+            if self.is_paused() {
+                // The pacer already waits a tick in realtime mode; without it
+                // a paused loop would spin.
+                if !self.config.realtime {
+                    std::thread::sleep(self.config.tick_duration());
+                }
+                continue;
             }
 
-            let tick_start = Instant::now();
-            let metrics = self.step();
+            let mut metrics = self.step();
+            metrics.compressed = paced.compressed;
+            metrics.skipped_ticks = paced.skipped;
             metrics.log();
             all_metrics.push(metrics);
-
-            // Sleep until next tick
-            let elapsed = tick_start.elapsed();
-            if elapsed < tick_duration {
-                std::thread::sleep(tick_duration - elapsed);
-            }
         }
 
         all_metrics
@@ -139,9 +334,9 @@ impl<S: SpaceModel> TickLoop<S> {
                 tracing::debug!(entity = %eid, "spawned entity");
             }
             EngineCommand::DestroyEntity { entity } => {
-                let _ = self.space.remove_entity(entity);
-                if let Err(e) = self.ecs.despawn_entity(entity) {
-                    tracing::warn!(entity = %entity, error = %e, "failed to despawn entity");
+                let outcome = despawn_with_space(&mut self.ecs, &mut self.space, entity);
+                if !outcome.despawned {
+                    tracing::warn!(entity = %entity, "failed to despawn entity: not alive");
                 }
             }
             EngineCommand::MoveEntity {
@@ -167,6 +362,15 @@ impl<S: SpaceModel> TickLoop<S> {
     }
 }
 
+/// Per-tick seed from the master seed (splitmix64 finalizer), so that
+/// neighbouring ticks get unrelated seeds.
+fn mix_seed(master: u64, tick: u64) -> u64 {
+    let mut z = master.wrapping_add(tick.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 /// Convert a WASM ABI command to an engine-internal command.
 fn convert_wasm_to_engine(cmd: WasmCommand) -> Option<EngineCommand> {
     Some(match cmd {
@@ -207,6 +411,7 @@ fn convert_wasm_to_engine(cmd: WasmCommand) -> Option<EngineCommand> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use space::model::MoveError;
     use space::RoomGraphSpace;
 
     #[test]
@@ -222,6 +427,7 @@ mod tests {
         let config = TickConfig {
             tps: 30,
             max_ticks: 1,
+            ..Default::default()
         };
         let mut tick_loop = TickLoop::new(config, RoomGraphSpace::new());
         let metrics = tick_loop.step();
@@ -231,11 +437,79 @@ mod tests {
         assert_eq!(metrics.wasm_duration_us, 0);
     }
 
+    #[test]
+    fn paused_loop_does_not_advance() {
+        let mut tick_loop = TickLoop::new(TickConfig::default(), RoomGraphSpace::new());
+        tick_loop.step();
+        assert_eq!(tick_loop.current_tick, 1);
+
+        let handle = tick_loop.pause_handle();
+        handle.pause();
+        assert!(tick_loop.is_paused());
+        for _ in 0..3 {
+            assert!(tick_loop.step_if_running().is_none());
+        }
+        assert_eq!(tick_loop.current_tick, 1);
+
+        handle.resume();
+        let metrics = tick_loop.step_if_running().unwrap();
+        assert_eq!(metrics.tick_number, 2);
+        assert_eq!(tick_loop.current_tick, 2);
+    }
+
+    #[test]
+    fn run_waits_while_paused() {
+        let config = TickConfig {
+            tps: 100,
+            max_ticks: 3,
+            ..Default::default()
+        };
+        let mut tick_loop = TickLoop::new(config, RoomGraphSpace::new());
+        let handle = tick_loop.pause_handle();
+        handle.pause();
+
+        let resumer = {
+            let handle = handle.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(100));
+                handle.resume();
+            })
+        };
+        let start = Instant::now();
+        let metrics = tick_loop.run();
+        resumer.join().unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(tick_loop.current_tick, 3);
+        // Paused time is not replayed as a catch-up burst
+        assert!(metrics.iter().all(|m| m.skipped_ticks == 0));
+    }
+
+    #[test]
+    fn tick_seed_follows_master_seed() {
+        let mut tick_loop = TickLoop::new(TickConfig::default(), RoomGraphSpace::new());
+        assert_eq!(tick_loop.tick_seed(), None);
+
+        tick_loop.set_master_seed(42);
+        let first = tick_loop.tick_seed().unwrap();
+        tick_loop.step();
+        let second = tick_loop.tick_seed().unwrap();
+        assert_ne!(first, second);
+
+        let mut other = TickLoop::new(TickConfig::default(), RoomGraphSpace::new());
+        other.set_master_seed(42);
+        assert_eq!(other.tick_seed(), Some(first));
+        other.set_master_seed(43);
+        assert_ne!(other.tick_seed(), Some(first));
+    }
+
     #[test]
     fn backward_compatible_no_plugins() {
         let config = TickConfig {
             tps: 30,
             max_ticks: 10,
+            ..Default::default()
         };
         let mut tick_loop = TickLoop::new(config, RoomGraphSpace::new());
         assert!(tick_loop.plugin_runtime.is_none());
@@ -243,6 +517,126 @@ mod tests {
         assert_eq!(metrics.len(), 10);
     }
 
+    #[test]
+    fn non_realtime_run_skips_sleep() {
+        // At 1 TPS, 50 paced ticks would take ~49s of wall time.
+        let config = TickConfig {
+            tps: 1,
+            max_ticks: 50,
+            realtime: false,
+            ..Default::default()
+        };
+        let mut tick_loop = TickLoop::new(config, RoomGraphSpace::new());
+        let start = Instant::now();
+        let metrics = tick_loop.run();
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(metrics.len(), 50);
+        assert_eq!(tick_loop.current_tick, 50);
+        assert!(metrics.iter().all(|m| !m.compressed && m.skipped_ticks == 0));
+    }
+
+    /// Room graph whose moves stall, to make one tick overrun.
+    struct SlowMoveSpace(RoomGraphSpace, Duration);
+
+    impl SpaceModel for SlowMoveSpace {
+        fn entities_in_same_area(&self, entity: EntityId) -> Result<Vec<EntityId>, MoveError> {
+            self.0.entities_in_same_area(entity)
+        }
+        fn neighbors(&self, room: EntityId) -> Result<Vec<EntityId>, MoveError> {
+            self.0.neighbors(room)
+        }
+        fn move_entity(&mut self, entity: EntityId, target_room: EntityId) -> Result<(), MoveError> {
+            std::thread::sleep(self.1);
+            self.0.move_entity(entity, target_room)
+        }
+        fn broadcast_targets(&self, entity: EntityId) -> Result<Vec<EntityId>, MoveError> {
+            self.0.broadcast_targets(entity)
+        }
+        fn place_entity(&mut self, entity: EntityId, room: EntityId) -> Result<(), MoveError> {
+            self.0.place_entity(entity, room)
+        }
+        fn remove_entity(&mut self, entity: EntityId) -> Result<(), MoveError> {
+            self.0.remove_entity(entity)
+        }
+        fn entity_room(&self, entity: EntityId) -> Option<EntityId> {
+            self.0.entity_room(entity)
+        }
+    }
+
+    #[test]
+    fn run_catches_up_after_slow_tick() {
+        let config = TickConfig {
+            tps: 100,
+            max_ticks: 6,
+            ..Default::default()
+        };
+        let space = SlowMoveSpace(RoomGraphSpace::new(), Duration::from_millis(50));
+        let mut tick_loop = TickLoop::new(config, space);
+        // The first tick stalls for ~5 tick durations on this move.
+        tick_loop.commands.push(EngineCommand::MoveEntity {
+            entity: EntityId::new(1, 0),
+            target_room: EntityId::new(2, 0),
+        });
+
+        let metrics = tick_loop.run();
+
+        assert_eq!(metrics.len(), 6);
+        assert!(!metrics[0].compressed && !metrics[1].compressed);
+        // The backlog is stepped back-to-back rather than slept through
+        assert!(metrics[2..].iter().all(|m| m.compressed));
+    }
+
+    #[test]
+    fn pacer_without_realtime_never_waits() {
+        let config = TickConfig {
+            tps: 1,
+            realtime: false,
+            ..Default::default()
+        };
+        let mut pacer = TickPacer::new(&config);
+        let start = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(pacer.next_tick(), PacedTick { compressed: false, skipped: 0 });
+        }
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn fixed_timestep_steady_state() {
+        let dur = Duration::from_millis(10);
+        let mut ts = FixedTimestep::new(dur, 5);
+        assert_eq!(ts.advance(Duration::ZERO), CatchUp { steps: 1, skipped: 0 });
+        assert_eq!(ts.advance(Duration::from_millis(4)), CatchUp { steps: 0, skipped: 0 });
+        assert_eq!(ts.time_until_next(), Duration::from_millis(6));
+        assert_eq!(ts.advance(Duration::from_millis(6)), CatchUp { steps: 1, skipped: 0 });
+    }
+
+    #[test]
+    fn fixed_timestep_catches_up_after_slow_tick() {
+        let dur = Duration::from_millis(10);
+        let mut ts = FixedTimestep::new(dur, 5);
+        ts.advance(Duration::ZERO);
+
+        // A 35ms tick: 3 ticks are owed, remainder carries over
+        assert_eq!(ts.advance(Duration::from_millis(35)), CatchUp { steps: 3, skipped: 0 });
+        assert_eq!(ts.time_until_next(), Duration::from_millis(5));
+        assert_eq!(ts.advance(Duration::from_millis(5)), CatchUp { steps: 1, skipped: 0 });
+    }
+
+    #[test]
+    fn fixed_timestep_caps_catchup() {
+        let dur = Duration::from_millis(10);
+        let mut ts = FixedTimestep::new(dur, 4);
+        ts.advance(Duration::ZERO);
+
+        // A 1s stall must not trigger 100 back-to-back steps
+        let plan = ts.advance(Duration::from_secs(1));
+        assert_eq!(plan, CatchUp { steps: 4, skipped: 96 });
+        // Backlog is dropped, not carried into the next frame
+        assert_eq!(ts.advance(Duration::ZERO), CatchUp { steps: 0, skipped: 0 });
+    }
+
     #[test]
     fn wasm_command_conversion() {
         let wasm_cmd = WasmCommand::MoveEntity {
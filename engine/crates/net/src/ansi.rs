@@ -1,4 +1,4 @@
-/// ANSI escape code constants for MUD text coloring.
+//! ANSI escape code constants for MUD text coloring.
 
 // Reset
 pub const RESET: &str = "\x1b[0m";
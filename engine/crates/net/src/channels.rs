@@ -1,4 +1,4 @@
-use session::{SessionId, SessionOutput};
+use session::{ConnectionInfo, SessionId, SessionOutput};
 use tokio::sync::mpsc;
 
 /// Messages from the network layer to the tick thread.
@@ -7,6 +7,8 @@ pub enum NetToTick {
     /// A new TCP connection was accepted.
     NewConnection {
         session_id: SessionId,
+        /// Peer address and accept time, for logging and moderation.
+        connection: ConnectionInfo,
     },
     /// Player typed a line of input.
     PlayerInput {
@@ -29,9 +31,36 @@ pub type OutputTx = mpsc::UnboundedSender<SessionOutput>;
 /// Receiver in the output router for session outputs.
 pub type OutputRx = mpsc::UnboundedReceiver<SessionOutput>;
 
+/// One unit of output for a session's writer task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionWrite {
+    /// Game text.
+    Text(String),
+    /// JSON `ServerMessage` for clients that negotiated structured output.
+    Structured(String),
+    /// `StateDelta` encoded once by the tick thread in both wire formats;
+    /// the writer picks the one its session negotiated.
+    Delta { json: String, binary: Vec<u8> },
+}
+
+impl From<SessionOutput> for SessionWrite {
+    fn from(output: SessionOutput) -> Self {
+        if let Some(binary) = output.binary {
+            SessionWrite::Delta {
+                json: output.text,
+                binary,
+            }
+        } else if output.structured {
+            SessionWrite::Structured(output.text)
+        } else {
+            SessionWrite::Text(output.text)
+        }
+    }
+}
+
 /// Per-session write channel (tick thread -> output router -> session task).
-pub type SessionWriteTx = mpsc::UnboundedSender<String>;
-pub type SessionWriteRx = mpsc::UnboundedReceiver<String>;
+pub type SessionWriteTx = mpsc::UnboundedSender<SessionWrite>;
+pub type SessionWriteRx = mpsc::UnboundedReceiver<SessionWrite>;
 
 /// Registration message for the output router.
 #[derive(Debug)]
@@ -56,6 +85,7 @@ mod tests {
 
         tx.send(NetToTick::NewConnection {
             session_id: SessionId(1),
+            connection: ConnectionInfo::new("127.0.0.1:4000".parse().unwrap()),
         })
         .unwrap();
 
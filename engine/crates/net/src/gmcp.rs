@@ -1,5 +1,5 @@
-/// GMCP (Generic MUD Communication Protocol) package support.
-/// GMCP sends structured data over Telnet subnegotiation.
+//! GMCP (Generic MUD Communication Protocol) package support.
+//! GMCP sends structured data over Telnet subnegotiation.
 
 use serde::Serialize;
 
@@ -52,6 +52,22 @@ pub fn is_gmcp_do(data: &[u8]) -> bool {
     data.len() >= 3 && data[0] == 255 && data[1] == 253 && data[2] == GMCP_OPTION
 }
 
+/// Scan client input for an answer to the GMCP offer. Returns Some(true) for
+/// IAC DO GMCP, Some(false) for IAC DONT GMCP (last one wins), None otherwise.
+pub fn gmcp_response(data: &[u8]) -> Option<bool> {
+    data.windows(3)
+        .rev()
+        .filter(|w| w[0] == 255 && w[2] == GMCP_OPTION)
+        .find_map(|w| match w[1] {
+            253 => Some(true),
+            254 => Some(false),
+            _ => None,
+        })
+}
+
+/// GMCP package carrying a JSON `ServerMessage`; clients branch on its `type`.
+pub const SERVER_MESSAGE_PACKAGE: &str = "Server.Message";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +124,12 @@ mod tests {
         assert!(!is_gmcp_do(&[255, 251, GMCP_OPTION])); // WILL, not DO
         assert!(!is_gmcp_do(&[255])); // Too short
     }
+
+    #[test]
+    fn gmcp_response_finds_last_answer() {
+        assert_eq!(gmcp_response(b"look"), None);
+        assert_eq!(gmcp_response(&[b'x', 255, 253, GMCP_OPTION, b'y']), Some(true));
+        assert_eq!(gmcp_response(&[255, 253, GMCP_OPTION, 255, 254, GMCP_OPTION]), Some(false));
+        assert_eq!(gmcp_response(&[255, 253, 86]), None); // MCCP2, not GMCP
+    }
 }
@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Shared record of when the tick thread last stepped.
+///
+/// The tick thread calls `beat()` once per tick; the web server reads it for
+/// `/readyz`. Cloning shares the same underlying timestamp.
+#[derive(Debug, Clone)]
+pub struct TickHeartbeat {
+    origin: Instant,
+    /// Milliseconds since `origin` of the last beat, plus one (0 = never).
+    last_beat_ms: Arc<AtomicU64>,
+}
+
+impl TickHeartbeat {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            last_beat_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Record that a tick just completed.
+    pub fn beat(&self) {
+        let now_ms = self.origin.elapsed().as_millis() as u64;
+        self.last_beat_ms.store(now_ms + 1, Ordering::Relaxed);
+    }
+
+    /// Time since the last beat, or None if the tick thread never stepped.
+    pub fn since_last_beat(&self) -> Option<Duration> {
+        match self.last_beat_ms.load(Ordering::Relaxed) {
+            0 => None,
+            stamp => {
+                let now_ms = self.origin.elapsed().as_millis() as u64;
+                Some(Duration::from_millis(now_ms.saturating_sub(stamp - 1)))
+            }
+        }
+    }
+}
+
+impl Default for TickHeartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Readiness rule for `/readyz`: the tick thread must have stepped within
+/// `max_tick_age`.
+#[derive(Debug, Clone)]
+pub struct ReadinessProbe {
+    pub heartbeat: TickHeartbeat,
+    pub max_tick_age: Duration,
+}
+
+impl ReadinessProbe {
+    pub fn new(heartbeat: TickHeartbeat, max_tick_age: Duration) -> Self {
+        Self {
+            heartbeat,
+            max_tick_age,
+        }
+    }
+
+    /// True if the last tick is recent enough. A server that has not ticked
+    /// yet is not ready.
+    pub fn is_ready(&self) -> bool {
+        self.heartbeat
+            .since_last_beat()
+            .is_some_and(|age| age <= self.max_tick_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heartbeat_never_beaten() {
+        let hb = TickHeartbeat::new();
+        assert!(hb.since_last_beat().is_none());
+        let probe = ReadinessProbe::new(hb, Duration::from_secs(60));
+        assert!(!probe.is_ready());
+    }
+
+    #[test]
+    fn heartbeat_shared_between_clones() {
+        let hb = TickHeartbeat::new();
+        let probe = ReadinessProbe::new(hb.clone(), Duration::from_secs(60));
+        hb.beat();
+        assert!(probe.heartbeat.since_last_beat().unwrap() < Duration::from_secs(1));
+        assert!(probe.is_ready());
+    }
+
+    #[test]
+    fn stale_heartbeat_not_ready() {
+        let hb = TickHeartbeat::new();
+        hb.beat();
+        let probe = ReadinessProbe::new(hb, Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!probe.is_ready());
+    }
+}
@@ -0,0 +1,321 @@
+//! Accept-time IP allow/deny lists (CIDR).
+//!
+//! Checked by the Telnet, WebSocket, and web servers before a session is
+//! created. The lists live behind an `IpFilterHandle` so they can be swapped
+//! at runtime when the config file changes.
+
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Errors parsing an allow/deny entry.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IpFilterError {
+    #[error("invalid IP address in '{0}'")]
+    InvalidAddr(String),
+
+    #[error("invalid prefix length in '{0}'")]
+    InvalidPrefix(String),
+}
+
+/// An address range in CIDR notation ("10.0.0.0/8", "::1/128").
+/// A bare address is treated as a single host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Whether `ip` falls inside this range. IPv4-mapped IPv6 addresses
+    /// match their IPv4 form.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_eq(u32::from(net) as u128, u32::from(ip) as u128, self.prefix_len, 32)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_eq(u128::from(net), u128::from(ip), self.prefix_len, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_eq(a: u128, b: u128, prefix_len: u8, bits: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = bits - prefix_len;
+    (a >> shift) == (b >> shift)
+}
+
+impl FromStr for IpCidr {
+    type Err = IpFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| IpFilterError::InvalidAddr(s.to_string()))?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|&p| p <= max)
+                .ok_or_else(|| IpFilterError::InvalidPrefix(s.to_string()))?,
+            None => max,
+        };
+        Ok(Self {
+            network: network.to_canonical(),
+            prefix_len,
+        })
+    }
+}
+
+/// Reason a connection was refused by the filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpRejection {
+    Denied,
+    NotAllowed,
+}
+
+impl std::fmt::Display for IpRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Denied => write!(f, "address is on the deny list"),
+            Self::NotAllowed => write!(f, "address is not on the allow list"),
+        }
+    }
+}
+
+/// Allow/deny lists. Deny entries win; an empty allow list admits everyone
+/// not denied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpFilter {
+    allow: Vec<IpCidr>,
+    deny: Vec<IpCidr>,
+}
+
+impl IpFilter {
+    /// Build a filter from config strings.
+    pub fn from_lists<S: AsRef<str>>(allow: &[S], deny: &[S]) -> Result<Self, IpFilterError> {
+        let parse = |list: &[S]| {
+            list.iter()
+                .map(|s| s.as_ref().parse())
+                .collect::<Result<Vec<IpCidr>, _>>()
+        };
+        Ok(Self {
+            allow: parse(allow)?,
+            deny: parse(deny)?,
+        })
+    }
+
+    pub fn check(&self, ip: IpAddr) -> Result<(), IpRejection> {
+        if self.deny.iter().any(|c| c.contains(ip)) {
+            return Err(IpRejection::Denied);
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|c| c.contains(ip)) {
+            return Err(IpRejection::NotAllowed);
+        }
+        Ok(())
+    }
+}
+
+/// How often `IpFilterHandle::start` polls a watched config file.
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shared, swappable filter. Clones see the same lists.
+#[derive(Debug, Clone, Default)]
+pub struct IpFilterHandle {
+    inner: Arc<RwLock<IpFilter>>,
+}
+
+impl IpFilterHandle {
+    pub fn new(filter: IpFilter) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(filter)),
+        }
+    }
+
+    /// Build the handle from the configured lists and, when the config was
+    /// loaded from `watch_path`, poll that file every `RELOAD_INTERVAL` so
+    /// edits apply without a restart. `load` re-reads the lists from the file.
+    /// Must be called from within the tokio runtime when watching.
+    pub fn start<S, F>(
+        allow: &[S],
+        deny: &[S],
+        watch_path: Option<PathBuf>,
+        load: F,
+    ) -> Result<Self, IpFilterError>
+    where
+        S: AsRef<str>,
+        F: Fn(&Path) -> Result<IpFilter, String> + Send + 'static,
+    {
+        let handle = Self::new(IpFilter::from_lists(allow, deny)?);
+        if let Some(path) = watch_path {
+            tokio::spawn(watch_config_file(path, RELOAD_INTERVAL, handle.clone(), load));
+        }
+        Ok(handle)
+    }
+
+    pub fn check(&self, ip: IpAddr) -> Result<(), IpRejection> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).check(ip)
+    }
+
+    /// Replace the lists; subsequent accepts use the new filter.
+    pub fn replace(&self, filter: IpFilter) {
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = filter;
+    }
+
+    /// Check an accepted peer, logging the drop if it is refused.
+    pub(crate) fn admit(&self, peer: std::net::SocketAddr, server: &str) -> bool {
+        match self.check(peer.ip()) {
+            Ok(()) => true,
+            Err(reason) => {
+                tracing::warn!(%peer, server, "Connection refused: {}", reason);
+                false
+            }
+        }
+    }
+}
+
+/// Poll `path` every `interval` and reload the filter via `load` whenever the
+/// file's modification time changes. A failed reload keeps the current lists.
+pub async fn watch_config_file<F>(path: PathBuf, interval: Duration, handle: IpFilterHandle, load: F)
+where
+    F: Fn(&Path) -> Result<IpFilter, String>,
+{
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let mut last: Option<SystemTime> = modified(&path);
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let current = modified(&path);
+        if current == last {
+            continue;
+        }
+        last = current;
+        match load(&path) {
+            Ok(filter) => {
+                handle.replace(filter);
+                tracing::info!(path = %path.display(), "Reloaded IP allow/deny lists");
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "IP filter reload failed, keeping current lists: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr_matching() {
+        let net: IpCidr = "10.1.0.0/16".parse().unwrap();
+        assert!(net.contains(ip("10.1.200.3")));
+        assert!(!net.contains(ip("10.2.0.1")));
+        assert!(net.contains(ip("::ffff:10.1.0.9")));
+
+        let host: IpCidr = "192.168.0.5".parse().unwrap();
+        assert!(host.contains(ip("192.168.0.5")));
+        assert!(!host.contains(ip("192.168.0.6")));
+
+        let v6: IpCidr = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains(ip("2001:db8:1::1")));
+        assert!(!v6.contains(ip("10.1.0.1")));
+
+        let any: IpCidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(ip("8.8.8.8")));
+    }
+
+    #[test]
+    fn rejects_malformed_entries() {
+        assert_eq!(
+            "10.0.0/8".parse::<IpCidr>(),
+            Err(IpFilterError::InvalidAddr("10.0.0/8".to_string()))
+        );
+        assert_eq!(
+            "10.0.0.0/33".parse::<IpCidr>(),
+            Err(IpFilterError::InvalidPrefix("10.0.0.0/33".to_string()))
+        );
+        assert!(IpFilter::from_lists(&["bogus"], &[]).is_err());
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let filter = IpFilter::from_lists(&["10.0.0.0/8"], &["10.6.6.0/24"]).unwrap();
+        assert_eq!(filter.check(ip("10.1.1.1")), Ok(()));
+        assert_eq!(filter.check(ip("10.6.6.6")), Err(IpRejection::Denied));
+        assert_eq!(filter.check(ip("172.16.0.1")), Err(IpRejection::NotAllowed));
+
+        let open = IpFilter::default();
+        assert_eq!(open.check(ip("172.16.0.1")), Ok(()));
+    }
+
+    #[test]
+    fn handle_replace_is_shared() {
+        let handle = IpFilterHandle::default();
+        let clone = handle.clone();
+        assert!(clone.check(ip("127.0.0.1")).is_ok());
+        handle.replace(IpFilter::from_lists::<&str>(&[], &["127.0.0.0/8"]).unwrap());
+        assert_eq!(clone.check(ip("127.0.0.1")), Err(IpRejection::Denied));
+    }
+
+    #[test]
+    fn start_without_watch_uses_configured_lists() {
+        let unused = |_: &Path| -> Result<IpFilter, String> { unreachable!() };
+        let handle = IpFilterHandle::start(&[] as &[&str], &["10.0.0.0/8"], None, unused).unwrap();
+        assert_eq!(handle.check(ip("10.1.2.3")), Err(IpRejection::Denied));
+        assert_eq!(handle.check(ip("127.0.0.1")), Ok(()));
+
+        assert!(IpFilterHandle::start(&["nonsense"], &[], None, unused).is_err());
+    }
+
+    #[tokio::test]
+    async fn watch_reloads_on_file_change() {
+        let dir = std::env::temp_dir().join(format!("ip_filter_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("deny.txt");
+        std::fs::write(&path, "").unwrap();
+
+        let handle = IpFilterHandle::default();
+        let load = |p: &Path| {
+            let text = std::fs::read_to_string(p).map_err(|e| e.to_string())?;
+            let deny: Vec<&str> = text.lines().collect();
+            IpFilter::from_lists(&[], &deny).map_err(|e| e.to_string())
+        };
+        let task = tokio::spawn(watch_config_file(
+            path.clone(),
+            Duration::from_millis(20),
+            handle.clone(),
+            load,
+        ));
+
+        // Ensure a distinct mtime even on coarse-grained filesystems.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut &file, b"127.0.0.1\n").unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        drop(file);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(handle.check(ip("127.0.0.1")), Err(IpRejection::Denied));
+        task.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,9 @@
 pub mod ansi;
 pub mod channels;
 pub mod gmcp;
+pub mod health;
+pub mod ip_filter;
+pub mod mccp;
 pub mod output_router;
 pub mod protocol;
 pub mod rate_limiter;
@@ -0,0 +1,147 @@
+//! MCCP2 (MUD Client Compression Protocol v2) support.
+//! After the client answers IAC DO COMPRESS2, the server sends
+//! IAC SB COMPRESS2 IAC SE and every following byte is a zlib stream.
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+/// Telnet option code for MCCP2 (COMPRESS2).
+pub const MCCP2_OPTION: u8 = 86;
+
+const IAC: u8 = 255;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+/// Sent on connect to offer compression: IAC WILL COMPRESS2.
+pub fn mccp2_will() -> [u8; 3] {
+    [IAC, WILL, MCCP2_OPTION]
+}
+
+/// Marks the start of the compressed stream: IAC SB COMPRESS2 IAC SE.
+/// Must be sent uncompressed.
+pub fn mccp2_start() -> [u8; 5] {
+    [IAC, SB, MCCP2_OPTION, IAC, SE]
+}
+
+/// Per-session zlib compressor. Each `compress` call is sync-flushed so the
+/// client can render the text immediately.
+pub struct Mccp2Compressor {
+    inner: ZlibEncoder<Vec<u8>>,
+}
+
+impl Mccp2Compressor {
+    pub fn new() -> Self {
+        Self {
+            inner: ZlibEncoder::new(Vec::new(), Compression::default()),
+        }
+    }
+
+    pub fn compress(&mut self, data: &[u8]) -> Vec<u8> {
+        // Writing into a Vec cannot fail.
+        let _ = self.inner.write_all(data);
+        let _ = self.inner.flush();
+        std::mem::take(self.inner.get_mut())
+    }
+
+    /// End the zlib stream; the returned bytes are the last compressed output.
+    pub fn finish(self) -> Vec<u8> {
+        self.inner.finish().unwrap_or_default()
+    }
+}
+
+impl Default for Mccp2Compressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Telnet output encoding: plain until the client accepts MCCP2.
+pub enum OutputEncoder {
+    Plain,
+    Compressed(Mccp2Compressor),
+}
+
+impl OutputEncoder {
+    pub fn is_compressed(&self) -> bool {
+        matches!(self, OutputEncoder::Compressed(_))
+    }
+
+    /// Switch to compressed output. Returns the start marker the caller must
+    /// write (uncompressed) before any encoded data, or None if already on.
+    pub fn start_compression(&mut self) -> Option<[u8; 5]> {
+        if self.is_compressed() {
+            return None;
+        }
+        *self = OutputEncoder::Compressed(Mccp2Compressor::new());
+        Some(mccp2_start())
+    }
+
+    /// Switch back to plain output (the client sent DONT COMPRESS2).
+    /// Returns the end of the zlib stream, which the caller must write
+    /// before any plain data, or None if compression was off.
+    pub fn stop_compression(&mut self) -> Option<Vec<u8>> {
+        match std::mem::replace(self, OutputEncoder::Plain) {
+            OutputEncoder::Compressed(c) => Some(c.finish()),
+            OutputEncoder::Plain => None,
+        }
+    }
+
+    pub fn encode(&mut self, data: &[u8]) -> Vec<u8> {
+        match self {
+            OutputEncoder::Plain => data.to_vec(),
+            OutputEncoder::Compressed(c) => c.compress(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Decompress, FlushDecompress};
+
+    fn inflate(d: &mut Decompress, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 * 1024);
+        d.decompress_vec(data, &mut out, FlushDecompress::Sync).unwrap();
+        out
+    }
+
+    #[test]
+    fn compressed_chunks_decompress_incrementally() {
+        let mut enc = OutputEncoder::Plain;
+        assert_eq!(enc.encode(b"plain"), b"plain");
+
+        assert_eq!(enc.start_compression(), Some(mccp2_start()));
+        assert!(enc.start_compression().is_none());
+
+        let mut d = Decompress::new(true);
+        let first = "긴 방 설명입니다. ".repeat(50);
+        let a = enc.encode(first.as_bytes());
+        assert!(a.len() < first.len());
+        assert_eq!(inflate(&mut d, &a), first.as_bytes());
+
+        // Each chunk is flushed, so it decodes without waiting for more.
+        let b = enc.encode(b"You hit the goblin.\r\n");
+        assert_eq!(inflate(&mut d, &b), b"You hit the goblin.\r\n");
+    }
+
+    #[test]
+    fn stop_compression_ends_the_zlib_stream() {
+        let mut enc = OutputEncoder::Plain;
+        assert!(enc.stop_compression().is_none());
+
+        enc.start_compression();
+        let mut d = Decompress::new(true);
+        let a = enc.encode(b"compressed");
+        assert_eq!(inflate(&mut d, &a), b"compressed");
+
+        let tail = enc.stop_compression().unwrap();
+        let mut out = Vec::with_capacity(64);
+        let status = d.decompress_vec(&tail, &mut out, FlushDecompress::Finish).unwrap();
+        assert_eq!(status, flate2::Status::StreamEnd);
+        assert!(!enc.is_compressed());
+        assert_eq!(enc.encode(b"plain"), b"plain");
+    }
+}
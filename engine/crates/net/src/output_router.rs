@@ -24,12 +24,13 @@ pub async fn run_output_router(
             }
             Some(output) = output_rx.recv() => {
                 if let Some(tx) = writers.get(&output.session_id) {
-                    if tx.send(output.text).is_err() {
-                        tracing::debug!(session_id = ?output.session_id, "Output router: session write channel closed");
-                        writers.remove(&output.session_id);
-                    } else if output.disconnect {
-                        tracing::debug!(session_id = ?output.session_id, "Output router: disconnect requested, dropping writer");
-                        writers.remove(&output.session_id);
+                    let (session_id, disconnect) = (output.session_id, output.disconnect);
+                    if tx.send(output.into()).is_err() {
+                        tracing::debug!(?session_id, "Output router: session write channel closed");
+                        writers.remove(&session_id);
+                    } else if disconnect {
+                        tracing::debug!(?session_id, "Output router: disconnect requested, dropping writer");
+                        writers.remove(&session_id);
                     }
                 }
             }
@@ -43,7 +44,7 @@ pub async fn run_output_router(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::channels::RegisterSession;
+    use crate::channels::{RegisterSession, SessionWrite};
     use session::SessionOutput;
     use tokio::sync::mpsc;
 
@@ -74,7 +75,7 @@ mod tests {
             .unwrap();
 
         let msg = write_rx.recv().await.unwrap();
-        assert_eq!(msg, "Hello, player!");
+        assert_eq!(msg, SessionWrite::Text("Hello, player!".to_string()));
 
         // Unregister
         unregister_tx.send(sid).unwrap();
@@ -1,13 +1,62 @@
+use std::time::Instant;
+
 use serde::{Deserialize, Serialize};
 
+/// Current grid protocol version, sent as `v` in every server message.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest client protocol version the server still accepts.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Errors decoding a client message envelope.
+#[derive(Debug, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("invalid message: {0}")]
+    Invalid(String),
+
+    #[error(
+        "unsupported protocol version {0} (server supports {min}..={max})",
+        min = MIN_PROTOCOL_VERSION,
+        max = PROTOCOL_VERSION
+    )]
+    UnsupportedVersion(u32),
+}
+
+/// Versioned envelope: `{ "v": <version>, "type": ..., ... }`.
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    v: u32,
+    #[serde(flatten)]
+    msg: &'a T,
+}
+
 /// Client-to-server message (internally tagged JSON).
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
-    Connect { name: String },
+    Connect {
+        name: String,
+        /// How the client wants `StateDelta`s delivered; JSON if omitted.
+        #[serde(default)]
+        encoding: DeltaEncoding,
+    },
     Move { dx: i32, dy: i32 },
     Action { name: String, args: Option<String> },
     Ping,
+    /// Ask the server to resend the full AOI. The next delta reports every
+    /// visible entity as `entered`; the client should drop its local set.
+    Resync,
+}
+
+/// Wire format for `StateDelta`, chosen per session in `connect`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaEncoding {
+    /// Versioned JSON text frames, like every other server message.
+    #[default]
+    Json,
+    /// `StateDeltaFrame` as postcard in binary frames. Other messages stay JSON.
+    Binary,
 }
 
 /// Server-to-client message (internally tagged JSON).
@@ -18,6 +67,8 @@ pub enum ServerMessage {
         session_id: u64,
         entity_id: u64,
         tick: u64,
+        /// Monotonic server clock (see `ServerClock`).
+        server_time_ms: u64,
         grid_config: GridConfigWire,
     },
     EntityUpdate {
@@ -30,6 +81,9 @@ pub enum ServerMessage {
     },
     StateDelta {
         tick: u64,
+        /// Monotonic server clock when the tick's delta was built, so clients
+        /// can interpolate without knowing the tick rate.
+        server_time_ms: u64,
         #[serde(skip_serializing_if = "Vec::is_empty", default)]
         entered: Vec<EntityWire>,
         #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -37,10 +91,160 @@ pub enum ServerMessage {
         #[serde(skip_serializing_if = "Vec::is_empty", default)]
         left: Vec<u64>,
     },
+    /// Sent instead of an empty `StateDelta` so idle clients still see the
+    /// tick advance.
+    Keepalive {
+        tick: u64,
+    },
     Error {
+        code: ErrorCode,
+        /// Human-readable text for display; clients branch on `code`.
         message: String,
     },
     Pong,
+    /// Player vitals and inventory, pushed when they change. Only delivered
+    /// to clients with structured output (GMCP or WebSocket).
+    StatusUpdate {
+        health: i32,
+        max_health: i32,
+        inventory: Vec<ItemWire>,
+    },
+}
+
+/// Machine-readable reason carried by `ServerMessage::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Target position is outside the grid.
+    OutOfBounds,
+    /// Move rejected for another reason (not adjacent, not placed, ...).
+    InvalidMove,
+    /// The action requires a logged-in session.
+    NotAuthenticated,
+    /// Too many commands or connections.
+    RateLimited,
+    /// The client's protocol version is not supported.
+    UnsupportedVersion,
+    /// The server is stopping; the connection will close.
+    ShuttingDown,
+    /// Unexpected server-side failure.
+    Internal,
+}
+
+impl ClientMessage {
+    /// Decode a client envelope. A missing `v` is treated as version 1
+    /// (pre-versioning clients); unknown fields are ignored.
+    pub fn decode(text: &str) -> Result<Self, ProtocolError> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| ProtocolError::Invalid(e.to_string()))?;
+        let version = match value.get("v") {
+            None => 1,
+            Some(v) => v
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| ProtocolError::Invalid(format!("invalid version field: {}", v)))?,
+        };
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+            return Err(ProtocolError::UnsupportedVersion(version));
+        }
+        serde_json::from_value(value).map_err(|e| ProtocolError::Invalid(e.to_string()))
+    }
+}
+
+impl ServerMessage {
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        ServerMessage::Error {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Encode as a versioned JSON envelope.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Envelope {
+            v: PROTOCOL_VERSION,
+            msg: self,
+        })
+        .unwrap_or_default()
+    }
+
+    /// Encode a `StateDelta` as a binary frame; `None` for other messages.
+    pub fn to_binary(&self) -> Option<Vec<u8>> {
+        StateDeltaFrame::from_message(self).map(|frame| frame.encode())
+    }
+
+    /// True for a `StateDelta` with nothing entered, moved or left.
+    pub fn is_empty_delta(&self) -> bool {
+        matches!(
+            self,
+            ServerMessage::StateDelta { entered, moved, left, .. }
+                if entered.is_empty() && moved.is_empty() && left.is_empty()
+        )
+    }
+}
+
+/// Monotonic millisecond clock for `server_time_ms`, counted from server
+/// start. Unaffected by wall-clock adjustments.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerClock {
+    start: Instant,
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    /// Milliseconds elapsed since the clock was created.
+    pub fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+impl Default for ServerClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-session coalescing of empty `StateDelta`s.
+///
+/// The first delta is always sent so a (re)connecting client gets its full
+/// refresh. After that, empty deltas are dropped and replaced by a
+/// `Keepalive` once every `keepalive_ticks`. `keepalive_ticks == 0` disables
+/// coalescing and every delta is sent as-is.
+#[derive(Debug, Clone)]
+pub struct DeltaPacer {
+    keepalive_ticks: u64,
+    last_sent_tick: Option<u64>,
+}
+
+impl DeltaPacer {
+    pub fn new(keepalive_ticks: u64) -> Self {
+        Self {
+            keepalive_ticks,
+            last_sent_tick: None,
+        }
+    }
+
+    /// Decide what to send for this tick's delta, if anything.
+    pub fn pace(&mut self, tick: u64, delta: ServerMessage) -> Option<ServerMessage> {
+        let idle = delta.is_empty_delta();
+        let msg = match self.last_sent_tick {
+            Some(_) if idle && self.keepalive_ticks == 0 => delta,
+            Some(last) if idle => {
+                if tick.saturating_sub(last) < self.keepalive_ticks {
+                    return None;
+                }
+                ServerMessage::Keepalive { tick }
+            }
+            _ => delta,
+        };
+        self.last_sent_tick = Some(tick);
+        Some(msg)
+    }
 }
 
 /// Wire representation of an entity's position.
@@ -52,14 +256,126 @@ pub struct EntityWire {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     pub is_self: bool,
+    /// Direction the entity faces (`"north"`, `"south"`, `"east"`, `"west"`).
+    pub facing: &'static str,
+    /// Client asset key; `null` for entities without an appearance, which
+    /// older clients ignore and newer ones draw with the default shape.
+    pub sprite: Option<String>,
+    /// CSS color override; `null` means the client default.
+    pub color: Option<String>,
 }
 
-/// Wire representation of a moved entity (minimal: id + new position).
+/// Wire representation of a moved entity (minimal: id + new position + facing).
 #[derive(Debug, Clone, Serialize)]
 pub struct EntityMovedWire {
     pub id: u64,
     pub x: i32,
     pub y: i32,
+    pub facing: &'static str,
+}
+
+/// Wire representation of an inventory item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemWire {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Binary (postcard) form of `ServerMessage::StateDelta` for sessions that
+/// negotiated `DeltaEncoding::Binary`. Field order is the wire layout, so
+/// new fields go at the end together with a `PROTOCOL_VERSION` bump.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDeltaFrame {
+    pub tick: u64,
+    pub server_time_ms: u64,
+    #[serde(default)]
+    pub entered: Vec<EntityFrame>,
+    #[serde(default)]
+    pub moved: Vec<EntityMovedFrame>,
+    #[serde(default)]
+    pub left: Vec<u64>,
+}
+
+/// Owned counterpart of `EntityWire` inside a `StateDeltaFrame`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityFrame {
+    pub id: u64,
+    pub x: i32,
+    pub y: i32,
+    pub name: Option<String>,
+    pub is_self: bool,
+    pub facing: String,
+    pub sprite: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Owned counterpart of `EntityMovedWire` inside a `StateDeltaFrame`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityMovedFrame {
+    pub id: u64,
+    pub x: i32,
+    pub y: i32,
+    pub facing: String,
+}
+
+impl StateDeltaFrame {
+    pub fn from_message(msg: &ServerMessage) -> Option<Self> {
+        let ServerMessage::StateDelta {
+            tick,
+            server_time_ms,
+            entered,
+            moved,
+            left,
+        } = msg
+        else {
+            return None;
+        };
+        Some(Self {
+            tick: *tick,
+            server_time_ms: *server_time_ms,
+            entered: entered
+                .iter()
+                .map(|e| EntityFrame {
+                    id: e.id,
+                    x: e.x,
+                    y: e.y,
+                    name: e.name.clone(),
+                    is_self: e.is_self,
+                    facing: e.facing.to_string(),
+                    sprite: e.sprite.clone(),
+                    color: e.color.clone(),
+                })
+                .collect(),
+            moved: moved
+                .iter()
+                .map(|m| EntityMovedFrame {
+                    id: m.id,
+                    x: m.x,
+                    y: m.y,
+                    facing: m.facing.to_string(),
+                })
+                .collect(),
+            left: left.clone(),
+        })
+    }
+
+    /// Parse an encoded `ServerMessage` JSON envelope; `None` unless it is a
+    /// `state_delta`.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(json).ok()?;
+        if value.get("type")?.as_str()? != "state_delta" {
+            return None;
+        }
+        serde_json::from_value(value).ok()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        postcard::to_allocvec(self).unwrap_or_default()
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        postcard::from_bytes(bytes).map_err(|e| ProtocolError::Invalid(e.to_string()))
+    }
 }
 
 /// Wire representation of grid configuration.
@@ -80,7 +396,10 @@ mod tests {
         let json = r#"{"type":"connect","name":"Player1"}"#;
         let msg: ClientMessage = serde_json::from_str(json).unwrap();
         match msg {
-            ClientMessage::Connect { name } => assert_eq!(name, "Player1"),
+            ClientMessage::Connect { name, encoding } => {
+                assert_eq!(name, "Player1");
+                assert_eq!(encoding, DeltaEncoding::Json);
+            }
             _ => panic!("Expected Connect"),
         }
     }
@@ -131,12 +450,19 @@ mod tests {
         assert!(matches!(msg, ClientMessage::Ping));
     }
 
+    #[test]
+    fn deserialize_resync() {
+        let msg = ClientMessage::decode(r#"{"v":1,"type":"resync"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Resync));
+    }
+
     #[test]
     fn serialize_welcome() {
         let msg = ServerMessage::Welcome {
             session_id: 1_000_000,
             entity_id: 42,
             tick: 0,
+            server_time_ms: 1_500,
             grid_config: GridConfigWire {
                 width: 256,
                 height: 256,
@@ -148,6 +474,26 @@ mod tests {
         assert!(json.contains(r#""type":"welcome""#));
         assert!(json.contains(r#""session_id":1000000"#));
         assert!(json.contains(r#""entity_id":42"#));
+        assert!(json.contains(r#""server_time_ms":1500"#));
+    }
+
+    #[test]
+    fn state_delta_carries_increasing_server_time() {
+        let clock = ServerClock::new();
+        let mut times = Vec::new();
+        for tick in 0..3 {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            let msg = ServerMessage::StateDelta {
+                tick,
+                server_time_ms: clock.now_ms(),
+                entered: vec![],
+                moved: vec![],
+                left: vec![],
+            };
+            let val: serde_json::Value = serde_json::from_str(&msg.to_json()).unwrap();
+            times.push(val["server_time_ms"].as_u64().expect("server_time_ms present"));
+        }
+        assert!(times.windows(2).all(|w| w[0] < w[1]), "{:?}", times);
     }
 
     #[test]
@@ -161,6 +507,9 @@ mod tests {
                     y: 128,
                     name: Some("Player1".to_string()),
                     is_self: true,
+                    facing: "south",
+                    sprite: None,
+                    color: None,
                 },
                 EntityWire {
                     id: 2,
@@ -168,6 +517,9 @@ mod tests {
                     y: 100,
                     name: None,
                     is_self: false,
+                    facing: "south",
+                    sprite: None,
+                    color: None,
                 },
             ],
         };
@@ -176,6 +528,9 @@ mod tests {
         assert!(json.contains(r#""is_self":true"#));
         // name:null should be skipped for entity 2
         assert!(!json.contains(r#""name":null"#));
+        // but appearance fields are always present so clients can rely on them
+        assert!(json.contains(r#""sprite":null"#));
+        assert!(json.contains(r#""color":null"#));
     }
 
     #[test]
@@ -191,14 +546,33 @@ mod tests {
 
     #[test]
     fn serialize_error() {
-        let msg = ServerMessage::Error {
-            message: "out of bounds".to_string(),
-        };
+        let msg = ServerMessage::error(ErrorCode::OutOfBounds, "out of bounds");
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"error""#));
+        assert!(json.contains(r#""code":"out_of_bounds""#));
         assert!(json.contains("out of bounds"));
     }
 
+    #[test]
+    fn serialize_every_error_code() {
+        let cases = [
+            (ErrorCode::OutOfBounds, "out_of_bounds"),
+            (ErrorCode::InvalidMove, "invalid_move"),
+            (ErrorCode::NotAuthenticated, "not_authenticated"),
+            (ErrorCode::RateLimited, "rate_limited"),
+            (ErrorCode::UnsupportedVersion, "unsupported_version"),
+            (ErrorCode::ShuttingDown, "shutting_down"),
+            (ErrorCode::Internal, "internal"),
+        ];
+        for (code, wire) in cases {
+            let json = ServerMessage::error(code, format!("detail for {}", wire)).to_json();
+            let val: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(val["type"], "error");
+            assert_eq!(val["code"], wire);
+            assert_eq!(val["message"], format!("detail for {}", wire));
+        }
+    }
+
     #[test]
     fn serialize_pong() {
         let msg = ServerMessage::Pong;
@@ -206,21 +580,43 @@ mod tests {
         assert_eq!(json, r#"{"type":"pong"}"#);
     }
 
+    #[test]
+    fn serialize_status_update() {
+        let msg = ServerMessage::StatusUpdate {
+            health: 7,
+            max_health: 10,
+            inventory: vec![ItemWire {
+                id: 3,
+                name: "Potion".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"status_update","health":7,"max_health":10,"inventory":[{"id":3,"name":"Potion"}]}"#
+        );
+    }
+
     #[test]
     fn serialize_state_delta_full() {
         let msg = ServerMessage::StateDelta {
             tick: 42,
+            server_time_ms: 0,
             entered: vec![EntityWire {
                 id: 123,
                 x: 50,
                 y: 50,
                 name: Some("Alice".to_string()),
                 is_self: true,
+                facing: "east",
+                sprite: Some("knight".to_string()),
+                color: Some("#c0392b".to_string()),
             }],
             moved: vec![EntityMovedWire {
                 id: 456,
                 x: 51,
                 y: 50,
+                facing: "east",
             }],
             left: vec![789],
         };
@@ -230,18 +626,72 @@ mod tests {
         assert!(json.contains(r#""entered""#));
         assert!(json.contains(r#""moved""#));
         assert!(json.contains(r#""left":[789]"#));
+        assert!(json.contains(r#""sprite":"knight""#));
+        assert!(json.contains(r##""color":"#c0392b""##));
+    }
+
+    #[test]
+    fn deserialize_connect_binary_encoding() {
+        let msg = ClientMessage::decode(r#"{"type":"connect","name":"A","encoding":"binary"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Connect { encoding: DeltaEncoding::Binary, .. }));
+        assert!(ClientMessage::decode(r#"{"type":"connect","name":"A","encoding":"xml"}"#).is_err());
+    }
+
+    #[test]
+    fn state_delta_binary_matches_json() {
+        let msg = ServerMessage::StateDelta {
+            tick: 42,
+            server_time_ms: 1_234,
+            entered: vec![EntityWire {
+                id: 123,
+                x: -3,
+                y: 50,
+                name: Some("Alice".to_string()),
+                is_self: true,
+                facing: "east",
+                sprite: Some("knight".to_string()),
+                color: None,
+            }],
+            moved: vec![EntityMovedWire {
+                id: 456,
+                x: 51,
+                y: 50,
+                facing: "north",
+            }],
+            left: vec![789],
+        };
+        let bytes = msg.to_binary().unwrap();
+        let from_binary = StateDeltaFrame::decode(&bytes).unwrap();
+        assert_eq!(Some(&from_binary), StateDeltaFrame::from_json(&msg.to_json()).as_ref());
+        assert_eq!(from_binary.entered[0].name.as_deref(), Some("Alice"));
+        assert!(bytes.len() < msg.to_json().len());
+
+        // Empty vectors are skipped in JSON but still round-trip.
+        let empty = delta(7, vec![]);
+        assert_eq!(
+            StateDeltaFrame::from_json(&empty.to_json()),
+            StateDeltaFrame::decode(&empty.to_binary().unwrap()).ok()
+        );
+
+        assert!(ServerMessage::Pong.to_binary().is_none());
+        assert!(StateDeltaFrame::from_json(&ServerMessage::Pong.to_json()).is_none());
+        assert!(StateDeltaFrame::decode(&[0xff]).is_err());
     }
 
     #[test]
     fn serialize_state_delta_entered_only() {
         let msg = ServerMessage::StateDelta {
             tick: 10,
+            server_time_ms: 0,
             entered: vec![EntityWire {
                 id: 1,
                 x: 10,
                 y: 20,
                 name: None,
                 is_self: false,
+                facing: "south",
+                sprite: None,
+                color: None,
             }],
             moved: vec![],
             left: vec![],
@@ -257,6 +707,7 @@ mod tests {
     fn serialize_state_delta_empty_skips() {
         let msg = ServerMessage::StateDelta {
             tick: 5,
+            server_time_ms: 0,
             entered: vec![],
             moved: vec![],
             left: vec![],
@@ -269,16 +720,111 @@ mod tests {
         assert!(!json.contains(r#""left""#));
     }
 
+    #[test]
+    fn envelope_encodes_version() {
+        let json = ServerMessage::Pong.to_json();
+        assert_eq!(json, r#"{"v":1,"type":"pong"}"#);
+
+        let json = ServerMessage::error(ErrorCode::Internal, "oops").to_json();
+        let val: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(val["v"], PROTOCOL_VERSION);
+        assert_eq!(val["type"], "error");
+        assert_eq!(val["message"], "oops");
+    }
+
+    #[test]
+    fn envelope_decodes_versioned_and_legacy() {
+        let msg = ClientMessage::decode(r#"{"v":1,"type":"move","dx":2,"dy":3}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Move { dx: 2, dy: 3 }));
+
+        // No version field: treated as v1
+        let msg = ClientMessage::decode(r#"{"type":"ping"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Ping));
+
+        // Unknown fields are tolerated
+        let msg = ClientMessage::decode(r#"{"v":1,"type":"connect","name":"A","client":"web"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Connect { name, .. } if name == "A"));
+    }
+
+    #[test]
+    fn envelope_rejects_unknown_version() {
+        let err = ClientMessage::decode(r#"{"v":99,"type":"ping"}"#).unwrap_err();
+        assert!(matches!(err, ProtocolError::UnsupportedVersion(99)));
+        assert!(err.to_string().contains("unsupported protocol version 99"));
+
+        let err = ClientMessage::decode(r#"{"v":0,"type":"ping"}"#).unwrap_err();
+        assert!(matches!(err, ProtocolError::UnsupportedVersion(0)));
+
+        let err = ClientMessage::decode(r#"{"v":"one","type":"ping"}"#).unwrap_err();
+        assert!(matches!(err, ProtocolError::Invalid(_)));
+
+        assert!(matches!(ClientMessage::decode("not json"), Err(ProtocolError::Invalid(_))));
+    }
+
     #[test]
     fn serialize_entity_moved_wire() {
         let wire = EntityMovedWire {
             id: 99,
             x: -5,
             y: 10,
+            facing: "west",
         };
         let json = serde_json::to_string(&wire).unwrap();
         assert!(json.contains(r#""id":99"#));
         assert!(json.contains(r#""x":-5"#));
         assert!(json.contains(r#""y":10"#));
+        assert!(json.contains(r#""facing":"west""#));
+    }
+
+    fn delta(tick: u64, moved: Vec<EntityMovedWire>) -> ServerMessage {
+        ServerMessage::StateDelta {
+            tick,
+            server_time_ms: 0,
+            entered: vec![],
+            moved,
+            left: vec![],
+        }
+    }
+
+    #[test]
+    fn pacer_drops_idle_deltas_until_movement() {
+        let mut pacer = DeltaPacer::new(5);
+        // Initial delta is always sent, even if empty.
+        assert!(matches!(
+            pacer.pace(1, delta(1, vec![])),
+            Some(ServerMessage::StateDelta { tick: 1, .. })
+        ));
+        for tick in 2..=5 {
+            assert!(pacer.pace(tick, delta(tick, vec![])).is_none());
+        }
+        let moved = vec![EntityMovedWire { id: 1, x: 2, y: 3, facing: "east" }];
+        assert!(matches!(
+            pacer.pace(6, delta(6, moved)),
+            Some(ServerMessage::StateDelta { tick: 6, .. })
+        ));
+        assert!(pacer.pace(7, delta(7, vec![])).is_none());
+    }
+
+    #[test]
+    fn pacer_sends_keepalive_every_k_ticks() {
+        let mut pacer = DeltaPacer::new(3);
+        assert!(pacer.pace(0, delta(0, vec![])).is_some());
+        assert!(pacer.pace(1, delta(1, vec![])).is_none());
+        assert!(pacer.pace(2, delta(2, vec![])).is_none());
+        let msg = pacer.pace(3, delta(3, vec![])).unwrap();
+        assert!(matches!(msg, ServerMessage::Keepalive { tick: 3 }));
+        assert!(msg.to_json().contains(r#""type":"keepalive""#));
+        assert!(pacer.pace(4, delta(4, vec![])).is_none());
+    }
+
+    #[test]
+    fn pacer_disabled_sends_every_delta() {
+        let mut pacer = DeltaPacer::new(0);
+        for tick in 0..3 {
+            assert!(matches!(
+                pacer.pace(tick, delta(tick, vec![])),
+                Some(ServerMessage::StateDelta { .. })
+            ));
+        }
     }
 }
@@ -1,13 +1,19 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use session::SessionId;
+use session::{ConnectionInfo, SessionId};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 
 use crate::channels::{
-    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWriteRx, UnregisterTx,
+    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWrite, SessionWriteRx, UnregisterTx,
 };
-use crate::telnet::LineBuffer;
+use crate::gmcp::{gmcp_subneg, gmcp_will, GMCP_OPTION, SERVER_MESSAGE_PACKAGE};
+use crate::ip_filter::IpFilterHandle;
+use crate::mccp::{mccp2_will, OutputEncoder, MCCP2_OPTION};
+use crate::telnet::{LineBuffer, LineLimit, Negotiation, OverlongLine};
+
+/// Sent before closing a connection under `OverlongLine::Disconnect`.
+const LINE_TOO_LONG_MSG: &str = "Input line too long. Disconnecting.";
 
 static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(0);
 
@@ -18,18 +24,42 @@ pub async fn run_tcp_server(
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
 ) -> Result<(), std::io::Error> {
-    run_tcp_server_inner(addr, player_tx, register_tx, unregister_tx, None).await
+    run_tcp_server_inner(
+        addr,
+        player_tx,
+        register_tx,
+        unregister_tx,
+        None,
+        None,
+        LineLimit::default(),
+    )
+    .await
 }
 
 /// Run the TCP server with optional shutdown receiver.
+///
+/// Connections from addresses refused by `ip_filter` are closed right after
+/// accept, before a session id is assigned. Input lines longer than
+/// `line_limit.max_len` are truncated or end the session, per its policy.
 pub async fn run_tcp_server_with_shutdown(
     addr: String,
     player_tx: PlayerTx,
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
     shutdown_rx: tokio::sync::watch::Receiver<bool>,
+    ip_filter: Option<IpFilterHandle>,
+    line_limit: LineLimit,
 ) -> Result<(), std::io::Error> {
-    run_tcp_server_inner(addr, player_tx, register_tx, unregister_tx, Some(shutdown_rx)).await
+    run_tcp_server_inner(
+        addr,
+        player_tx,
+        register_tx,
+        unregister_tx,
+        Some(shutdown_rx),
+        ip_filter,
+        line_limit,
+    )
+    .await
 }
 
 async fn run_tcp_server_inner(
@@ -38,6 +68,8 @@ async fn run_tcp_server_inner(
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
     mut shutdown_rx: Option<tokio::sync::watch::Receiver<bool>>,
+    ip_filter: Option<IpFilterHandle>,
+    line_limit: LineLimit,
 ) -> Result<(), std::io::Error> {
     let listener = TcpListener::bind(&addr).await?;
     tracing::info!("TCP server listening on {}", addr);
@@ -61,6 +93,12 @@ async fn run_tcp_server_inner(
             }
         };
 
+        if let Some(filter) = &ip_filter {
+            if !filter.admit(peer_addr, "telnet") {
+                continue;
+            }
+        }
+
         let session_id = SessionId(NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed));
 
         tracing::info!(?session_id, %peer_addr, "New connection");
@@ -70,7 +108,17 @@ async fn run_tcp_server_inner(
         let unregister_tx = unregister_tx.clone();
 
         tokio::spawn(async move {
-            handle_session(stream, session_id, player_tx, register_tx, unregister_tx).await;
+            let connection = ConnectionInfo::new(peer_addr);
+            handle_session(
+                stream,
+                session_id,
+                connection,
+                player_tx,
+                register_tx,
+                unregister_tx,
+                line_limit,
+            )
+            .await;
         });
     }
 }
@@ -86,9 +134,11 @@ async fn wait_shutdown(rx: &mut tokio::sync::watch::Receiver<bool>) {
 async fn handle_session(
     stream: tokio::net::TcpStream,
     session_id: SessionId,
+    connection: ConnectionInfo,
     player_tx: PlayerTx,
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
+    line_limit: LineLimit,
 ) {
     let (mut reader, mut writer) = stream.into_split();
 
@@ -103,29 +153,115 @@ async fn handle_session(
     });
 
     // Notify tick thread of new connection
-    let _ = player_tx.send(NetToTick::NewConnection { session_id });
-
-    // Spawn writer task
-    let writer_handle = tokio::spawn(async move {
-        while let Some(text) = write_rx.recv().await {
-            // Convert bare \n to \r\n for Telnet clients (e.g. PuTTY)
-            let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
-            let msg = format!("{}\r\n", text);
-            if writer.write_all(msg.as_bytes()).await.is_err() {
-                break;
+    let _ = player_tx.send(NetToTick::NewConnection {
+        session_id,
+        connection,
+    });
+
+    // Reader -> writer: client's DO/DONT answer to the MCCP2 offer
+    let (mccp_tx, mut mccp_rx) = tokio::sync::mpsc::unbounded_channel::<bool>();
+    // Reader -> writer: client's DO/DONT answer to the GMCP offer
+    let (gmcp_tx, mut gmcp_rx) = tokio::sync::mpsc::unbounded_channel::<bool>();
+    // Reader -> writer: send this final message, then close
+    let (close_tx, mut close_rx) = tokio::sync::mpsc::unbounded_channel::<&'static str>();
+
+    // Spawn writer task. It ends when the output router drops the write
+    // channel (a `disconnect` output): everything queued before that is
+    // written, then the socket is half-closed so the client sees EOF only
+    // after the final message.
+    let mut writer_handle = tokio::spawn(async move {
+        // Offer compression; clients that don't know the option ignore it.
+        if writer.write_all(&mccp2_will()).await.is_err() {
+            return;
+        }
+        // Offer GMCP; structured messages are dropped until the client accepts.
+        if writer.write_all(&gmcp_will()).await.is_err() {
+            return;
+        }
+        let mut encoder = OutputEncoder::Plain;
+        let mut gmcp = false;
+        loop {
+            tokio::select! {
+                Some(accepted) = mccp_rx.recv() => {
+                    let switch = if accepted {
+                        encoder.start_compression().map(|marker| marker.to_vec())
+                    } else {
+                        // Close the zlib stream so the client can go back to plain text.
+                        encoder.stop_compression()
+                    };
+                    if let Some(bytes) = switch {
+                        if writer.write_all(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(accepted) = gmcp_rx.recv() => {
+                    gmcp = accepted;
+                }
+                Some(reason) = close_rx.recv() => {
+                    let _ = writer.write_all(&encoder.encode(format!("{}\r\n", reason).as_bytes())).await;
+                    break;
+                }
+                frame = write_rx.recv() => {
+                    let bytes = match frame {
+                        None => break,
+                        Some(SessionWrite::Text(text)) => {
+                            // Convert bare \n to \r\n for Telnet clients (e.g. PuTTY)
+                            let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+                            let msg = format!("{}\r\n", text);
+                            encoder.encode(msg.as_bytes())
+                        }
+                        Some(SessionWrite::Structured(json) | SessionWrite::Delta { json, .. }) => {
+                            if !gmcp {
+                                continue;
+                            }
+                            let payload = format!("{} {}", SERVER_MESSAGE_PACKAGE, json);
+                            encoder.encode(&gmcp_subneg(&payload))
+                        }
+                    };
+                    if writer.write_all(&bytes).await.is_err() {
+                        break;
+                    }
+                }
             }
         }
+        let _ = writer.flush().await;
+        let _ = writer.shutdown().await;
     });
 
     // Reader loop
-    let mut line_buffer = LineBuffer::new();
+    let mut line_buffer = LineBuffer::with_max_len(line_limit.max_len);
     let mut buf = [0u8; 4096];
+    let mut overlong = false;
 
     loop {
-        match reader.read(&mut buf).await {
+        let read = tokio::select! {
+            read = reader.read(&mut buf) => read,
+            // Writer drained and half-closed: the server ended the session.
+            _ = &mut writer_handle => break,
+        };
+        match read {
             Ok(0) => break, // Connection closed
             Ok(n) => {
                 let lines = line_buffer.feed(&buf[..n]);
+                // Answers to our offers, even when split across reads.
+                for negotiation in line_buffer.take_negotiations() {
+                    let (tx, accepted) = match negotiation {
+                        Negotiation::Do(MCCP2_OPTION) => (&mccp_tx, true),
+                        Negotiation::Dont(MCCP2_OPTION) => (&mccp_tx, false),
+                        Negotiation::Do(GMCP_OPTION) => (&gmcp_tx, true),
+                        Negotiation::Dont(GMCP_OPTION) => (&gmcp_tx, false),
+                        _ => continue,
+                    };
+                    let _ = tx.send(accepted);
+                }
+                if line_buffer.take_overflow() && line_limit.on_overflow == OverlongLine::Disconnect {
+                    // Nothing from this read reaches the game.
+                    tracing::warn!(?session_id, max_len = line_limit.max_len, "Input line too long, disconnecting");
+                    let _ = close_tx.send(LINE_TOO_LONG_MSG);
+                    overlong = true;
+                    break;
+                }
                 for line in lines {
                     let _ = player_tx.send(NetToTick::PlayerInput {
                         session_id,
@@ -141,13 +277,24 @@ async fn handle_session(
     let _ = player_tx.send(NetToTick::Disconnected { session_id });
     let _ = unregister_tx.send(session_id);
 
-    writer_handle.abort();
+    if overlong {
+        // Let the writer deliver the notice and half-close.
+        if tokio::time::timeout(std::time::Duration::from_secs(1), &mut writer_handle)
+            .await
+            .is_err()
+        {
+            writer_handle.abort();
+        }
+    } else {
+        writer_handle.abort();
+    }
     tracing::info!(?session_id, "Session ended");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ip_filter::IpFilter;
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::TcpStream;
     use tokio::sync::mpsc;
@@ -176,9 +323,14 @@ mod tests {
         // Connect
         let mut stream = TcpStream::connect(addr).await.unwrap();
 
-        // Should receive NewConnection
+        // Should receive NewConnection carrying the client's address
         let msg = player_rx.recv().await.unwrap();
-        assert!(matches!(msg, NetToTick::NewConnection { .. }));
+        match msg {
+            NetToTick::NewConnection { connection, .. } => {
+                assert_eq!(connection.peer_addr, stream.local_addr().unwrap());
+            }
+            other => panic!("expected NewConnection, got {:?}", other),
+        }
 
         // Send input
         stream.write_all(b"north\n").await.unwrap();
@@ -226,7 +378,7 @@ mod tests {
         let reg = register_rx.recv().await.unwrap();
 
         // Send text through the write channel
-        reg.write_tx.send("Welcome!".to_string()).unwrap();
+        reg.write_tx.send(SessionWrite::Text("Welcome!".to_string())).unwrap();
         tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
         // Read from client
@@ -238,4 +390,369 @@ mod tests {
         drop(stream);
         server_handle.abort();
     }
+
+    #[tokio::test]
+    async fn server_compresses_after_mccp2_do() {
+        let (player_tx, _player_rx) = mpsc::unbounded_channel();
+        let (register_tx, mut register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let server_handle = tokio::spawn(run_tcp_server(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+        ));
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let reg = register_rx.recv().await.unwrap();
+
+        // Server offers compression first, then GMCP
+        let mut will = [0u8; 3];
+        stream.read_exact(&mut will).await.unwrap();
+        assert_eq!(will, mccp2_will());
+        stream.read_exact(&mut will).await.unwrap();
+        assert_eq!(will, gmcp_will());
+
+        // Accept it
+        stream
+            .write_all(&[255, 253, crate::mccp::MCCP2_OPTION])
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        reg.write_tx.send(SessionWrite::Text("Welcome!".to_string())).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut marker = [0u8; 5];
+        stream.read_exact(&mut marker).await.unwrap();
+        assert_eq!(marker, crate::mccp::mccp2_start());
+
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        let mut d = flate2::Decompress::new(true);
+        let mut out = Vec::with_capacity(1024);
+        d.decompress_vec(&buf[..n], &mut out, flate2::FlushDecompress::Sync)
+            .unwrap();
+        assert_eq!(out, b"Welcome!\r\n");
+
+        drop(stream);
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn split_mccp2_do_and_later_dont_are_honoured() {
+        let (player_tx, _player_rx) = mpsc::unbounded_channel();
+        let (register_tx, mut register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let server_handle = tokio::spawn(run_tcp_server(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let reg = register_rx.recv().await.unwrap();
+        let mut offers = [0u8; 6];
+        stream.read_exact(&mut offers).await.unwrap();
+
+        // IAC DO arrives in one read, the option byte in the next
+        stream.write_all(&[255, 253]).await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        stream.write_all(&[crate::mccp::MCCP2_OPTION]).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut marker = [0u8; 5];
+        stream.read_exact(&mut marker).await.unwrap();
+        assert_eq!(marker, crate::mccp::mccp2_start());
+
+        // DONT ends the zlib stream; later output is plain again
+        stream
+            .write_all(&[255, 254, crate::mccp::MCCP2_OPTION])
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        reg.write_tx.send(SessionWrite::Text("plain".to_string())).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        let mut d = flate2::Decompress::new(true);
+        let mut out = Vec::with_capacity(1024);
+        let status = d
+            .decompress_vec(&buf[..n], &mut out, flate2::FlushDecompress::Finish)
+            .unwrap();
+        assert_eq!(status, flate2::Status::StreamEnd);
+        let consumed = d.total_in() as usize;
+        assert_eq!(&buf[consumed..n], b"plain\r\n");
+
+        drop(stream);
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn structured_output_needs_gmcp() {
+        let (player_tx, _player_rx) = mpsc::unbounded_channel();
+        let (register_tx, mut register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let server_handle = tokio::spawn(run_tcp_server(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+        ));
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let reg = register_rx.recv().await.unwrap();
+        let mut offers = [0u8; 6];
+        stream.read_exact(&mut offers).await.unwrap();
+
+        // Not negotiated yet: dropped
+        reg.write_tx.send(SessionWrite::Structured("{\"type\":\"pong\"}".to_string())).unwrap();
+        reg.write_tx.send(SessionWrite::Text("plain".to_string())).unwrap();
+        let mut buf = [0u8; 7];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"plain\r\n");
+
+        stream
+            .write_all(&[255, 253, crate::gmcp::GMCP_OPTION])
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let json = "{\"type\":\"pong\"}";
+        reg.write_tx.send(SessionWrite::Structured(json.to_string())).unwrap();
+        let expected = gmcp_subneg(&format!("Server.Message {}", json));
+        let mut frame = vec![0u8; expected.len()];
+        stream.read_exact(&mut frame).await.unwrap();
+        assert_eq!(frame, expected);
+
+        drop(stream);
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn disconnect_flushes_final_message_before_close() {
+        let (player_tx, mut player_rx) = mpsc::unbounded_channel();
+        let (register_tx, register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, unregister_rx) = mpsc::unbounded_channel();
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        let router_handle = tokio::spawn(crate::output_router::run_output_router(
+            output_rx,
+            register_rx,
+            unregister_rx,
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let server_handle = tokio::spawn(run_tcp_server(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+        ));
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let session_id = match player_rx.recv().await.unwrap() {
+            NetToTick::NewConnection { session_id, .. } => session_id,
+            other => panic!("expected NewConnection, got {:?}", other),
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        output_tx
+            .send(session::SessionOutput::new(session_id, "Server shutting down."))
+            .unwrap();
+        output_tx
+            .send(session::SessionOutput::with_disconnect(session_id, "Goodbye!"))
+            .unwrap();
+
+        // The server closes the connection; the client never has to.
+        let mut received = Vec::new();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            stream.read_to_end(&mut received),
+        )
+        .await
+        .expect("server did not close the connection")
+        .unwrap();
+        assert!(received.ends_with(b"Server shutting down.\r\nGoodbye!\r\n"));
+
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(1), player_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(msg, NetToTick::Disconnected { .. }));
+
+        server_handle.abort();
+        router_handle.abort();
+    }
+
+    async fn start_with_line_limit(
+        line_limit: LineLimit,
+    ) -> (
+        std::net::SocketAddr,
+        mpsc::UnboundedReceiver<NetToTick>,
+        tokio::task::JoinHandle<()>,
+        tokio::sync::watch::Sender<bool>,
+    ) {
+        let (player_tx, player_rx) = mpsc::unbounded_channel();
+        let (register_tx, register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, unregister_rx) = mpsc::unbounded_channel();
+        let (_output_tx, output_rx) = mpsc::unbounded_channel();
+        let router_handle = tokio::spawn(crate::output_router::run_output_router(
+            output_rx,
+            register_rx,
+            unregister_rx,
+        ));
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        tokio::spawn(run_tcp_server_with_shutdown(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            shutdown_rx,
+            None,
+            line_limit,
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        (addr, player_rx, router_handle, shutdown_tx)
+    }
+
+    #[tokio::test]
+    async fn overlong_line_is_truncated() {
+        let limit = LineLimit {
+            max_len: 16,
+            on_overflow: OverlongLine::Truncate,
+        };
+        let (addr, mut player_rx, router_handle, _shutdown) = start_with_line_limit(limit).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        assert!(matches!(player_rx.recv().await.unwrap(), NetToTick::NewConnection { .. }));
+        stream.write_all(&[b'a'; 100]).await.unwrap();
+        stream.write_all(b"\nlook\n").await.unwrap();
+
+        for expected in ["a".repeat(16), "look".to_string()] {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(2), player_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            match msg {
+                NetToTick::PlayerInput { line, .. } => assert_eq!(line, expected),
+                other => panic!("expected PlayerInput, got {:?}", other),
+            }
+        }
+
+        router_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn overlong_line_disconnects() {
+        let limit = LineLimit {
+            max_len: 16,
+            on_overflow: OverlongLine::Disconnect,
+        };
+        let (addr, mut player_rx, router_handle, _shutdown) = start_with_line_limit(limit).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        assert!(matches!(player_rx.recv().await.unwrap(), NetToTick::NewConnection { .. }));
+        // No newline: the buffer must not keep growing while waiting for one.
+        stream.write_all(&[b'a'; 100]).await.unwrap();
+
+        let mut received = Vec::new();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            stream.read_to_end(&mut received),
+        )
+        .await
+        .expect("server did not close the connection")
+        .unwrap();
+        assert!(received.ends_with(format!("{}\r\n", LINE_TOO_LONG_MSG).as_bytes()));
+
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(1), player_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(msg, NetToTick::Disconnected { .. }));
+
+        router_handle.abort();
+    }
+
+    async fn start_filtered(
+        filter: IpFilter,
+    ) -> (std::net::SocketAddr, mpsc::UnboundedReceiver<NetToTick>, tokio::sync::watch::Sender<bool>) {
+        let (player_tx, player_rx) = mpsc::unbounded_channel();
+        let (register_tx, _register_rx) = mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        tokio::spawn(run_tcp_server_with_shutdown(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            shutdown_rx,
+            Some(IpFilterHandle::new(filter)),
+            LineLimit::default(),
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        (addr, player_rx, shutdown_tx)
+    }
+
+    #[tokio::test]
+    async fn denied_cidr_is_refused() {
+        let filter = IpFilter::from_lists(&[], &["127.0.0.0/8"]).unwrap();
+        let (addr, mut player_rx, _shutdown) = start_filtered(filter).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut buf = [0u8; 16];
+        let n = tokio::time::timeout(std::time::Duration::from_secs(2), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap_or(0);
+        assert_eq!(n, 0, "denied connection should be closed without output");
+        assert!(player_rx.try_recv().is_err(), "no session should be created");
+    }
+
+    #[tokio::test]
+    async fn allowed_cidr_proceeds() {
+        let filter = IpFilter::from_lists(&["127.0.0.0/8"], &["10.0.0.0/8"]).unwrap();
+        let (addr, mut player_rx, _shutdown) = start_filtered(filter).await;
+
+        let _stream = TcpStream::connect(addr).await.unwrap();
+        let msg = player_rx.recv().await.unwrap();
+        assert!(matches!(msg, NetToTick::NewConnection { .. }));
+    }
 }
@@ -51,40 +51,223 @@ pub fn strip_iac(bytes: &[u8]) -> Vec<u8> {
     result
 }
 
-const MAX_LINE_LEN: usize = 4096;
+/// Default cap on a single input line, in bytes.
+pub const DEFAULT_MAX_LINE_LEN: usize = 4096;
+
+/// What the server does when a client sends a line over the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlongLine {
+    /// Keep the first `max_len` bytes and drop the rest of the line.
+    #[default]
+    Truncate,
+    /// Close the connection.
+    Disconnect,
+}
+
+/// Per-connection input line limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineLimit {
+    pub max_len: usize,
+    pub on_overflow: OverlongLine,
+}
+
+impl Default for LineLimit {
+    fn default() -> Self {
+        Self {
+            max_len: DEFAULT_MAX_LINE_LEN,
+            on_overflow: OverlongLine::Truncate,
+        }
+    }
+}
+
+const BS: u8 = 0x08;
+const TAB: u8 = 0x09;
+const ESC: u8 = 0x1b;
+const DEL: u8 = 0x7f;
+
+/// Progress through an ANSI escape sequence (cursor keys etc.), which may be
+/// split across reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    None,
+    /// Saw ESC.
+    Start,
+    /// Inside `ESC [ params final`.
+    Csi,
+    /// `ESC O x` (application-mode cursor keys): one more byte.
+    Ss3,
+}
+
+/// Progress through a Telnet IAC sequence, which may be split across reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Telnet {
+    Data,
+    /// Saw IAC.
+    Iac,
+    /// Saw IAC WILL/WONT/DO/DONT; the option byte is next.
+    Option(u8),
+    /// Inside IAC SB ... IAC SE.
+    Sb,
+    /// Saw IAC inside a subnegotiation.
+    SbIac,
+}
+
+/// An option negotiation received from the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Negotiation {
+    Will(u8),
+    Wont(u8),
+    Do(u8),
+    Dont(u8),
+}
 
 /// Buffered line reader for Telnet input.
+///
+/// Acts as a minimal line editor for clients in character mode: backspace
+/// and delete erase the previous character (whole UTF-8 sequence), tabs
+/// become spaces, and ANSI escape sequences and other control bytes are
+/// dropped, so only the corrected text reaches the game.
+///
+/// IAC sequences are parsed statefully, so a negotiation split across reads
+/// is still recognised; see `take_negotiations`.
 pub struct LineBuffer {
     buf: Vec<u8>,
+    escape: Escape,
+    telnet: Telnet,
+    negotiations: Vec<Negotiation>,
+    max_len: usize,
+    overflowed: bool,
 }
 
 impl LineBuffer {
     pub fn new() -> Self {
-        Self { buf: Vec::new() }
+        Self::with_max_len(DEFAULT_MAX_LINE_LEN)
+    }
+
+    /// A buffer that keeps at most `max_len` bytes of each line. The buffer
+    /// never grows past that, however long the client's line is.
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            escape: Escape::None,
+            telnet: Telnet::Data,
+            negotiations: Vec::new(),
+            max_len,
+            overflowed: false,
+        }
+    }
+
+    /// Whether any line hit the length limit since the last call.
+    pub fn take_overflow(&mut self) -> bool {
+        std::mem::take(&mut self.overflowed)
+    }
+
+    /// DO/DONT/WILL/WONT commands seen since the last call, in order.
+    pub fn take_negotiations(&mut self) -> Vec<Negotiation> {
+        std::mem::take(&mut self.negotiations)
     }
 
     /// Feed raw data into the buffer. Returns any complete lines.
     pub fn feed(&mut self, data: &[u8]) -> Vec<String> {
-        let cleaned = strip_iac(data);
         let mut lines = Vec::new();
 
-        for &byte in &cleaned {
+        for &byte in data {
+            if !self.telnet_data(byte) {
+                continue;
+            }
             if byte == b'\n' {
+                // A newline always ends the line, even mid-sequence.
+                self.escape = Escape::None;
                 let line = self.take_line();
                 lines.push(line);
-            } else if byte == b'\r' {
+                continue;
+            }
+            if self.skip_escape(byte) {
+                continue;
+            }
+            match byte {
                 // Ignore CR, we split on LF
-            } else {
-                if self.buf.len() < MAX_LINE_LEN {
-                    self.buf.push(byte);
-                }
-                // Silently drop bytes beyond MAX_LINE_LEN
+                b'\r' => {}
+                BS | DEL => self.erase_char(),
+                ESC => self.escape = Escape::Start,
+                TAB => self.push(b' '),
+                0x00..=0x1f => {}
+                _ => self.push(byte),
             }
         }
 
         lines
     }
 
+    /// Run `byte` through the IAC parser. Returns true if it is input data.
+    fn telnet_data(&mut self, byte: u8) -> bool {
+        self.telnet = match (self.telnet, byte) {
+            (Telnet::Data, IAC) => Telnet::Iac,
+            (Telnet::Data, _) => return true,
+            // Escaped IAC (literal 255)
+            (Telnet::Iac, IAC) => {
+                self.telnet = Telnet::Data;
+                return true;
+            }
+            (Telnet::Iac, WILL | WONT | DO | DONT) => Telnet::Option(byte),
+            (Telnet::Iac, SB) => Telnet::Sb,
+            // Unknown 2-byte IAC command
+            (Telnet::Iac, _) => Telnet::Data,
+            (Telnet::Option(command), option) => {
+                self.negotiations.push(match command {
+                    WILL => Negotiation::Will(option),
+                    WONT => Negotiation::Wont(option),
+                    DO => Negotiation::Do(option),
+                    _ => Negotiation::Dont(option),
+                });
+                Telnet::Data
+            }
+            (Telnet::Sb, IAC) => Telnet::SbIac,
+            (Telnet::Sb, _) => Telnet::Sb,
+            (Telnet::SbIac, SE) => Telnet::Data,
+            (Telnet::SbIac, _) => Telnet::Sb,
+        };
+        false
+    }
+
+    /// Consume `byte` if it belongs to an escape sequence in progress.
+    fn skip_escape(&mut self, byte: u8) -> bool {
+        self.escape = match self.escape {
+            Escape::None => return false,
+            Escape::Start => match byte {
+                b'[' => Escape::Csi,
+                b'O' => Escape::Ss3,
+                // Alt+key and other two-byte sequences
+                _ => Escape::None,
+            },
+            // Parameter/intermediate bytes continue; 0x40..=0x7e ends it.
+            Escape::Csi if (0x20..=0x3f).contains(&byte) => Escape::Csi,
+            Escape::Csi | Escape::Ss3 => Escape::None,
+        };
+        true
+    }
+
+    fn push(&mut self, byte: u8) {
+        // Drop bytes beyond max_len; the caller decides via take_overflow
+        // whether that is worth a disconnect.
+        if self.buf.len() < self.max_len {
+            self.buf.push(byte);
+        } else {
+            self.overflowed = true;
+        }
+    }
+
+    /// Remove the last character, including all bytes of a multi-byte
+    /// UTF-8 sequence (Korean input is 3 bytes per syllable).
+    fn erase_char(&mut self) {
+        while let Some(byte) = self.buf.pop() {
+            if byte & 0xc0 != 0x80 {
+                break;
+            }
+        }
+    }
+
     fn take_line(&mut self) -> String {
         let bytes = std::mem::take(&mut self.buf);
         String::from_utf8_lossy(&bytes).into_owned()
@@ -175,7 +358,74 @@ mod tests {
         lb.feed(&long_data);
         let lines = lb.feed(b"\n");
         assert_eq!(lines.len(), 1);
-        assert_eq!(lines[0].len(), MAX_LINE_LEN);
+        assert_eq!(lines[0].len(), DEFAULT_MAX_LINE_LEN);
+        assert!(lb.take_overflow());
+        assert!(!lb.take_overflow());
+    }
+
+    #[test]
+    fn line_buffer_custom_max_len() {
+        let mut lb = LineBuffer::with_max_len(8);
+        assert_eq!(lb.feed(b"12345678\n"), vec!["12345678"]);
+        assert!(!lb.take_overflow());
+        assert_eq!(lb.feed(b"123456789abc\n"), vec!["12345678"]);
+        assert!(lb.take_overflow());
+    }
+
+    #[test]
+    fn line_buffer_backspace_and_delete() {
+        let mut lb = LineBuffer::new();
+        assert_eq!(lb.feed(b"lokk\x08\x08ok\r\n"), vec!["look"]);
+        assert_eq!(lb.feed(b"sya\x7f\x7fay\n"), vec!["say"]);
+        // Erasing past the start of the line is a no-op
+        assert_eq!(lb.feed(b"\x08\x08n\n"), vec!["n"]);
+    }
+
+    #[test]
+    fn line_buffer_backspace_erases_whole_utf8_char() {
+        let mut lb = LineBuffer::new();
+        let mut data = "고블린 공격격".as_bytes().to_vec();
+        data.extend_from_slice(b"\x7f\n");
+        assert_eq!(lb.feed(&data), vec!["고블린 공격"]);
+    }
+
+    #[test]
+    fn line_buffer_drops_ansi_and_controls() {
+        let mut lb = LineBuffer::new();
+        // Up arrow, left arrow with params, SS3 cursor key, bell, NUL, tab
+        let lines = lb.feed(b"\x1b[Alo\x1b[1;5Dok\x1bOB\x07\x00\tgoblin\n");
+        assert_eq!(lines, vec!["look goblin"]);
+    }
+
+    #[test]
+    fn line_buffer_escape_split_across_feeds() {
+        let mut lb = LineBuffer::new();
+        assert!(lb.feed(b"wh\x1b").is_empty());
+        assert!(lb.feed(b"[1").is_empty());
+        assert_eq!(lb.feed(b"0~o\n"), vec!["who"]);
+    }
+
+    #[test]
+    fn line_buffer_reports_negotiations_split_across_feeds() {
+        let mut lb = LineBuffer::new();
+        assert!(lb.feed(&[b'l', IAC]).is_empty());
+        assert!(lb.take_negotiations().is_empty());
+        assert!(lb.feed(&[DO]).is_empty());
+        assert_eq!(lb.feed(&[86, b'o', b'o', b'k', IAC, DONT, 201, b'\n']), vec!["look"]);
+        assert_eq!(
+            lb.take_negotiations(),
+            vec![Negotiation::Do(86), Negotiation::Dont(201)]
+        );
+        assert!(lb.take_negotiations().is_empty());
+    }
+
+    #[test]
+    fn line_buffer_skips_subnegotiation_split_across_feeds() {
+        let mut lb = LineBuffer::new();
+        assert!(lb.feed(&[b'a', IAC, SB, 24, 0, b'x']).is_empty());
+        assert!(lb.feed(&[IAC]).is_empty());
+        assert_eq!(lb.feed(&[SE, b'b', b'\n']), vec!["ab"]);
+        assert!(lb.take_negotiations().is_empty());
     }
 
     #[test]
@@ -1,19 +1,30 @@
+use std::io::Write;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use axum::extract::ws::{Message, WebSocket};
-use axum::extract::{State, WebSocketUpgrade};
-use axum::response::IntoResponse;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Request, State, WebSocketUpgrade};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::Router;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use futures_util::{SinkExt, StreamExt};
-use session::SessionId;
+use session::{ConnectionInfo, SessionId};
 use tower_http::services::{ServeDir, ServeFile};
 
 use crate::channels::{
-    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWriteRx, UnregisterTx,
+    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWrite, SessionWriteRx, UnregisterTx,
 };
+use crate::health::ReadinessProbe;
+use crate::ip_filter::IpFilterHandle;
+use crate::protocol::{ErrorCode, ProtocolError, ServerMessage};
+use crate::ws_server::{DeltaFormat, Heartbeat, WsFrame, WsHandshake};
 
 /// Shared state for the axum WebSocket handler.
 #[derive(Clone)]
@@ -22,12 +33,19 @@ struct AppState {
     player_tx: PlayerTx,
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
+    readiness: Option<ReadinessProbe>,
+    ip_filter: Option<IpFilterHandle>,
+    handshake: WsHandshake,
+    heartbeat: Heartbeat,
 }
 
 /// Run the web server with WebSocket upgrade and optional static file serving.
 ///
 /// If `static_dir` is Some, serves files from that directory (SPA fallback to index.html).
-/// The `/ws` route always handles WebSocket upgrades.
+/// Static files honor `Accept-Encoding`: pre-compressed `.br`/`.gz` siblings are
+/// served when present, otherwise text-like assets (html, css, js, json, svg, wasm) are
+/// gzipped on the fly. The `/ws` route always handles WebSocket upgrades; `/healthz`, `/readyz`
+/// and `/metrics` (Prometheus text format) are always served (see `run_web_server_with_shutdown`).
 pub async fn run_web_server(
     addr: String,
     player_tx: PlayerTx,
@@ -35,10 +53,32 @@ pub async fn run_web_server(
     unregister_tx: UnregisterTx,
     static_dir: Option<PathBuf>,
 ) -> Result<(), std::io::Error> {
-    run_web_server_inner(addr, player_tx, register_tx, unregister_tx, static_dir, None).await
+    run_web_server_inner(
+        addr,
+        player_tx,
+        register_tx,
+        unregister_tx,
+        static_dir,
+        None,
+        None,
+        None,
+        WsHandshake::default(),
+        Heartbeat::default(),
+    )
+    .await
 }
 
 /// Run the web server with optional shutdown receiver.
+///
+/// `/healthz` returns 200 while the process is serving. `/readyz` returns 200
+/// only if `readiness` reports a recent tick, and 503 otherwise (e.g. a hung
+/// tick thread). Without a probe, `/readyz` mirrors `/healthz`.
+///
+/// WebSocket upgrades from addresses refused by `ip_filter` get 403 before a
+/// session is created. Upgrades failing `handshake` (origin not allowed,
+/// foreign subprotocol) are refused with its status code. Open sessions are
+/// pinged per `heartbeat` and dropped when they stop answering.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_web_server_with_shutdown(
     addr: String,
     player_tx: PlayerTx,
@@ -46,10 +86,27 @@ pub async fn run_web_server_with_shutdown(
     unregister_tx: UnregisterTx,
     static_dir: Option<PathBuf>,
     shutdown_rx: tokio::sync::watch::Receiver<bool>,
+    readiness: Option<ReadinessProbe>,
+    ip_filter: Option<IpFilterHandle>,
+    handshake: WsHandshake,
+    heartbeat: Heartbeat,
 ) -> Result<(), std::io::Error> {
-    run_web_server_inner(addr, player_tx, register_tx, unregister_tx, static_dir, Some(shutdown_rx)).await
+    run_web_server_inner(
+        addr,
+        player_tx,
+        register_tx,
+        unregister_tx,
+        static_dir,
+        Some(shutdown_rx),
+        readiness,
+        ip_filter,
+        handshake,
+        heartbeat,
+    )
+    .await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_web_server_inner(
     addr: String,
     player_tx: PlayerTx,
@@ -57,28 +114,47 @@ async fn run_web_server_inner(
     unregister_tx: UnregisterTx,
     static_dir: Option<PathBuf>,
     shutdown_rx: Option<tokio::sync::watch::Receiver<bool>>,
+    readiness: Option<ReadinessProbe>,
+    ip_filter: Option<IpFilterHandle>,
+    handshake: WsHandshake,
+    heartbeat: Heartbeat,
 ) -> Result<(), std::io::Error> {
     let state = AppState {
         next_session_id: Arc::new(AtomicU64::new(1_000_000)),
         player_tx,
         register_tx,
         unregister_tx,
+        readiness,
+        ip_filter,
+        handshake,
+        heartbeat,
     };
 
     let mut app = Router::new()
         .route("/ws", get(ws_upgrade_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .route("/metrics", get(metrics_handler))
         .with_state(state);
 
     if let Some(dir) = static_dir {
         let index_path = dir.join("index.html");
-        let serve_dir = ServeDir::new(&dir).not_found_service(ServeFile::new(index_path));
-        app = app.fallback_service(serve_dir);
+        let index = ServeFile::new(index_path).precompressed_br().precompressed_gzip();
+        let serve_dir = ServeDir::new(&dir)
+            .precompressed_br()
+            .precompressed_gzip()
+            .not_found_service(index);
+        let static_files = Router::new()
+            .fallback_service(serve_dir)
+            .layer(axum::middleware::from_fn(compress_static));
+        app = app.fallback_service(static_files);
         tracing::info!(dir = %dir.display(), "Serving static files");
     }
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("Web server listening on {}", addr);
 
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
     if let Some(mut rx) = shutdown_rx {
         axum::serve(listener, app)
             .with_graceful_shutdown(async move {
@@ -90,58 +166,223 @@ async fn run_web_server_inner(
                 tracing::info!("Web server shutting down gracefully");
             })
             .await
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
     } else {
         axum::serve(listener, app)
             .await
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(std::io::Error::other)
+    }
+}
+
+/// Text assets smaller than this are sent as-is; gzip overhead outweighs the gain.
+const MIN_COMPRESS_BYTES: u64 = 256;
+/// Larger responses are streamed uncompressed rather than buffered.
+const MAX_COMPRESS_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Gzip text responses from the static file service when the client accepts
+/// it and no pre-compressed sibling was served.
+async fn compress_static(request: Request, next: Next) -> Response {
+    let gzip_ok = accepts_gzip(request.headers());
+    let response = next.run(request).await;
+
+    let compressible = response.status() == StatusCode::OK
+        && !response.headers().contains_key(header::CONTENT_ENCODING)
+        && is_compressible(response.headers());
+    if !compressible {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    let len = parts
+        .headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if !gzip_ok || !len.is_some_and(|n| (MIN_COMPRESS_BYTES..=MAX_COMPRESS_BYTES).contains(&n)) {
+        return Response::from_parts(parts, body);
     }
+
+    let bytes = match axum::body::to_bytes(body, MAX_COMPRESS_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer static response for compression: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "failed to read file").into_response();
+        }
+    };
+    let mut encoder = GzEncoder::new(Vec::with_capacity(bytes.len() / 2), Compression::default());
+    // Writing into a Vec cannot fail.
+    let _ = encoder.write_all(&bytes);
+    let compressed = encoder.finish().unwrap_or_default();
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::ACCEPT_RANGES);
+    parts
+        .headers
+        .insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    Response::from_parts(parts, Body::from(compressed))
+}
+
+/// Whether `Accept-Encoding` admits gzip (explicitly or via `*`) with a
+/// non-zero quality.
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|entry| {
+            let mut params = entry.split(';');
+            let coding = params.next().unwrap_or("").trim();
+            let q = params
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (coding.eq_ignore_ascii_case("gzip") || coding == "*") && q > 0.0
+        })
+}
+
+fn is_compressible(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/javascript" | "application/json" | "application/wasm" | "image/svg+xml"
+        )
+}
+
+async fn healthz_handler() -> impl IntoResponse {
+    (StatusCode::OK, "ok")
+}
+
+async fn readyz_handler(State(state): State<AppState>) -> impl IntoResponse {
+    match &state.readiness {
+        Some(probe) if !probe.is_ready() => (StatusCode::SERVICE_UNAVAILABLE, "tick stalled"),
+        _ => (StatusCode::OK, "ready"),
+    }
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        observability::metrics::counters().render_prometheus(),
+    )
 }
 
 async fn ws_upgrade_handler(
     ws: WebSocketUpgrade,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_ws_connection(socket, state))
+    headers: HeaderMap,
+) -> axum::response::Response {
+    if let Some(filter) = &state.ip_filter {
+        if !filter.admit(peer, "websocket") {
+            return (StatusCode::FORBIDDEN, "forbidden").into_response();
+        }
+    }
+    let header = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+    let ws = match state
+        .handshake
+        .check(header(header::ORIGIN), header(header::SEC_WEBSOCKET_PROTOCOL))
+    {
+        Ok(Some(protocol)) => ws.protocols([protocol]),
+        Ok(None) => ws,
+        Err(e) => {
+            tracing::info!(%peer, "Refusing WebSocket upgrade: {}", e);
+            let status = StatusCode::from_u16(e.status()).unwrap_or(StatusCode::FORBIDDEN);
+            return (status, e.to_string()).into_response();
+        }
+    };
+    let connection = ConnectionInfo::new(peer);
+    ws.on_upgrade(move |socket| handle_ws_connection(socket, state, connection))
 }
 
-async fn handle_ws_connection(socket: WebSocket, state: AppState) {
+async fn handle_ws_connection(socket: WebSocket, state: AppState, connection: ConnectionInfo) {
     let session_id = SessionId(state.next_session_id.fetch_add(1, Ordering::Relaxed));
-    tracing::info!(?session_id, "New WebSocket connection (axum)");
+    tracing::info!(?session_id, peer_addr = %connection.peer_addr, "New WebSocket connection (axum)");
 
     let (mut ws_writer, mut ws_reader) = socket.split();
+    let (ping_tx, mut ping_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
 
     // Create per-session write channel
     let (write_tx, mut write_rx): (_, SessionWriteRx) = tokio::sync::mpsc::unbounded_channel();
 
     // Register with output router
+    let reject_tx = write_tx.clone();
     let _ = state.register_tx.send(RegisterSession {
         session_id,
         write_tx,
     });
 
     // Notify tick thread of new connection
-    let _ = state.player_tx.send(NetToTick::NewConnection { session_id });
+    let _ = state.player_tx.send(NetToTick::NewConnection {
+        session_id,
+        connection,
+    });
 
-    // Writer task: forward output_router messages as WS text frames
-    let writer_handle = tokio::spawn(async move {
-        while let Some(text) = write_rx.recv().await {
-            if ws_writer.send(Message::Text(text.into())).await.is_err() {
+    // Writer task: forward output_router messages as WS frames
+    let format = DeltaFormat::default();
+    let writer_format = format.clone();
+    let mut writer_handle = tokio::spawn(async move {
+        loop {
+            let msg = tokio::select! {
+                Some(()) = ping_rx.recv() => Message::Ping(Default::default()),
+                frame = write_rx.recv() => match frame {
+                    Some(write) => match writer_format.frame(write) {
+                        WsFrame::Text(text) => Message::Text(text.into()),
+                        WsFrame::Binary(bytes) => Message::Binary(bytes.into()),
+                    },
+                    None => break,
+                },
+            };
+            if ws_writer.send(msg).await.is_err() {
                 break;
             }
         }
+        // Channel closed by a `disconnect` output: queued frames are sent,
+        // now close cleanly so the client's reply ends the reader loop.
+        let _ = ws_writer.close().await;
     });
 
     // Reader loop: parse WS messages and convert to NetToTick
-    while let Some(result) = ws_reader.next().await {
+    let mut rejected = false;
+    let mut beat = state.heartbeat.timer();
+    loop {
+        let result = tokio::select! {
+            next = ws_reader.next() => match next {
+                Some(result) => result,
+                None => break,
+            },
+            timed_out = beat.beat() => {
+                if timed_out {
+                    tracing::info!(?session_id, "WebSocket client stopped answering pings, disconnecting");
+                    break;
+                }
+                let _ = ping_tx.send(());
+                continue;
+            }
+        };
+        beat.alive();
         match result {
-            Ok(Message::Text(text)) => {
-                if let Some(net_msg) =
-                    crate::ws_server::handle_ws_message(session_id, &text)
-                {
+            Ok(Message::Text(text)) => match crate::ws_server::handle_ws_message(session_id, &text, &format) {
+                Ok(net_msg) => {
                     let _ = state.player_tx.send(net_msg);
                 }
-            }
+                Err(e @ ProtocolError::UnsupportedVersion(_)) => {
+                    tracing::info!(?session_id, "Rejecting client: {}", e);
+                    rejected = true;
+                    let _ = reject_tx.send(SessionWrite::Structured(ServerMessage::error(ErrorCode::UnsupportedVersion, e.to_string()).to_json()));
+                    break;
+                }
+                Err(e) => {
+                    tracing::debug!(?session_id, "Invalid client message: {}", e);
+                }
+            },
             Ok(Message::Close(_)) => break,
             Ok(Message::Ping(_)) => {
                 // axum handles pong automatically
@@ -158,13 +399,25 @@ async fn handle_ws_connection(socket: WebSocket, state: AppState) {
     let _ = state.player_tx.send(NetToTick::Disconnected { session_id });
     let _ = state.unregister_tx.send(session_id);
 
-    writer_handle.abort();
+    // Give the writer a moment to flush the rejection error before closing
+    drop(reject_tx);
+    if !rejected
+        || tokio::time::timeout(std::time::Duration::from_millis(500), &mut writer_handle)
+            .await
+            .is_err()
+    {
+        writer_handle.abort();
+    }
     tracing::info!(?session_id, "WebSocket session ended (axum)");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::health::TickHeartbeat;
+    use crate::ip_filter::IpFilter;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     #[test]
     fn app_state_is_clone() {
@@ -172,4 +425,250 @@ mod tests {
         fn assert_clone<T: Clone>() {}
         assert_clone::<AppState>();
     }
+
+    /// Start a web server on a random port; returns its address and the
+    /// shutdown sender that keeps it alive.
+    async fn start_server(
+        readiness: Option<ReadinessProbe>,
+        ip_filter: Option<IpFilterHandle>,
+    ) -> (std::net::SocketAddr, tokio::sync::watch::Sender<bool>) {
+        start_server_with_static(None, readiness, ip_filter).await
+    }
+
+    async fn start_server_with_static(
+        static_dir: Option<PathBuf>,
+        readiness: Option<ReadinessProbe>,
+        ip_filter: Option<IpFilterHandle>,
+    ) -> (std::net::SocketAddr, tokio::sync::watch::Sender<bool>) {
+        start_server_full(static_dir, readiness, ip_filter, WsHandshake::default(), Heartbeat::default())
+            .await
+    }
+
+    async fn start_server_full(
+        static_dir: Option<PathBuf>,
+        readiness: Option<ReadinessProbe>,
+        ip_filter: Option<IpFilterHandle>,
+        handshake: WsHandshake,
+        heartbeat: Heartbeat,
+    ) -> (std::net::SocketAddr, tokio::sync::watch::Sender<bool>) {
+        let (player_tx, _player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (register_tx, _register_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        tokio::spawn(run_web_server_with_shutdown(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            static_dir,
+            shutdown_rx,
+            readiness,
+            ip_filter,
+            handshake,
+            heartbeat,
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        (addr, shutdown_tx)
+    }
+
+    /// Minimal HTTP/1.1 GET returning the status line.
+    async fn http_get_status(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            path, addr
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn health_endpoints_when_ticking() {
+        let heartbeat = TickHeartbeat::new();
+        heartbeat.beat();
+        let probe = ReadinessProbe::new(heartbeat, Duration::from_secs(5));
+        let (addr, _shutdown) = start_server(Some(probe), None).await;
+
+        assert!(http_get_status(addr, "/healthz").await.contains("200"));
+        assert!(http_get_status(addr, "/readyz").await.contains("200"));
+    }
+
+    #[tokio::test]
+    async fn readyz_unavailable_when_tick_stalled() {
+        let heartbeat = TickHeartbeat::new();
+        heartbeat.beat();
+        let probe = ReadinessProbe::new(heartbeat.clone(), Duration::from_millis(50));
+        let (addr, _shutdown) = start_server(Some(probe), None).await;
+
+        // No beats since startup: the heartbeat is now older than the threshold.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(http_get_status(addr, "/healthz").await.contains("200"));
+        assert!(http_get_status(addr, "/readyz").await.contains("503"));
+
+        // Tick thread recovers
+        heartbeat.beat();
+        assert!(http_get_status(addr, "/readyz").await.contains("200"));
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_serves_counters() {
+        observability::metrics::record_script_error("on_tick(web_server_test)");
+        let (addr, _shutdown) = start_server(None, None).await;
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET /metrics HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", addr);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("# TYPE script_errors_total counter"));
+        assert!(response.contains("script_errors_total{hook=\"on_tick(web_server_test)\"}"));
+    }
+
+    #[tokio::test]
+    async fn readyz_unavailable_before_first_tick() {
+        let probe = ReadinessProbe::new(TickHeartbeat::new(), Duration::from_secs(5));
+        let (addr, _shutdown) = start_server(Some(probe), None).await;
+        assert!(http_get_status(addr, "/readyz").await.contains("503"));
+    }
+
+    /// WebSocket upgrade request returning the status line.
+    async fn ws_upgrade_status(addr: std::net::SocketAddr) -> String {
+        ws_upgrade_response(addr, "")
+            .await
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// WebSocket upgrade request with extra header lines; returns the
+    /// response head.
+    async fn ws_upgrade_response(addr: std::net::SocketAddr, extra: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET /ws HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+             Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n{}\r\n",
+            addr, extra
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut buf = [0u8; 512];
+        let n = stream.read(&mut buf).await.unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_string()
+    }
+
+    #[tokio::test]
+    async fn ws_upgrade_checks_origin_and_subprotocol() {
+        use crate::ws_server::GAME_SUBPROTOCOL;
+
+        let handshake = WsHandshake::new(vec!["https://play.example.com".to_string()]);
+        let (addr, _shutdown) = start_server_full(None, None, None, handshake, Heartbeat::default()).await;
+
+        let accepted = ws_upgrade_response(
+            addr,
+            &format!(
+                "Origin: https://play.example.com\r\nSec-WebSocket-Protocol: {}\r\n",
+                GAME_SUBPROTOCOL
+            ),
+        )
+        .await
+        .to_ascii_lowercase();
+        assert!(accepted.starts_with("http/1.1 101"), "{}", accepted);
+        assert!(accepted.contains(&format!("sec-websocket-protocol: {}", GAME_SUBPROTOCOL)));
+
+        let refused = ws_upgrade_response(addr, "Origin: https://evil.example\r\n").await;
+        assert!(refused.starts_with("HTTP/1.1 403"), "{}", refused);
+        let refused = ws_upgrade_response(addr, "Sec-WebSocket-Protocol: chat\r\n").await;
+        assert!(refused.starts_with("HTTP/1.1 400"), "{}", refused);
+    }
+
+    #[tokio::test]
+    async fn ws_upgrade_respects_ip_filter() {
+        let denied = IpFilterHandle::new(IpFilter::from_lists(&[], &["127.0.0.0/8"]).unwrap());
+        let (addr, _shutdown) = start_server(None, Some(denied.clone())).await;
+        assert!(ws_upgrade_status(addr).await.contains("403"));
+        // Non-WebSocket routes are unaffected
+        assert!(http_get_status(addr, "/healthz").await.contains("200"));
+
+        // Hot swap to an allow list containing loopback
+        denied.replace(IpFilter::from_lists(&["127.0.0.1"], &[]).unwrap());
+        assert!(ws_upgrade_status(addr).await.contains("101"));
+    }
+
+    /// GET with optional extra header lines; returns (lowercased header
+    /// block, body bytes).
+    async fn http_get(addr: std::net::SocketAddr, path: &str, extra: &str) -> (String, Vec<u8>) {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\n{}Connection: close\r\n\r\n",
+            path, addr, extra
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&response[..split]).to_lowercase();
+        (head, response[split + 4..].to_vec())
+    }
+
+    #[test]
+    fn accept_encoding_parsing() {
+        let headers = |v: &str| {
+            let mut h = HeaderMap::new();
+            h.insert(header::ACCEPT_ENCODING, HeaderValue::from_str(v).unwrap());
+            h
+        };
+        assert!(accepts_gzip(&headers("gzip, deflate, br")));
+        assert!(accepts_gzip(&headers("br;q=1.0, GZIP;q=0.5")));
+        assert!(accepts_gzip(&headers("*")));
+        assert!(!accepts_gzip(&headers("gzip;q=0")));
+        assert!(!accepts_gzip(&headers("br, identity")));
+        assert!(!accepts_gzip(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn static_assets_negotiate_gzip() {
+        let dir = std::env::temp_dir().join(format!("web_static_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = "console.log('grid client');\n".repeat(100);
+        std::fs::write(dir.join("app.js"), &script).unwrap();
+        std::fs::write(dir.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(dir.join("style.css"), "body { margin: 0 }\n".repeat(50)).unwrap();
+        std::fs::write(dir.join("style.css.gz"), b"precompressed").unwrap();
+
+        let (addr, _shutdown) = start_server_with_static(Some(dir.clone()), None, None).await;
+
+        // Without Accept-Encoding: plain body
+        let (head, body) = http_get(addr, "/app.js", "").await;
+        assert!(head.contains("200"), "{}", head);
+        assert!(!head.contains("content-encoding"), "{}", head);
+        assert!(head.contains("vary: accept-encoding"), "{}", head);
+        assert_eq!(body, script.as_bytes());
+
+        // With gzip: compressed on the fly, decodes to the original
+        let (head, body) = http_get(addr, "/app.js", "Accept-Encoding: gzip\r\n").await;
+        assert!(head.contains("content-encoding: gzip"), "{}", head);
+        assert!(body.len() < script.len());
+        let mut decoded = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(&body[..]), &mut decoded).unwrap();
+        assert_eq!(decoded, script);
+
+        // A pre-compressed sibling is served verbatim
+        let (head, body) = http_get(addr, "/style.css", "Accept-Encoding: gzip\r\n").await;
+        assert!(head.contains("content-encoding: gzip"), "{}", head);
+        assert_eq!(body, b"precompressed");
+
+        // Small files are not worth compressing
+        let (head, _) = http_get(addr, "/index.html", "Accept-Encoding: gzip\r\n").await;
+        assert!(!head.contains("content-encoding"), "{}", head);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
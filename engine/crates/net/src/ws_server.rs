@@ -1,30 +1,242 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
-use session::SessionId;
+use session::{ConnectionInfo, SessionId};
 use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{HeaderValue, StatusCode};
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::channels::{
-    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWriteRx, UnregisterTx,
+    NetToTick, PlayerTx, RegisterSession, RegisterTx, SessionWrite, SessionWriteRx, UnregisterTx,
+};
+use crate::ip_filter::IpFilterHandle;
+use crate::protocol::{
+    ClientMessage, DeltaEncoding, ErrorCode, ProtocolError, ServerMessage,
 };
-use crate::protocol::ClientMessage;
 
 /// WebSocket session IDs start at 1_000_000 to avoid collision with Telnet sessions.
 static NEXT_WS_SESSION_ID: AtomicU64 = AtomicU64::new(1_000_000);
 
+/// A session's negotiated `DeltaEncoding`, shared between the reader (which
+/// sees `connect`) and the writer task. JSON until the client asks otherwise.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DeltaFormat(Arc<AtomicBool>);
+
+/// One outgoing WebSocket frame, independent of the WebSocket library.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum WsFrame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl DeltaFormat {
+    fn set(&self, encoding: DeltaEncoding) {
+        self.0
+            .store(encoding == DeltaEncoding::Binary, Ordering::Relaxed);
+    }
+
+    /// Frame one write for this session: deltas go out as their binary
+    /// encoding in binary mode, everything else is sent as JSON text.
+    pub(crate) fn frame(&self, write: SessionWrite) -> WsFrame {
+        match write {
+            SessionWrite::Delta { binary, .. } if self.0.load(Ordering::Relaxed) => WsFrame::Binary(binary),
+            // Text and structured frames are both JSON on this transport.
+            SessionWrite::Text(json) | SessionWrite::Structured(json) | SessionWrite::Delta { json, .. } => {
+                WsFrame::Text(json)
+            }
+        }
+    }
+}
+
+/// `Sec-WebSocket-Protocol` name of the game's JSON protocol.
+pub const GAME_SUBPROTOCOL: &str = "project-g.v1";
+
+/// Why a WebSocket upgrade was refused.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandshakeError {
+    #[error("origin not allowed: {0}")]
+    OriginNotAllowed(String),
+
+    #[error("no supported subprotocol in '{0}' (server speaks {GAME_SUBPROTOCOL})")]
+    UnsupportedSubprotocol(String),
+}
+
+/// Upgrade-time checks shared by `run_ws_server` and the axum `/ws` route.
+#[derive(Debug, Clone, Default)]
+pub struct WsHandshake {
+    /// Browser origins allowed to connect, as `scheme://host[:port]`
+    /// (case-insensitive). Empty allows every origin. Requests without an
+    /// `Origin` header come from non-browser clients and are not checked.
+    pub allowed_origins: Vec<String>,
+}
+
+impl WsHandshake {
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Self { allowed_origins }
+    }
+
+    /// Validate the upgrade's `Origin` and `Sec-WebSocket-Protocol` headers.
+    /// Returns the subprotocol to echo back: `Some` if the client offered
+    /// ours, `None` if it offered none (older clients).
+    pub fn check(
+        &self,
+        origin: Option<&str>,
+        protocols: Option<&str>,
+    ) -> Result<Option<&'static str>, HandshakeError> {
+        if let Some(origin) = origin {
+            let origin = origin.trim().trim_end_matches('/');
+            if !self.allowed_origins.is_empty()
+                && !self
+                    .allowed_origins
+                    .iter()
+                    .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+            {
+                return Err(HandshakeError::OriginNotAllowed(origin.to_string()));
+            }
+        }
+        match protocols {
+            None => Ok(None),
+            Some(offered) if offered.split(',').any(|p| p.trim() == GAME_SUBPROTOCOL) => {
+                Ok(Some(GAME_SUBPROTOCOL))
+            }
+            Some(offered) => Err(HandshakeError::UnsupportedSubprotocol(offered.to_string())),
+        }
+    }
+}
+
+impl HandshakeError {
+    /// HTTP status to refuse the upgrade with.
+    pub fn status(&self) -> u16 {
+        match self {
+            HandshakeError::OriginNotAllowed(_) => 403,
+            HandshakeError::UnsupportedSubprotocol(_) => 400,
+        }
+    }
+}
+
+/// Server-side keepalive: a Ping is sent every `interval`, and a client that
+/// sends nothing back (no Pong, no message) for `max_missed` consecutive
+/// intervals is disconnected. A zero interval disables it. A `max_missed` of
+/// zero counts as one, so a client always gets a ping before it is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub max_missed: u32,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            max_missed: 2,
+        }
+    }
+}
+
+impl Heartbeat {
+    pub fn new(interval: Duration, max_missed: u32) -> Self {
+        Self { interval, max_missed }
+    }
+
+    /// Never ping and never time out.
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+
+    pub(crate) fn timer(&self) -> HeartbeatTimer {
+        let interval = self.is_enabled().then(|| {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + self.interval, self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+        HeartbeatTimer {
+            interval,
+            max_missed: self.max_missed.max(1),
+            missed: 0,
+        }
+    }
+}
+
+/// Per-session heartbeat state, polled by the reader loop next to the socket.
+pub(crate) struct HeartbeatTimer {
+    interval: Option<tokio::time::Interval>,
+    max_missed: u32,
+    missed: u32,
+}
+
+impl HeartbeatTimer {
+    /// Wait for the next heartbeat. Returns `true` when the client has let
+    /// `max_missed` pings go unanswered; otherwise the caller sends a Ping.
+    /// Never resolves when the heartbeat is disabled.
+    pub(crate) async fn beat(&mut self) -> bool {
+        match &mut self.interval {
+            Some(interval) => {
+                interval.tick().await;
+            }
+            None => std::future::pending::<()>().await,
+        }
+        if self.missed >= self.max_missed {
+            return true;
+        }
+        self.missed += 1;
+        false
+    }
+
+    /// Any frame from the client proves it is alive.
+    pub(crate) fn alive(&mut self) {
+        self.missed = 0;
+    }
+}
+
 /// Run the WebSocket server, accepting connections and spawning per-session tasks.
 pub async fn run_ws_server(
     addr: String,
     player_tx: PlayerTx,
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
+) -> Result<(), std::io::Error> {
+    run_ws_server_with_filter(
+        addr,
+        player_tx,
+        register_tx,
+        unregister_tx,
+        None,
+        WsHandshake::default(),
+        Heartbeat::default(),
+    )
+    .await
+}
+
+/// Like `run_ws_server`, but closes connections refused by `ip_filter`
+/// before the handshake and before a session id is assigned, and refuses
+/// upgrades that fail `handshake`. Sessions are kept alive by `heartbeat`.
+pub async fn run_ws_server_with_filter(
+    addr: String,
+    player_tx: PlayerTx,
+    register_tx: RegisterTx,
+    unregister_tx: UnregisterTx,
+    ip_filter: Option<IpFilterHandle>,
+    handshake: WsHandshake,
+    heartbeat: Heartbeat,
 ) -> Result<(), std::io::Error> {
     let listener = TcpListener::bind(&addr).await?;
     tracing::info!("WebSocket server listening on {}", addr);
 
     loop {
         let (stream, peer_addr) = listener.accept().await?;
+        if let Some(filter) = &ip_filter {
+            if !filter.admit(peer_addr, "websocket") {
+                continue;
+            }
+        }
         let session_id = SessionId(NEXT_WS_SESSION_ID.fetch_add(1, Ordering::Relaxed));
 
         tracing::info!(?session_id, %peer_addr, "New WebSocket connection");
@@ -33,11 +245,42 @@ pub async fn run_ws_server(
         let register_tx = register_tx.clone();
         let unregister_tx = unregister_tx.clone();
 
+        let connection = ConnectionInfo::new(peer_addr);
+        let handshake = handshake.clone();
+
         tokio::spawn(async move {
-            match tokio_tungstenite::accept_async(stream).await {
+            // The error type is fixed by tungstenite's `Callback` trait.
+            #[allow(clippy::result_large_err)]
+            let callback = |req: &Request, mut resp: Response| -> Result<Response, ErrorResponse> {
+                let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+                match handshake.check(header("origin"), header("sec-websocket-protocol")) {
+                    Ok(Some(protocol)) => {
+                        resp.headers_mut()
+                            .insert("sec-websocket-protocol", HeaderValue::from_static(protocol));
+                        Ok(resp)
+                    }
+                    Ok(None) => Ok(resp),
+                    Err(e) => {
+                        tracing::info!(?session_id, "Refusing WebSocket upgrade: {}", e);
+                        let mut refusal = ErrorResponse::new(Some(e.to_string()));
+                        *refusal.status_mut() =
+                            StatusCode::from_u16(e.status()).unwrap_or(StatusCode::FORBIDDEN);
+                        Err(refusal)
+                    }
+                }
+            };
+            match tokio_tungstenite::accept_hdr_async(stream, callback).await {
                 Ok(ws_stream) => {
-                    handle_ws_session(ws_stream, session_id, player_tx, register_tx, unregister_tx)
-                        .await;
+                    handle_ws_session(
+                        ws_stream,
+                        session_id,
+                        connection,
+                        player_tx,
+                        register_tx,
+                        unregister_tx,
+                        heartbeat,
+                    )
+                    .await;
                 }
                 Err(e) => {
                     tracing::warn!(?session_id, "WebSocket handshake failed: {}", e);
@@ -50,41 +293,89 @@ pub async fn run_ws_server(
 async fn handle_ws_session(
     ws_stream: tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
     session_id: SessionId,
+    connection: ConnectionInfo,
     player_tx: PlayerTx,
     register_tx: RegisterTx,
     unregister_tx: UnregisterTx,
+    heartbeat: Heartbeat,
 ) {
     let (mut ws_writer, mut ws_reader) = ws_stream.split();
+    let (ping_tx, mut ping_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
 
     // Create per-session write channel
     let (write_tx, mut write_rx): (_, SessionWriteRx) = tokio::sync::mpsc::unbounded_channel();
 
     // Register with output router
+    let reject_tx = write_tx.clone();
     let _ = register_tx.send(RegisterSession {
         session_id,
         write_tx,
     });
 
     // Notify tick thread of new connection
-    let _ = player_tx.send(NetToTick::NewConnection { session_id });
+    let _ = player_tx.send(NetToTick::NewConnection {
+        session_id,
+        connection,
+    });
 
-    // Writer task: forward output_router messages as WS text frames
-    let writer_handle = tokio::spawn(async move {
-        while let Some(text) = write_rx.recv().await {
-            if ws_writer.send(Message::Text(text.into())).await.is_err() {
+    // Writer task: forward output_router messages as WS frames
+    let format = DeltaFormat::default();
+    let writer_format = format.clone();
+    let mut writer_handle = tokio::spawn(async move {
+        loop {
+            let msg = tokio::select! {
+                Some(()) = ping_rx.recv() => Message::Ping(Vec::new()),
+                frame = write_rx.recv() => match frame {
+                    Some(write) => match writer_format.frame(write) {
+                        WsFrame::Text(text) => Message::Text(text),
+                        WsFrame::Binary(bytes) => Message::Binary(bytes),
+                    },
+                    None => break,
+                },
+            };
+            if ws_writer.send(msg).await.is_err() {
                 break;
             }
         }
+        // Channel closed by a `disconnect` output: queued frames are sent,
+        // now close cleanly so the client's reply ends the reader loop.
+        let _ = ws_writer.close().await;
     });
 
     // Reader loop: parse WS messages and convert to NetToTick
-    while let Some(result) = ws_reader.next().await {
+    let mut rejected = false;
+    let mut beat = heartbeat.timer();
+    loop {
+        let result = tokio::select! {
+            next = ws_reader.next() => match next {
+                Some(result) => result,
+                None => break,
+            },
+            timed_out = beat.beat() => {
+                if timed_out {
+                    tracing::info!(?session_id, "WebSocket client stopped answering pings, disconnecting");
+                    break;
+                }
+                let _ = ping_tx.send(());
+                continue;
+            }
+        };
+        beat.alive();
         match result {
-            Ok(Message::Text(text)) => {
-                if let Some(net_msg) = handle_ws_message(session_id, &text) {
+            Ok(Message::Text(text)) => match handle_ws_message(session_id, &text, &format) {
+                Ok(net_msg) => {
                     let _ = player_tx.send(net_msg);
                 }
-            }
+                Err(e @ ProtocolError::UnsupportedVersion(_)) => {
+                    tracing::info!(?session_id, "Rejecting client: {}", e);
+                    rejected = true;
+                    let _ = reject_tx.send(SessionWrite::Structured(ServerMessage::error(ErrorCode::UnsupportedVersion, e.to_string()).to_json()));
+                    break;
+                }
+                Err(e) => {
+                    tracing::debug!(?session_id, "Invalid client message: {}", e);
+                }
+            },
             Ok(Message::Close(_)) => break,
             Ok(Message::Ping(_)) => {
                 // tungstenite handles pong automatically
@@ -101,36 +392,46 @@ async fn handle_ws_session(
     let _ = player_tx.send(NetToTick::Disconnected { session_id });
     let _ = unregister_tx.send(session_id);
 
-    writer_handle.abort();
+    // Give the writer a moment to flush the rejection error before closing
+    drop(reject_tx);
+    if !rejected
+        || tokio::time::timeout(std::time::Duration::from_millis(500), &mut writer_handle)
+            .await
+            .is_err()
+    {
+        writer_handle.abort();
+    }
     tracing::info!(?session_id, "WebSocket session ended");
 }
 
-/// Parse a WebSocket text message into a NetToTick message.
-pub(crate) fn handle_ws_message(session_id: SessionId, text: &str) -> Option<NetToTick> {
-    let msg: ClientMessage = match serde_json::from_str(text) {
-        Ok(m) => m,
-        Err(e) => {
-            tracing::debug!(?session_id, "Invalid client message: {}", e);
-            return None;
-        }
-    };
+/// Parse a versioned WebSocket text message into a NetToTick message.
+/// A `connect` also records the session's requested delta encoding.
+pub(crate) fn handle_ws_message(
+    session_id: SessionId,
+    text: &str,
+    format: &DeltaFormat,
+) -> Result<NetToTick, ProtocolError> {
+    let msg = ClientMessage::decode(text)?;
 
-    match msg {
-        ClientMessage::Connect { name } => Some(NetToTick::PlayerInput {
-            session_id,
-            line: name,
-        }),
-        ClientMessage::Move { dx, dy } => Some(NetToTick::PlayerInput {
+    Ok(match msg {
+        ClientMessage::Connect { name, encoding } => {
+            format.set(encoding);
+            NetToTick::PlayerInput {
+                session_id,
+                line: name,
+            }
+        }
+        ClientMessage::Move { dx, dy } => NetToTick::PlayerInput {
             session_id,
             line: format!("__grid_move {} {}", dx, dy),
-        }),
+        },
         ClientMessage::Action { name, args } => {
             let line = if let Some(a) = args {
                 format!("{} {}", name, a)
             } else {
                 name
             };
-            Some(NetToTick::PlayerInput { session_id, line })
+            NetToTick::PlayerInput { session_id, line }
         }
         ClientMessage::Ping => {
             // Pong is handled at the protocol level by sending a ServerMessage::Pong
@@ -139,12 +440,16 @@ pub(crate) fn handle_ws_message(session_id: SessionId, text: &str) -> Option<Net
             // Instead, we return None and the ws_server could send pong directly.
             // However, our architecture routes everything through output_router,
             // so we use a special line prefix.
-            Some(NetToTick::PlayerInput {
+            NetToTick::PlayerInput {
                 session_id,
                 line: "__ping".to_string(),
-            })
+            }
         }
-    }
+        ClientMessage::Resync => NetToTick::PlayerInput {
+            session_id,
+            line: "__resync".to_string(),
+        },
+    })
 }
 
 #[cfg(test)]
@@ -154,9 +459,9 @@ mod tests {
     #[test]
     fn handle_connect_message() {
         let sid = SessionId(1_000_000);
-        let msg = handle_ws_message(sid, r#"{"type":"connect","name":"Alice"}"#);
+        let msg = handle_ws_message(sid, r#"{"type":"connect","name":"Alice"}"#, &DeltaFormat::default());
         match msg {
-            Some(NetToTick::PlayerInput { session_id, line }) => {
+            Ok(NetToTick::PlayerInput { session_id, line }) => {
                 assert_eq!(session_id, sid);
                 assert_eq!(line, "Alice");
             }
@@ -167,9 +472,9 @@ mod tests {
     #[test]
     fn handle_move_message() {
         let sid = SessionId(1_000_000);
-        let msg = handle_ws_message(sid, r#"{"type":"move","dx":1,"dy":0}"#);
+        let msg = handle_ws_message(sid, r#"{"type":"move","dx":1,"dy":0}"#, &DeltaFormat::default());
         match msg {
-            Some(NetToTick::PlayerInput { session_id, line }) => {
+            Ok(NetToTick::PlayerInput { session_id, line }) => {
                 assert_eq!(session_id, sid);
                 assert_eq!(line, "__grid_move 1 0");
             }
@@ -180,9 +485,9 @@ mod tests {
     #[test]
     fn handle_action_message() {
         let sid = SessionId(1_000_001);
-        let msg = handle_ws_message(sid, r#"{"type":"action","name":"attack","args":"goblin"}"#);
+        let msg = handle_ws_message(sid, r#"{"type":"action","name":"attack","args":"goblin"}"#, &DeltaFormat::default());
         match msg {
-            Some(NetToTick::PlayerInput { session_id, line }) => {
+            Ok(NetToTick::PlayerInput { session_id, line }) => {
                 assert_eq!(session_id, sid);
                 assert_eq!(line, "attack goblin");
             }
@@ -193,19 +498,297 @@ mod tests {
     #[test]
     fn handle_ping_message() {
         let sid = SessionId(1_000_000);
-        let msg = handle_ws_message(sid, r#"{"type":"ping"}"#);
+        let msg = handle_ws_message(sid, r#"{"type":"ping"}"#, &DeltaFormat::default());
         match msg {
-            Some(NetToTick::PlayerInput { line, .. }) => {
+            Ok(NetToTick::PlayerInput { line, .. }) => {
                 assert_eq!(line, "__ping");
             }
             _ => panic!("Expected PlayerInput with __ping"),
         }
     }
 
+    #[test]
+    fn handle_resync_message() {
+        let sid = SessionId(1_000_000);
+        let msg = handle_ws_message(sid, r#"{"type":"resync"}"#, &DeltaFormat::default());
+        match msg {
+            Ok(NetToTick::PlayerInput { line, .. }) => {
+                assert_eq!(line, "__resync");
+            }
+            _ => panic!("Expected PlayerInput with __resync"),
+        }
+    }
+
     #[test]
     fn handle_invalid_json() {
         let sid = SessionId(1_000_000);
-        let msg = handle_ws_message(sid, "not json");
-        assert!(msg.is_none());
+        let msg = handle_ws_message(sid, "not json", &DeltaFormat::default());
+        assert!(matches!(msg, Err(ProtocolError::Invalid(_))));
+    }
+
+    #[test]
+    fn handle_unsupported_version() {
+        let sid = SessionId(1_000_000);
+        let msg = handle_ws_message(sid, r#"{"v":2,"type":"connect","name":"Alice"}"#, &DeltaFormat::default());
+        assert!(matches!(msg, Err(ProtocolError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn frame_picks_the_negotiated_delta_encoding() {
+        let delta = || SessionWrite::Delta {
+            json: "{}".to_string(),
+            binary: vec![1, 2, 3],
+        };
+        let format = DeltaFormat::default();
+        assert_eq!(format.frame(delta()), WsFrame::Text("{}".to_string()));
+
+        format.set(DeltaEncoding::Binary);
+        assert_eq!(format.frame(delta()), WsFrame::Binary(vec![1, 2, 3]));
+        // Only deltas switch encoding; other JSON stays text without being parsed
+        assert_eq!(
+            format.frame(SessionWrite::Structured("{}".to_string())),
+            WsFrame::Text("{}".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn binary_client_receives_state_delta_as_binary_frame() {
+        use crate::protocol::{EntityMovedWire, EntityWire, StateDeltaFrame};
+        use session::SessionOutput;
+
+        let (player_tx, mut player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (register_tx, mut register_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        tokio::spawn(run_ws_server(addr.to_string(), player_tx, register_tx, unregister_tx));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let delta = ServerMessage::StateDelta {
+            tick: 9,
+            server_time_ms: 450,
+            entered: vec![EntityWire {
+                id: 1,
+                x: 4,
+                y: -2,
+                name: Some("Alice".to_string()),
+                is_self: true,
+                facing: "south",
+                sprite: None,
+                color: Some("#fff".to_string()),
+            }],
+            moved: vec![EntityMovedWire { id: 2, x: 5, y: 5, facing: "west" }],
+            left: vec![3],
+        };
+
+        let url = format!("ws://{}", addr);
+        let mut received = Vec::new();
+        let mut clients = Vec::new();
+        for connect in [
+            r#"{"type":"connect","name":"Alice"}"#,
+            r#"{"type":"connect","name":"Bob","encoding":"binary"}"#,
+        ] {
+            let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+            let reg = register_rx.recv().await.unwrap();
+            assert!(matches!(player_rx.recv().await, Some(NetToTick::NewConnection { .. })));
+            ws.send(Message::Text(connect.to_string())).await.unwrap();
+            assert!(matches!(player_rx.recv().await, Some(NetToTick::PlayerInput { .. })));
+
+            let output = SessionOutput::new(reg.session_id, delta.to_json())
+                .with_binary(delta.to_binary().unwrap());
+            reg.write_tx.send(output.into()).unwrap();
+            reg.write_tx.send(SessionWrite::Structured(ServerMessage::Pong.to_json())).unwrap();
+            let frame = ws.next().await.unwrap().unwrap();
+            // Non-delta messages stay JSON text in either mode.
+            let pong = ws.next().await.unwrap().unwrap();
+            assert_eq!(pong, Message::Text(ServerMessage::Pong.to_json()));
+            received.push(frame);
+            clients.push(ws);
+        }
+
+        let Message::Text(json) = &received[0] else {
+            panic!("JSON client got {:?}", received[0]);
+        };
+        let Message::Binary(bytes) = &received[1] else {
+            panic!("binary client got {:?}", received[1]);
+        };
+        let from_json = StateDeltaFrame::from_json(json).unwrap();
+        assert_eq!(StateDeltaFrame::decode(bytes).unwrap(), from_json);
+        assert_eq!(from_json.moved[0].facing, "west");
+    }
+
+    #[test]
+    fn handshake_checks_origin_and_subprotocol() {
+        let open = WsHandshake::default();
+        assert_eq!(open.check(Some("https://evil.example"), None), Ok(None));
+
+        let hs = WsHandshake::new(vec!["https://play.example.com/".to_string()]);
+        assert_eq!(hs.check(Some("https://PLAY.example.com"), None), Ok(None));
+        assert_eq!(hs.check(None, None), Ok(None));
+        assert_eq!(
+            hs.check(Some("https://play.example.com"), Some("chat, project-g.v1")),
+            Ok(Some(GAME_SUBPROTOCOL))
+        );
+        let err = hs.check(Some("https://evil.example"), Some(GAME_SUBPROTOCOL)).unwrap_err();
+        assert_eq!(err, HandshakeError::OriginNotAllowed("https://evil.example".to_string()));
+        assert_eq!(err.status(), 403);
+        let err = hs.check(None, Some("chat")).unwrap_err();
+        assert!(matches!(err, HandshakeError::UnsupportedSubprotocol(_)));
+        assert_eq!(err.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn upgrade_negotiates_subprotocol_and_refuses_foreign_origin() {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+        use tokio_tungstenite::tungstenite::Error as WsError;
+
+        let (player_tx, _player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (register_tx, _register_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        tokio::spawn(run_ws_server_with_filter(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            None,
+            WsHandshake::new(vec!["https://play.example.com".to_string()]),
+            Heartbeat::default(),
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let request = |origin: &str| {
+            let mut req = format!("ws://{}", addr).into_client_request().unwrap();
+            req.headers_mut().insert("origin", origin.parse().unwrap());
+            req.headers_mut()
+                .insert("sec-websocket-protocol", GAME_SUBPROTOCOL.parse().unwrap());
+            req
+        };
+
+        let (_ws, resp) = tokio_tungstenite::connect_async(request("https://play.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers().get("sec-websocket-protocol").unwrap(),
+            GAME_SUBPROTOCOL
+        );
+
+        match tokio_tungstenite::connect_async(request("https://evil.example")).await {
+            Err(WsError::Http(resp)) => assert_eq!(resp.status(), StatusCode::FORBIDDEN),
+            other => panic!("expected 403, got {:?}", other.map(|(_, r)| r.status())),
+        }
+    }
+
+    #[tokio::test]
+    async fn ip_filter_refuses_before_handshake() {
+        use crate::ip_filter::IpFilter;
+
+        let (player_tx, mut player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (register_tx, _register_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (unregister_tx, _unregister_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let filter = IpFilterHandle::new(IpFilter::from_lists(&[], &["127.0.0.1/32"]).unwrap());
+        tokio::spawn(run_ws_server_with_filter(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            Some(filter.clone()),
+            WsHandshake::default(),
+            Heartbeat::default(),
+        ));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let url = format!("ws://{}", addr);
+        assert!(tokio_tungstenite::connect_async(&url).await.is_err());
+        assert!(player_rx.try_recv().is_err());
+
+        filter.replace(IpFilter::default());
+        let (_ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let msg = player_rx.recv().await.unwrap();
+        assert!(matches!(msg, NetToTick::NewConnection { .. }));
+    }
+
+    #[tokio::test]
+    async fn silent_client_is_disconnected_after_missed_pings() {
+        let (player_tx, mut player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (register_tx, _register_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (unregister_tx, mut unregister_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        tokio::spawn(run_ws_server_with_filter(
+            addr.to_string(),
+            player_tx,
+            register_tx,
+            unregister_tx,
+            None,
+            WsHandshake::default(),
+            Heartbeat::new(Duration::from_millis(50), 2),
+        ));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let url = format!("ws://{}", addr);
+
+        // Reading the stream makes tungstenite answer each Ping with a Pong.
+        let (mut responsive, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let Some(NetToTick::NewConnection { session_id: kept, .. }) = player_rx.recv().await else {
+            panic!("expected NewConnection");
+        };
+        let reader = tokio::spawn(async move {
+            let mut pings = 0;
+            while let Some(Ok(msg)) = responsive.next().await {
+                if matches!(msg, Message::Ping(_)) {
+                    pings += 1;
+                }
+            }
+            pings
+        });
+
+        // Never polled, so its pings go unanswered.
+        let (_silent, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let Some(NetToTick::NewConnection { session_id: dropped, .. }) = player_rx.recv().await else {
+            panic!("expected NewConnection");
+        };
+
+        let msg = tokio::time::timeout(Duration::from_secs(2), player_rx.recv())
+            .await
+            .expect("silent client was not disconnected");
+        assert!(matches!(msg, Some(NetToTick::Disconnected { session_id }) if session_id == dropped));
+        assert_eq!(unregister_rx.recv().await, Some(dropped));
+
+        // Well past the deadline, the responsive client is still connected.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(player_rx.try_recv().is_err(), "{:?} was dropped", kept);
+        assert!(!reader.is_finished());
+        reader.abort();
+    }
+
+    #[tokio::test]
+    async fn heartbeat_timer_times_out_only_without_traffic() {
+        let mut beat = Heartbeat::new(Duration::from_millis(5), 2).timer();
+        assert!(!beat.beat().await);
+        assert!(!beat.beat().await);
+        beat.alive();
+        assert!(!beat.beat().await);
+        assert!(!beat.beat().await);
+        assert!(beat.beat().await);
+
+        // Zero tolerance still pings once before timing out.
+        let mut strict = Heartbeat::new(Duration::from_millis(5), 0).timer();
+        assert!(!strict.beat().await);
+        assert!(strict.beat().await);
+
+        let mut off = Heartbeat::disabled().timer();
+        assert!(tokio::time::timeout(Duration::from_millis(50), off.beat()).await.is_err());
     }
 }
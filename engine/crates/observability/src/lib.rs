@@ -1,12 +1,76 @@
+pub mod metrics;
+mod profile;
+mod slow_tick;
+
+pub use profile::{TickPhase, TickProfile, TickProfiler};
+pub use slow_tick::{
+    record_plugin_cost, record_script_cost, slow_tick_sampler, slow_ticks, SlowTickSample,
+    SlowTickSampler, MAX_SLOW_TICK_SAMPLES,
+};
+
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{fmt, EnvFilter};
 
+/// Log output format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable `fmt` output.
+    #[default]
+    Text,
+    /// One JSON object per line, for log shippers (ELK, Loki, ...).
+    Json,
+}
+
+impl LogFormat {
+    /// Read the format from `LOG_FORMAT` (`json` or `text`; default text).
+    pub fn from_env() -> Self {
+        std::env::var("LOG_FORMAT")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// Parse a format name, case-insensitively.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Initialize logging, choosing the format from `LOG_FORMAT`.
 pub fn init_logging() {
+    init_logging_with(LogFormat::from_env());
+}
+
+/// Initialize logging with an explicit format. The filter still comes from
+/// `RUST_LOG` (default `info`).
+pub fn init_logging_with(format: LogFormat) {
     let filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info"));
-    fmt()
+    build_subscriber(format, filter, std::io::stdout).init();
+}
+
+fn build_subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = fmt()
         .with_env_filter(filter)
         .with_target(true)
-        .init();
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => Box::new(builder.finish()),
+        LogFormat::Json => Box::new(builder.json().finish()),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -17,11 +81,26 @@ pub struct TickMetrics {
     pub entity_count: usize,
     /// WASM plugin execution time in microseconds (0 if no plugins).
     pub wasm_duration_us: u128,
+    /// True if this tick ran back-to-back (no sleep) to catch up after an overrun.
+    pub compressed: bool,
+    /// Ticks dropped right before this one because the catch-up backlog hit its cap.
+    pub skipped_ticks: u64,
+    /// Plugin component writes dropped because a higher-priority plugin
+    /// wrote the same (entity, component) this tick.
+    pub plugin_conflicts: usize,
 }
 
 impl TickMetrics {
     pub fn log(&self) {
         const TICK_BUDGET_US: u128 = 33_000;
+        if self.skipped_ticks > 0 {
+            tracing::warn!(
+                tick = self.tick_number,
+                skipped = self.skipped_ticks,
+                "tick loop fell behind, dropped {} ticks",
+                self.skipped_ticks
+            );
+        }
         if self.duration_us > TICK_BUDGET_US {
             tracing::warn!(
                 tick = self.tick_number,
@@ -29,6 +108,7 @@ impl TickMetrics {
                 wasm_us = self.wasm_duration_us,
                 commands = self.command_count,
                 entities = self.entity_count,
+                plugin_conflicts = self.plugin_conflicts,
                 "tick exceeded budget ({}us > {}us)",
                 self.duration_us,
                 TICK_BUDGET_US
@@ -40,8 +120,78 @@ impl TickMetrics {
                 wasm_us = self.wasm_duration_us,
                 commands = self.command_count,
                 entities = self.entity_count,
+                plugin_conflicts = self.plugin_conflicts,
+                compressed = self.compressed,
                 "tick completed"
             );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn capture(format: LogFormat) -> String {
+        let buf = Capture::default();
+        let subscriber = build_subscriber(format, EnvFilter::new("info"), buf.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(tick = 7, "tick completed");
+            tracing::debug!("filtered out");
+        });
+        let bytes = buf.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn json_format_emits_json_lines() {
+        let out = capture(LogFormat::Json);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let v: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(v["level"], "INFO");
+        assert_eq!(v["target"], module_path!());
+        assert_eq!(v["fields"]["message"], "tick completed");
+        assert_eq!(v["fields"]["tick"], 7);
+        assert!(v["timestamp"].is_string());
+    }
+
+    #[test]
+    fn text_format_is_not_json() {
+        let out = capture(LogFormat::Text);
+        assert!(out.contains("tick completed"));
+        assert!(serde_json::from_str::<serde_json::Value>(out.trim()).is_err());
+    }
+
+    #[test]
+    fn parse_log_format() {
+        assert_eq!(LogFormat::parse("json"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" JSON "), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+
+/// Lua hook errors, labeled `hook` (e.g. `on_action('look')`).
+pub const SCRIPT_ERRORS_TOTAL: &str = "script_errors_total";
+/// WASM plugin traps other than fuel exhaustion, labeled `plugin`.
+pub const PLUGIN_TRAPS_TOTAL: &str = "plugin_traps_total";
+/// WASM plugin ticks that ran out of fuel, labeled `plugin`.
+pub const PLUGIN_FUEL_EXHAUSTED_TOTAL: &str = "plugin_fuel_exhausted_total";
+
+/// (metric, label name, label value)
+type CounterKey = (&'static str, &'static str, String);
+
+/// Process-wide counters with a single label each, rendered in the
+/// Prometheus text exposition format. Keys are kept in a BTreeMap so the
+/// rendered output is stable.
+#[derive(Debug, Default)]
+pub struct CounterRegistry {
+    counters: Mutex<BTreeMap<CounterKey, u64>>,
+}
+
+impl CounterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increment `metric{label_name="label_value"}` by one.
+    pub fn inc(&self, metric: &'static str, label_name: &'static str, label_value: &str) {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        *counters
+            .entry((metric, label_name, label_value.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// Current value of `metric` for the given label value (0 if never hit).
+    pub fn get(&self, metric: &'static str, label_value: &str) -> u64 {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        counters
+            .iter()
+            .filter(|((m, _, v), _)| *m == metric && v == label_value)
+            .map(|(_, count)| *count)
+            .sum()
+    }
+
+    /// Render all counters in the Prometheus text format.
+    pub fn render_prometheus(&self) -> String {
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        let mut last_metric = "";
+        for ((metric, label_name, label_value), count) in counters.iter() {
+            if *metric != last_metric {
+                let _ = writeln!(out, "# TYPE {} counter", metric);
+                last_metric = metric;
+            }
+            let _ = writeln!(
+                out,
+                "{}{{{}=\"{}\"}} {}",
+                metric,
+                label_name,
+                escape_label(label_value),
+                count
+            );
+        }
+        out
+    }
+}
+
+/// The process-wide registry that engine error paths report into.
+pub fn counters() -> &'static CounterRegistry {
+    static COUNTERS: OnceLock<CounterRegistry> = OnceLock::new();
+    COUNTERS.get_or_init(CounterRegistry::new)
+}
+
+/// Count a Lua hook error.
+pub fn record_script_error(hook: &str) {
+    counters().inc(SCRIPT_ERRORS_TOTAL, "hook", hook);
+}
+
+/// Count a WASM plugin trap.
+pub fn record_plugin_trap(plugin: &str) {
+    counters().inc(PLUGIN_TRAPS_TOTAL, "plugin", plugin);
+}
+
+/// Count a WASM plugin running out of fuel.
+pub fn record_plugin_fuel_exhausted(plugin: &str) {
+    counters().inc(PLUGIN_FUEL_EXHAUSTED_TOTAL, "plugin", plugin);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_increment_per_label() {
+        let registry = CounterRegistry::new();
+        registry.inc(PLUGIN_TRAPS_TOTAL, "plugin", "a");
+        registry.inc(PLUGIN_TRAPS_TOTAL, "plugin", "a");
+        registry.inc(PLUGIN_TRAPS_TOTAL, "plugin", "b");
+        assert_eq!(registry.get(PLUGIN_TRAPS_TOTAL, "a"), 2);
+        assert_eq!(registry.get(PLUGIN_TRAPS_TOTAL, "b"), 1);
+        assert_eq!(registry.get(PLUGIN_TRAPS_TOTAL, "c"), 0);
+        assert_eq!(registry.get(SCRIPT_ERRORS_TOTAL, "a"), 0);
+    }
+
+    #[test]
+    fn render_prometheus_text() {
+        let registry = CounterRegistry::new();
+        registry.inc(SCRIPT_ERRORS_TOTAL, "hook", "on_action('look')");
+        registry.inc(PLUGIN_FUEL_EXHAUSTED_TOTAL, "plugin", "combat");
+        registry.inc(SCRIPT_ERRORS_TOTAL, "hook", "say \"hi\"");
+        assert_eq!(
+            registry.render_prometheus(),
+            "# TYPE plugin_fuel_exhausted_total counter\n\
+             plugin_fuel_exhausted_total{plugin=\"combat\"} 1\n\
+             # TYPE script_errors_total counter\n\
+             script_errors_total{hook=\"on_action('look')\"} 1\n\
+             script_errors_total{hook=\"say \\\"hi\\\"\"} 1\n"
+        );
+    }
+}
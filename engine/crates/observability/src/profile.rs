@@ -0,0 +1,218 @@
+use std::time::{Duration, Instant};
+
+/// Phases of a server tick, timed by the tick thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TickPhase {
+    /// Draining network messages (connects, input, disconnects).
+    Network,
+    /// `TickLoop::step` — WASM plugins and the command stream.
+    EngineStep,
+    /// Game systems / on_action hooks for player input.
+    Systems,
+    /// Admin commands via on_admin hooks.
+    Admin,
+    /// Lua on_tick hooks.
+    ScriptTick,
+    /// Closing sessions that scripts asked to disconnect.
+    Disconnect,
+    /// Sending per-tick state to clients.
+    Broadcast,
+    /// Snapshots, character saves, lingering cleanup.
+    Persistence,
+}
+
+impl TickPhase {
+    pub const ALL: [TickPhase; 8] = [
+        TickPhase::Network,
+        TickPhase::EngineStep,
+        TickPhase::Systems,
+        TickPhase::Admin,
+        TickPhase::ScriptTick,
+        TickPhase::Disconnect,
+        TickPhase::Broadcast,
+        TickPhase::Persistence,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TickPhase::Network => "network",
+            TickPhase::EngineStep => "engine_step",
+            TickPhase::Systems => "systems",
+            TickPhase::Admin => "admin",
+            TickPhase::ScriptTick => "script_tick",
+            TickPhase::Disconnect => "disconnect",
+            TickPhase::Broadcast => "broadcast",
+            TickPhase::Persistence => "persistence",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Per-phase durations for one tick, in microseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TickProfile {
+    pub tick_number: u64,
+    /// Wall time of the whole tick (excluding the end-of-tick sleep).
+    pub total_us: u128,
+    /// Ticks the pacer dropped right before this one to cap its catch-up.
+    pub skipped_ticks: u64,
+    phases_us: [u128; TickPhase::ALL.len()],
+}
+
+impl TickProfile {
+    pub fn new(tick_number: u64) -> Self {
+        Self {
+            tick_number,
+            ..Self::default()
+        }
+    }
+
+    /// Add time to a phase. Phases may be recorded more than once per tick.
+    pub fn record(&mut self, phase: TickPhase, elapsed: Duration) {
+        self.phases_us[phase.index()] += elapsed.as_micros();
+    }
+
+    pub fn phase_us(&self, phase: TickPhase) -> u128 {
+        self.phases_us[phase.index()]
+    }
+
+    /// Sum of all recorded phases; at most `total_us` when filled by a
+    /// `TickProfiler`.
+    pub fn phases_total_us(&self) -> u128 {
+        self.phases_us.iter().sum()
+    }
+
+    /// `(name, microseconds)` pairs in phase order, for exporters.
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, u128)> + '_ {
+        TickPhase::ALL
+            .iter()
+            .map(move |&p| (p.name(), self.phase_us(p)))
+    }
+
+    /// Whether this tick ran over `budget` or follows dropped ticks.
+    pub fn is_slow(&self, budget: Duration) -> bool {
+        self.total_us > budget.as_micros() || self.skipped_ticks > 0
+    }
+
+    /// Log the breakdown: debug every tick, one warning when slow.
+    pub fn log(&self, budget: Duration) {
+        if self.is_slow(budget) {
+            tracing::warn!(
+                tick = self.tick_number,
+                total_us = self.total_us,
+                skipped_ticks = self.skipped_ticks,
+                network_us = self.phase_us(TickPhase::Network),
+                engine_step_us = self.phase_us(TickPhase::EngineStep),
+                systems_us = self.phase_us(TickPhase::Systems),
+                admin_us = self.phase_us(TickPhase::Admin),
+                script_tick_us = self.phase_us(TickPhase::ScriptTick),
+                disconnect_us = self.phase_us(TickPhase::Disconnect),
+                broadcast_us = self.phase_us(TickPhase::Broadcast),
+                persistence_us = self.phase_us(TickPhase::Persistence),
+                "tick over budget"
+            );
+        } else {
+            tracing::debug!(
+                tick = self.tick_number,
+                total_us = self.total_us,
+                network_us = self.phase_us(TickPhase::Network),
+                engine_step_us = self.phase_us(TickPhase::EngineStep),
+                systems_us = self.phase_us(TickPhase::Systems),
+                admin_us = self.phase_us(TickPhase::Admin),
+                script_tick_us = self.phase_us(TickPhase::ScriptTick),
+                disconnect_us = self.phase_us(TickPhase::Disconnect),
+                broadcast_us = self.phase_us(TickPhase::Broadcast),
+                persistence_us = self.phase_us(TickPhase::Persistence),
+                "tick profile"
+            );
+        }
+    }
+}
+
+/// Lap timer filling a `TickProfile`. Each `lap` charges the time since the
+/// previous lap (or `start`) to a phase, so phases never overlap.
+#[derive(Debug)]
+pub struct TickProfiler {
+    start: Instant,
+    last: Instant,
+    profile: TickProfile,
+}
+
+impl TickProfiler {
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last: now,
+            profile: TickProfile::default(),
+        }
+    }
+
+    pub fn lap(&mut self, phase: TickPhase) {
+        let now = Instant::now();
+        self.profile.record(phase, now - self.last);
+        self.last = now;
+    }
+
+    pub fn finish(mut self, tick_number: u64) -> TickProfile {
+        self.profile.tick_number = tick_number;
+        self.profile.total_us = self.start.elapsed().as_micros();
+        self.profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_accumulate_and_stay_within_total() {
+        let mut profiler = TickProfiler::start();
+        std::thread::sleep(Duration::from_millis(2));
+        profiler.lap(TickPhase::Network);
+        std::thread::sleep(Duration::from_millis(1));
+        profiler.lap(TickPhase::ScriptTick);
+        std::thread::sleep(Duration::from_millis(1));
+        profiler.lap(TickPhase::ScriptTick);
+        // Unattributed time still counts toward the total
+        std::thread::sleep(Duration::from_millis(1));
+        let profile = profiler.finish(42);
+
+        assert_eq!(profile.tick_number, 42);
+        assert!(profile.phase_us(TickPhase::Network) >= 2_000);
+        assert!(profile.phase_us(TickPhase::ScriptTick) >= 2_000);
+        assert_eq!(profile.phase_us(TickPhase::Admin), 0);
+        assert!(profile.phases_total_us() <= profile.total_us);
+        assert!(profile.total_us >= 5_000);
+    }
+
+    #[test]
+    fn phases_export_in_order() {
+        let mut profile = TickProfile::new(1);
+        profile.record(TickPhase::Broadcast, Duration::from_micros(30));
+        profile.record(TickPhase::Network, Duration::from_micros(10));
+        profile.record(TickPhase::Network, Duration::from_micros(5));
+
+        let phases: Vec<_> = profile.phases().collect();
+        assert_eq!(phases.len(), TickPhase::ALL.len());
+        assert_eq!(phases[0], ("network", 15));
+        assert_eq!(phases[6], ("broadcast", 30));
+        assert_eq!(profile.phases_total_us(), 45);
+    }
+
+    #[test]
+    fn skipped_ticks_count_as_slow() {
+        let budget = Duration::from_millis(10);
+        let mut profile = TickProfile::new(1);
+        profile.total_us = 1_000;
+        assert!(!profile.is_slow(budget));
+        profile.skipped_ticks = 3;
+        assert!(profile.is_slow(budget));
+        profile.skipped_ticks = 0;
+        profile.total_us = 20_000;
+        assert!(profile.is_slow(budget));
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::profile::TickProfile;
+
+/// Slow-tick samples kept by the process-wide sampler; older ones are dropped.
+pub const MAX_SLOW_TICK_SAMPLES: usize = 32;
+
+/// Everything known about one tick that went over budget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlowTickSample {
+    pub profile: TickProfile,
+    pub budget_us: u128,
+    /// Time spent in each WASM plugin's tick, by plugin id.
+    pub plugin_us: BTreeMap<String, u128>,
+    /// Time spent in each Lua script's on_tick/on_interval hooks, by script name.
+    pub script_us: BTreeMap<String, u128>,
+}
+
+#[derive(Debug, Default)]
+struct SamplerState {
+    plugin_us: BTreeMap<String, u128>,
+    script_us: BTreeMap<String, u128>,
+    samples: VecDeque<SlowTickSample>,
+}
+
+/// Collects per-plugin and per-script costs during a tick and, when the tick
+/// ends over budget, keeps them with its `TickProfile` in a bounded ring
+/// buffer for post-hoc analysis.
+#[derive(Debug)]
+pub struct SlowTickSampler {
+    capacity: usize,
+    state: Mutex<SamplerState>,
+}
+
+impl SlowTickSampler {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(SamplerState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SamplerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Charge `elapsed` to a plugin for the current tick.
+    pub fn record_plugin(&self, plugin: &str, elapsed: Duration) {
+        add(&mut self.state().plugin_us, plugin, elapsed);
+    }
+
+    /// Charge `elapsed` to a script for the current tick.
+    pub fn record_script(&self, script: &str, elapsed: Duration) {
+        add(&mut self.state().script_us, script, elapsed);
+    }
+
+    /// Close the current tick: if `profile` is over `budget`, store a sample
+    /// with the costs recorded since the previous call. Costs are reset
+    /// either way. Returns whether a sample was taken.
+    pub fn finish_tick(&self, profile: &TickProfile, budget: Duration) -> bool {
+        let mut state = self.state();
+        let plugin_us = std::mem::take(&mut state.plugin_us);
+        let script_us = std::mem::take(&mut state.script_us);
+        if profile.total_us <= budget.as_micros() || self.capacity == 0 {
+            return false;
+        }
+        if state.samples.len() == self.capacity {
+            state.samples.pop_front();
+        }
+        state.samples.push_back(SlowTickSample {
+            profile: profile.clone(),
+            budget_us: budget.as_micros(),
+            plugin_us,
+            script_us,
+        });
+        true
+    }
+
+    /// Stored samples, oldest first.
+    pub fn samples(&self) -> Vec<SlowTickSample> {
+        self.state().samples.iter().cloned().collect()
+    }
+}
+
+fn add(costs: &mut BTreeMap<String, u128>, name: &str, elapsed: Duration) {
+    *costs.entry(name.to_string()).or_insert(0) += elapsed.as_micros();
+}
+
+/// The sampler the engine's plugin and script runners report into.
+pub fn slow_tick_sampler() -> &'static SlowTickSampler {
+    static SAMPLER: OnceLock<SlowTickSampler> = OnceLock::new();
+    SAMPLER.get_or_init(|| SlowTickSampler::new(MAX_SLOW_TICK_SAMPLES))
+}
+
+/// Recent over-budget ticks, oldest first.
+pub fn slow_ticks() -> Vec<SlowTickSample> {
+    slow_tick_sampler().samples()
+}
+
+/// Charge a WASM plugin's tick time to the current tick.
+pub fn record_plugin_cost(plugin: &str, elapsed: Duration) {
+    slow_tick_sampler().record_plugin(plugin, elapsed);
+}
+
+/// Charge a Lua script's hook time to the current tick.
+pub fn record_script_cost(script: &str, elapsed: Duration) {
+    slow_tick_sampler().record_script(script, elapsed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::{TickPhase, TickProfiler};
+
+    #[test]
+    fn over_budget_tick_is_sampled_with_breakdown() {
+        let sampler = SlowTickSampler::new(4);
+        let budget = Duration::from_millis(1);
+
+        let mut profiler = TickProfiler::start();
+        sampler.record_plugin("combat", Duration::from_micros(400));
+        sampler.record_plugin("combat", Duration::from_micros(100));
+        sampler.record_script("20_npc", Duration::from_micros(700));
+        std::thread::sleep(Duration::from_millis(3));
+        profiler.lap(TickPhase::ScriptTick);
+        let profile = profiler.finish(12);
+
+        assert!(sampler.finish_tick(&profile, budget));
+        let samples = sampler.samples();
+        assert_eq!(samples.len(), 1);
+        let sample = &samples[0];
+        assert_eq!(sample.profile.tick_number, 12);
+        assert_eq!(sample.budget_us, 1_000);
+        assert!(sample.profile.phase_us(TickPhase::ScriptTick) >= 3_000);
+        assert_eq!(sample.plugin_us.get("combat"), Some(&500));
+        assert_eq!(sample.script_us.get("20_npc"), Some(&700));
+    }
+
+    #[test]
+    fn fast_ticks_are_not_sampled_and_reset_costs() {
+        let sampler = SlowTickSampler::new(4);
+        sampler.record_plugin("combat", Duration::from_micros(10));
+        let mut fast = TickProfile::new(1);
+        fast.total_us = 500;
+        assert!(!sampler.finish_tick(&fast, Duration::from_millis(1)));
+        assert!(sampler.samples().is_empty());
+
+        // The fast tick's costs don't leak into the next slow one.
+        let mut slow = TickProfile::new(2);
+        slow.total_us = 5_000;
+        assert!(sampler.finish_tick(&slow, Duration::from_millis(1)));
+        assert!(sampler.samples()[0].plugin_us.is_empty());
+    }
+
+    #[test]
+    fn ring_buffer_keeps_most_recent_samples() {
+        let sampler = SlowTickSampler::new(2);
+        for tick in 1..=5 {
+            let mut profile = TickProfile::new(tick);
+            profile.total_us = 2_000;
+            sampler.finish_tick(&profile, Duration::from_millis(1));
+        }
+        let ticks: Vec<u64> = sampler.samples().iter().map(|s| s.profile.tick_number).collect();
+        assert_eq!(ticks, vec![4, 5]);
+    }
+}
@@ -3,6 +3,39 @@ use std::path::{Path, PathBuf};
 use crate::error::PersistenceError;
 use crate::snapshot::WorldSnapshot;
 
+/// Leading bytes of a checksummed snapshot file. Followed by the CRC32 of
+/// the payload (u32 little-endian) and the bincode payload itself. Files
+/// without the magic are pre-checksum snapshots and load unverified.
+const ENVELOPE_MAGIC: &[u8; 4] = b"SNP1";
+const ENVELOPE_HEADER_LEN: usize = 8;
+
+fn encode_envelope(payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(ENVELOPE_HEADER_LEN + payload.len());
+    bytes.extend_from_slice(ENVELOPE_MAGIC);
+    bytes.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// Strip and verify the envelope, returning the bincode payload.
+fn decode_envelope(bytes: &[u8]) -> Result<&[u8], PersistenceError> {
+    if !bytes.starts_with(ENVELOPE_MAGIC) {
+        return Ok(bytes);
+    }
+    if bytes.len() < ENVELOPE_HEADER_LEN {
+        return Err(PersistenceError::Corrupt("truncated snapshot header".to_string()));
+    }
+    let expected = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let payload = &bytes[ENVELOPE_HEADER_LEN..];
+    let actual = crc32fast::hash(payload);
+    if actual != expected {
+        return Err(PersistenceError::Corrupt(format!(
+            "checksum mismatch: expected {expected:08x}, got {actual:08x}"
+        )));
+    }
+    Ok(payload)
+}
+
 /// Manages snapshot persistence to disk.
 pub struct SnapshotManager {
     save_dir: PathBuf,
@@ -22,7 +55,7 @@ impl SnapshotManager {
         let filename = format!("snapshot_tick_{}.bin", snapshot.tick);
         let path = self.save_dir.join(&filename);
 
-        let bytes = bincode::serialize(snapshot)?;
+        let bytes = encode_envelope(&bincode::serialize(snapshot)?);
 
         // Write to temp file first, then rename for atomicity
         let tmp_path = self.save_dir.join(format!("{}.tmp", filename));
@@ -45,16 +78,57 @@ impl SnapshotManager {
         Ok(path)
     }
 
-    /// Load the latest snapshot from disk.
+    /// Load the latest snapshot from disk. If `latest.bin` is corrupt, falls
+    /// back to the newest `snapshot_tick_*.bin` that verifies; the original
+    /// error is returned when none does.
     pub fn load_latest(&self) -> Result<WorldSnapshot, PersistenceError> {
         let path = self.save_dir.join("latest.bin");
-        self.load_from_path(&path)
+        let err = match self.load_from_path(&path) {
+            Err(err @ PersistenceError::Corrupt(_)) => err,
+            other => return other,
+        };
+        tracing::warn!(path = %path.display(), error = %err, "Latest snapshot is corrupt, trying older snapshots");
+
+        for (_, older) in self.tick_snapshots().into_iter().rev() {
+            match self.load_from_path(&older) {
+                Ok(snapshot) => return Ok(snapshot),
+                Err(e) => {
+                    tracing::warn!(path = %older.display(), error = %e, "Skipping unreadable snapshot");
+                }
+            }
+        }
+        Err(err)
     }
 
-    /// Load a snapshot from a specific path.
+    /// `snapshot_tick_<N>.bin` files in the save directory, oldest first.
+    fn tick_snapshots(&self) -> Vec<(u64, PathBuf)> {
+        let Ok(entries) = std::fs::read_dir(&self.save_dir) else {
+            return Vec::new();
+        };
+        let mut found: Vec<(u64, PathBuf)> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let tick = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix("snapshot_tick_")?
+                    .strip_suffix(".bin")?
+                    .parse()
+                    .ok()?;
+                Some((tick, path))
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Load a snapshot from a specific path, verifying its checksum.
+    /// A checksum mismatch or undecodable payload is `PersistenceError::Corrupt`.
     pub fn load_from_path(&self, path: &Path) -> Result<WorldSnapshot, PersistenceError> {
         let bytes = std::fs::read(path)?;
-        let snapshot: WorldSnapshot = bincode::deserialize(&bytes)?;
+        let payload = decode_envelope(&bytes)?;
+        let snapshot: WorldSnapshot =
+            bincode::deserialize(payload).map_err(|e| PersistenceError::Corrupt(e.to_string()))?;
         tracing::info!(
             tick = snapshot.tick,
             version = snapshot.version,
@@ -64,6 +138,24 @@ impl SnapshotManager {
         Ok(snapshot)
     }
 
+    /// Write a `snapshot::export_json` dump to `path` as pretty-printed JSON.
+    /// Independent of the binary snapshot files in the save directory.
+    pub fn export_json_to(&self, dump: &serde_json::Value, path: &Path) -> Result<(), PersistenceError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string_pretty(dump)
+            .map_err(|e| PersistenceError::Serialization(e.to_string()))?;
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        std::fs::write(&tmp_path, text)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        tracing::info!(path = %path.display(), "World dump exported");
+        Ok(())
+    }
+
     /// Check if a latest snapshot exists.
     pub fn has_latest(&self) -> bool {
         self.save_dir.join("latest.bin").exists()
@@ -92,6 +184,11 @@ mod tests {
                 .ok()
                 .and_then(|c| bincode::serialize(c).ok())
         }
+        fn capture_json(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<serde_json::Value> {
+            ecs.get_component::<TestName>(eid)
+                .ok()
+                .and_then(|c| serde_json::to_value(c).ok())
+        }
         fn restore(
             &self,
             ecs: &mut EcsAdapter,
@@ -137,6 +234,59 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn lingering_survives_save_and_load() {
+        let dir = std::env::temp_dir().join("mud_test_persistence_lingering");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let space = RoomGraphSpace::new();
+        let hero = ecs.spawn_entity();
+
+        let mut snap = snapshot::capture(&ecs, &space, 42, &registry);
+        assert!(snap.lingering.is_empty());
+        snap.lingering.push(session::LingeringEntity {
+            entity: hero,
+            character_id: 7,
+            account_id: 3,
+            disconnect_tick: 40,
+        });
+
+        let mgr = SnapshotManager::new(&dir);
+        mgr.save_to_disk(&snap).unwrap();
+        let loaded = mgr.load_latest().unwrap();
+        assert_eq!(loaded.lingering, snap.lingering);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn export_json_writes_readable_dump() {
+        let dir = std::env::temp_dir().join("mud_test_persistence_export_json");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let space = RoomGraphSpace::new();
+        let e1 = ecs.spawn_entity();
+        ecs.set_component(e1, TestName("Hero".to_string())).unwrap();
+
+        let mgr = SnapshotManager::new(&dir);
+        let path = dir.join("dumps").join("world.json");
+        mgr.export_json_to(&snapshot::export_json(&ecs, &space, &registry), &path)
+            .unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains('\n'), "expected pretty-printed JSON");
+        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed["entities"][0]["components"]["TestName"], "Hero");
+        // The binary save path is untouched
+        assert!(!mgr.has_latest());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn load_nonexistent_fails() {
         let dir = std::env::temp_dir().join("mud_test_persistence_nonexistent");
@@ -147,6 +297,84 @@ mod tests {
         assert!(mgr.load_latest().is_err());
     }
 
+    fn flip_byte(path: &Path) {
+        let mut bytes = std::fs::read(path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn corrupt_latest_falls_back_to_previous_snapshot() {
+        let dir = std::env::temp_dir().join("mud_test_persistence_corrupt_fallback");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let space = RoomGraphSpace::new();
+        let e1 = ecs.spawn_entity();
+        ecs.set_component(e1, TestName("Hero".to_string())).unwrap();
+
+        let mgr = SnapshotManager::new(&dir);
+        mgr.save_to_disk(&snapshot::capture(&ecs, &space, 100, &registry)).unwrap();
+        let newest = mgr.save_to_disk(&snapshot::capture(&ecs, &space, 200, &registry)).unwrap();
+        assert_eq!(mgr.load_latest().unwrap().tick, 200);
+
+        // A crash mid-write damaged both copies of tick 200.
+        flip_byte(&dir.join("latest.bin"));
+        flip_byte(&newest);
+        assert!(matches!(
+            mgr.load_from_path(&dir.join("latest.bin")),
+            Err(PersistenceError::Corrupt(_))
+        ));
+        assert!(matches!(mgr.load_from_path(&newest), Err(PersistenceError::Corrupt(_))));
+
+        let loaded = mgr.load_latest().unwrap();
+        assert_eq!(loaded.tick, 100);
+        assert_eq!(loaded.entities.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn corrupt_latest_without_fallback_reports_corrupt() {
+        let dir = std::env::temp_dir().join("mud_test_persistence_corrupt_only");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let registry = test_registry();
+        let ecs = EcsAdapter::new();
+        let space = RoomGraphSpace::new();
+        let mgr = SnapshotManager::new(&dir);
+        let path = mgr.save_to_disk(&snapshot::capture(&ecs, &space, 5, &registry)).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        // Truncated to half: checksum no longer matches.
+        let latest = dir.join("latest.bin");
+        let bytes = std::fs::read(&latest).unwrap();
+        std::fs::write(&latest, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(mgr.load_latest(), Err(PersistenceError::Corrupt(_))));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unchecksummed_snapshot_still_loads() {
+        let dir = std::env::temp_dir().join("mud_test_persistence_legacy");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let registry = test_registry();
+        let ecs = EcsAdapter::new();
+        let space = RoomGraphSpace::new();
+        let snap = snapshot::capture(&ecs, &space, 9, &registry);
+        std::fs::write(dir.join("latest.bin"), bincode::serialize(&snap).unwrap()).unwrap();
+
+        let mgr = SnapshotManager::new(&dir);
+        assert_eq!(mgr.load_latest().unwrap().tick, 9);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn multiple_snapshots() {
         let dir = std::env::temp_dir().join("mud_test_persistence_multiple");
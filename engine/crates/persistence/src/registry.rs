@@ -12,6 +12,10 @@ pub trait PersistentComponent: Send + Sync {
     /// Returns None if the entity does not have this component.
     fn capture(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<Vec<u8>>;
 
+    /// The component as JSON, for admin inspection and character saves.
+    /// Returns None if the entity does not have this component.
+    fn capture_json(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<serde_json::Value>;
+
     /// Deserialize and attach the component to the given entity.
     fn restore(
         &self,
@@ -42,6 +46,17 @@ impl PersistenceRegistry {
     pub fn components(&self) -> &[Box<dyn PersistentComponent>] {
         &self.components
     }
+
+    /// Every registered component present on `eid`, as a JSON object keyed
+    /// by tag. Entities without any registered component export `{}`.
+    pub fn export_entity_json(&self, ecs: &EcsAdapter, eid: EntityId) -> serde_json::Value {
+        let components = self
+            .components
+            .iter()
+            .filter_map(|c| Some((c.tag().to_string(), c.capture_json(ecs, eid)?)))
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::Value::Object(components)
+    }
 }
 
 impl Default for PersistenceRegistry {
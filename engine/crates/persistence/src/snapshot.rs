@@ -2,11 +2,13 @@ use std::collections::BTreeMap;
 
 use ecs_adapter::{EcsAdapter, EntityAllocator, EntityId};
 use serde::{Deserialize, Serialize};
+use session::LingeringEntity;
 use space::snapshot::{SpaceSnapshotCapture, SpaceSnapshotData};
+use space::SpaceModel;
 
 use crate::registry::PersistenceRegistry;
 
-pub const SNAPSHOT_VERSION: u32 = 2;
+pub const SNAPSHOT_VERSION: u32 = 6;
 
 /// Component data for a single entity.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +25,35 @@ pub struct WorldSnapshot {
     pub allocator: EntityAllocator,
     pub entities: Vec<EntitySnapshot>,
     pub space: SpaceSnapshotData,
+    /// Disconnected players still in-world. `capture` leaves this empty;
+    /// the tick loop fills it from `SessionManager::export_lingering`.
+    pub lingering: Vec<LingeringEntity>,
+    /// WASM plugin linear memory by plugin id. `capture` leaves this empty;
+    /// hosts running plugins fill it from `PluginRuntime::export_memory`.
+    pub plugin_memory: BTreeMap<String, Vec<u8>>,
+}
+
+/// Snapshot of a subset of entities, for zone resets and per-player exports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialSnapshot {
+    pub version: u32,
+    pub tick: u64,
+    pub entities: Vec<EntitySnapshot>,
+    /// Entity → room (or grid cell id) it occupied, for placed entities.
+    pub placements: BTreeMap<EntityId, EntityId>,
+}
+
+fn capture_entity(ecs: &EcsAdapter, eid: EntityId, registry: &PersistenceRegistry) -> EntitySnapshot {
+    let mut comps = BTreeMap::new();
+    for handler in registry.components() {
+        if let Some(bytes) = handler.capture(ecs, eid) {
+            comps.insert(handler.tag().to_string(), bytes);
+        }
+    }
+    EntitySnapshot {
+        entity_id: eid,
+        components: comps,
+    }
 }
 
 /// Capture a complete world snapshot from the current ECS and space state.
@@ -35,19 +66,10 @@ pub fn capture<S: SpaceSnapshotCapture>(
     let allocator = ecs.allocator().clone();
     let all_entities = ecs.all_entities();
 
-    let mut entities = Vec::new();
-    for &eid in &all_entities {
-        let mut comps = BTreeMap::new();
-        for handler in registry.components() {
-            if let Some(bytes) = handler.capture(ecs, eid) {
-                comps.insert(handler.tag().to_string(), bytes);
-            }
-        }
-        entities.push(EntitySnapshot {
-            entity_id: eid,
-            components: comps,
-        });
-    }
+    let entities = all_entities
+        .iter()
+        .map(|&eid| capture_entity(ecs, eid, registry))
+        .collect();
 
     let space_snap = space.capture_snapshot();
 
@@ -57,9 +79,38 @@ pub fn capture<S: SpaceSnapshotCapture>(
         allocator,
         entities,
         space: space_snap,
+        lingering: Vec::new(),
+        plugin_memory: BTreeMap::new(),
     }
 }
 
+/// Human-readable dump of the world for builders to review and diff: every
+/// entity (by packed id, in entity order) with its registered components as
+/// JSON, plus the room graph or grid layout. Object keys are sorted, so two
+/// dumps of the same world are identical. Not loadable — `capture` remains the
+/// save path.
+pub fn export_json<S: SpaceSnapshotCapture>(
+    ecs: &EcsAdapter,
+    space: &S,
+    registry: &PersistenceRegistry,
+) -> serde_json::Value {
+    let entities: Vec<serde_json::Value> = ecs
+        .all_entities()
+        .into_iter()
+        .map(|eid| {
+            serde_json::json!({
+                "id": eid.to_u64(),
+                "components": registry.export_entity_json(ecs, eid),
+            })
+        })
+        .collect();
+    let space = serde_json::to_value(space.capture_snapshot()).unwrap_or(serde_json::Value::Null);
+    serde_json::json!({
+        "entities": entities,
+        "space": space,
+    })
+}
+
 /// Restore a world snapshot into the provided ECS and space.
 /// This clears the existing ECS and space, then rebuilds from the snapshot.
 pub fn restore<S: SpaceSnapshotCapture>(
@@ -109,13 +160,149 @@ pub fn restore<S: SpaceSnapshotCapture>(
     Ok(snapshot.tick)
 }
 
+/// Capture only the given entities (dead ones are skipped) and where they
+/// are placed in the space.
+pub fn capture_entities<S: SpaceModel>(
+    ecs: &EcsAdapter,
+    space: &S,
+    tick: u64,
+    registry: &PersistenceRegistry,
+    entities: &[EntityId],
+) -> PartialSnapshot {
+    let mut ids: Vec<EntityId> = entities
+        .iter()
+        .copied()
+        .filter(|&eid| ecs.allocator().is_alive(eid))
+        .collect();
+    ids.sort();
+    ids.dedup();
+
+    let placements = ids
+        .iter()
+        .filter_map(|&eid| space.entity_room(eid).map(|room| (eid, room)))
+        .collect();
+    let entities = ids
+        .iter()
+        .map(|&eid| capture_entity(ecs, eid, registry))
+        .collect();
+
+    PartialSnapshot {
+        version: SNAPSHOT_VERSION,
+        tick,
+        entities,
+        placements,
+    }
+}
+
+/// Merge a partial snapshot into a live world.
+///
+/// Entities in the snapshot replace any live entity with the same id; all
+/// other entities are left untouched. The merge is all-or-nothing: it fails
+/// without changing anything if an id's slot is held by a different
+/// generation, if any component fails to decode, or if an entity cannot be
+/// placed.
+pub fn restore_partial<S: SpaceModel>(
+    snapshot: PartialSnapshot,
+    ecs: &mut EcsAdapter,
+    space: &mut S,
+    registry: &PersistenceRegistry,
+) -> Result<u64, crate::error::PersistenceError> {
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(crate::error::PersistenceError::VersionMismatch {
+            expected: SNAPSHOT_VERSION,
+            got: snapshot.version,
+        });
+    }
+
+    for entity_snap in &snapshot.entities {
+        let eid = entity_snap.entity_id;
+        if !ecs.allocator().is_alive(eid) && !ecs.allocator().can_claim(eid) {
+            return Err(crate::error::PersistenceError::Corrupt(format!(
+                "entity slot for {} is in use",
+                eid
+            )));
+        }
+    }
+
+    let handler_map: BTreeMap<&str, &dyn crate::registry::PersistentComponent> = registry
+        .components()
+        .iter()
+        .map(|h| (h.tag(), h.as_ref()))
+        .collect();
+
+    // Decode everything into a scratch world first, so corrupt data fails
+    // before the live world is touched.
+    let mut scratch = EcsAdapter::new();
+    for entity_snap in &snapshot.entities {
+        let scratch_eid = scratch.spawn_entity();
+        for (tag, data) in &entity_snap.components {
+            if let Some(handler) = handler_map.get(tag.as_str()) {
+                handler.restore(&mut scratch, scratch_eid, data)?;
+            } else {
+                tracing::warn!("Unknown component tag during restore: {}", tag);
+            }
+        }
+    }
+
+    // Placement can still be refused by the space; keep what is about to be
+    // replaced so it can be put back.
+    let ids: Vec<EntityId> = snapshot.entities.iter().map(|e| e.entity_id).collect();
+    let previous = capture_entities(ecs, space, snapshot.tick, registry, &ids);
+
+    if let Err(e) = merge_entities(&snapshot, ecs, space, &handler_map) {
+        remove_entities(ecs, space, &ids);
+        if let Err(undo) = merge_entities(&previous, ecs, space, &handler_map) {
+            tracing::error!("Failed to roll back partial restore: {}", undo);
+        }
+        return Err(e);
+    }
+
+    Ok(snapshot.tick)
+}
+
+fn remove_entities<S: SpaceModel>(ecs: &mut EcsAdapter, space: &mut S, ids: &[EntityId]) {
+    for &eid in ids {
+        if ecs.allocator().is_alive(eid) {
+            let _ = space.remove_entity(eid);
+            let _ = ecs.despawn_entity(eid);
+        }
+    }
+}
+
+/// Replace each snapshot entity in the live world and place it.
+fn merge_entities<S: SpaceModel>(
+    snapshot: &PartialSnapshot,
+    ecs: &mut EcsAdapter,
+    space: &mut S,
+    handler_map: &BTreeMap<&str, &dyn crate::registry::PersistentComponent>,
+) -> Result<(), crate::error::PersistenceError> {
+    for entity_snap in &snapshot.entities {
+        let eid = entity_snap.entity_id;
+        remove_entities(ecs, space, &[eid]);
+        ecs.claim_entity(eid)
+            .map_err(|e| crate::error::PersistenceError::Corrupt(e.to_string()))?;
+
+        for (tag, data) in &entity_snap.components {
+            if let Some(handler) = handler_map.get(tag.as_str()) {
+                handler.restore(ecs, eid, data)?;
+            }
+        }
+
+        if let Some(&room) = snapshot.placements.get(&eid) {
+            space
+                .place_entity(eid, room)
+                .map_err(|e| crate::error::PersistenceError::Corrupt(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::registry::{PersistenceRegistry, PersistentComponent};
     use ecs_adapter::Component;
     use serde::{Deserialize, Serialize};
-    use space::SpaceModel;
 
     #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
     struct TestName(String);
@@ -136,6 +323,11 @@ mod tests {
                 .ok()
                 .and_then(|c| bincode::serialize(c).ok())
         }
+        fn capture_json(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<serde_json::Value> {
+            ecs.get_component::<TestName>(eid)
+                .ok()
+                .and_then(|c| serde_json::to_value(c).ok())
+        }
         fn restore(
             &self,
             ecs: &mut EcsAdapter,
@@ -158,6 +350,11 @@ mod tests {
                 .ok()
                 .and_then(|c| bincode::serialize(c).ok())
         }
+        fn capture_json(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<serde_json::Value> {
+            ecs.get_component::<TestHealth>(eid)
+                .ok()
+                .and_then(|c| serde_json::to_value(c).ok())
+        }
         fn restore(
             &self,
             ecs: &mut EcsAdapter,
@@ -213,6 +410,51 @@ mod tests {
         assert_eq!(space2.entity_room(e1), Some(room));
     }
 
+    #[test]
+    fn export_json_lists_components_and_exits() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+
+        let hall = ecs.spawn_entity();
+        let yard = ecs.spawn_entity();
+        space.register_room(
+            hall,
+            space::room_graph::RoomExits {
+                north: Some(yard),
+                ..Default::default()
+            },
+        );
+        space.register_room(yard, space::room_graph::RoomExits::default());
+
+        let e1 = ecs.spawn_entity();
+        ecs.set_component(e1, TestName("Hero".to_string())).unwrap();
+        ecs.set_component(e1, TestHealth { current: 80, max: 100 }).unwrap();
+        space.place_entity(e1, hall).unwrap();
+
+        let dump = export_json(&ecs, &space, &registry);
+
+        let hero = dump["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["id"] == e1.to_u64())
+            .unwrap();
+        assert_eq!(hero["components"]["TestName"], "Hero");
+        assert_eq!(hero["components"]["TestHealth"]["current"], 80);
+
+        let rooms = dump["space"]["RoomGraph"]["rooms"].as_array().unwrap();
+        let hall_json = rooms
+            .iter()
+            .find(|r| r["room_id"]["index"] == hall.index)
+            .unwrap();
+        assert_eq!(hall_json["exits"]["north"]["index"], yard.index);
+        assert_eq!(hall_json["occupants"][0]["index"], e1.index);
+
+        // Stable across calls, so dumps can be diffed
+        assert_eq!(dump, export_json(&ecs, &space, &registry));
+    }
+
     #[test]
     fn version_mismatch_rejected() {
         let registry = test_registry();
@@ -296,4 +538,146 @@ mod tests {
             Some(space::grid_space::GridPos::new(10, 20))
         );
     }
+
+    #[test]
+    fn partial_capture_restores_only_selected_entities() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+        let room = ecs.spawn_entity();
+        space.register_room(room, space::room_graph::RoomExits::default());
+
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        let e3 = ecs.spawn_entity();
+        for (eid, name) in [(e1, "A"), (e2, "B"), (e3, "C")] {
+            ecs.set_component(eid, TestName(name.to_string())).unwrap();
+            space.place_entity(eid, room).unwrap();
+        }
+        ecs.set_component(e2, TestHealth { current: 5, max: 10 }).unwrap();
+
+        let snap = capture_entities(&ecs, &space, 9, &registry, &[e2, e1, e2]);
+        assert_eq!(snap.entities.len(), 2);
+        assert_eq!(snap.placements.len(), 2);
+
+        // Fresh world that only knows the room.
+        let mut ecs2 = EcsAdapter::new();
+        let mut space2 = space::RoomGraphSpace::new();
+        space2.register_room(room, space::room_graph::RoomExits::default());
+
+        let tick = restore_partial(snap, &mut ecs2, &mut space2, &registry).unwrap();
+        assert_eq!(tick, 9);
+        assert_eq!(ecs2.entity_count(), 2);
+        assert_eq!(ecs2.get_component::<TestName>(e1).unwrap().0, "A");
+        assert_eq!(ecs2.get_component::<TestName>(e2).unwrap().0, "B");
+        assert_eq!(
+            ecs2.get_component::<TestHealth>(e2).unwrap(),
+            &TestHealth { current: 5, max: 10 }
+        );
+        assert!(!ecs2.allocator().is_alive(e3));
+        assert_eq!(space2.room_occupants(room), vec![e1, e2]);
+
+        // New spawns don't collide with the restored ids.
+        let fresh = ecs2.spawn_entity();
+        assert!(fresh != e1 && fresh != e2);
+    }
+
+    #[test]
+    fn partial_restore_replaces_without_clobbering_others() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+        let room = ecs.spawn_entity();
+        space.register_room(room, space::room_graph::RoomExits::default());
+
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        ecs.set_component(e1, TestName("Orig".to_string())).unwrap();
+        ecs.set_component(e2, TestName("Other".to_string())).unwrap();
+        space.place_entity(e1, room).unwrap();
+        space.place_entity(e2, room).unwrap();
+
+        let snap = capture_entities(&ecs, &space, 1, &registry, &[e1]);
+
+        ecs.set_component(e1, TestName("Changed".to_string())).unwrap();
+        ecs.set_component(e2, TestName("Other2".to_string())).unwrap();
+
+        restore_partial(snap, &mut ecs, &mut space, &registry).unwrap();
+        assert_eq!(ecs.get_component::<TestName>(e1).unwrap().0, "Orig");
+        assert_eq!(ecs.get_component::<TestName>(e2).unwrap().0, "Other2");
+        assert_eq!(space.room_occupants(room), vec![e1, e2]);
+    }
+
+    #[test]
+    fn partial_restore_rejects_slot_in_use() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+        let e1 = ecs.spawn_entity();
+        let snap = capture_entities(&ecs, &space, 0, &registry, &[e1]);
+
+        // Slot reused by a newer generation.
+        ecs.despawn_entity(e1).unwrap();
+        let reused = ecs.spawn_entity();
+        assert_eq!(reused.index, e1.index);
+
+        assert!(restore_partial(snap, &mut ecs, &mut space, &registry).is_err());
+        assert!(ecs.allocator().is_alive(reused));
+    }
+
+    #[test]
+    fn partial_restore_with_corrupt_entity_changes_nothing() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+        let room = ecs.spawn_entity();
+        space.register_room(room, space::room_graph::RoomExits::default());
+
+        let ids: Vec<EntityId> = (0..3).map(|_| ecs.spawn_entity()).collect();
+        for (i, &eid) in ids.iter().enumerate() {
+            ecs.set_component(eid, TestName(format!("Orig{}", i))).unwrap();
+            space.place_entity(eid, room).unwrap();
+        }
+        let mut snap = capture_entities(&ecs, &space, 1, &registry, &ids);
+        for (i, &eid) in ids.iter().enumerate() {
+            ecs.set_component(eid, TestName(format!("Live{}", i))).unwrap();
+        }
+        // The middle entity's data is garbage
+        snap.entities[1]
+            .components
+            .insert("TestHealth".to_string(), vec![0xff]);
+
+        assert!(restore_partial(snap, &mut ecs, &mut space, &registry).is_err());
+        for (i, &eid) in ids.iter().enumerate() {
+            assert_eq!(ecs.get_component::<TestName>(eid).unwrap().0, format!("Live{}", i));
+            assert!(ecs.get_component::<TestHealth>(eid).is_err());
+        }
+        assert_eq!(space.room_occupants(room), ids);
+    }
+
+    #[test]
+    fn partial_restore_rolls_back_when_placement_fails() {
+        let registry = test_registry();
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::RoomGraphSpace::new();
+        let room = ecs.spawn_entity();
+        let gone = ecs.spawn_entity();
+        space.register_room(room, space::room_graph::RoomExits::default());
+
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        ecs.set_component(e1, TestName("Orig1".to_string())).unwrap();
+        ecs.set_component(e2, TestName("Orig2".to_string())).unwrap();
+        space.place_entity(e1, room).unwrap();
+        space.place_entity(e2, room).unwrap();
+        let mut snap = capture_entities(&ecs, &space, 1, &registry, &[e1, e2]);
+        ecs.set_component(e1, TestName("Live1".to_string())).unwrap();
+        // e2 would be placed in a room this world does not have
+        snap.placements.insert(e2, gone);
+
+        assert!(restore_partial(snap, &mut ecs, &mut space, &registry).is_err());
+        assert_eq!(ecs.get_component::<TestName>(e1).unwrap().0, "Live1");
+        assert_eq!(ecs.get_component::<TestName>(e2).unwrap().0, "Orig2");
+        assert_eq!(space.room_occupants(room), vec![e1, e2]);
+    }
 }
@@ -9,6 +9,21 @@ use serde::{Deserialize, Serialize};
 pub const ABI_VERSION_MAJOR: u32 = 1;
 pub const ABI_VERSION_MINOR: u32 = 0;
 
+/// This crate's ABI version, packed as returned by a plugin's
+/// `abi_version() -> u64` export. Plugins export it verbatim:
+/// `pub extern "C" fn abi_version() -> u64 { plugin_abi::ABI_VERSION }`.
+pub const ABI_VERSION: u64 = pack_abi_version(ABI_VERSION_MAJOR, ABI_VERSION_MINOR);
+
+/// Pack a version as `major << 32 | minor`.
+pub const fn pack_abi_version(major: u32, minor: u32) -> u64 {
+    ((major as u64) << 32) | minor as u64
+}
+
+/// Split a packed version into `(major, minor)`.
+pub const fn unpack_abi_version(packed: u64) -> (u32, u32) {
+    ((packed >> 32) as u32, packed as u32)
+}
+
 // --- Return Codes ---
 
 pub const RESULT_OK: i32 = 0;
@@ -106,5 +121,13 @@ mod tests {
     fn abi_version_constants() {
         assert_eq!(ABI_VERSION_MAJOR, 1);
         assert_eq!(ABI_VERSION_MINOR, 0);
+        assert_eq!(ABI_VERSION, 1 << 32);
+    }
+
+    #[test]
+    fn abi_version_pack_roundtrip() {
+        let packed = pack_abi_version(3, 7);
+        assert_eq!(unpack_abi_version(packed), (3, 7));
+        assert_eq!(unpack_abi_version(ABI_VERSION), (ABI_VERSION_MAJOR, ABI_VERSION_MINOR));
     }
 }
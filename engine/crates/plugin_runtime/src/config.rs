@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use plugin_abi::WasmCommand;
 use serde::{Deserialize, Serialize};
 
 /// Global fuel configuration for the plugin runtime.
@@ -20,6 +21,49 @@ impl Default for FuelConfig {
     }
 }
 
+/// A class of `WasmCommand` a plugin may be authorized to emit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Capability {
+    /// `SetComponent` and `RemoveComponent`.
+    WriteComponents,
+    /// `MoveEntity`.
+    MoveEntities,
+    /// `SpawnEntity`.
+    SpawnEntities,
+    /// `DestroyEntity`.
+    DestroyEntities,
+    /// `EmitEvent`.
+    EmitEvents,
+}
+
+impl Capability {
+    /// Every capability, in declaration order.
+    pub const ALL: [Capability; 5] = [
+        Capability::WriteComponents,
+        Capability::MoveEntities,
+        Capability::SpawnEntities,
+        Capability::DestroyEntities,
+        Capability::EmitEvents,
+    ];
+
+    /// The capability a plugin needs in order to emit `cmd`.
+    pub fn required_for(cmd: &WasmCommand) -> Capability {
+        match cmd {
+            WasmCommand::SetComponent { .. } | WasmCommand::RemoveComponent { .. } => {
+                Capability::WriteComponents
+            }
+            WasmCommand::MoveEntity { .. } => Capability::MoveEntities,
+            WasmCommand::SpawnEntity { .. } => Capability::SpawnEntities,
+            WasmCommand::DestroyEntity { .. } => Capability::DestroyEntities,
+            WasmCommand::EmitEvent { .. } => Capability::EmitEvents,
+        }
+    }
+}
+
+fn all_capabilities() -> Vec<Capability> {
+    Capability::ALL.to_vec()
+}
+
 /// Configuration for a single plugin.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
@@ -33,6 +77,56 @@ pub struct PluginConfig {
     pub fuel_limit: Option<u64>,
     /// Whether the plugin is enabled.
     pub enabled: bool,
+    /// Command classes the plugin may emit. Anything else is dropped by
+    /// `PluginRuntime::run_tick`. Manifests that omit this get every capability.
+    #[serde(default = "all_capabilities")]
+    pub capabilities: Vec<Capability>,
+    /// Quarantine the plugin once it has emitted this many unauthorized
+    /// commands (None = only drop them, 0 acts as 1).
+    #[serde(default)]
+    pub max_capability_violations: Option<u32>,
+    /// Hex SHA-256 the wasm binary must match (None = not checked).
+    #[serde(default)]
+    pub expected_sha256: Option<String>,
+    /// Lowest `host_log` level written (`plugin_abi::LOG_*`). Defaults to
+    /// `LOG_TRACE`, i.e. everything.
+    #[serde(default)]
+    pub min_log_level: u32,
+    /// `host_log` messages written per tick; extra ones are dropped and
+    /// counted (None = unlimited).
+    #[serde(default = "default_max_logs_per_tick")]
+    pub max_logs_per_tick: Option<u32>,
+}
+
+fn default_max_logs_per_tick() -> Option<u32> {
+    Some(DEFAULT_MAX_LOGS_PER_TICK)
+}
+
+/// Per-tick `host_log` budget for manifests that don't set one.
+pub const DEFAULT_MAX_LOGS_PER_TICK: u32 = 100;
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            plugin_id: String::new(),
+            wasm_path: PathBuf::new(),
+            priority: 0,
+            fuel_limit: None,
+            enabled: true,
+            capabilities: all_capabilities(),
+            max_capability_violations: None,
+            expected_sha256: None,
+            min_log_level: plugin_abi::LOG_TRACE,
+            max_logs_per_tick: default_max_logs_per_tick(),
+        }
+    }
+}
+
+impl PluginConfig {
+    /// Check whether this plugin is authorized to emit `cmd`.
+    pub fn permits(&self, cmd: &WasmCommand) -> bool {
+        self.capabilities.contains(&Capability::required_for(cmd))
+    }
 }
 
 /// Collection of plugin configs, sorted by priority.
@@ -62,15 +156,13 @@ mod tests {
                     plugin_id: "b".into(),
                     wasm_path: "b.wasm".into(),
                     priority: 10,
-                    fuel_limit: None,
-                    enabled: true,
+                    ..Default::default()
                 },
                 PluginConfig {
                     plugin_id: "a".into(),
                     wasm_path: "a.wasm".into(),
                     priority: 1,
-                    fuel_limit: None,
-                    enabled: true,
+                    ..Default::default()
                 },
             ],
         };
@@ -78,4 +170,56 @@ mod tests {
         assert_eq!(sorted[0].plugin_id, "a");
         assert_eq!(sorted[1].plugin_id, "b");
     }
+
+    #[test]
+    fn movement_only_capabilities() {
+        let config = PluginConfig {
+            plugin_id: "mover".into(),
+            wasm_path: "mover.wasm".into(),
+            priority: 1,
+            capabilities: vec![Capability::MoveEntities],
+            ..Default::default()
+        };
+        assert!(config.permits(&WasmCommand::MoveEntity {
+            entity_id: 1,
+            target_room_id: 2,
+        }));
+        assert!(!config.permits(&WasmCommand::DestroyEntity { entity_id: 1 }));
+        assert!(!config.permits(&WasmCommand::SetComponent {
+            entity_id: 1,
+            component_id: 1,
+            data: vec![],
+        }));
+    }
+
+    #[test]
+    fn capabilities_default_to_all_when_omitted() {
+        let json = r#"{
+            "plugin_id": "legacy",
+            "wasm_path": "legacy.wasm",
+            "priority": 0,
+            "fuel_limit": null,
+            "enabled": true
+        }"#;
+        let config: PluginConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.capabilities, Capability::ALL.to_vec());
+        assert_eq!(config.max_capability_violations, None);
+        assert_eq!(config.expected_sha256, None);
+        assert_eq!(config.min_log_level, plugin_abi::LOG_TRACE);
+        assert_eq!(config.max_logs_per_tick, Some(DEFAULT_MAX_LOGS_PER_TICK));
+    }
+
+    #[test]
+    fn default_matches_manifest_defaults() {
+        let json = r#"{
+            "plugin_id": "",
+            "wasm_path": "",
+            "priority": 0,
+            "fuel_limit": null,
+            "enabled": true
+        }"#;
+        let parsed: PluginConfig = serde_json::from_str(json).unwrap();
+        let default = PluginConfig::default();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::to_value(&default).unwrap());
+    }
 }
@@ -27,6 +27,29 @@ pub enum PluginError {
     #[error("plugin {0} not found")]
     PluginNotFound(String),
 
+    #[error("plugin {plugin_id} integrity check failed: expected sha256 {expected}, got {actual}")]
+    IntegrityMismatch {
+        plugin_id: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("plugin {plugin_id} built for ABI {found_major}.{found_minor}, host is {expected_major}.{expected_minor}")]
+    AbiMismatch {
+        plugin_id: String,
+        expected_major: u32,
+        expected_minor: u32,
+        found_major: u32,
+        found_minor: u32,
+    },
+
+    #[error("plugin {plugin_id} memory snapshot is {size} bytes, limit is {limit}")]
+    MemorySnapshotTooLarge {
+        plugin_id: String,
+        size: usize,
+        limit: usize,
+    },
+
     #[error("missing wasm export: {0}")]
     MissingExport(String),
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use plugin_abi::WasmCommand;
 use wasmtime::{Caller, Linker};
@@ -15,6 +15,16 @@ pub struct HostState {
     /// Cached component data for host_get_component.
     /// Key: (entity_id_u64, component_id_u32) → serialized bytes.
     pub component_data_cache: HashMap<(u64, u32), Vec<u8>>,
+    /// Component name → id, from the runtime's ComponentRegistry at load time.
+    pub component_names: BTreeMap<String, u32>,
+    /// `host_log` calls below this level (`plugin_abi::LOG_*`) are dropped.
+    pub min_log_level: u32,
+    /// `host_log` messages written per tick (None = unlimited).
+    pub max_logs_per_tick: Option<u32>,
+    /// Messages written so far this tick.
+    pub logs_this_tick: u32,
+    /// Messages dropped for exceeding `max_logs_per_tick`, since load.
+    pub logs_dropped: u64,
 }
 
 impl HostState {
@@ -24,8 +34,28 @@ impl HostState {
             random_seed: 0,
             pending_commands: Vec::new(),
             component_data_cache: HashMap::new(),
+            component_names: BTreeMap::new(),
+            min_log_level: plugin_abi::LOG_TRACE,
+            max_logs_per_tick: None,
+            logs_this_tick: 0,
+            logs_dropped: 0,
         }
     }
+
+    /// Whether a `host_log` call at `level` may be written. Sub-threshold
+    /// messages are ignored silently; over-rate ones are counted in
+    /// `logs_dropped`.
+    pub fn admit_log(&mut self, level: u32) -> bool {
+        if level < self.min_log_level {
+            return false;
+        }
+        if self.max_logs_per_tick.is_some_and(|max| self.logs_this_tick >= max) {
+            self.logs_dropped += 1;
+            return false;
+        }
+        self.logs_this_tick += 1;
+        true
+    }
 }
 
 impl Default for HostState {
@@ -66,10 +96,14 @@ pub fn register_host_functions(linker: &mut Linker<HostState>) -> Result<(), was
     )?;
 
     // host_log(level: u32, msg_ptr: u32, msg_len: u32)
+    // Filtered by the plugin's minimum level and per-tick message budget.
     linker.func_wrap(
         "env",
         "host_log",
         |mut caller: Caller<'_, HostState>, level: u32, msg_ptr: u32, msg_len: u32| {
+            if !caller.data_mut().admit_log(level) {
+                return;
+            }
             let memory = match caller.get_export("memory") {
                 Some(wasmtime::Extern::Memory(mem)) => mem,
                 _ => return,
@@ -150,15 +184,69 @@ pub fn register_host_functions(linker: &mut Linker<HostState>) -> Result<(), was
         },
     )?;
 
+    // host_resolve_component(name_ptr: u32, name_len: u32) -> i32
+    // Returns the component id, or RESULT_ERR_UNKNOWN_COMPONENT.
+    linker.func_wrap(
+        "env",
+        "host_resolve_component",
+        |mut caller: Caller<'_, HostState>, name_ptr: u32, name_len: u32| -> i32 {
+            let memory = match caller.get_export("memory") {
+                Some(wasmtime::Extern::Memory(mem)) => mem,
+                _ => return plugin_abi::RESULT_ERR_OUT_OF_BOUNDS,
+            };
+
+            let data = memory.data(&caller);
+            let start = name_ptr as usize;
+            let end = start + name_len as usize;
+            if end > data.len() {
+                return plugin_abi::RESULT_ERR_OUT_OF_BOUNDS;
+            }
+
+            let name = match std::str::from_utf8(&data[start..end]) {
+                Ok(name) => name,
+                Err(_) => return plugin_abi::RESULT_ERR_UNKNOWN_COMPONENT,
+            };
+            match caller.data().component_names.get(name) {
+                Some(&id) => i32::try_from(id).unwrap_or(plugin_abi::RESULT_ERR_UNKNOWN_COMPONENT),
+                None => plugin_abi::RESULT_ERR_UNKNOWN_COMPONENT,
+            }
+        },
+    )?;
+
     Ok(())
 }
 
-/// Generate a deterministic seed from tick and plugin ID.
-/// Same tick + same plugin = same seed (for deterministic PRNG in plugins).
-pub fn deterministic_seed(tick: u64, plugin_id: &str) -> u64 {
-    let mut hash: u64 = tick;
+/// Generate a deterministic seed from a base (the tick, or the engine's
+/// per-tick seed) and plugin ID.
+/// Same base + same plugin = same seed (for deterministic PRNG in plugins).
+pub fn deterministic_seed(base: u64, plugin_id: &str) -> u64 {
+    let mut hash: u64 = base;
     for byte in plugin_id.bytes() {
         hash = hash.wrapping_mul(31).wrapping_add(byte as u64);
     }
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admit_log_filters_level_and_caps_per_tick() {
+        let mut state = HostState::new();
+        state.min_log_level = plugin_abi::LOG_INFO;
+        state.max_logs_per_tick = Some(2);
+
+        assert!(!state.admit_log(plugin_abi::LOG_DEBUG));
+        assert!(state.admit_log(plugin_abi::LOG_INFO));
+        assert!(state.admit_log(plugin_abi::LOG_ERROR));
+        assert!(!state.admit_log(plugin_abi::LOG_ERROR));
+        assert_eq!(state.logs_dropped, 1);
+
+        // Sub-threshold messages never count against the budget.
+        state.logs_this_tick = 0;
+        assert!(!state.admit_log(plugin_abi::LOG_TRACE));
+        assert!(state.admit_log(plugin_abi::LOG_WARN));
+        assert_eq!(state.logs_dropped, 1);
+    }
+}
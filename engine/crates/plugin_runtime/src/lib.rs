@@ -6,7 +6,10 @@ pub mod plugin;
 pub mod registry;
 pub mod serializer;
 
+use std::collections::BTreeMap;
+
 use plugin_abi::WasmCommand;
+use sha2::{Digest, Sha256};
 use wasmtime::{Engine, Linker};
 
 use crate::config::{FuelConfig, PluginConfig};
@@ -15,20 +18,54 @@ use crate::host_api::HostState;
 use crate::plugin::LoadedPlugin;
 use crate::registry::ComponentRegistry;
 
+pub use crate::config::Capability;
 pub use crate::config::FuelConfig as FuelCfg;
 pub use crate::error::PluginError as Error;
-pub use crate::plugin::PluginState;
+pub use crate::plugin::{PluginState, MAX_MEMORY_SNAPSHOT_BYTES};
 pub use crate::registry::ComponentRegistry as Registry;
 pub use plugin_abi::WasmCommand as WasmCmd;
 
+/// A component write dropped because a higher-priority plugin already
+/// wrote the same (entity, component) this tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandConflict {
+    pub entity_id: u64,
+    pub component_id: u32,
+    /// Plugin whose write was kept.
+    pub kept_plugin: String,
+    /// Plugin whose write was dropped.
+    pub dropped_plugin: String,
+}
+
+/// Read-only summary of a loaded plugin, for admin listings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub id: String,
+    pub priority: u32,
+    pub quarantined: bool,
+    /// Effective per-tick fuel budget.
+    pub fuel_limit: u64,
+    /// `host_log` messages dropped by the per-tick log budget since load.
+    pub logs_dropped: u64,
+}
+
 /// The main WASM plugin runtime.
 /// Manages plugin loading, execution, and lifecycle.
+///
+/// Ordering guarantee: plugins run in ascending `priority` (lower = earlier,
+/// load order breaks ties), and their commands are returned in that order.
+/// When several plugins write the same (entity, component) in one tick via
+/// SetComponent/RemoveComponent, only the first plugin's writes survive —
+/// the highest-priority plugin wins, independent of who ran last.
 pub struct PluginRuntime {
     engine: Engine,
     linker: Linker<HostState>,
     plugins: Vec<LoadedPlugin>,
     fuel_config: FuelConfig,
     pub registry: ComponentRegistry,
+    last_conflicts: Vec<CommandConflict>,
+    /// Engine-provided seed for the next `run_tick`; None = derive from the tick.
+    tick_seed: Option<u64>,
 }
 
 impl PluginRuntime {
@@ -47,6 +84,8 @@ impl PluginRuntime {
             plugins: Vec::new(),
             fuel_config,
             registry: ComponentRegistry::new(),
+            last_conflicts: Vec::new(),
+            tick_seed: None,
         })
     }
 
@@ -57,61 +96,112 @@ impl PluginRuntime {
             return Ok(());
         }
 
-        let wasm_bytes = std::fs::read(&config.wasm_path).map_err(|e| {
-            PluginError::LoadError(format!(
-                "failed to read {}: {}",
-                config.wasm_path.display(),
-                e
-            ))
-        })?;
-
+        let wasm_bytes = read_wasm(config)?;
         self.load_plugin_from_bytes(&wasm_bytes, config)
     }
 
     /// Load a plugin from raw WASM bytes (useful for testing).
+    /// Rejects the bytes with `IntegrityMismatch` if `config.expected_sha256`
+    /// is set and does not match.
     pub fn load_plugin_from_bytes(
         &mut self,
         wasm_bytes: &[u8],
         config: &PluginConfig,
     ) -> Result<(), PluginError> {
-        let plugin = LoadedPlugin::from_bytes(
-            &self.engine,
-            wasm_bytes,
-            config,
-            &self.fuel_config,
-            &self.linker,
-        )?;
+        let plugin = self.instantiate(wasm_bytes, config)?;
 
         tracing::info!(
             plugin = %config.plugin_id,
             priority = config.priority,
             "plugin loaded"
         );
+        self.insert(plugin);
+        Ok(())
+    }
+
+    /// Verify, compile, and instantiate a plugin (running its `on_load`)
+    /// without adding it to the runtime.
+    fn instantiate(&self, wasm_bytes: &[u8], config: &PluginConfig) -> Result<LoadedPlugin, PluginError> {
+        if let Some(expected) = &config.expected_sha256 {
+            let actual = sha256_hex(wasm_bytes);
+            if !actual.eq_ignore_ascii_case(expected.trim()) {
+                return Err(PluginError::IntegrityMismatch {
+                    plugin_id: config.plugin_id.clone(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
 
-        // Insert maintaining priority order
+        LoadedPlugin::from_bytes(
+            &self.engine,
+            wasm_bytes,
+            config,
+            &self.fuel_config,
+            &self.linker,
+            self.registry.name_table(),
+        )
+    }
+
+    /// Insert maintaining priority order; equal priorities keep load order.
+    fn insert(&mut self, plugin: LoadedPlugin) {
         let pos = self
             .plugins
-            .binary_search_by_key(&plugin.priority, |p| p.priority)
-            .unwrap_or_else(|pos| pos);
+            .partition_point(|p| p.priority <= plugin.priority);
         self.plugins.insert(pos, plugin);
+    }
 
-        Ok(())
+    /// Seed the next `run_tick` from the engine's master seed. Each plugin
+    /// still gets its own `host_random_seed`, mixed with its id; with `None`
+    /// the tick number is used instead.
+    pub fn set_tick_seed(&mut self, seed: Option<u64>) {
+        self.tick_seed = seed;
     }
 
     /// Execute all active plugins for a tick.
     /// Returns collected WasmCommands from all plugins (in priority order).
-    /// Conversion to EngineCommand is the caller's responsibility.
+    /// Commands a plugin lacks the capability for are dropped here, as are
+    /// component writes that conflict with a higher-priority plugin (see
+    /// `last_tick_conflicts`). Conversion to EngineCommand is the caller's
+    /// responsibility.
     pub fn run_tick(&mut self, tick: u64) -> Vec<WasmCommand> {
         let mut all_commands = Vec::new();
+        // (entity, component) -> plugin that claimed it first this tick
+        let mut writers: BTreeMap<(u64, u32), String> = BTreeMap::new();
+        self.last_conflicts.clear();
 
         for plugin in &mut self.plugins {
             if plugin.is_quarantined() {
                 continue;
             }
 
-            match plugin.execute_tick(tick) {
+            let started = std::time::Instant::now();
+            let result = plugin.execute_tick(tick, self.tick_seed.unwrap_or(tick));
+            observability::record_plugin_cost(&plugin.id, started.elapsed());
+            match result {
                 PluginExecResult::Success(wasm_cmds) => {
-                    all_commands.extend(wasm_cmds);
+                    for cmd in plugin.enforce_capabilities(wasm_cmds, tick) {
+                        if let Some(key) = component_key(&cmd) {
+                            let owner = writers.entry(key).or_insert_with(|| plugin.id.clone());
+                            if *owner != plugin.id {
+                                tracing::debug!(
+                                    entity_id = key.0,
+                                    component_id = key.1,
+                                    kept = %owner,
+                                    dropped = %plugin.id,
+                                    "conflicting component write dropped"
+                                );
+                                self.last_conflicts.push(CommandConflict {
+                                    entity_id: key.0,
+                                    component_id: key.1,
+                                    kept_plugin: owner.clone(),
+                                    dropped_plugin: plugin.id.clone(),
+                                });
+                                continue;
+                            }
+                        }
+                        all_commands.push(cmd);
+                    }
                 }
                 PluginExecResult::FuelExceeded | PluginExecResult::Trapped(_) => {
                     // Commands already discarded inside execute_tick
@@ -122,15 +212,73 @@ impl PluginRuntime {
         all_commands
     }
 
-    /// Unload a plugin by ID.
-    pub fn unload_plugin(&mut self, plugin_id: &str) -> Result<(), PluginError> {
-        let pos = self
-            .plugins
+    /// Component writes dropped during the most recent `run_tick`, in the
+    /// order they were emitted.
+    pub fn last_tick_conflicts(&self) -> &[CommandConflict] {
+        &self.last_conflicts
+    }
+
+    /// Unload a plugin by ID, calling its `on_unload` export first if it
+    /// has one. Returns the commands `on_unload` emitted.
+    pub fn unload_plugin(&mut self, plugin_id: &str) -> Result<Vec<WasmCommand>, PluginError> {
+        let pos = self.position(plugin_id)?;
+        let mut plugin = self.plugins.remove(pos);
+        let commands = plugin.execute_unload();
+        tracing::info!(plugin = %plugin_id, commands = commands.len(), "plugin unloaded");
+        Ok(commands)
+    }
+
+    /// Replace a loaded plugin with a fresh instance from `config.wasm_path`.
+    /// The new instance is loaded first, so a bad module leaves the old one
+    /// in place; then the old instance's `on_unload` runs and its commands
+    /// are returned.
+    pub fn reload_plugin(&mut self, config: &PluginConfig) -> Result<Vec<WasmCommand>, PluginError> {
+        let wasm_bytes = read_wasm(config)?;
+        self.reload_plugin_from_bytes(&wasm_bytes, config)
+    }
+
+    /// `reload_plugin` with raw WASM bytes (useful for testing).
+    pub fn reload_plugin_from_bytes(
+        &mut self,
+        wasm_bytes: &[u8],
+        config: &PluginConfig,
+    ) -> Result<Vec<WasmCommand>, PluginError> {
+        let pos = self.position(&config.plugin_id)?;
+        let fresh = self.instantiate(wasm_bytes, config)?;
+        let mut old = self.plugins.remove(pos);
+        let commands = old.execute_unload();
+        self.insert(fresh);
+        tracing::info!(plugin = %config.plugin_id, commands = commands.len(), "plugin reloaded");
+        Ok(commands)
+    }
+
+    fn position(&self, plugin_id: &str) -> Result<usize, PluginError> {
+        self.plugins
             .iter()
             .position(|p| p.id == plugin_id)
-            .ok_or_else(|| PluginError::PluginNotFound(plugin_id.to_string()))?;
-        self.plugins.remove(pos);
-        tracing::info!(plugin = %plugin_id, "plugin unloaded");
+            .ok_or_else(|| PluginError::PluginNotFound(plugin_id.to_string()))
+    }
+
+    /// Linear memory of every loaded plugin, keyed by plugin id, for the
+    /// engine snapshot. Fails if any plugin's memory exceeds
+    /// `MAX_MEMORY_SNAPSHOT_BYTES`.
+    pub fn export_memory(&mut self) -> Result<BTreeMap<String, Vec<u8>>, PluginError> {
+        self.plugins
+            .iter_mut()
+            .map(|p| Ok((p.id.clone(), p.export_memory()?)))
+            .collect()
+    }
+
+    /// Restore plugin memory captured by `export_memory`. Entries for
+    /// plugins that are no longer loaded are skipped with a warning; loaded
+    /// plugins missing from `memory` keep their fresh state.
+    pub fn import_memory(&mut self, memory: &BTreeMap<String, Vec<u8>>) -> Result<(), PluginError> {
+        for (plugin_id, bytes) in memory {
+            match self.plugins.iter_mut().find(|p| &p.id == plugin_id) {
+                Some(plugin) => plugin.import_memory(bytes)?,
+                None => tracing::warn!(plugin = %plugin_id, "memory snapshot for unloaded plugin skipped"),
+            }
+        }
         Ok(())
     }
 
@@ -143,6 +291,20 @@ impl PluginRuntime {
             .collect()
     }
 
+    /// Describe every loaded plugin, in execution (priority) order.
+    pub fn list_plugins(&self) -> Vec<PluginInfo> {
+        self.plugins
+            .iter()
+            .map(|p| PluginInfo {
+                id: p.id.clone(),
+                priority: p.priority,
+                quarantined: p.is_quarantined(),
+                fuel_limit: p.fuel_limit,
+                logs_dropped: p.logs_dropped(),
+            })
+            .collect()
+    }
+
     /// Get number of loaded plugins.
     pub fn plugin_count(&self) -> usize {
         self.plugins.len()
@@ -153,3 +315,651 @@ impl PluginRuntime {
         self.plugins.iter().filter(|p| !p.is_quarantined()).count()
     }
 }
+
+fn read_wasm(config: &PluginConfig) -> Result<Vec<u8>, PluginError> {
+    std::fs::read(&config.wasm_path).map_err(|e| {
+        PluginError::LoadError(format!(
+            "failed to read {}: {}",
+            config.wasm_path.display(),
+            e
+        ))
+    })
+}
+
+/// The (entity, component) a command writes, if it is a component write.
+fn component_key(cmd: &WasmCommand) -> Option<(u64, u32)> {
+    match cmd {
+        WasmCommand::SetComponent {
+            entity_id,
+            component_id,
+            ..
+        }
+        | WasmCommand::RemoveComponent {
+            entity_id,
+            component_id,
+        } => Some((*entity_id, *component_id)),
+        _ => None,
+    }
+}
+
+/// Lowercase hex SHA-256 of a wasm binary, as expected in
+/// `PluginConfig::expected_sha256`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Capability;
+
+    /// Emits `MoveEntity { 42, 100 }` then `DestroyEntity { 7 }` every tick
+    /// (postcard-encoded in the data segment).
+    const MOVE_AND_DESTROY_WAT: &str = r#"
+        (module
+            (import "env" "host_emit_command" (func $emit (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\05\2a\64")
+            (data (i32.const 16) "\04\07")
+            (func (export "abi_version") (result i64) (i64.const 4294967296))
+            (func (export "on_tick") (param i64) (result i32)
+                (drop (call $emit (i32.const 0) (i32.const 3)))
+                (drop (call $emit (i32.const 16) (i32.const 2)))
+                (i32.const 0)))
+    "#;
+
+    fn plugin_config(capabilities: Vec<Capability>, max_violations: Option<u32>) -> PluginConfig {
+        PluginConfig {
+            plugin_id: "mover".into(),
+            wasm_path: "mover.wasm".into(),
+            priority: 1,
+            capabilities,
+            max_capability_violations: max_violations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn movement_only_plugin_destroy_dropped() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &plugin_config(vec![Capability::MoveEntities], None),
+            )
+            .unwrap();
+
+        let cmds = runtime.run_tick(0);
+        assert_eq!(
+            cmds,
+            vec![WasmCommand::MoveEntity {
+                entity_id: 42,
+                target_room_id: 100,
+            }]
+        );
+        // Without a violation limit the plugin keeps running.
+        runtime.run_tick(1);
+        assert_eq!(runtime.active_plugin_count(), 1);
+    }
+
+    #[test]
+    fn full_capabilities_pass_everything() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &plugin_config(Capability::ALL.to_vec(), None),
+            )
+            .unwrap();
+
+        let cmds = runtime.run_tick(0);
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[1], WasmCommand::DestroyEntity { entity_id: 7 });
+    }
+
+    #[test]
+    fn repeat_offender_quarantined() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &plugin_config(vec![Capability::MoveEntities], Some(2)),
+            )
+            .unwrap();
+
+        // Tick 0: first violation, allowed commands still delivered.
+        assert_eq!(runtime.run_tick(0).len(), 1);
+        assert!(runtime.quarantined_plugins().is_empty());
+
+        // Tick 1: second violation reaches the limit.
+        assert_eq!(runtime.run_tick(1).len(), 1);
+        assert_eq!(runtime.quarantined_plugins(), vec!["mover"]);
+
+        assert!(runtime.run_tick(2).is_empty());
+    }
+
+    #[test]
+    fn zero_violation_limit_spares_well_behaved_plugins() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &plugin_config(Capability::ALL.to_vec(), Some(0)),
+            )
+            .unwrap();
+
+        for tick in 0..3 {
+            assert_eq!(runtime.run_tick(tick).len(), 2);
+        }
+        assert!(runtime.quarantined_plugins().is_empty());
+    }
+
+    #[test]
+    fn zero_violation_limit_quarantines_on_first_violation() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &plugin_config(vec![Capability::MoveEntities], Some(0)),
+            )
+            .unwrap();
+
+        assert_eq!(runtime.run_tick(0).len(), 1);
+        assert_eq!(runtime.quarantined_plugins(), vec!["mover"]);
+    }
+
+    /// Emits `SetComponent { entity 5, component 3, data [value] }` every tick.
+    fn set_component_wat(value: u8) -> String {
+        format!(
+            r#"
+            (module
+                (import "env" "host_emit_command" (func $emit (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "\00\05\03\01\{:02x}")
+                (func (export "abi_version") (result i64) (i64.const 4294967296))
+                (func (export "on_tick") (param i64) (result i32)
+                    (drop (call $emit (i32.const 0) (i32.const 5)))
+                    (i32.const 0)))
+            "#,
+            value
+        )
+    }
+
+    fn named_config(id: &str, priority: u32) -> PluginConfig {
+        PluginConfig {
+            plugin_id: id.into(),
+            priority,
+            ..plugin_config(Capability::ALL.to_vec(), None)
+        }
+    }
+
+    #[test]
+    fn higher_priority_plugin_wins_component_conflict() {
+        // Load order is the reverse of priority order on purpose.
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(set_component_wat(2).as_bytes(), &named_config("late", 20))
+            .unwrap();
+        runtime
+            .load_plugin_from_bytes(set_component_wat(1).as_bytes(), &named_config("early", 10))
+            .unwrap();
+
+        for tick in 0..3 {
+            let cmds = runtime.run_tick(tick);
+            assert_eq!(
+                cmds,
+                vec![WasmCommand::SetComponent {
+                    entity_id: 5,
+                    component_id: 3,
+                    data: vec![1],
+                }]
+            );
+            assert_eq!(
+                runtime.last_tick_conflicts(),
+                &[CommandConflict {
+                    entity_id: 5,
+                    component_id: 3,
+                    kept_plugin: "early".into(),
+                    dropped_plugin: "late".into(),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn list_plugins_reports_order_and_status() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                MOVE_AND_DESTROY_WAT.as_bytes(),
+                &PluginConfig {
+                    plugin_id: "offender".into(),
+                    priority: 20,
+                    fuel_limit: Some(5_000),
+                    ..plugin_config(vec![Capability::MoveEntities], Some(1))
+                },
+            )
+            .unwrap();
+        runtime
+            .load_plugin_from_bytes(set_component_wat(1).as_bytes(), &named_config("writer", 10))
+            .unwrap();
+        assert!(runtime.list_plugins().iter().all(|p| !p.quarantined));
+
+        // The offender's DestroyEntity lacks a capability and trips its limit.
+        runtime.run_tick(0);
+
+        let default_fuel = FuelConfig::default().default_fuel_limit;
+        assert_eq!(
+            runtime.list_plugins(),
+            vec![
+                PluginInfo {
+                    id: "writer".into(),
+                    priority: 10,
+                    quarantined: false,
+                    fuel_limit: default_fuel,
+                    logs_dropped: 0,
+                },
+                PluginInfo {
+                    id: "offender".into(),
+                    priority: 20,
+                    quarantined: true,
+                    fuel_limit: 5_000,
+                    logs_dropped: 0,
+                },
+            ]
+        );
+    }
+
+    /// Calls `host_log` 50 times per tick at level `tick` (so the test picks
+    /// the level by choosing the tick number).
+    const CHATTY_WAT: &str = r#"
+        (module
+            (import "env" "host_log" (func $log (param i32 i32 i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "spam")
+            (func (export "abi_version") (result i64) (i64.const 4294967296))
+            (func (export "on_tick") (param i64) (result i32)
+                (local $i i32)
+                (loop $again
+                    (call $log (i32.wrap_i64 (local.get 0)) (i32.const 0) (i32.const 4))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br_if $again (i32.lt_u (local.get $i) (i32.const 50))))
+                (i32.const 0)))
+    "#;
+
+    #[test]
+    fn host_log_capped_per_tick_and_filtered_by_level() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let config = PluginConfig {
+            min_log_level: plugin_abi::LOG_INFO,
+            max_logs_per_tick: Some(5),
+            ..named_config("chatty", 1)
+        };
+        runtime
+            .load_plugin_from_bytes(CHATTY_WAT.as_bytes(), &config)
+            .unwrap();
+        let dropped = |rt: &PluginRuntime| rt.list_plugins()[0].logs_dropped;
+
+        // Tick 1 logs at LOG_DEBUG: below the threshold, nothing written or counted.
+        runtime.run_tick(plugin_abi::LOG_DEBUG as u64);
+        assert_eq!(dropped(&runtime), 0);
+
+        // Tick 3 logs at LOG_WARN: 5 written, 45 dropped.
+        runtime.run_tick(plugin_abi::LOG_WARN as u64);
+        assert_eq!(dropped(&runtime), 45);
+
+        // The budget resets each tick.
+        runtime.run_tick(plugin_abi::LOG_ERROR as u64);
+        assert_eq!(dropped(&runtime), 90);
+        assert_eq!(runtime.active_plugin_count(), 1);
+    }
+
+    #[test]
+    fn traps_and_fuel_exhaustion_counted_in_metrics() {
+        use observability::metrics::{counters, PLUGIN_FUEL_EXHAUSTED_TOTAL, PLUGIN_TRAPS_TOTAL};
+
+        const TRAP_WAT: &str = r#"
+            (module
+                (func (export "abi_version") (result i64) (i64.const 4294967296))
+                (func (export "on_tick") (param i64) (result i32) unreachable))
+        "#;
+        const SPIN_WAT: &str = r#"
+            (module
+                (func (export "abi_version") (result i64) (i64.const 4294967296))
+                (func (export "on_tick") (param i64) (result i32)
+                    (loop $spin (br $spin))
+                    (i32.const 0)))
+        "#;
+
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(TRAP_WAT.as_bytes(), &named_config("metrics_trap", 1))
+            .unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                SPIN_WAT.as_bytes(),
+                &PluginConfig {
+                    fuel_limit: Some(10_000),
+                    ..named_config("metrics_spin", 2)
+                },
+            )
+            .unwrap();
+
+        let traps = counters().get(PLUGIN_TRAPS_TOTAL, "metrics_trap");
+        let fuel = counters().get(PLUGIN_FUEL_EXHAUSTED_TOTAL, "metrics_spin");
+        runtime.run_tick(0);
+        runtime.run_tick(1);
+        assert_eq!(counters().get(PLUGIN_TRAPS_TOTAL, "metrics_trap"), traps + 2);
+        assert_eq!(counters().get(PLUGIN_FUEL_EXHAUSTED_TOTAL, "metrics_spin"), fuel + 2);
+        // Each kind is only counted under its own metric
+        assert_eq!(counters().get(PLUGIN_TRAPS_TOTAL, "metrics_spin"), 0);
+    }
+
+    #[test]
+    fn equal_priority_keeps_load_order() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(set_component_wat(1).as_bytes(), &named_config("first", 5))
+            .unwrap();
+        runtime
+            .load_plugin_from_bytes(set_component_wat(2).as_bytes(), &named_config("second", 5))
+            .unwrap();
+
+        let cmds = runtime.run_tick(0);
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(runtime.last_tick_conflicts()[0].kept_plugin, "first");
+    }
+
+    /// on_load returns 0 only if "Health" resolves to 7 and "Bogus" to
+    /// RESULT_ERR_UNKNOWN_COMPONENT.
+    const RESOLVE_WAT: &str = r#"
+        (module
+            (import "env" "host_resolve_component" (func $resolve (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "Health")
+            (data (i32.const 16) "Bogus")
+            (func (export "on_load") (result i32)
+                (if (i32.ne (call $resolve (i32.const 0) (i32.const 6)) (i32.const 7))
+                    (then (return (i32.const 1))))
+                (if (i32.ne (call $resolve (i32.const 16) (i32.const 5)) (i32.const -3))
+                    (then (return (i32.const 2))))
+                (i32.const 0))
+            (func (export "abi_version") (result i64) (i64.const 4294967296))
+            (func (export "on_tick") (param i64) (result i32)
+                (i32.const 0)))
+    "#;
+
+    #[derive(ecs_adapter::Component, serde::Serialize)]
+    struct Health(i32);
+
+    #[test]
+    fn plugin_resolves_component_by_name() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .registry
+            .register_named::<Health>(ecs_adapter::ComponentId(7), "Health");
+        runtime
+            .load_plugin_from_bytes(
+                RESOLVE_WAT.as_bytes(),
+                &plugin_config(Capability::ALL.to_vec(), None),
+            )
+            .unwrap();
+        assert_eq!(runtime.plugin_count(), 1);
+    }
+
+    #[test]
+    fn unresolved_component_fails_on_load() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let err = runtime
+            .load_plugin_from_bytes(
+                RESOLVE_WAT.as_bytes(),
+                &plugin_config(Capability::ALL.to_vec(), None),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("error code: 1"), "{}", err);
+    }
+
+    /// A plugin reporting `version` (packed) from its `abi_version` export.
+    fn versioned_wat(version: u64) -> String {
+        format!(
+            r#"
+            (module
+                (func (export "abi_version") (result i64) (i64.const {}))
+                (func (export "on_tick") (param i64) (result i32)
+                    (i32.const 0)))
+            "#,
+            version
+        )
+    }
+
+    #[test]
+    fn abi_major_mismatch_rejected() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let wat = versioned_wat(plugin_abi::pack_abi_version(plugin_abi::ABI_VERSION_MAJOR + 1, 0));
+        let err = runtime
+            .load_plugin_from_bytes(wat.as_bytes(), &plugin_config(Capability::ALL.to_vec(), None))
+            .unwrap_err();
+        assert!(
+            matches!(err, PluginError::AbiMismatch { found_major, .. } if found_major == plugin_abi::ABI_VERSION_MAJOR + 1),
+            "{}",
+            err
+        );
+        assert_eq!(runtime.plugin_count(), 0);
+    }
+
+    #[test]
+    fn abi_minor_difference_accepted() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let wat = versioned_wat(plugin_abi::pack_abi_version(
+            plugin_abi::ABI_VERSION_MAJOR,
+            plugin_abi::ABI_VERSION_MINOR + 3,
+        ));
+        runtime
+            .load_plugin_from_bytes(wat.as_bytes(), &plugin_config(Capability::ALL.to_vec(), None))
+            .unwrap();
+        assert_eq!(runtime.plugin_count(), 1);
+    }
+
+    #[test]
+    fn missing_abi_version_export_rejected() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let wat = r#"(module (func (export "on_tick") (param i64) (result i32) (i32.const 0)))"#;
+        let err = runtime
+            .load_plugin_from_bytes(wat.as_bytes(), &plugin_config(Capability::ALL.to_vec(), None))
+            .unwrap_err();
+        assert!(matches!(err, PluginError::MissingExport(ref e) if e.starts_with("abi_version")), "{}", err);
+    }
+
+    #[test]
+    fn sha256_hex_known_vector() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn matching_hash_loads() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let wasm = MOVE_AND_DESTROY_WAT.as_bytes();
+        let config = PluginConfig {
+            expected_sha256: Some(sha256_hex(wasm).to_uppercase()),
+            plugin_id: "mover".into(),
+            ..Default::default()
+        };
+        runtime.load_plugin_from_bytes(wasm, &config).unwrap();
+        assert_eq!(runtime.plugin_count(), 1);
+    }
+
+    #[test]
+    fn mismatched_hash_is_rejected() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let config = PluginConfig {
+            expected_sha256: Some(sha256_hex(b"some other build")),
+            plugin_id: "mover".into(),
+            ..Default::default()
+        };
+        let err = runtime
+            .load_plugin_from_bytes(MOVE_AND_DESTROY_WAT.as_bytes(), &config)
+            .unwrap_err();
+        match err {
+            PluginError::IntegrityMismatch { plugin_id, actual, .. } => {
+                assert_eq!(plugin_id, "mover");
+                assert_eq!(actual, sha256_hex(MOVE_AND_DESTROY_WAT.as_bytes()));
+            }
+            other => panic!("expected IntegrityMismatch, got {}", other),
+        }
+        assert_eq!(runtime.plugin_count(), 0);
+    }
+
+    /// Keeps a counter in linear memory: each tick bumps the data byte of a
+    /// `SetComponent { entity 5, component 3, data [n] }` and emits it. The
+    /// first tick also grows memory to two pages.
+    const COUNTER_WAT: &str = r#"
+        (module
+            (import "env" "host_emit_command" (func $emit (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\00\05\03\01\00")
+            (func (export "abi_version") (result i64) (i64.const 4294967296))
+            (func (export "on_tick") (param i64) (result i32)
+                (if (i32.eq (memory.size) (i32.const 1))
+                    (then (drop (memory.grow (i32.const 1)))))
+                (i32.store8 (i32.const 4) (i32.add (i32.load8_u (i32.const 4)) (i32.const 1)))
+                (drop (call $emit (i32.const 0) (i32.const 5)))
+                (i32.const 0)))
+    "#;
+
+    fn counter_value(cmds: &[WasmCommand]) -> u8 {
+        match cmds {
+            [WasmCommand::SetComponent { data, .. }] => data[0],
+            other => panic!("unexpected commands: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn plugin_memory_survives_snapshot_and_restore() {
+        let config = plugin_config(Capability::ALL.to_vec(), None);
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime.load_plugin_from_bytes(COUNTER_WAT.as_bytes(), &config).unwrap();
+        for tick in 0..3 {
+            assert_eq!(counter_value(&runtime.run_tick(tick)), tick as u8 + 1);
+        }
+        let snapshot = runtime.export_memory().unwrap();
+        assert_eq!(snapshot["mover"].len(), 2 * 64 * 1024);
+
+        // A fresh runtime starts over unless the memory is restored.
+        let mut restored = PluginRuntime::new(FuelConfig::default()).unwrap();
+        restored.load_plugin_from_bytes(COUNTER_WAT.as_bytes(), &config).unwrap();
+        restored.import_memory(&snapshot).unwrap();
+        assert_eq!(counter_value(&restored.run_tick(3)), 4);
+        assert_eq!(counter_value(&runtime.run_tick(3)), 4);
+
+        // Unknown plugins are skipped.
+        let mut extra = snapshot.clone();
+        extra.insert("gone".into(), vec![0; 64 * 1024]);
+        restored.import_memory(&extra).unwrap();
+    }
+
+    #[test]
+    fn plugin_memory_import_is_bounded() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(COUNTER_WAT.as_bytes(), &plugin_config(Capability::ALL.to_vec(), None))
+            .unwrap();
+
+        let mut too_large = BTreeMap::new();
+        too_large.insert("mover".to_string(), vec![0; MAX_MEMORY_SNAPSHOT_BYTES + 64 * 1024]);
+        assert!(matches!(
+            runtime.import_memory(&too_large),
+            Err(PluginError::MemorySnapshotTooLarge { size, limit, .. })
+                if size > limit && limit == MAX_MEMORY_SNAPSHOT_BYTES
+        ));
+
+        let mut ragged = BTreeMap::new();
+        ragged.insert("mover".to_string(), vec![0; 100]);
+        assert!(matches!(
+            runtime.import_memory(&ragged),
+            Err(PluginError::SerializationError(_))
+        ));
+        // The failed imports left the plugin untouched.
+        assert_eq!(counter_value(&runtime.run_tick(0)), 1);
+    }
+
+    /// Emits nothing per tick; `on_unload` emits `DestroyEntity { 7 }`.
+    const UNLOAD_FLUSH_WAT: &str = r#"
+        (module
+            (import "env" "host_emit_command" (func $emit (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\04\07")
+            (func (export "abi_version") (result i64) (i64.const 4294967296))
+            (func (export "on_tick") (param i64) (result i32)
+                (i32.const 0))
+            (func (export "on_unload") (result i32)
+                (drop (call $emit (i32.const 0) (i32.const 2)))
+                (i32.const 0)))
+    "#;
+
+    #[test]
+    fn unload_collects_on_unload_commands() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let config = plugin_config(Capability::ALL.to_vec(), None);
+        runtime
+            .load_plugin_from_bytes(UNLOAD_FLUSH_WAT.as_bytes(), &config)
+            .unwrap();
+        assert!(runtime.run_tick(0).is_empty());
+
+        let cmds = runtime.unload_plugin("mover").unwrap();
+        assert_eq!(cmds, vec![WasmCommand::DestroyEntity { entity_id: 7 }]);
+        assert_eq!(runtime.plugin_count(), 0);
+
+        // Plugins without the export unload cleanly with no commands
+        runtime
+            .load_plugin_from_bytes(MOVE_AND_DESTROY_WAT.as_bytes(), &config)
+            .unwrap();
+        assert!(runtime.unload_plugin("mover").unwrap().is_empty());
+    }
+
+    #[test]
+    fn on_unload_commands_respect_capabilities() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        runtime
+            .load_plugin_from_bytes(
+                UNLOAD_FLUSH_WAT.as_bytes(),
+                &plugin_config(vec![Capability::MoveEntities], None),
+            )
+            .unwrap();
+        assert!(runtime.unload_plugin("mover").unwrap().is_empty());
+    }
+
+    #[test]
+    fn reload_runs_old_on_unload_and_swaps_instance() {
+        let mut runtime = PluginRuntime::new(FuelConfig::default()).unwrap();
+        let config = plugin_config(Capability::ALL.to_vec(), None);
+        runtime
+            .load_plugin_from_bytes(UNLOAD_FLUSH_WAT.as_bytes(), &config)
+            .unwrap();
+
+        // A module that fails to compile leaves the old instance running
+        assert!(runtime.reload_plugin_from_bytes(b"not wasm", &config).is_err());
+        assert_eq!(runtime.plugin_count(), 1);
+
+        let cmds = runtime
+            .reload_plugin_from_bytes(MOVE_AND_DESTROY_WAT.as_bytes(), &config)
+            .unwrap();
+        assert_eq!(cmds, vec![WasmCommand::DestroyEntity { entity_id: 7 }]);
+        assert_eq!(runtime.plugin_count(), 1);
+        assert_eq!(runtime.run_tick(1).len(), 2);
+
+        let missing = PluginConfig {
+            plugin_id: "ghost".into(),
+            ..config
+        };
+        assert!(matches!(
+            runtime.reload_plugin_from_bytes(MOVE_AND_DESTROY_WAT.as_bytes(), &missing),
+            Err(PluginError::PluginNotFound(_))
+        ));
+    }
+}
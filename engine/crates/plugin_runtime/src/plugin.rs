@@ -1,9 +1,16 @@
-use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+use plugin_abi::WasmCommand;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
 
-use crate::config::{FuelConfig, PluginConfig};
+use crate::config::{Capability, FuelConfig, PluginConfig};
 use crate::error::{PluginError, PluginExecResult};
 use crate::host_api::{deterministic_seed, HostState};
 
+/// Largest linear memory `export_memory`/`import_memory` will copy (16 MiB).
+pub const MAX_MEMORY_SNAPSHOT_BYTES: usize = 16 * 1024 * 1024;
+
+/// WASM page size in bytes.
+const WASM_PAGE_SIZE: usize = 64 * 1024;
+
 /// Plugin lifecycle state.
 #[derive(Debug, Clone)]
 pub enum PluginState {
@@ -22,10 +29,14 @@ pub struct LoadedPlugin {
     pub state: PluginState,
     pub consecutive_failures: u32,
     max_consecutive_failures: u32,
+    pub capabilities: Vec<Capability>,
+    /// Total commands dropped for lacking a capability.
+    pub capability_violations: u32,
+    max_capability_violations: Option<u32>,
     store: Store<HostState>,
-    #[allow(dead_code)]
     instance: Instance,
     fn_on_tick: TypedFunc<u64, i32>,
+    fn_on_unload: Option<TypedFunc<(), i32>>,
 }
 
 impl LoadedPlugin {
@@ -36,11 +47,16 @@ impl LoadedPlugin {
         config: &PluginConfig,
         fuel_config: &FuelConfig,
         linker: &wasmtime::Linker<HostState>,
+        component_names: std::collections::BTreeMap<String, u32>,
     ) -> Result<Self, PluginError> {
         let module = Module::new(engine, wasm_bytes)
             .map_err(|e| PluginError::LoadError(format!("failed to compile module: {}", e)))?;
 
-        let mut store = Store::new(engine, HostState::new());
+        let mut host_state = HostState::new();
+        host_state.component_names = component_names;
+        host_state.min_log_level = config.min_log_level;
+        host_state.max_logs_per_tick = config.max_logs_per_tick;
+        let mut store = Store::new(engine, host_state);
         store.set_fuel(fuel_config.default_fuel_limit).map_err(|e| {
             PluginError::LoadError(format!("failed to set initial fuel: {}", e))
         })?;
@@ -48,10 +64,14 @@ impl LoadedPlugin {
         let instance = linker.instantiate(&mut store, &module)
             .map_err(|e| PluginError::LoadError(format!("failed to instantiate: {}", e)))?;
 
+        check_abi_version(&instance, &mut store, &config.plugin_id, fuel_config.default_fuel_limit)?;
+
         let fn_on_tick = instance
             .get_typed_func::<u64, i32>(&mut store, "on_tick")
             .map_err(|e| PluginError::MissingExport(format!("on_tick: {}", e)))?;
 
+        let fn_on_unload = instance.get_typed_func::<(), i32>(&mut store, "on_unload").ok();
+
         // Call on_load if exported
         if let Ok(on_load) = instance.get_typed_func::<(), i32>(&mut store, "on_load") {
             store.set_fuel(fuel_config.default_fuel_limit)?;
@@ -81,9 +101,13 @@ impl LoadedPlugin {
             state: PluginState::Active,
             consecutive_failures: 0,
             max_consecutive_failures: fuel_config.max_consecutive_failures,
+            capabilities: config.capabilities.clone(),
+            capability_violations: 0,
+            max_capability_violations: config.max_capability_violations,
             store,
             instance,
             fn_on_tick,
+            fn_on_unload,
         })
     }
 
@@ -92,16 +116,23 @@ impl LoadedPlugin {
         matches!(self.state, PluginState::Quarantined { .. })
     }
 
+    /// `host_log` messages dropped for exceeding the per-tick budget.
+    pub fn logs_dropped(&self) -> u64 {
+        self.store.data().logs_dropped
+    }
+
     /// Execute on_tick for this plugin. Returns collected commands or failure info.
-    pub fn execute_tick(&mut self, tick: u64) -> PluginExecResult {
+    /// `seed_base` is mixed with the plugin id to form `host_random_seed`.
+    pub fn execute_tick(&mut self, tick: u64, seed_base: u64) -> PluginExecResult {
         if self.is_quarantined() {
             return PluginExecResult::Trapped(format!("plugin {} is quarantined", self.id));
         }
 
         // Prepare host state for this tick
         self.store.data_mut().current_tick = tick;
-        self.store.data_mut().random_seed = deterministic_seed(tick, &self.id);
+        self.store.data_mut().random_seed = deterministic_seed(seed_base, &self.id);
         self.store.data_mut().pending_commands.clear();
+        self.store.data_mut().logs_this_tick = 0;
 
         // Refill fuel
         if let Err(e) = self.store.set_fuel(self.fuel_limit) {
@@ -143,6 +174,7 @@ impl LoadedPlugin {
                         consecutive = self.consecutive_failures,
                         "plugin fuel exhausted — commands discarded"
                     );
+                    observability::metrics::record_plugin_fuel_exhausted(&self.id);
                     self.maybe_quarantine(tick);
                     PluginExecResult::FuelExceeded
                 } else {
@@ -154,6 +186,7 @@ impl LoadedPlugin {
                         error = %msg,
                         "plugin trapped — commands discarded"
                     );
+                    observability::metrics::record_plugin_trap(&self.id);
                     self.maybe_quarantine(tick);
                     PluginExecResult::Trapped(msg)
                 }
@@ -161,6 +194,145 @@ impl LoadedPlugin {
         }
     }
 
+    /// Call `on_unload` if exported, with the plugin's normal fuel budget.
+    /// Returns the commands it emitted (capability-checked), or nothing if
+    /// the export is absent, the plugin is quarantined, or the call traps.
+    pub fn execute_unload(&mut self) -> Vec<WasmCommand> {
+        let Some(on_unload) = self.fn_on_unload.clone() else {
+            return Vec::new();
+        };
+        if self.is_quarantined() {
+            return Vec::new();
+        }
+
+        self.store.data_mut().pending_commands.clear();
+        if let Err(e) = self.store.set_fuel(self.fuel_limit) {
+            tracing::warn!(plugin = %self.id, error = %e, "failed to set fuel for on_unload");
+            return Vec::new();
+        }
+
+        let tick = self.store.data().current_tick;
+        match on_unload.call(&mut self.store, ()) {
+            Ok(code) => {
+                if code != plugin_abi::RESULT_OK {
+                    tracing::warn!(plugin = %self.id, error_code = code, "on_unload returned error code");
+                }
+                let commands = std::mem::take(&mut self.store.data_mut().pending_commands);
+                self.enforce_capabilities(commands, tick)
+            }
+            Err(trap) => {
+                self.store.data_mut().pending_commands.clear();
+                tracing::warn!(
+                    plugin = %self.id,
+                    error = %trap,
+                    "on_unload trapped — commands discarded"
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Copy the plugin's exported linear memory, so state a plugin keeps in
+    /// `static`s survives a snapshot. Mutable wasm globals are not captured;
+    /// plugins must not keep state in them across calls.
+    pub fn export_memory(&mut self) -> Result<Vec<u8>, PluginError> {
+        let memory = self.memory()?;
+        let data = memory.data(&self.store);
+        if data.len() > MAX_MEMORY_SNAPSHOT_BYTES {
+            return Err(PluginError::MemorySnapshotTooLarge {
+                plugin_id: self.id.clone(),
+                size: data.len(),
+                limit: MAX_MEMORY_SNAPSHOT_BYTES,
+            });
+        }
+        Ok(data.to_vec())
+    }
+
+    /// Overwrite the plugin's linear memory with bytes from `export_memory`.
+    /// Memory is grown to fit; anything past the snapshot is zeroed.
+    pub fn import_memory(&mut self, bytes: &[u8]) -> Result<(), PluginError> {
+        if bytes.len() > MAX_MEMORY_SNAPSHOT_BYTES {
+            return Err(PluginError::MemorySnapshotTooLarge {
+                plugin_id: self.id.clone(),
+                size: bytes.len(),
+                limit: MAX_MEMORY_SNAPSHOT_BYTES,
+            });
+        }
+        if !bytes.len().is_multiple_of(WASM_PAGE_SIZE) {
+            return Err(PluginError::SerializationError(format!(
+                "memory snapshot for {} is {} bytes, not a whole number of pages",
+                self.id,
+                bytes.len()
+            )));
+        }
+
+        let memory = self.memory()?;
+        let current = memory.data_size(&self.store);
+        if bytes.len() > current {
+            let delta = ((bytes.len() - current) / WASM_PAGE_SIZE) as u64;
+            memory.grow(&mut self.store, delta).map_err(|e| {
+                PluginError::LoadError(format!("memory grow failed for {}: {}", self.id, e))
+            })?;
+        }
+        let data = memory.data_mut(&mut self.store);
+        data[..bytes.len()].copy_from_slice(bytes);
+        data[bytes.len()..].fill(0);
+        Ok(())
+    }
+
+    fn memory(&mut self) -> Result<Memory, PluginError> {
+        self.instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or_else(|| PluginError::MissingExport(format!("memory ({})", self.id)))
+    }
+
+    /// Drop commands this plugin is not authorized to emit.
+    /// Each dropped command counts as a violation; reaching the configured
+    /// limit quarantines the plugin. The limit is only checked on ticks that
+    /// add a violation, so a plugin that never violates is never quarantined.
+    pub fn enforce_capabilities(
+        &mut self,
+        commands: Vec<WasmCommand>,
+        tick: u64,
+    ) -> Vec<WasmCommand> {
+        let violations_before = self.capability_violations;
+        let mut allowed = Vec::with_capacity(commands.len());
+        for cmd in commands {
+            let required = Capability::required_for(&cmd);
+            if self.capabilities.contains(&required) {
+                allowed.push(cmd);
+                continue;
+            }
+            self.capability_violations += 1;
+            tracing::warn!(
+                plugin = %self.id,
+                tick = tick,
+                capability = ?required,
+                violations = self.capability_violations,
+                "plugin emitted unauthorized command — dropped"
+            );
+        }
+
+        let violated_this_tick = self.capability_violations > violations_before;
+        if let Some(max) = self.max_capability_violations {
+            if violated_this_tick && self.capability_violations >= max && !self.is_quarantined() {
+                let reason = format!("{} capability violations", self.capability_violations);
+                tracing::error!(
+                    plugin = %self.id,
+                    tick = tick,
+                    reason = %reason,
+                    "plugin quarantined"
+                );
+                self.state = PluginState::Quarantined {
+                    since_tick: tick,
+                    reason,
+                };
+            }
+        }
+
+        allowed
+    }
+
     /// Populate the component data cache from the ECS for this plugin's tick.
     pub fn populate_component_cache(
         &mut self,
@@ -196,6 +368,38 @@ impl std::fmt::Debug for LoadedPlugin {
             .field("priority", &self.priority)
             .field("state", &self.state)
             .field("consecutive_failures", &self.consecutive_failures)
+            .field("capabilities", &self.capabilities)
+            .field("capability_violations", &self.capability_violations)
             .finish()
     }
 }
+
+/// Call the required `abi_version() -> u64` export and reject plugins built
+/// against a different ABI major version. Minor versions are additive and
+/// accepted either way.
+fn check_abi_version(
+    instance: &Instance,
+    store: &mut Store<HostState>,
+    plugin_id: &str,
+    fuel: u64,
+) -> Result<(), PluginError> {
+    let abi_version = instance
+        .get_typed_func::<(), u64>(&mut *store, "abi_version")
+        .map_err(|e| PluginError::MissingExport(format!("abi_version: {}", e)))?;
+    store.set_fuel(fuel)?;
+    let packed = abi_version
+        .call(&mut *store, ())
+        .map_err(|e| PluginError::LoadError(format!("abi_version trapped: {}", e)))?;
+
+    let (found_major, found_minor) = plugin_abi::unpack_abi_version(packed);
+    if found_major != plugin_abi::ABI_VERSION_MAJOR {
+        return Err(PluginError::AbiMismatch {
+            plugin_id: plugin_id.to_string(),
+            expected_major: plugin_abi::ABI_VERSION_MAJOR,
+            expected_minor: plugin_abi::ABI_VERSION_MINOR,
+            found_major,
+            found_minor,
+        });
+    }
+    Ok(())
+}
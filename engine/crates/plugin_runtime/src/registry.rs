@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use ecs_adapter::{ComponentId, EcsAdapter, EntityId};
 use serde::Serialize;
@@ -42,6 +42,8 @@ where
 #[derive(Default)]
 pub struct ComponentRegistry {
     serializers: HashMap<ComponentId, Box<dyn ComponentSerializer>>,
+    /// Component name → id, for plugins resolving ids via host_resolve_component.
+    names: BTreeMap<String, ComponentId>,
 }
 
 impl ComponentRegistry {
@@ -63,6 +65,26 @@ impl ComponentRegistry {
         );
     }
 
+    /// Register a component type under both its ComponentId and a name
+    /// plugins can resolve at load time.
+    pub fn register_named<C>(&mut self, component_id: ComponentId, name: &str)
+    where
+        C: ecs_adapter::Component + Serialize + 'static,
+    {
+        self.register::<C>(component_id);
+        self.names.insert(name.to_string(), component_id);
+    }
+
+    /// Look up a component id by registered name.
+    pub fn resolve(&self, name: &str) -> Option<ComponentId> {
+        self.names.get(name).copied()
+    }
+
+    /// Name → raw id table handed to each plugin's host state.
+    pub fn name_table(&self) -> BTreeMap<String, u32> {
+        self.names.iter().map(|(n, id)| (n.clone(), id.0)).collect()
+    }
+
     /// Serialize a component for a given entity using its ComponentId.
     pub fn serialize_component(
         &self,
@@ -128,6 +150,17 @@ mod tests {
         assert!(registry.serialize_component(&ecs, fake_entity, ComponentId(1)).is_err());
     }
 
+    #[test]
+    fn register_named_resolves_by_name() {
+        let mut registry = ComponentRegistry::new();
+        registry.register_named::<Health>(ComponentId(10), "Health");
+
+        assert_eq!(registry.resolve("Health"), Some(ComponentId(10)));
+        assert_eq!(registry.resolve("health"), None);
+        assert!(registry.has_component(ComponentId(10)));
+        assert_eq!(registry.name_table().get("Health"), Some(&10));
+    }
+
     #[test]
     fn unregistered_component_id_returns_error() {
         let registry = ComponentRegistry::new();
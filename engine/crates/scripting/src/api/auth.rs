@@ -137,6 +137,8 @@ impl UserData for AuthProxy {
                     t.set("name", detail.name)?;
                     let comp_val: mlua::Value = lua.to_value(&detail.components)?;
                     t.set("components", comp_val)?;
+                    let data_val: mlua::Value = lua.to_value(&detail.script_data)?;
+                    t.set("script_data", data_val)?;
                     if let Some(rid) = detail.room_id {
                         t.set("room_id", rid)?;
                     }
@@ -146,6 +148,28 @@ impl UserData for AuthProxy {
             }
         });
 
+        // auth:get_script_data(character_id) -> table
+        methods.add_method("get_script_data", |lua, this, character_id: i64| {
+            let result = this.with_provider(|p| p.load_character(character_id));
+            match result {
+                Ok(detail) => lua.to_value(&detail.script_data),
+                Err(e) => Err(mlua::Error::runtime(format!("{}", e))),
+            }
+        });
+
+        // auth:save_script_data(character_id, data_table)
+        methods.add_method(
+            "save_script_data",
+            |lua, this, (character_id, data): (i64, mlua::Value)| {
+                let data_json: serde_json::Value = lua.from_value(data)?;
+                let result = this.with_provider(|p| p.save_script_data(character_id, &data_json));
+                match result {
+                    Ok(()) => Ok(()),
+                    Err(e) => Err(mlua::Error::runtime(format!("{}", e))),
+                }
+            },
+        );
+
         // auth:save_character(character_id, components_table, room_id_or_nil)
         methods.add_method(
             "save_character",
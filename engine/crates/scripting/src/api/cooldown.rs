@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use mlua::{Lua, Result as LuaResult};
+
+/// Per-key cooldown timers for `cooldown.*`, stored in Lua app data.
+/// The engine advances `tick` before each hook pass; timers are not
+/// persisted in snapshots.
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    tick: u64,
+    /// key -> first tick at which the key is ready again
+    ready_at: BTreeMap<String, u64>,
+}
+
+impl Cooldowns {
+    /// Move to `tick`, dropping timers that have elapsed.
+    pub fn advance(&mut self, tick: u64) {
+        if tick != self.tick {
+            self.tick = tick;
+            self.ready_at.retain(|_, &mut at| at > tick);
+        }
+    }
+
+    /// True (and re-arm for `ticks`) if `key` is not cooling down.
+    pub fn ready(&mut self, key: &str, ticks: u64) -> bool {
+        if self.ready_at.get(key).is_some_and(|&at| at > self.tick) {
+            return false;
+        }
+        self.ready_at
+            .insert(key.to_string(), self.tick.saturating_add(ticks));
+        true
+    }
+
+    /// Number of keys still cooling down.
+    pub fn len(&self) -> usize {
+        self.ready_at.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ready_at.is_empty()
+    }
+}
+
+/// Register cooldown.* API functions on the Lua global table.
+pub fn register_cooldown_api(lua: &Lua) -> LuaResult<()> {
+    lua.set_app_data(Cooldowns::default());
+    let cooldown_table = lua.create_table()?;
+
+    // cooldown.ready(key, ticks) -> bool
+    // True and arms the timer if `key` is not cooling down; false otherwise.
+    let ready_fn = lua.create_function(|lua, (key, ticks): (String, u64)| {
+        let mut cooldowns = lua
+            .app_data_mut::<Cooldowns>()
+            .ok_or_else(|| mlua::Error::runtime("cooldown table not initialized"))?;
+        Ok(cooldowns.ready(&key, ticks))
+    })?;
+    cooldown_table.set("ready", ready_fn)?;
+
+    lua.globals().set("cooldown", cooldown_table)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{ScriptConfig, create_sandboxed_lua};
+
+    fn setup() -> Lua {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_cooldown_api(&lua).unwrap();
+        lua
+    }
+
+    fn ready(lua: &Lua, key: &str, ticks: u64) -> bool {
+        lua.load(format!(r#"return cooldown.ready("{}", {})"#, key, ticks))
+            .eval()
+            .unwrap()
+    }
+
+    fn advance(lua: &Lua, tick: u64) {
+        lua.app_data_mut::<Cooldowns>().unwrap().advance(tick);
+    }
+
+    #[test]
+    fn ready_once_then_blocked_until_window_elapses() {
+        let lua = setup();
+        advance(&lua, 10);
+        assert!(ready(&lua, "attack:1", 3));
+        assert!(!ready(&lua, "attack:1", 3));
+
+        advance(&lua, 12);
+        assert!(!ready(&lua, "attack:1", 3));
+
+        advance(&lua, 13);
+        assert!(ready(&lua, "attack:1", 3));
+        assert!(!ready(&lua, "attack:1", 3));
+    }
+
+    #[test]
+    fn keys_are_independent() {
+        let lua = setup();
+        assert!(ready(&lua, "attack:1", 5));
+        assert!(ready(&lua, "attack:2", 5));
+        assert!(!ready(&lua, "attack:1", 5));
+    }
+
+    #[test]
+    fn zero_ticks_is_always_ready() {
+        let lua = setup();
+        assert!(ready(&lua, "k", 0));
+        assert!(ready(&lua, "k", 0));
+    }
+
+    #[test]
+    fn elapsed_timers_are_pruned() {
+        let mut cooldowns = Cooldowns::default();
+        cooldowns.ready("a", 2);
+        cooldowns.ready("b", 10);
+        cooldowns.advance(5);
+        assert_eq!(cooldowns.len(), 1);
+    }
+
+    #[test]
+    fn negative_ticks_rejected() {
+        let lua = setup();
+        assert!(lua.load(r#"return cooldown.ready("k", -1)"#).exec().is_err());
+    }
+}
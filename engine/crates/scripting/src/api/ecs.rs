@@ -1,9 +1,23 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use ecs_adapter::{EcsAdapter, EntityId};
-use mlua::{Lua, Result as LuaResult, UserData, UserDataMethods, Value};
-
-use crate::component_registry::ScriptComponentRegistry;
+use mlua::{Function, Lua, MultiValue, Result as LuaResult, UserData, UserDataMethods, Value};
+
+use crate::component_registry::{CurrencyComponent, ScriptComponent, ScriptComponentRegistry};
+
+/// State of a component before a write inside `ecs:transaction`.
+/// `None` means the entity did not have it.
+enum JournalEntry {
+    Component {
+        eid: EntityId,
+        tag: String,
+        prior: Option<Value>,
+    },
+    Money {
+        eid: EntityId,
+        prior: Option<i64>,
+    },
+}
 
 /// Proxy object that Lua scripts use to access ECS operations.
 /// Wraps a RefCell<&mut EcsAdapter> so that multiple Lua functions
@@ -11,6 +25,10 @@ use crate::component_registry::ScriptComponentRegistry;
 pub struct EcsProxy {
     ecs: RefCell<*mut EcsAdapter>,
     registry: *const ScriptComponentRegistry,
+    /// Undo log for open transactions, oldest write first.
+    journal: RefCell<Vec<JournalEntry>>,
+    /// Nesting depth of `ecs:transaction`; writes are journaled while > 0.
+    tx_depth: Cell<u32>,
 }
 
 // SAFETY: EcsProxy is only used within a single tick-thread scope.
@@ -28,6 +46,8 @@ impl EcsProxy {
         Self {
             ecs: RefCell::new(ecs),
             registry,
+            journal: RefCell::new(Vec::new()),
+            tx_depth: Cell::new(0),
         }
     }
 
@@ -47,6 +67,89 @@ impl EcsProxy {
         // SAFETY: valid for scope lifetime
         unsafe { &*self.registry }
     }
+
+    fn currency(&self) -> LuaResult<&dyn CurrencyComponent> {
+        self.registry()
+            .currency()
+            .ok_or_else(|| mlua::Error::runtime("no currency component registered"))
+    }
+
+    /// Remember the current value of `tag` on `eid` if a transaction is open.
+    fn journal_component(
+        &self,
+        lua: &Lua,
+        handler: &dyn ScriptComponent,
+        eid: EntityId,
+        tag: &str,
+    ) -> LuaResult<()> {
+        if self.tx_depth.get() == 0 {
+            return Ok(());
+        }
+        let prior = self
+            .with_ecs(|ecs| handler.get_as_lua(ecs, eid, lua))
+            .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+        self.journal.borrow_mut().push(JournalEntry::Component {
+            eid,
+            tag: tag.to_string(),
+            prior,
+        });
+        Ok(())
+    }
+
+    /// Remove registered components `tags` (all present on `eid`),
+    /// journaling each inside a transaction. Returns how many were removed.
+    fn remove_tags(&self, lua: &Lua, eid: EntityId, tags: &[String]) -> LuaResult<usize> {
+        for tag in tags {
+            let handler = self
+                .registry()
+                .get(tag)
+                .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+            self.journal_component(lua, handler, eid, tag)?;
+            self.with_ecs_mut(|ecs| handler.remove(ecs, eid))
+                .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+        }
+        Ok(tags.len())
+    }
+
+    fn journal_money(&self, prior: Option<i64>, eid: EntityId) {
+        if self.tx_depth.get() > 0 {
+            self.journal.borrow_mut().push(JournalEntry::Money { eid, prior });
+        }
+    }
+
+    /// Undo every journaled write after `mark`, newest first.
+    fn rollback_to(&self, lua: &Lua, mark: usize) {
+        let entries = self.journal.borrow_mut().split_off(mark);
+        for entry in entries.into_iter().rev() {
+            let result = match entry {
+                JournalEntry::Component { eid, tag, prior } => match self.registry().get(&tag) {
+                    Some(handler) => self.with_ecs_mut(|ecs| match prior {
+                        Some(value) => handler.set_from_lua(ecs, eid, value, lua),
+                        None if handler.has(ecs, eid) => handler.remove(ecs, eid),
+                        None => Ok(()),
+                    }),
+                    None => Ok(()),
+                },
+                JournalEntry::Money { eid, prior } => match self.registry().currency() {
+                    Some(currency) => self.with_ecs_mut(|ecs| match prior {
+                        Some(balance) => currency.set_balance(ecs, eid, balance),
+                        None => currency.remove_wallet(ecs, eid),
+                    }),
+                    None => Ok(()),
+                },
+            };
+            if let Err(e) = result {
+                tracing::warn!("ecs:transaction rollback step failed: {}", e);
+            }
+        }
+    }
+}
+
+fn check_amount(amount: i64) -> LuaResult<()> {
+    if amount < 0 {
+        return Err(mlua::Error::runtime(format!("money amount must not be negative: {}", amount)));
+    }
+    Ok(())
 }
 
 impl UserData for EcsProxy {
@@ -66,6 +169,28 @@ impl UserData for EcsProxy {
             }
         });
 
+        // ecs:get_all(component_tag) -> {[entity_id] = value, ...}
+        // One boundary crossing for every entity with the component.
+        methods.add_method("get_all", |lua, this, tag: String| {
+            let handler = this
+                .registry()
+                .get(&tag)
+                .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+            let table = lua.create_table()?;
+            this.with_ecs(|ecs| -> LuaResult<()> {
+                for eid in handler.entities_with(ecs) {
+                    let value = handler
+                        .get_as_lua(ecs, eid, lua)
+                        .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+                    if let Some(v) = value {
+                        table.raw_set(eid.to_u64(), v)?;
+                    }
+                }
+                Ok(())
+            })?;
+            Ok(table)
+        });
+
         // ecs:set(entity_id, component_tag, value)
         methods.add_method("set", |lua, this, (eid_u64, tag, value): (u64, String, Value)| {
             let eid = EntityId::from_u64(eid_u64);
@@ -73,11 +198,108 @@ impl UserData for EcsProxy {
                 .registry()
                 .get(&tag)
                 .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+            this.journal_component(lua, handler, eid, &tag)?;
             this.with_ecs_mut(|ecs| handler.set_from_lua(ecs, eid, value, lua))
                 .map_err(|e| mlua::Error::runtime(e.to_string()))?;
             Ok(())
         });
 
+        // ecs:set_many(entity_id, {tag = value, ...}) -> count
+        // One boundary crossing for several components, applied in tag
+        // order. Every tag must be registered; nothing is set if one is not.
+        methods.add_method("set_many", |lua, this, (eid_u64, values): (u64, mlua::Table)| {
+            let eid = EntityId::from_u64(eid_u64);
+            let mut entries = Vec::new();
+            for pair in values.pairs::<String, Value>() {
+                let (tag, value) = pair?;
+                let handler = this
+                    .registry()
+                    .get(&tag)
+                    .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+                entries.push((tag, handler, value));
+            }
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (tag, handler, _) in &entries {
+                this.journal_component(lua, *handler, eid, tag)?;
+            }
+            let count = entries.len();
+            this.with_ecs_mut(|ecs| {
+                entries
+                    .into_iter()
+                    .try_for_each(|(_, handler, value)| handler.set_from_lua(ecs, eid, value, lua))
+            })
+            .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+            Ok(count)
+        });
+
+        // ecs:transaction(fn) -> fn's return values
+        // Component writes made by fn (set, remove, money helpers) are undone
+        // if it raises an error, which is then re-raised. Spawns, despawns and
+        // tags are not journaled. Nested transactions roll back only their
+        // own writes.
+        methods.add_method("transaction", |lua, this, f: Function| {
+            let mark = this.journal.borrow().len();
+            this.tx_depth.set(this.tx_depth.get() + 1);
+            let result = f.call::<MultiValue>(());
+            this.tx_depth.set(this.tx_depth.get() - 1);
+            match result {
+                Ok(values) => {
+                    if this.tx_depth.get() == 0 {
+                        this.journal.borrow_mut().clear();
+                    }
+                    Ok(values)
+                }
+                Err(e) => {
+                    this.rollback_to(lua, mark);
+                    Err(e)
+                }
+            }
+        });
+
+        // ecs:money(entity_id) -> balance (0 without a wallet)
+        methods.add_method("money", |_lua, this, eid_u64: u64| {
+            let eid = EntityId::from_u64(eid_u64);
+            let currency = this.currency()?;
+            Ok(this.with_ecs(|ecs| currency.balance(ecs, eid)).unwrap_or(0))
+        });
+
+        // ecs:add_money(entity_id, amount) -> new balance
+        // Creates the wallet if missing. Negative amounts are an error; use
+        // spend_money to take money away.
+        methods.add_method("add_money", |_lua, this, (eid_u64, amount): (u64, i64)| {
+            check_amount(amount)?;
+            let eid = EntityId::from_u64(eid_u64);
+            let currency = this.currency()?;
+            let prior = this.with_ecs(|ecs| currency.balance(ecs, eid));
+            let new_balance = prior
+                .unwrap_or(0)
+                .checked_add(amount)
+                .ok_or_else(|| mlua::Error::runtime("money balance overflow"))?;
+            this.journal_money(prior, eid);
+            this.with_ecs_mut(|ecs| currency.set_balance(ecs, eid, new_balance))
+                .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+            Ok(new_balance)
+        });
+
+        // ecs:spend_money(entity_id, amount) -> ok, balance
+        // Deducts only if the balance covers it; otherwise returns false and
+        // leaves the wallet untouched.
+        methods.add_method("spend_money", |_lua, this, (eid_u64, amount): (u64, i64)| {
+            check_amount(amount)?;
+            let eid = EntityId::from_u64(eid_u64);
+            let currency = this.currency()?;
+            let prior = this.with_ecs(|ecs| currency.balance(ecs, eid));
+            let balance = prior.unwrap_or(0);
+            if balance < amount {
+                return Ok((false, balance));
+            }
+            let new_balance = balance - amount;
+            this.journal_money(prior, eid);
+            this.with_ecs_mut(|ecs| currency.set_balance(ecs, eid, new_balance))
+                .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+            Ok((true, new_balance))
+        });
+
         // ecs:has(entity_id, component_tag) -> bool
         methods.add_method("has", |_lua, this, (eid_u64, tag): (u64, String)| {
             let eid = EntityId::from_u64(eid_u64);
@@ -89,17 +311,46 @@ impl UserData for EcsProxy {
         });
 
         // ecs:remove(entity_id, component_tag)
-        methods.add_method("remove", |_lua, this, (eid_u64, tag): (u64, String)| {
+        methods.add_method("remove", |lua, this, (eid_u64, tag): (u64, String)| {
             let eid = EntityId::from_u64(eid_u64);
             let handler = this
                 .registry()
                 .get(&tag)
                 .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+            this.journal_component(lua, handler, eid, &tag)?;
             this.with_ecs_mut(|ecs| handler.remove(ecs, eid))
                 .map_err(|e| mlua::Error::runtime(e.to_string()))?;
             Ok(())
         });
 
+        // ecs:remove_all(entity_id) -> count: remove every registered
+        // component; the entity itself stays alive.
+        methods.add_method("remove_all", |lua, this, eid_u64: u64| {
+            let eid = EntityId::from_u64(eid_u64);
+            let tags: Vec<String> = this.with_ecs(|ecs| {
+                this.registry().tags_on(ecs, eid).into_iter().map(String::from).collect()
+            });
+            this.remove_tags(lua, eid, &tags)
+        });
+
+        // ecs:remove_many(entity_id, {tag, ...}) -> count of components
+        // actually removed. Every tag must be registered; nothing is removed
+        // if one is not.
+        methods.add_method("remove_many", |lua, this, (eid_u64, tags): (u64, Vec<String>)| {
+            let eid = EntityId::from_u64(eid_u64);
+            let mut present = Vec::with_capacity(tags.len());
+            for tag in tags {
+                let handler = this
+                    .registry()
+                    .get(&tag)
+                    .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", tag)))?;
+                if this.with_ecs(|ecs| handler.has(ecs, eid)) && !present.contains(&tag) {
+                    present.push(tag);
+                }
+            }
+            this.remove_tags(lua, eid, &present)
+        });
+
         // ecs:spawn() -> entity_id (u64)
         methods.add_method("spawn", |_lua, this, ()| {
             let eid = this.with_ecs_mut(|ecs| ecs.spawn_entity());
@@ -114,9 +365,17 @@ impl UserData for EcsProxy {
             Ok(())
         });
 
-        // ecs:query(tag1, tag2, ...) -> list of entity_ids
-        // Returns entities that have ALL specified components
-        methods.add_method("query", |_lua, this, tags: mlua::Variadic<String>| {
+        // ecs:is_alive(entity_id) -> bool (false for despawned or stale ids)
+        methods.add_method("is_alive", |_lua, this, eid_u64: u64| {
+            let eid = EntityId::from_u64(eid_u64);
+            Ok(this.with_ecs(|ecs| ecs.is_alive(eid)))
+        });
+
+        // ecs:query(tag1, tag2, ...) or ecs:query({tag1, tag2, ...}) -> list of entity_ids
+        // Returns entities that have ALL specified components, ordered by
+        // (index, generation) so iteration is identical across replays.
+        methods.add_method("query", |_lua, this, args: mlua::Variadic<Value>| {
+            let tags = collect_query_tags(args)?;
             if tags.is_empty() {
                 return Err(mlua::Error::runtime("query requires at least one component tag"));
             }
@@ -130,6 +389,8 @@ impl UserData for EcsProxy {
                 .ok_or_else(|| mlua::Error::runtime(format!("component not registered: {}", first_tag)))?;
 
             let mut result = this.with_ecs(|ecs| first_handler.entities_with(ecs));
+            // Don't rely on every handler sorting; the order is part of the contract.
+            result.sort_unstable();
 
             // Intersect with remaining tags
             for tag in tags.iter().skip(1) {
@@ -145,7 +406,56 @@ impl UserData for EcsProxy {
             let u64s: Vec<u64> = result.iter().map(|e| e.to_u64()).collect();
             Ok(u64s)
         });
+
+        // ecs:add_tag(entity_id, tag) -> bool (false if already tagged)
+        methods.add_method("add_tag", |_lua, this, (eid_u64, tag): (u64, String)| {
+            let eid = EntityId::from_u64(eid_u64);
+            this.with_ecs_mut(|ecs| ecs.add_tag(eid, &tag))
+                .map_err(|e| mlua::Error::runtime(e.to_string()))
+        });
+
+        // ecs:has_tag(entity_id, tag) -> bool
+        methods.add_method("has_tag", |_lua, this, (eid_u64, tag): (u64, String)| {
+            let eid = EntityId::from_u64(eid_u64);
+            Ok(this.with_ecs(|ecs| ecs.has_tag(eid, &tag)))
+        });
+
+        // ecs:remove_tag(entity_id, tag) -> bool (false if the tag wasn't set)
+        methods.add_method("remove_tag", |_lua, this, (eid_u64, tag): (u64, String)| {
+            let eid = EntityId::from_u64(eid_u64);
+            this.with_ecs_mut(|ecs| ecs.remove_tag(eid, &tag))
+                .map_err(|e| mlua::Error::runtime(e.to_string()))
+        });
+
+        // ecs:entities_with_tag(tag) -> list of entity_ids, in query order
+        methods.add_method("entities_with_tag", |_lua, this, tag: String| {
+            let result = this.with_ecs(|ecs| ecs.entities_with_tag(&tag));
+            Ok(result.iter().map(|e| e.to_u64()).collect::<Vec<u64>>())
+        });
+    }
+}
+
+/// Flatten `ecs:query` arguments: each argument is either a tag string
+/// or a list table of tag strings.
+fn collect_query_tags(args: mlua::Variadic<Value>) -> LuaResult<Vec<String>> {
+    let mut tags = Vec::new();
+    for arg in args {
+        match arg {
+            Value::String(s) => tags.push(s.to_str()?.to_string()),
+            Value::Table(t) => {
+                for tag in t.sequence_values::<String>() {
+                    tags.push(tag?);
+                }
+            }
+            other => {
+                return Err(mlua::Error::runtime(format!(
+                    "query expects component tags or a list of tags, got {}",
+                    other.type_name()
+                )));
+            }
+        }
     }
+    Ok(tags)
 }
 
 /// Register the `ecs` global table in Lua using function-style API.
@@ -254,6 +564,27 @@ mod tests {
         }
     }
 
+    #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Purse(i64);
+
+    struct PurseCurrency;
+
+    impl CurrencyComponent for PurseCurrency {
+        fn balance(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<i64> {
+            ecs.get_component::<Purse>(eid).ok().map(|p| p.0)
+        }
+
+        fn set_balance(&self, ecs: &mut EcsAdapter, eid: EntityId, amount: i64) -> Result<(), ScriptError> {
+            ecs.set_component(eid, Purse(amount))
+                .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))
+        }
+
+        fn remove_wallet(&self, ecs: &mut EcsAdapter, eid: EntityId) -> Result<(), ScriptError> {
+            ecs.remove_component::<Purse>(eid)
+                .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))
+        }
+    }
+
     fn make_registry() -> ScriptComponentRegistry {
         let mut reg = ScriptComponentRegistry::new();
         reg.register(Box::new(JsonComponentHandler::<Health>::new("Health")));
@@ -262,6 +593,288 @@ mod tests {
         reg
     }
 
+    #[test]
+    fn test_is_alive() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+        let live = ecs.spawn_entity();
+        let stale = ecs.spawn_entity();
+        ecs.despawn_entity(stale).unwrap();
+        let reused = ecs.spawn_entity();
+        assert_eq!(reused.index, stale.index);
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            let (a, b, c): (bool, bool, bool) = lua
+                .load(format!(
+                    "return _ecs:is_alive({}), _ecs:is_alive({}), _ecs:is_alive({})",
+                    live.to_u64(),
+                    stale.to_u64(),
+                    reused.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert!(a);
+            assert!(!b, "stale handle must not alias the reused index");
+            assert!(c);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_remove_all_and_remove_many() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+        let a = ecs.spawn_entity();
+        let b = ecs.spawn_entity();
+        for eid in [a, b] {
+            ecs.set_component(eid, Health { current: 5, max: 10 }).unwrap();
+            ecs.set_component(eid, Name("goblin".into())).unwrap();
+            ecs.set_component(eid, PlayerTag).unwrap();
+        }
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            lua.globals().set("_ecs", scope.create_userdata(proxy)?)?;
+            let (all, again, alive): (u32, u32, bool) = lua
+                .load(format!(
+                    "local n = _ecs:remove_all({a}); return n, _ecs:remove_all({a}), _ecs:is_alive({a})",
+                    a = a.to_u64()
+                ))
+                .eval()?;
+            assert_eq!((all, again), (3, 0));
+            assert!(alive, "remove_all must not despawn");
+
+            let many: u32 = lua
+                .load(format!(r#"return _ecs:remove_many({}, {{"Health", "PlayerTag", "Health"}})"#, b.to_u64()))
+                .eval()?;
+            assert_eq!(many, 2);
+            let err = lua
+                .load(format!(r#"_ecs:remove_many({}, {{"Name", "Mana"}})"#, b.to_u64()))
+                .exec()
+                .unwrap_err();
+            assert!(err.to_string().contains("component not registered: Mana"));
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(registry.tags_on(&ecs, a).is_empty());
+        assert!(ecs.is_alive(a));
+        // Only the listed components went; the failed call removed nothing.
+        assert_eq!(registry.tags_on(&ecs, b), vec!["Name"]);
+    }
+
+    #[test]
+    fn test_set_many() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+        let e = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            lua.globals().set("_ecs", scope.create_userdata(proxy)?)?;
+            let count: u32 = lua
+                .load(format!(
+                    r#"return _ecs:set_many({}, {{Name = "Hero", Health = {{current = 7, max = 9}}}})"#,
+                    e.to_u64()
+                ))
+                .eval()?;
+            assert_eq!(count, 2);
+            let err = lua
+                .load(format!(r#"_ecs:set_many({}, {{Name = "Other", Mana = 5}})"#, e.to_u64()))
+                .exec()
+                .unwrap_err();
+            assert!(err.to_string().contains("component not registered: Mana"));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(ecs.get_component::<Name>(e).unwrap().0, "Hero");
+        assert_eq!(ecs.get_component::<Health>(e).unwrap(), &Health { current: 7, max: 9 });
+    }
+
+    #[test]
+    fn test_money_add_and_spend() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut registry = make_registry();
+        registry.set_currency(Box::new(PurseCurrency));
+        let e = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+
+            let (empty, added, ok, left, short, kept, after): (i64, i64, bool, i64, bool, i64, i64) = lua
+                .load(format!(
+                    r#"
+                    local e = {}
+                    local empty = _ecs:money(e)
+                    local added = _ecs:add_money(e, 100)
+                    local ok, left = _ecs:spend_money(e, 30)
+                    local short, kept = _ecs:spend_money(e, 71)
+                    return empty, added, ok, left, short, kept, _ecs:money(e)
+                    "#,
+                    e.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!((empty, added), (0, 100));
+            assert!(ok);
+            assert_eq!(left, 70);
+            assert!(!short);
+            assert_eq!((kept, after), (70, 70));
+
+            assert!(lua.load(format!("_ecs:add_money({}, -5)", e.to_u64())).exec().is_err());
+            assert!(lua.load(format!("_ecs:spend_money({}, -5)", e.to_u64())).exec().is_err());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(ecs.get_component::<Purse>(e).unwrap(), &Purse(70));
+    }
+
+    #[test]
+    fn test_transaction_commits_on_success() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut registry = make_registry();
+        registry.set_currency(Box::new(PurseCurrency));
+        let e = ecs.spawn_entity();
+        ecs.set_component(e, Health { current: 10, max: 10 }).unwrap();
+        ecs.set_component(e, Name("Old".to_string())).unwrap();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            let result: String = lua
+                .load(format!(
+                    r#"
+                    local e = {}
+                    return _ecs:transaction(function()
+                        _ecs:set(e, "Health", {{current = 3, max = 10}})
+                        _ecs:remove(e, "Name")
+                        _ecs:add_money(e, 25)
+                        return "done"
+                    end)
+                    "#,
+                    e.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(result, "done");
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(ecs.get_component::<Health>(e).unwrap(), &Health { current: 3, max: 10 });
+        assert!(!ecs.has_component::<Name>(e));
+        assert_eq!(ecs.get_component::<Purse>(e).unwrap(), &Purse(25));
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut registry = make_registry();
+        registry.set_currency(Box::new(PurseCurrency));
+        let e = ecs.spawn_entity();
+        ecs.set_component(e, Health { current: 10, max: 10 }).unwrap();
+        ecs.set_component(e, Name("Old".to_string())).unwrap();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            let (ok, err, inner_kept): (bool, String, bool) = lua
+                .load(format!(
+                    r#"
+                    local e = {}
+                    local ok, err = pcall(function()
+                        _ecs:transaction(function()
+                            _ecs:set(e, "Health", {{current = 1, max = 10}})
+                            _ecs:set(e, "Health", {{current = 0, max = 10}})
+                            _ecs:remove(e, "Name")
+                            _ecs:add_money(e, 50)
+                            error("boom")
+                        end)
+                    end)
+
+                    -- A failed nested transaction only undoes its own writes.
+                    _ecs:transaction(function()
+                        _ecs:set(e, "Name", "Outer")
+                        pcall(_ecs.transaction, _ecs, function()
+                            _ecs:set(e, "Name", "Inner")
+                            error("inner")
+                        end)
+                    end)
+                    return ok, tostring(err), _ecs:get(e, "Name") == "Outer"
+                    "#,
+                    e.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert!(!ok);
+            assert!(err.contains("boom"), "{}", err);
+            assert!(inner_kept);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(ecs.get_component::<Health>(e).unwrap(), &Health { current: 10, max: 10 });
+        assert!(!ecs.has_component::<Purse>(e));
+        assert_eq!(ecs.get_component::<Name>(e).unwrap(), &Name("Outer".to_string()));
+    }
+
+    #[test]
+    fn test_money_spend_without_wallet_fails_without_mutation() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut registry = make_registry();
+        registry.set_currency(Box::new(PurseCurrency));
+        let e = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            let (ok, balance): (bool, i64) = lua
+                .load(format!("return _ecs:spend_money({}, 1)", e.to_u64()))
+                .eval()
+                .unwrap();
+            assert!(!ok);
+            assert_eq!(balance, 0);
+            Ok(())
+        })
+        .unwrap();
+        assert!(!ecs.has_component::<Purse>(e));
+    }
+
+    #[test]
+    fn test_money_requires_currency_component() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+        let e = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            let err = lua.load(format!("_ecs:add_money({}, 1)", e.to_u64())).exec().unwrap_err();
+            assert!(err.to_string().contains("no currency component"));
+            Ok(())
+        })
+        .unwrap();
+    }
+
     #[test]
     fn test_ecs_get_set_roundtrip() {
         let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
@@ -277,7 +890,7 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_ecs", ud).unwrap();
 
-            let result: mlua::Value = lua.load(&format!(
+            let result: mlua::Value = lua.load(format!(
                 "return _ecs:get({}, 'Health')", e.to_u64()
             )).eval().unwrap();
 
@@ -308,12 +921,12 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_ecs", ud).unwrap();
 
-            let has_health: bool = lua.load(&format!(
+            let has_health: bool = lua.load(format!(
                 "return _ecs:has({}, 'Health')", e.to_u64()
             )).eval().unwrap();
             assert!(has_health);
 
-            let has_name: bool = lua.load(&format!(
+            let has_name: bool = lua.load(format!(
                 "return _ecs:has({}, 'Name')", e.to_u64()
             )).eval().unwrap();
             assert!(!has_name);
@@ -337,7 +950,7 @@ mod tests {
 
             let eid: u64 = lua.load("return _ecs:spawn()").eval().unwrap();
 
-            lua.load(&format!("_ecs:despawn({})", eid)).exec().unwrap();
+            lua.load(format!("_ecs:despawn({})", eid)).exec().unwrap();
 
             Ok(())
         }).unwrap();
@@ -378,6 +991,122 @@ mod tests {
         }).unwrap();
     }
 
+    #[test]
+    fn test_ecs_query_with_tag_list() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        let e3 = ecs.spawn_entity();
+
+        ecs.set_component(e1, Health { current: 80, max: 100 }).unwrap();
+        ecs.set_component(e1, PlayerTag).unwrap();
+        ecs.set_component(e1, Name("hero".into())).unwrap();
+        ecs.set_component(e2, Health { current: 50, max: 50 }).unwrap();
+        ecs.set_component(e2, PlayerTag).unwrap();
+        ecs.set_component(e3, Name("rock".into())).unwrap();
+        ecs.set_component(e3, PlayerTag).unwrap();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+
+            let result: Vec<u64> = lua.load("return _ecs:query({'PlayerTag', 'Health'})").eval().unwrap();
+            assert_eq!(result, vec![e1.to_u64(), e2.to_u64()]);
+
+            let result: Vec<u64> = lua.load("return _ecs:query({'PlayerTag', 'Name', 'Health'})").eval().unwrap();
+            assert_eq!(result, vec![e1.to_u64()]);
+
+            // Strings and lists can be mixed
+            let result: Vec<u64> = lua.load("return _ecs:query('Name', {'PlayerTag'})").eval().unwrap();
+            assert_eq!(result, vec![e1.to_u64(), e3.to_u64()]);
+
+            assert!(lua.load("return _ecs:query({})").eval::<Vec<u64>>().is_err());
+            assert!(lua.load("return _ecs:query(42)").eval::<Vec<u64>>().is_err());
+
+            Ok(())
+        }).unwrap();
+    }
+
+    #[test]
+    fn test_ecs_query_order_is_deterministic() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+
+        let e0 = ecs.spawn_entity();
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        ecs.despawn_entity(e0).unwrap();
+        // Reuses index 0 with a higher generation, so its packed u64 is the largest
+        let recycled = ecs.spawn_entity();
+
+        for eid in [e2, recycled, e1] {
+            ecs.set_component(eid, PlayerTag).unwrap();
+            ecs.set_component(eid, Health { current: 1, max: 1 }).unwrap();
+        }
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+
+            let expected = vec![recycled.to_u64(), e1.to_u64(), e2.to_u64()];
+            for _ in 0..3 {
+                let result: Vec<u64> = lua.load("return _ecs:query('Health', 'PlayerTag')").eval().unwrap();
+                assert_eq!(result, expected);
+            }
+            Ok(())
+        }).unwrap();
+    }
+
+    #[test]
+    fn test_ecs_get_all() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+
+        let e1 = ecs.spawn_entity();
+        let e2 = ecs.spawn_entity();
+        let e3 = ecs.spawn_entity();
+        ecs.set_component(e1, Health { current: 80, max: 100 }).unwrap();
+        ecs.set_component(e2, Health { current: 50, max: 50 }).unwrap();
+        ecs.set_component(e3, PlayerTag).unwrap();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+
+            let all: mlua::Table = lua.load("return _ecs:get_all('Health')").eval().unwrap();
+            let mut found: Vec<(u64, i64, i64)> = all
+                .pairs::<u64, mlua::Table>()
+                .map(|pair| {
+                    let (eid, hp) = pair.unwrap();
+                    (eid, hp.get("current").unwrap(), hp.get("max").unwrap())
+                })
+                .collect();
+            found.sort();
+            assert_eq!(
+                found,
+                vec![(e1.to_u64(), 80, 100), (e2.to_u64(), 50, 50)]
+            );
+
+            // Marker components are included as well
+            let count: i64 = lua
+                .load("local n = 0; for _ in pairs(_ecs:get_all('PlayerTag')) do n = n + 1 end; return n")
+                .eval()
+                .unwrap();
+            assert_eq!(count, 1);
+
+            assert!(lua.load("return _ecs:get_all('Bogus')").exec().is_err());
+            Ok(())
+        }).unwrap();
+    }
+
     #[test]
     fn test_ecs_get_nil_for_missing() {
         let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
@@ -391,7 +1120,7 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_ecs", ud).unwrap();
 
-            let result: mlua::Value = lua.load(&format!(
+            let result: mlua::Value = lua.load(format!(
                 "return _ecs:get({}, 'Health')", e.to_u64()
             )).eval().unwrap();
 
@@ -400,4 +1129,55 @@ mod tests {
             Ok(())
         }).unwrap();
     }
+
+    #[test]
+    fn test_ecs_tags() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut ecs = EcsAdapter::new();
+        let registry = make_registry();
+
+        let a = ecs.spawn_entity();
+        let b = ecs.spawn_entity();
+        let c = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_ecs", ud).unwrap();
+            lua.globals().set("a", a.to_u64()).unwrap();
+            lua.globals().set("b", b.to_u64()).unwrap();
+            lua.globals().set("c", c.to_u64()).unwrap();
+
+            let (added, again, has_a, has_b): (bool, bool, bool, bool) = lua
+                .load(
+                    r#"
+                    local added = _ecs:add_tag(a, "aggressive")
+                    local again = _ecs:add_tag(a, "aggressive")
+                    _ecs:add_tag(c, "aggressive")
+                    _ecs:add_tag(b, "quest_giver")
+                    return added, again, _ecs:has_tag(a, "aggressive"), _ecs:has_tag(b, "aggressive")
+                    "#,
+                )
+                .eval()
+                .unwrap();
+            assert!(added);
+            assert!(!again);
+            assert!(has_a);
+            assert!(!has_b);
+
+            let tagged: Vec<u64> = lua.load(r#"return _ecs:entities_with_tag("aggressive")"#).eval().unwrap();
+            assert_eq!(tagged, vec![a.to_u64(), c.to_u64()]);
+
+            let (removed, removed_again): (bool, bool) = lua
+                .load(r#"return _ecs:remove_tag(a, "aggressive"), _ecs:remove_tag(a, "aggressive")"#)
+                .eval()
+                .unwrap();
+            assert!(removed);
+            assert!(!removed_again);
+
+            let tagged: Vec<u64> = lua.load(r#"return _ecs:entities_with_tag("aggressive")"#).eval().unwrap();
+            assert_eq!(tagged, vec![c.to_u64()]);
+            Ok(())
+        }).unwrap();
+    }
 }
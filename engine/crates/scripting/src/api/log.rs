@@ -1,4 +1,10 @@
-use mlua::{Lua, Result as LuaResult};
+use std::cmp::Ordering;
+use std::ffi::c_void;
+
+use mlua::{Lua, Result as LuaResult, Table, Value, Variadic};
+
+/// Tables nested deeper than this render as `{...}`.
+const INSPECT_MAX_DEPTH: usize = 4;
 
 /// Register log.* API functions on the Lua global table.
 /// Maps to Rust tracing macros.
@@ -30,9 +36,117 @@ pub fn register_log_api(lua: &Lua) -> LuaResult<()> {
     log_table.set("debug", debug_fn)?;
 
     lua.globals().set("log", log_table)?;
+
+    // inspect(value) -> string: pretty-print to the debug log, tagged with
+    // the calling script, and return the rendering.
+    let inspect_fn = lua.create_function(|lua, value: Value| {
+        let rendered = inspect_value(&value);
+        tracing::debug!(target: "lua_script", script = %caller_script(lua), "{}", rendered);
+        Ok(rendered)
+    })?;
+    lua.globals().set("inspect", inspect_fn)?;
+
+    // print(...) goes to the debug log instead of the server's stdout.
+    let print_fn = lua.create_function(|lua, args: Variadic<Value>| {
+        let line = args
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => s.to_string_lossy(),
+                other => inspect_value(other),
+            })
+            .collect::<Vec<_>>()
+            .join("\t");
+        tracing::debug!(target: "lua_script", script = %caller_script(lua), "{}", line);
+        Ok(())
+    })?;
+    lua.globals().set("print", print_fn)?;
+
     Ok(())
 }
 
+/// Render a Lua value for debugging. Tables are expanded recursively with
+/// sorted keys (array indices first); a table already being rendered further
+/// up shows as `<cycle>`, and nesting past `INSPECT_MAX_DEPTH` as `{...}`.
+pub fn inspect_value(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, 0, &mut Vec::new());
+    out
+}
+
+fn write_value(out: &mut String, value: &Value, depth: usize, path: &mut Vec<*const c_void>) {
+    match value {
+        Value::Nil => out.push_str("nil"),
+        Value::Boolean(b) => out.push_str(&b.to_string()),
+        Value::Integer(i) => out.push_str(&i.to_string()),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) => out.push_str(&format!("{:?}", s.to_string_lossy())),
+        Value::Table(t) => write_table(out, t, depth, path),
+        other => out.push_str(&format!("<{}>", other.type_name())),
+    }
+}
+
+fn write_table(out: &mut String, table: &Table, depth: usize, path: &mut Vec<*const c_void>) {
+    let ptr = table.to_pointer();
+    if path.contains(&ptr) {
+        out.push_str("<cycle>");
+        return;
+    }
+    let mut entries: Vec<(Value, Value)> = table.pairs::<Value, Value>().filter_map(Result::ok).collect();
+    if entries.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+    if depth >= INSPECT_MAX_DEPTH {
+        out.push_str("{...}");
+        return;
+    }
+    entries.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+
+    path.push(ptr);
+    out.push_str("{\n");
+    for (key, value) in &entries {
+        out.push_str(&"  ".repeat(depth + 1));
+        out.push_str(&inspect_key(key));
+        out.push_str(" = ");
+        write_value(out, value, depth + 1, path);
+        out.push_str(",\n");
+    }
+    out.push_str(&"  ".repeat(depth));
+    out.push('}');
+    path.pop();
+}
+
+/// Numeric keys first in numeric order, then the rest by rendered key.
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => inspect_key(a).cmp(&inspect_key(b)),
+    }
+}
+
+/// `name` for identifier-like string keys, `[key]` otherwise.
+fn inspect_key(key: &Value) -> String {
+    if let Value::String(s) = key {
+        let s = s.to_string_lossy();
+        let mut chars = s.chars();
+        let ident = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if ident {
+            return s;
+        }
+    }
+    format!("[{}]", inspect_value(key))
+}
+
+/// Name of the script chunk that called into Rust (as passed to `load_script`).
+fn caller_script(lua: &Lua) -> String {
+    lua.inspect_stack(1)
+        .and_then(|d| d.source().source.map(|s| s.trim_start_matches(['=', '@']).to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,4 +163,47 @@ mod tests {
         lua.load(r#"log.error("test error message")"#).exec().unwrap();
         lua.load(r#"log.debug("test debug message")"#).exec().unwrap();
     }
+
+    #[test]
+    fn test_inspect_nested_table() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_log_api(&lua).unwrap();
+
+        let rendered: String = lua
+            .load(r#"return inspect({ name = "goblin", hp = 7, tags = { "npc", "hostile" }, ["two words"] = true })"#)
+            .eval()
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "{\n  [\"two words\"] = true,\n  hp = 7,\n  name = \"goblin\",\n  tags = {\n    [1] = \"npc\",\n    [2] = \"hostile\",\n  },\n}"
+        );
+        let scalar: String = lua.load("return inspect(nil)").eval().unwrap();
+        assert_eq!(scalar, "nil");
+    }
+
+    #[test]
+    fn test_inspect_cycle_and_depth_cap() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_log_api(&lua).unwrap();
+
+        let cyclic: String = lua
+            .load("local t = { id = 1 }; t.self = t; return inspect(t)")
+            .eval()
+            .unwrap();
+        assert_eq!(cyclic, "{\n  id = 1,\n  self = <cycle>,\n}");
+
+        let deep: String = lua
+            .load("return inspect({ a = { b = { c = { d = { e = {} } } } } })")
+            .eval()
+            .unwrap();
+        assert!(deep.contains("d = {...}"), "{}", deep);
+        assert!(!deep.contains("e ="), "{}", deep);
+    }
+
+    #[test]
+    fn test_print_does_not_error() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_log_api(&lua).unwrap();
+        lua.load(r#"print("hp", 7, { x = 1 }, nil)"#).exec().unwrap();
+    }
 }
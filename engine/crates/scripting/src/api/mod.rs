@@ -2,5 +2,9 @@ pub mod ecs;
 pub mod space;
 pub mod output;
 pub mod log;
+pub mod strutil;
+pub mod cooldown;
+pub mod time;
+pub mod require;
 pub mod session;
 pub mod auth;
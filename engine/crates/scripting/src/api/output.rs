@@ -1,12 +1,15 @@
 use std::cell::RefCell;
 
+use ecs_adapter::EntityId;
 use mlua::{UserData, UserDataMethods, Value};
-use session::{SessionId, SessionOutput};
+use session::{SessionId, SessionManager, SessionOutput};
 
 /// Proxy for collecting session outputs from Lua scripts.
 /// Outputs are accumulated and returned after script execution.
 pub struct OutputProxy {
     outputs: RefCell<*mut Vec<SessionOutput>>,
+    /// Used to resolve `send_entity` targets; None disables entity sends.
+    sessions: Option<*const SessionManager>,
 }
 
 // SAFETY: OutputProxy is only used within a single tick-thread scope.
@@ -19,9 +22,28 @@ impl OutputProxy {
     pub unsafe fn new(outputs: *mut Vec<SessionOutput>) -> Self {
         Self {
             outputs: RefCell::new(outputs),
+            sessions: None,
         }
     }
 
+    /// Like `new`, but able to route `send_entity` through `sessions`.
+    ///
+    /// # Safety
+    /// Caller must ensure both pointers outlive the proxy and are only used
+    /// from one thread.
+    pub unsafe fn with_sessions(outputs: *mut Vec<SessionOutput>, sessions: *const SessionManager) -> Self {
+        Self {
+            outputs: RefCell::new(outputs),
+            sessions: Some(sessions),
+        }
+    }
+
+    /// Session currently controlling `entity`, if any.
+    fn session_for_entity(&self, entity: EntityId) -> Option<SessionId> {
+        let ptr = self.sessions?;
+        unsafe { (*ptr).session_id_for_entity(entity) }
+    }
+
     fn push_output(&self, output: SessionOutput) {
         let ptr = *self.outputs.borrow();
         unsafe { (*ptr).push(output) };
@@ -37,6 +59,29 @@ impl UserData for OutputProxy {
             Ok(())
         });
 
+        // output:send_entity(entity_id, text) -> bool
+        // Sends to the session controlling the entity; false (and nothing
+        // sent) if no session currently controls it.
+        methods.add_method("send_entity", |_lua, this, (eid_u64, text): (u64, String)| {
+            match this.session_for_entity(EntityId::from_u64(eid_u64)) {
+                Some(sid) => {
+                    this.push_output(SessionOutput::new(sid, text));
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        });
+
+        // output:send_and_disconnect(session_id, text)
+        // Delivers a final message, then the server closes the session.
+        methods.add_method(
+            "send_and_disconnect",
+            |_lua, this, (sid_u64, text): (u64, String)| {
+                this.push_output(SessionOutput::with_disconnect(SessionId(sid_u64), text));
+                Ok(())
+            },
+        );
+
         // output:broadcast_room(room_id, text, {exclude=entity_id})
         // This collects a broadcast request. The actual expansion to
         // per-session outputs is done by the caller after script execution,
@@ -100,6 +145,63 @@ mod tests {
         assert_eq!(outputs[0].text, "Hello, world!");
         assert_eq!(outputs[1].session_id, SessionId(99));
         assert_eq!(outputs[1].text, "Goodbye!");
+        assert!(outputs.iter().all(|o| !o.disconnect));
+    }
+
+    #[test]
+    fn test_output_send_entity() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut outputs: Vec<SessionOutput> = Vec::new();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        let player = EntityId::new(7, 0);
+        let npc = EntityId::new(8, 0);
+        sessions.bind_entity(sid, player);
+
+        let proxy = unsafe { OutputProxy::with_sessions(&mut outputs as *mut _, &sessions as *const _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_output", ud).unwrap();
+
+            let (sent, skipped): (bool, bool) = lua
+                .load(format!(
+                    "return _output:send_entity({}, 'Hi'), _output:send_entity({}, 'Nobody')",
+                    player.to_u64(),
+                    npc.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert!(sent);
+            assert!(!skipped);
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].session_id, sid);
+        assert_eq!(outputs[0].text, "Hi");
+    }
+
+    #[test]
+    fn test_output_send_and_disconnect() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut outputs: Vec<SessionOutput> = Vec::new();
+
+        let proxy = unsafe { OutputProxy::new(&mut outputs as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_output", ud).unwrap();
+
+            lua.load("_output:send_and_disconnect(42, 'You are banned.')")
+                .exec()
+                .unwrap();
+
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].session_id, SessionId(42));
+        assert_eq!(outputs[0].text, "You are banned.");
+        assert!(outputs[0].disconnect);
     }
 
     #[test]
@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+
+use mlua::{Lua, Result as LuaResult, Table, Value};
+
+/// Registry key of the table caching loaded modules by name.
+const LOADED_KEY: &str = "__require_loaded";
+
+/// Per-VM state for `require`: the library root and the chain of modules
+/// currently being loaded (for cycle detection).
+struct RequireState {
+    lib_dir: Option<PathBuf>,
+    loading: Vec<String>,
+}
+
+/// Register a sandboxed `require(name)` global.
+///
+/// `name` is a dotted module path (`"util.text"` → `<lib_dir>/util/text.lua`
+/// or `.luau`). Each module runs once; its return value (or `true` if it
+/// returns nothing) is cached and handed to every later caller. Names are
+/// restricted to `[A-Za-z0-9_]` segments and the resolved file must lie under
+/// the real `lib_dir`, so nothing outside it can be reached, even via symlinks.
+/// Without a `lib_dir`, `require` always raises.
+pub fn register_require_api(lua: &Lua, lib_dir: Option<PathBuf>) -> LuaResult<()> {
+    lua.set_app_data(RequireState {
+        lib_dir,
+        loading: Vec::new(),
+    });
+    lua.set_named_registry_value(LOADED_KEY, lua.create_table()?)?;
+
+    let require_fn = lua.create_function(|lua, name: String| {
+        let loaded: Table = lua.named_registry_value(LOADED_KEY)?;
+        let cached: Value = loaded.raw_get(name.as_str())?;
+        if !cached.is_nil() {
+            return Ok(cached);
+        }
+
+        let path = {
+            let mut state = lua
+                .app_data_mut::<RequireState>()
+                .ok_or_else(|| mlua::Error::runtime("require is not initialized"))?;
+            if let Some(pos) = state.loading.iter().position(|m| *m == name) {
+                let mut chain = state.loading[pos..].to_vec();
+                chain.push(name.clone());
+                return Err(mlua::Error::runtime(format!(
+                    "cyclic require: {}",
+                    chain.join(" -> ")
+                )));
+            }
+            let lib_dir = state.lib_dir.as_deref().ok_or_else(|| {
+                mlua::Error::runtime("require is unavailable: no script lib directory configured")
+            })?;
+            let path = resolve_module(lib_dir, &name)?;
+            state.loading.push(name.clone());
+            path
+        };
+
+        // The state borrow is released so nested requires can take it again.
+        let result = load_module(lua, &name, &path);
+
+        if let Some(mut state) = lua.app_data_mut::<RequireState>() {
+            state.loading.pop();
+        }
+
+        let value = match result? {
+            Value::Nil => Value::Boolean(true),
+            v => v,
+        };
+        loaded.raw_set(name.as_str(), value.clone())?;
+        Ok(value)
+    })?;
+    lua.globals().set("require", require_fn)?;
+    Ok(())
+}
+
+fn load_module(lua: &Lua, name: &str, path: &Path) -> LuaResult<Value> {
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        mlua::Error::runtime(format!("require '{}': failed to read {}: {}", name, path.display(), e))
+    })?;
+    lua.load(source)
+        .set_name(format!("lib/{}", name))
+        .call(())
+}
+
+/// Map a dotted module name to a file under `lib_dir`.
+fn resolve_module(lib_dir: &Path, name: &str) -> LuaResult<PathBuf> {
+    let valid = !name.is_empty()
+        && name.split('.').all(|seg| {
+            !seg.is_empty() && seg.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if !valid {
+        return Err(mlua::Error::runtime(format!("invalid module name '{}'", name)));
+    }
+
+    let base = name.split('.').fold(lib_dir.to_path_buf(), |p, seg| p.join(seg));
+    let path = ["lua", "luau"]
+        .iter()
+        .map(|ext| base.with_extension(ext))
+        .find(|p| p.is_file())
+        .ok_or_else(|| mlua::Error::runtime(format!("module '{}' not found", name)))?;
+
+    // A symlink inside lib_dir may still point elsewhere; compare real paths.
+    let root = lib_dir.canonicalize().map_err(|e| {
+        mlua::Error::runtime(format!("require: cannot resolve {}: {}", lib_dir.display(), e))
+    })?;
+    let real = path.canonicalize().map_err(|e| {
+        mlua::Error::runtime(format!("require '{}': cannot resolve {}: {}", name, path.display(), e))
+    })?;
+    if !real.starts_with(&root) {
+        return Err(mlua::Error::runtime(format!(
+            "module '{}' resolves outside the script lib directory",
+            name
+        )));
+    }
+    Ok(real)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{create_sandboxed_lua, ScriptConfig};
+
+    fn lib_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scripting_require_{}_{}", tag, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn setup(dir: &Path) -> Lua {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_require_api(&lua, Some(dir.to_path_buf())).unwrap();
+        lua
+    }
+
+    #[test]
+    fn module_returns_table() {
+        let dir = lib_dir("table");
+        std::fs::create_dir_all(dir.join("util")).unwrap();
+        std::fs::write(
+            dir.join("util").join("math2.lua"),
+            "local M = {}\nfunction M.double(x) return x * 2 end\nreturn M",
+        )
+        .unwrap();
+
+        let lua = setup(&dir);
+        let n: i64 = lua
+            .load(r#"return require("util.math2").double(21)"#)
+            .eval()
+            .unwrap();
+        assert_eq!(n, 42);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn module_is_loaded_once() {
+        let dir = lib_dir("cache");
+        std::fs::write(
+            dir.join("counter.lua"),
+            "load_count = (load_count or 0) + 1\nreturn { id = load_count }",
+        )
+        .unwrap();
+
+        let lua = setup(&dir);
+        let same: bool = lua
+            .load(r#"local a = require("counter"); local b = require("counter"); return rawequal(a, b)"#)
+            .eval()
+            .unwrap();
+        assert!(same);
+        let count: i64 = lua.load("return load_count").eval().unwrap();
+        assert_eq!(count, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cyclic_require_is_an_error() {
+        let dir = lib_dir("cycle");
+        std::fs::write(dir.join("a.lua"), r#"return { b = require("b") }"#).unwrap();
+        std::fs::write(dir.join("b.lua"), r#"return { a = require("a") }"#).unwrap();
+
+        let lua = setup(&dir);
+        let err = lua.load(r#"require("a")"#).exec().unwrap_err();
+        assert!(err.to_string().contains("cyclic require: a -> b -> a"), "{}", err);
+
+        // A failed load is not cached; the loading chain is reset.
+        std::fs::write(dir.join("b.lua"), "return {}").unwrap();
+        lua.load(r#"require("a")"#).exec().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn names_cannot_escape_lib_dir() {
+        let dir = lib_dir("escape");
+        let lua = setup(&dir);
+        for name in ["../secret", "/etc/passwd", "a..b", "", "x/y"] {
+            let err = lua
+                .load(format!("require({:?})", name))
+                .exec()
+                .unwrap_err();
+            assert!(err.to_string().contains("invalid module name"), "{}: {}", name, err);
+        }
+        let err = lua.load(r#"require("missing")"#).exec().unwrap_err();
+        assert!(err.to_string().contains("module 'missing' not found"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_out_of_lib_dir_is_rejected() {
+        let dir = lib_dir("symlink");
+        let outside = lib_dir("symlink_outside");
+        std::fs::write(outside.join("secret.lua"), "return 'leaked'").unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.lua"), dir.join("secret.lua")).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("linked")).unwrap();
+
+        let lua = setup(&dir);
+        for name in ["secret", "linked.secret"] {
+            let err = lua.load(format!("require({:?})", name)).exec().unwrap_err();
+            assert!(err.to_string().contains("outside the script lib directory"), "{}: {}", name, err);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_lib_dir_still_loads() {
+        let real = lib_dir("symlink_real");
+        std::fs::write(real.join("ok.lua"), "return 7").unwrap();
+        let link = std::env::temp_dir().join(format!("scripting_require_symlink_link_{}", std::process::id()));
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let lua = setup(&link);
+        let n: i64 = lua.load(r#"return require("ok")"#).eval().unwrap();
+        assert_eq!(n, 7);
+        let _ = std::fs::remove_file(&link);
+        let _ = std::fs::remove_dir_all(&real);
+    }
+
+    #[test]
+    fn require_without_lib_dir_fails() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_require_api(&lua, None).unwrap();
+        let err = lua.load(r#"require("anything")"#).exec().unwrap_err();
+        assert!(err.to_string().contains("no script lib directory"));
+    }
+}
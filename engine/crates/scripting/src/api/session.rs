@@ -103,6 +103,127 @@ impl UserData for SessionProxy {
             Ok(result)
         });
 
+        // Read-only accessors returning nil for unknown sessions.
+        // sessions:name(session_id) -> string | nil
+        methods.add_method("name", |_lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let result = this.with_sessions(|sessions| {
+                sessions.get_session(sid).and_then(|s| s.player_name.clone())
+            });
+            Ok(result)
+        });
+
+        // sessions:permission(session_id) -> number | nil (0=Player..3=Owner)
+        methods.add_method("permission", |_lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let result = this.with_sessions(|sessions| {
+                sessions.session_permission(sid).map(|p| p.as_i32())
+            });
+            Ok(result)
+        });
+
+        // sessions:entity(session_id) -> entity_id | nil
+        methods.add_method("entity", |_lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let result = this.with_sessions(|sessions| {
+                sessions.get_session(sid).and_then(|s| s.entity.map(|e| e.to_u64()))
+            });
+            Ok(result)
+        });
+
+        // sessions:can(session_id, level) -> bool (false for unknown sessions)
+        methods.add_method("can", |_lua, this, (sid_u64, level): (u64, i32)| {
+            let sid = session::SessionId(sid_u64);
+            let required = session::PermissionLevel::from_i32(level);
+            let result = this.with_sessions(|sessions| {
+                sessions.get_session(sid).is_some_and(|s| s.can(required))
+            });
+            Ok(result)
+        });
+
+        // sessions:width(session_id) -> number | nil (terminal columns for word wrap)
+        methods.add_method("width", |_lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let result = this.with_sessions(|sessions| {
+                sessions.get_session(sid).map(|s| s.line_width)
+            });
+            Ok(result)
+        });
+
+        // sessions:set_width(session_id, columns) — 0 disables wrapping
+        methods.add_method("set_width", |_lua, this, (sid_u64, columns): (u64, usize)| {
+            let sid = session::SessionId(sid_u64);
+            this.with_sessions_mut(|sessions| {
+                if let Some(s) = sessions.get_session_mut(sid) {
+                    s.line_width = columns;
+                }
+            });
+            Ok(())
+        });
+
+        // sessions:get_meta(session_id, key) -> string | nil
+        methods.add_method("get_meta", |_lua, this, (sid_u64, key): (u64, String)| {
+            let sid = session::SessionId(sid_u64);
+            let result = this.with_sessions(|sessions| {
+                sessions.get_meta(sid, &key).map(str::to_string)
+            });
+            Ok(result)
+        });
+
+        // sessions:set_meta(session_id, key, value) -> bool (false if no such session)
+        methods.add_method("set_meta", |_lua, this, (sid_u64, key, value): (u64, String, String)| {
+            let sid = session::SessionId(sid_u64);
+            Ok(this.with_sessions_mut(|sessions| sessions.set_meta(sid, key, value)))
+        });
+
+        // sessions:clear_meta(session_id[, key]) -> bool — clears all tags when key is nil
+        methods.add_method("clear_meta", |_lua, this, (sid_u64, key): (u64, Option<String>)| {
+            let sid = session::SessionId(sid_u64);
+            Ok(this.with_sessions_mut(|sessions| sessions.clear_meta(sid, key.as_deref())))
+        });
+
+        // sessions:connection(session_id) -> {ip, port, connected_at} | nil
+        // connected_at is in unix seconds; nil for sessions without a network peer.
+        methods.add_method("connection", |lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let conn = this.with_sessions(|sessions| sessions.get_session(sid).and_then(|s| s.connection));
+            let Some(conn) = conn else {
+                return Ok(None);
+            };
+            let connected_at = conn
+                .connected_at
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let table = lua.create_table()?;
+            table.set("ip", conn.peer_addr.ip().to_string())?;
+            table.set("port", conn.peer_addr.port())?;
+            table.set("connected_at", connected_at)?;
+            Ok(Some(table))
+        });
+
+        // sessions:stats(session_id) -> {inputs, bytes, connected_tick} | nil
+        methods.add_method("stats", |lua, this, sid_u64: u64| {
+            let sid = session::SessionId(sid_u64);
+            let Some(stats) = this.with_sessions(|sessions| sessions.session_stats(sid)) else {
+                return Ok(None);
+            };
+            let table = lua.create_table()?;
+            table.set("inputs", stats.input_count)?;
+            table.set("bytes", stats.input_bytes)?;
+            table.set("connected_tick", stats.connected_tick)?;
+            Ok(Some(table))
+        });
+
+        // sessions:sessions_from_ip(ip) -> {session_id, ...} (empty for an invalid ip)
+        methods.add_method("sessions_from_ip", |_lua, this, ip: String| {
+            let Ok(ip) = ip.parse::<std::net::IpAddr>() else {
+                return Ok(Vec::new());
+            };
+            let sids = this.with_sessions(|sessions| sessions.sessions_from_ip(ip));
+            Ok(sids.into_iter().map(|sid| sid.0).collect::<Vec<_>>())
+        });
+
         // sessions:start_playing(session_id, entity_id)
         // Binds entity to session and transitions to Playing state.
         methods.add_method("start_playing", |_lua, this, (sid_u64, eid_u64): (u64, u64)| {
@@ -177,6 +298,28 @@ impl UserData for SessionProxy {
             }
         });
 
+        // sessions:find_hibernated(character_id) -> {character_id, account_id, disconnect_tick} | nil
+        // A hibernated character's entity was saved to the DB and despawned.
+        methods.add_method("find_hibernated", |lua, this, character_id: i64| {
+            let record = this.with_sessions(|sessions| sessions.find_hibernated(character_id).cloned());
+            match record {
+                Some(h) => {
+                    let t = lua.create_table()?;
+                    t.set("character_id", h.character_id)?;
+                    t.set("account_id", h.account_id)?;
+                    t.set("disconnect_tick", h.disconnect_tick)?;
+                    Ok(Some(t))
+                }
+                None => Ok(None),
+            }
+        });
+
+        // sessions:wake_hibernated(character_id) -> bool
+        // Drops the hibernated record before the character is rebuilt from the DB.
+        methods.add_method("wake_hibernated", |_lua, this, character_id: i64| {
+            Ok(this.with_sessions_mut(|sessions| sessions.wake_hibernated(character_id).is_some()))
+        });
+
         // sessions:rebind_lingering(session_id, character_id) -> entity_id | nil
         methods.add_method("rebind_lingering", |_lua, this, (sid_u64, character_id): (u64, i64)| {
             let sid = session::SessionId(sid_u64);
@@ -221,6 +364,12 @@ impl UserData for SessionProxy {
             Ok(())
         });
 
+        // sessions:all_ids() -> {session_id, ...} (every state, ascending)
+        methods.add_method("all_ids", |_lua, this, ()| {
+            let sids = this.with_sessions(|sessions| sessions.all_session_ids());
+            Ok(sids.into_iter().map(|sid| sid.0).collect::<Vec<_>>())
+        });
+
         // sessions:playing_list() -> [{session_id, entity, name}, ...]
         methods.add_method("playing_list", |lua, this, ()| {
             let list = this.with_sessions(|sessions| {
@@ -273,7 +422,7 @@ mod tests {
             lua.globals().set("_sessions", ud).unwrap();
 
             let result: u64 = lua
-                .load(&format!(
+                .load(format!(
                     "return _sessions:session_for({})",
                     eid.to_u64()
                 ))
@@ -325,4 +474,262 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_can() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        sessions.get_session_mut(sid).unwrap().permission = session::PermissionLevel::Admin;
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (builder, admin, owner, missing): (bool, bool, bool, bool) = lua
+                .load(format!(
+                    "return _sessions:can({0}, 1), _sessions:can({0}, 2), _sessions:can({0}, 3), _sessions:can(9999, 0)",
+                    sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert!(builder && admin);
+            assert!(!owner);
+            assert!(!missing);
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (first, overwritten, after_clear, other, missing): (String, String, Option<String>, String, bool) = lua
+                .load(format!(
+                    r#"
+                    local sid = {0}
+                    _sessions:set_meta(sid, "client", "mudlet")
+                    _sessions:set_meta(sid, "locale", "ko")
+                    local first = _sessions:get_meta(sid, "client")
+                    _sessions:set_meta(sid, "client", "tintin")
+                    local overwritten = _sessions:get_meta(sid, "client")
+                    _sessions:clear_meta(sid, "client")
+                    return first, overwritten, _sessions:get_meta(sid, "client"),
+                        _sessions:get_meta(sid, "locale"), _sessions:set_meta(9999, "k", "v")
+                    "#,
+                    sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(first, "mudlet");
+            assert_eq!(overwritten, "tintin");
+            assert_eq!(after_clear, None);
+            assert_eq!(other, "ko");
+            assert!(!missing);
+
+            let cleared: bool = lua
+                .load(format!("return _sessions:clear_meta({})", sid.0))
+                .eval()
+                .unwrap();
+            assert!(cleared);
+            Ok(())
+        })
+        .unwrap();
+        assert!(sessions.get_session(sid).unwrap().metadata.is_empty());
+    }
+
+    #[test]
+    fn test_connection_info() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let addr: std::net::SocketAddr = "192.0.2.10:4242".parse().unwrap();
+        sessions.create_session_with_connection(session::SessionId(1), session::ConnectionInfo::new(addr));
+        let local = sessions.create_session();
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (ip, port, recent, local_none, by_ip, bad_ip): (String, u16, bool, bool, Vec<u64>, usize) = lua
+                .load(format!(
+                    r#"
+                    local c = _sessions:connection(1)
+                    return c.ip, c.port, c.connected_at > 0, _sessions:connection({}) == nil,
+                        _sessions:sessions_from_ip("192.0.2.10"), #_sessions:sessions_from_ip("not an ip")
+                    "#,
+                    local.0
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(ip, "192.0.2.10");
+            assert_eq!(port, 4242);
+            assert!(recent);
+            assert!(local_none);
+            assert_eq!(by_ip, vec![1]);
+            assert_eq!(bad_ip, 0);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_session_stats() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        sessions.get_session_mut(sid).unwrap().connected_tick = 7;
+        sessions.record_input(sid, 4);
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (inputs, bytes, tick, missing): (u64, u64, u64, bool) = lua
+                .load(format!(
+                    r#"
+                    local s = _sessions:stats({})
+                    return s.inputs, s.bytes, s.connected_tick,
+                        _sessions:stats(999) == nil and #_sessions:all_ids() == 1
+                    "#,
+                    sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!((inputs, bytes, tick), (1, 4, 7));
+            assert!(missing);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_hibernated_find_and_wake() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        sessions.add_lingering(LingeringEntity {
+            entity: EntityId::new(4, 0),
+            character_id: 12,
+            account_id: 3,
+            disconnect_tick: 50,
+        });
+        sessions.hibernate_lingering(12).unwrap();
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (account, tick, lingering_nil, woke, woke_again, gone): (i64, u64, bool, bool, bool, bool) = lua
+                .load(
+                    r#"
+                    local h = _sessions:find_hibernated(12)
+                    return h.account_id, h.disconnect_tick, _sessions:find_lingering(12) == nil,
+                        _sessions:wake_hibernated(12), _sessions:wake_hibernated(12),
+                        _sessions:find_hibernated(12) == nil
+                    "#,
+                )
+                .eval()
+                .unwrap();
+            assert_eq!(account, 3);
+            assert_eq!(tick, 50);
+            assert!(lingering_nil);
+            assert!(woke);
+            assert!(!woke_again);
+            assert!(gone);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_width_defaults_and_set() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (before, after, unknown): (usize, usize, Option<usize>) = lua
+                .load(format!(
+                    "local w = _sessions:width({0}); _sessions:set_width({0}, 60); return w, _sessions:width({0}), _sessions:width(9999)",
+                    sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(before, session::DEFAULT_LINE_WIDTH);
+            assert_eq!(after, 60);
+            assert!(unknown.is_none());
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(sessions.get_session(sid).unwrap().line_width, 60);
+    }
+
+    #[test]
+    fn test_name_permission_entity() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        let eid = ecs_adapter::EntityId::new(7, 0);
+        sessions.bind_entity(sid, eid);
+        if let Some(s) = sessions.get_session_mut(sid) {
+            s.player_name = Some("Bob".to_string());
+            s.permission = session::PermissionLevel::Builder;
+        }
+        let login_sid = sessions.create_session();
+
+        let proxy = unsafe { SessionProxy::new(&mut sessions as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_sessions", ud).unwrap();
+
+            let (name, perm, entity): (String, i32, u64) = lua
+                .load(format!(
+                    "return _sessions:name({0}), _sessions:permission({0}), _sessions:entity({0})",
+                    sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert_eq!(name, "Bob");
+            assert_eq!(perm, 1);
+            assert_eq!(entity, eid.to_u64());
+
+            // Login session: no name or entity yet, default permission
+            let (name, perm, entity): (Option<String>, i32, Option<u64>) = lua
+                .load(format!(
+                    "return _sessions:name({0}), _sessions:permission({0}), _sessions:entity({0})",
+                    login_sid.0
+                ))
+                .eval()
+                .unwrap();
+            assert!(name.is_none());
+            assert_eq!(perm, 0);
+            assert!(entity.is_none());
+
+            // Unknown session: all nil
+            let all_nil: bool = lua
+                .load("return _sessions:name(9999) == nil and _sessions:permission(9999) == nil and _sessions:entity(9999) == nil")
+                .eval()
+                .unwrap();
+            assert!(all_nil);
+
+            Ok(())
+        })
+        .unwrap();
+    }
 }
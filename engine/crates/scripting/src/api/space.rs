@@ -2,7 +2,7 @@ use std::cell::RefCell;
 
 use ecs_adapter::EntityId;
 use mlua::{UserData, UserDataMethods};
-use space::grid_space::GridSpace;
+use space::grid_space::{GridPos, GridSpace};
 use space::model::SpaceModel;
 use space::room_graph::RoomExits;
 use space::RoomGraphSpace;
@@ -120,6 +120,24 @@ impl SpaceProxy {
     }
 }
 
+/// Parse one `register_room` exit entry: a room id, or `{id=..., cost=...}`.
+fn parse_exit(value: mlua::Value) -> Result<Option<(EntityId, Option<u32>)>, mlua::Error> {
+    match value {
+        mlua::Value::Nil => Ok(None),
+        mlua::Value::Integer(n) => Ok(Some((EntityId::from_u64(n as u64), None))),
+        mlua::Value::Number(n) => Ok(Some((EntityId::from_u64(n as u64), None))),
+        mlua::Value::Table(t) => {
+            let id: u64 = t.get("id")?;
+            let cost: Option<u32> = t.get("cost")?;
+            Ok(Some((EntityId::from_u64(id), cost)))
+        }
+        other => Err(mlua::Error::runtime(format!(
+            "exit must be a room id or {{id=, cost=}} table, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
 impl UserData for SpaceProxy {
     fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
         // ===== Common SpaceModel methods =====
@@ -170,28 +188,54 @@ impl UserData for SpaceProxy {
             Ok(u64s)
         });
 
-        // space:register_room(entity_id, exits_table)
-        methods.add_method("register_room", |_lua, this, (eid_u64, exits_table): (u64, mlua::Table)| {
+        // space:register_room(entity_id, exits_table, zone?)
+        // Each direction is either a room id or {id=room_id, cost=number}.
+        methods.add_method("register_room", |_lua, this, (eid_u64, exits_table, zone): (u64, mlua::Table, Option<String>)| {
             let room_id = EntityId::from_u64(eid_u64);
             let mut exits = RoomExits::default();
 
-            if let Ok(n) = exits_table.get::<u64>("north") {
-                exits.north = Some(EntityId::from_u64(n));
-            }
-            if let Ok(s) = exits_table.get::<u64>("south") {
-                exits.south = Some(EntityId::from_u64(s));
-            }
-            if let Ok(e) = exits_table.get::<u64>("east") {
-                exits.east = Some(EntityId::from_u64(e));
-            }
-            if let Ok(w) = exits_table.get::<u64>("west") {
-                exits.west = Some(EntityId::from_u64(w));
+            for dir in ["north", "south", "east", "west"] {
+                let Some((target, cost)) = parse_exit(exits_table.get(dir)?)? else {
+                    continue;
+                };
+                let slot = match dir {
+                    "north" => &mut exits.north,
+                    "south" => &mut exits.south,
+                    "east" => &mut exits.east,
+                    _ => &mut exits.west,
+                };
+                *slot = Some(target);
+                if let Some(cost) = cost {
+                    exits.costs.insert(dir.to_string(), cost);
+                }
             }
 
-            this.with_room_graph_mut(|space| space.register_room(room_id, exits))?;
+            this.with_room_graph_mut(|space| match zone {
+                Some(zone) => space.register_room_in_zone(room_id, exits, zone),
+                None => space.register_room(room_id, exits),
+            })?;
             Ok(())
         });
 
+        // space:set_zone(room_id, zone|nil) -> bool (false if the room is unknown)
+        methods.add_method("set_zone", |_lua, this, (room_u64, zone): (u64, Option<String>)| {
+            let room = EntityId::from_u64(room_u64);
+            this.with_room_graph_mut(|space| space.set_zone(room, zone))
+        });
+
+        // space:zone_of(room_id) -> string or nil
+        methods.add_method("zone_of", |_lua, this, room_u64: u64| {
+            let room = EntityId::from_u64(room_u64);
+            this.with_room_graph(|space| space.zone_of(room).map(str::to_string))
+        });
+
+        // space:rooms_in_zone(zone) -> sorted list of room ids
+        methods.add_method("rooms_in_zone", |_lua, this, zone: String| {
+            let rooms = this.with_room_graph(|space| space.rooms_in_zone(&zone))?;
+            let u64s: Vec<u64> = rooms.iter().map(|e| e.to_u64()).collect();
+            Ok(u64s)
+        });
+
         // space:room_exists(room_id) -> bool
         methods.add_method("room_exists", |_lua, this, room_u64: u64| {
             let room = EntityId::from_u64(room_u64);
@@ -258,6 +302,13 @@ impl UserData for SpaceProxy {
             }
         });
 
+        // space:facing(entity_id) -> "north"|"south"|"east"|"west" or nil
+        methods.add_method("facing", |_lua, this, eid_u64: u64| {
+            let eid = EntityId::from_u64(eid_u64);
+            let facing = this.with_grid(|grid| grid.facing(eid))?;
+            Ok(facing.map(|f| f.as_str()))
+        });
+
         // space:set_position(entity_id, x, y)
         methods.add_method("set_position", |_lua, this, (eid_u64, x, y): (u64, i32, i32)| {
             let eid = EntityId::from_u64(eid_u64);
@@ -266,6 +317,27 @@ impl UserData for SpaceProxy {
             Ok(())
         });
 
+        // space:set_positions({{entity_id, x, y}, ...}) -> {{entity=id, ok=bool, error=string|nil}, ...}
+        // Bulk teleport; every move is attempted and results follow input order.
+        methods.add_method("set_positions", |lua, this, moves: Vec<mlua::Table>| {
+            let moves = moves
+                .iter()
+                .map(|m| Ok((EntityId::from_u64(m.get::<u64>(1)?), m.get::<i32>(2)?, m.get::<i32>(3)?)))
+                .collect::<mlua::Result<Vec<_>>>()?;
+            let results = this.with_grid_mut(|grid| grid.set_positions(&moves))?;
+            let out = lua.create_table()?;
+            for (i, (eid, result)) in results.into_iter().enumerate() {
+                let entry = lua.create_table()?;
+                entry.set("entity", eid.to_u64())?;
+                entry.set("ok", result.is_ok())?;
+                if let Err(e) = result {
+                    entry.set("error", e.to_string())?;
+                }
+                out.set(i + 1, entry)?;
+            }
+            Ok(out)
+        });
+
         // space:move_to(entity_id, x, y) — adjacent move (Chebyshev distance 1)
         methods.add_method("move_to", |_lua, this, (eid_u64, x, y): (u64, i32, i32)| {
             let eid = EntityId::from_u64(eid_u64);
@@ -286,6 +358,32 @@ impl UserData for SpaceProxy {
             this.with_grid(|grid| grid.in_bounds(x, y))
         });
 
+        // space:set_blocked(x, y, blocked) -> bool (false if out of bounds)
+        methods.add_method("set_blocked", |_lua, this, (x, y, blocked): (i32, i32, bool)| {
+            this.with_grid_mut(|grid| grid.set_blocked(x, y, blocked))
+        });
+
+        // space:is_blocked(x, y) -> bool
+        methods.add_method("is_blocked", |_lua, this, (x, y): (i32, i32)| {
+            this.with_grid(|grid| grid.is_blocked(x, y))
+        });
+
+        // space:neighbors(x, y, diagonal?) -> [{x=number, y=number}, ...]
+        // In-bounds, non-blocked adjacent cells; 8 directions when diagonal is true.
+        methods.add_method("neighbors", |lua, this, (x, y, diagonal): (i32, i32, Option<bool>)| {
+            let cells = this.with_grid(|grid| {
+                grid.walkable_neighbors(GridPos::new(x, y), diagonal.unwrap_or(false))
+            })?;
+            let out = lua.create_table()?;
+            for (i, pos) in cells.into_iter().enumerate() {
+                let cell = lua.create_table()?;
+                cell.set("x", pos.x)?;
+                cell.set("y", pos.y)?;
+                out.set(i + 1, cell)?;
+            }
+            Ok(out)
+        });
+
         // space:grid_config() -> {width=number, height=number, origin_x=number, origin_y=number}
         methods.add_method("grid_config", |lua, this, ()| {
             let config = this.with_grid(|grid| grid.config().clone())?;
@@ -349,7 +447,7 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            let room: u64 = lua.load(&format!(
+            let room: u64 = lua.load(format!(
                 "return _space:entity_room({})", entity.to_u64()
             )).eval().unwrap();
             assert_eq!(room, room_a.to_u64());
@@ -372,7 +470,7 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            let occupants: Vec<u64> = lua.load(&format!(
+            let occupants: Vec<u64> = lua.load(format!(
                 "return _space:room_occupants({})", room_a.to_u64()
             )).eval().unwrap();
             assert_eq!(occupants.len(), 2);
@@ -391,7 +489,7 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            let north: u64 = lua.load(&format!(
+            let north: u64 = lua.load(format!(
                 "local e = _space:exits({}) return e.north", room_a.to_u64()
             )).eval().unwrap();
             assert_eq!(north, room_b.to_u64());
@@ -412,11 +510,11 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            lua.load(&format!(
+            lua.load(format!(
                 "_space:move_entity({}, {})", entity.to_u64(), room_b.to_u64()
             )).exec().unwrap();
 
-            let room: u64 = lua.load(&format!(
+            let room: u64 = lua.load(format!(
                 "return _space:entity_room({})", entity.to_u64()
             )).eval().unwrap();
             assert_eq!(room, room_b.to_u64());
@@ -438,11 +536,11 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            lua.load(&format!(
+            lua.load(format!(
                 "_space:set_position({}, 3, 4)", entity.to_u64()
             )).exec().unwrap();
 
-            let result: mlua::Table = lua.load(&format!(
+            let result: mlua::Table = lua.load(format!(
                 "return _space:get_position({})", entity.to_u64()
             )).eval().unwrap();
             assert_eq!(result.get::<i32>("x").unwrap(), 3);
@@ -452,6 +550,39 @@ mod tests {
         }).unwrap();
     }
 
+    #[test]
+    fn test_grid_set_positions() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut grid = setup_grid();
+        let (e1, e2) = (EntityId::new(1, 0), EntityId::new(2, 0));
+
+        let proxy = unsafe { SpaceProxy::from_space(&mut grid as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_space", ud).unwrap();
+
+            let (ok1, ok2, err2, id2): (bool, bool, String, u64) = lua
+                .load(format!(
+                    r#"
+                    local r = _space:set_positions({{ {{{0}, 2, 3}}, {{{1}, -1, 0}} }})
+                    return r[1].ok, r[2].ok, r[2].error, r[2].entity
+                    "#,
+                    e1.to_u64(),
+                    e2.to_u64()
+                ))
+                .eval()
+                .unwrap();
+            assert!(ok1);
+            assert!(!ok2);
+            assert!(!err2.is_empty());
+            assert_eq!(id2, e2.to_u64());
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(grid.get_position(e1), Some(space::grid_space::GridPos::new(2, 3)));
+        assert_eq!(grid.get_position(e2), None);
+    }
+
     #[test]
     fn test_grid_move_to() {
         let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
@@ -464,16 +595,23 @@ mod tests {
             let ud = scope.create_userdata(proxy).unwrap();
             lua.globals().set("_space", ud).unwrap();
 
-            lua.load(&format!(
+            lua.load(format!(
                 "_space:move_to({}, 6, 5)", entity.to_u64()
             )).exec().unwrap();
 
-            let result: mlua::Table = lua.load(&format!(
+            let result: mlua::Table = lua.load(format!(
                 "return _space:get_position({})", entity.to_u64()
             )).eval().unwrap();
             assert_eq!(result.get::<i32>("x").unwrap(), 6);
             assert_eq!(result.get::<i32>("y").unwrap(), 5);
 
+            let facing: String = lua.load(format!(
+                "return _space:facing({})", entity.to_u64()
+            )).eval().unwrap();
+            assert_eq!(facing, "east");
+            let unplaced: Option<String> = lua.load("return _space:facing(999)").eval().unwrap();
+            assert!(unplaced.is_none());
+
             Ok(())
         }).unwrap();
     }
@@ -525,6 +663,34 @@ mod tests {
         }).unwrap();
     }
 
+    #[test]
+    fn test_grid_neighbors() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut grid = setup_grid();
+
+        let proxy = unsafe { SpaceProxy::from_space(&mut grid as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_space", ud).unwrap();
+
+            let (corner, open, first_x, first_y): (usize, usize, i32, i32) = lua
+                .load("local n = _space:neighbors(5, 5); return #_space:neighbors(0, 0, true), #_space:neighbors(5, 5, true), n[1].x, n[1].y")
+                .eval()
+                .unwrap();
+            assert_eq!((corner, open), (3, 8));
+            assert_eq!((first_x, first_y), (5, 4));
+
+            let (blocked, walled): (bool, usize) = lua
+                .load("_space:set_blocked(5, 4, true); return _space:is_blocked(5, 4), #_space:neighbors(5, 5)")
+                .eval()
+                .unwrap();
+            assert!(blocked);
+            assert_eq!(walled, 3);
+
+            Ok(())
+        }).unwrap();
+    }
+
     #[test]
     fn test_grid_config() {
         let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
@@ -564,6 +730,78 @@ mod tests {
         }).unwrap();
     }
 
+    #[test]
+    fn test_register_room_with_exit_costs() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let mut space = RoomGraphSpace::new();
+        let (a, b, c) = (EntityId::new(1, 0), EntityId::new(2, 0), EntityId::new(3, 0));
+
+        let proxy = unsafe { SpaceProxy::from_space(&mut space as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_space", ud).unwrap();
+
+            lua.load(format!(
+                r#"
+                _space:register_room({a}, {{ east = {{ id = {c}, cost = 10 }}, north = {b} }})
+                _space:register_room({b}, {{ east = {{ id = {c} }} }})
+                _space:register_room({c}, {{}})
+                "#,
+                a = a.to_u64(), b = b.to_u64(), c = c.to_u64()
+            )).exec().unwrap();
+
+            let result = lua.load("_space:register_room(1, { north = true })").exec();
+            assert!(result.is_err());
+
+            Ok(())
+        }).unwrap();
+
+        let exits = space.room_exits(a).unwrap();
+        assert_eq!(exits.east, Some(c));
+        assert_eq!(exits.cost("east"), 10);
+        assert_eq!(exits.cost("north"), 1);
+        assert_eq!(space.path(a, c).unwrap(), vec![a, b, c]);
+        assert_eq!(space.distance(a, c), Some(2));
+    }
+
+    #[test]
+    fn test_rooms_in_zone() {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        let (mut space, room_a, room_b) = setup_space();
+        let room_c = EntityId::new(102, 0);
+
+        let proxy = unsafe { SpaceProxy::from_space(&mut space as *mut _) };
+        lua.scope(|scope| {
+            let ud = scope.create_userdata(proxy).unwrap();
+            lua.globals().set("_space", ud).unwrap();
+
+            lua.load(format!(
+                r#"
+                _space:register_room({c}, {{}}, "forest")
+                assert(_space:set_zone({a}, "town"))
+                assert(not _space:set_zone(999, "town"))
+                "#,
+                a = room_a.to_u64(), c = room_c.to_u64()
+            )).exec().unwrap();
+
+            let town: Vec<u64> = lua.load(r#"return _space:rooms_in_zone("town")"#).eval().unwrap();
+            assert_eq!(town, vec![room_a.to_u64()]);
+            let unzoned: Option<String> = lua
+                .load(format!("return _space:zone_of({})", room_b.to_u64()))
+                .eval()
+                .unwrap();
+            assert_eq!(unzoned, None);
+            let forest: String = lua
+                .load(format!("return _space:zone_of({})", room_c.to_u64()))
+                .eval()
+                .unwrap();
+            assert_eq!(forest, "forest");
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(space.rooms_in_zone("forest"), vec![room_c]);
+    }
+
     #[test]
     fn test_roomgraph_only_methods_fail_on_grid() {
         let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
@@ -629,7 +867,7 @@ mod tests {
             lua.globals().set("_space", ud).unwrap();
 
             // entity_room should work on Grid (returns synthetic cell EntityId)
-            let room: u64 = lua.load(&format!(
+            let room: u64 = lua.load(format!(
                 "return _space:entity_room({})", entity.to_u64()
             )).eval().unwrap();
             // Should be a valid cell EntityId
@@ -0,0 +1,150 @@
+use mlua::{Lua, Result as LuaResult};
+
+/// Register strutil.* API functions on the Lua global table.
+/// Implemented in Rust so scripts get consistent, Unicode-aware behavior
+/// regardless of which parts of the Lua string library the sandbox exposes.
+pub fn register_strutil_api(lua: &Lua) -> LuaResult<()> {
+    let strutil_table = lua.create_table()?;
+
+    // strutil.split(s, sep?) -> {string}
+    // Without a separator, splits on runs of whitespace and drops empty pieces.
+    // With a separator (any length), keeps empty pieces between separators.
+    let split_fn = lua.create_function(|lua, (s, sep): (String, Option<String>)| {
+        let parts: Vec<&str> = match sep.as_deref() {
+            _ if s.is_empty() => Vec::new(),
+            None | Some("") => s.split_whitespace().collect(),
+            Some(sep) => s.split(sep).collect(),
+        };
+        lua.create_sequence_from(parts)
+    })?;
+    strutil_table.set("split", split_fn)?;
+
+    // strutil.trim(s) -> string (Unicode whitespace, incl. U+3000 full-width space)
+    let trim_fn = lua.create_function(|_lua, s: String| Ok(s.trim().to_string()))?;
+    strutil_table.set("trim", trim_fn)?;
+
+    // strutil.starts_with(s, prefix) -> bool
+    let starts_with_fn = lua.create_function(|_lua, (s, prefix): (String, String)| {
+        Ok(s.starts_with(&prefix))
+    })?;
+    strutil_table.set("starts_with", starts_with_fn)?;
+
+    // strutil.join(list, sep?) -> string
+    let join_fn = lua.create_function(|_lua, (list, sep): (Vec<String>, Option<String>)| {
+        Ok(list.join(sep.as_deref().unwrap_or("")))
+    })?;
+    strutil_table.set("join", join_fn)?;
+
+    lua.globals().set("strutil", strutil_table)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{ScriptConfig, create_sandboxed_lua};
+
+    fn setup() -> Lua {
+        let lua = create_sandboxed_lua(&ScriptConfig::default()).unwrap();
+        register_strutil_api(&lua).unwrap();
+        lua
+    }
+
+    fn split(lua: &Lua, expr: &str) -> Vec<String> {
+        lua.load(expr).eval().unwrap()
+    }
+
+    #[test]
+    fn test_split_empty_input() {
+        let lua = setup();
+        assert!(split(&lua, r#"return strutil.split("")"#).is_empty());
+        assert!(split(&lua, r#"return strutil.split("", ",")"#).is_empty());
+        assert!(split(&lua, r#"return strutil.split("   ")"#).is_empty());
+    }
+
+    #[test]
+    fn test_split_whitespace_default() {
+        let lua = setup();
+        assert_eq!(
+            split(&lua, "return strutil.split(\"  get   sword \\t bag \")"),
+            vec!["get", "sword", "bag"]
+        );
+        // Full-width space separates Korean words too.
+        assert_eq!(
+            split(&lua, "return strutil.split(\"검\u{3000}가방\")"),
+            vec!["검", "가방"]
+        );
+    }
+
+    #[test]
+    fn test_split_multi_char_separator() {
+        let lua = setup();
+        assert_eq!(
+            split(&lua, r#"return strutil.split("a::b::::c", "::")"#),
+            vec!["a", "b", "", "c"]
+        );
+        assert_eq!(
+            split(&lua, r#"return strutil.split("no-sep-here", "::")"#),
+            vec!["no-sep-here"]
+        );
+    }
+
+    #[test]
+    fn test_trim_whitespace_and_full_width_space() {
+        let lua = setup();
+        let plain: String = lua
+            .load("return strutil.trim(\" \\t hello \\n\")")
+            .eval()
+            .unwrap();
+        assert_eq!(plain, "hello");
+
+        let korean: String = lua
+            .load("return strutil.trim(\"\u{3000}\u{3000}안녕 하세요\u{3000} \")")
+            .eval()
+            .unwrap();
+        assert_eq!(korean, "안녕 하세요");
+
+        let empty: String = lua.load(r#"return strutil.trim("")"#).eval().unwrap();
+        assert_eq!(empty, "");
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let lua = setup();
+        let result: (bool, bool, bool) = lua
+            .load(
+                r#"return strutil.starts_with("공격 고블린", "공격"),
+                    strutil.starts_with("look", "lo "),
+                    strutil.starts_with("anything", "")"#,
+            )
+            .eval()
+            .unwrap();
+        assert_eq!(result, (true, false, true));
+    }
+
+    #[test]
+    fn test_join() {
+        let lua = setup();
+        let joined: String = lua
+            .load(r#"return strutil.join({"a", "b", 3}, ", ")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(joined, "a, b, 3");
+
+        let empty: String = lua.load(r#"return strutil.join({}, ",")"#).eval().unwrap();
+        assert_eq!(empty, "");
+
+        let no_sep: String = lua.load(r#"return strutil.join({"x", "y"})"#).eval().unwrap();
+        assert_eq!(no_sep, "xy");
+    }
+
+    #[test]
+    fn test_split_join_roundtrip() {
+        let lua = setup();
+        let s: String = lua
+            .load(r#"return strutil.join(strutil.split("a, b,, c", ", "), ", ")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(s, "a, b,, c");
+    }
+}
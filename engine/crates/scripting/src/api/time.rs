@@ -0,0 +1,36 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mlua::{Lua, Result as LuaResult};
+
+/// Register the `now()` global: wall-clock unix time in milliseconds.
+///
+/// Not deterministic and not replayable. Game logic that must line up with
+/// snapshots or seeded runs should use the hook's tick instead; `now()` is
+/// for things like measuring how long a script took or stamping log lines.
+pub fn register_time_api(lua: &Lua) -> LuaResult<()> {
+    let now_fn = lua.create_function(|_lua, ()| {
+        let ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Ok(ms)
+    })?;
+    lua.globals().set("now", now_fn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn now_returns_current_millis() {
+        let lua = Lua::new();
+        register_time_api(&lua).unwrap();
+
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let (a, b): (u64, u64) = lua.load("return now(), now()").eval().unwrap();
+        let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        assert!(before <= a && a <= b && b <= after);
+    }
+}
@@ -22,6 +22,8 @@ pub struct AuthCharacterDetail {
     pub account_id: i64,
     pub name: String,
     pub components: serde_json::Value,
+    /// Script-owned persistent data, separate from `components`.
+    pub script_data: serde_json::Value,
     pub room_id: Option<u64>,
     pub position_x: Option<i32>,
     pub position_y: Option<i32>,
@@ -86,4 +88,11 @@ pub trait AuthProvider {
         room_id: Option<u64>,
         position: Option<(i32, i32)>,
     ) -> Result<(), AuthError>;
+
+    /// Save the script-owned data blob for a character.
+    fn save_script_data(
+        &self,
+        character_id: i64,
+        script_data: &serde_json::Value,
+    ) -> Result<(), AuthError>;
 }
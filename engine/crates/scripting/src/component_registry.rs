@@ -35,22 +35,50 @@ pub trait ScriptComponent: Send + Sync {
     /// Remove this component from the entity.
     fn remove(&self, ecs: &mut EcsAdapter, eid: EntityId) -> Result<(), ScriptError>;
 
-    /// Get all entity IDs that have this component.
+    /// Get all entity IDs that have this component. Should be sorted by
+    /// `(index, generation)`; `ecs:query` re-sorts regardless.
     fn entities_with(&self, ecs: &EcsAdapter) -> Vec<EntityId>;
 }
 
+/// Balance storage behind `ecs:money`/`ecs:add_money`/`ecs:spend_money`.
+/// The game decides which component holds money; the engine owns the
+/// arithmetic so scripts can't drive a balance negative.
+pub trait CurrencyComponent: Send + Sync {
+    /// Current balance, or None if the entity has no wallet.
+    fn balance(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<i64>;
+
+    /// Store a new balance, creating the wallet if needed.
+    fn set_balance(&self, ecs: &mut EcsAdapter, eid: EntityId, amount: i64) -> Result<(), ScriptError>;
+
+    /// Remove the wallet. Used to undo a wallet created inside a rolled-back
+    /// `ecs:transaction`.
+    fn remove_wallet(&self, ecs: &mut EcsAdapter, eid: EntityId) -> Result<(), ScriptError>;
+}
+
 /// Registry mapping string tags to ScriptComponent trait objects.
 pub struct ScriptComponentRegistry {
     components: HashMap<String, Box<dyn ScriptComponent>>,
+    currency: Option<Box<dyn CurrencyComponent>>,
 }
 
 impl ScriptComponentRegistry {
     pub fn new() -> Self {
         Self {
             components: HashMap::new(),
+            currency: None,
         }
     }
 
+    /// Install the component backing the money helpers. Replaces any
+    /// previous one.
+    pub fn set_currency(&mut self, currency: Box<dyn CurrencyComponent>) {
+        self.currency = Some(currency);
+    }
+
+    pub fn currency(&self) -> Option<&dyn CurrencyComponent> {
+        self.currency.as_deref()
+    }
+
     /// Register a component handler by its tag.
     pub fn register(&mut self, handler: Box<dyn ScriptComponent>) {
         let tag = handler.tag().to_string();
@@ -69,6 +97,18 @@ impl ScriptComponentRegistry {
         tags
     }
 
+    /// Registered tags present on `eid` (sorted for determinism).
+    pub fn tags_on(&self, ecs: &EcsAdapter, eid: EntityId) -> Vec<&str> {
+        let mut tags: Vec<&str> = self
+            .components
+            .iter()
+            .filter(|(_, handler)| handler.has(ecs, eid))
+            .map(|(tag, _)| tag.as_str())
+            .collect();
+        tags.sort();
+        tags
+    }
+
     /// Number of registered component types.
     pub fn len(&self) -> usize {
         self.components.len()
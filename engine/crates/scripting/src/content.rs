@@ -13,6 +13,12 @@ pub struct ContentRegistry {
     collections: BTreeMap<String, BTreeMap<String, Value>>,
 }
 
+impl Default for ContentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ContentRegistry {
     pub fn new() -> Self {
         Self {
@@ -75,6 +81,14 @@ impl ContentRegistry {
         Ok(registry)
     }
 
+    /// Re-read `path` and replace this registry's data with the result.
+    /// On error the registry is left unchanged.
+    pub fn reload_dir(&mut self, path: &Path) -> Result<(), ScriptError> {
+        let fresh = Self::load_dir(path)?;
+        self.collections = fresh.collections;
+        Ok(())
+    }
+
     /// Load a single JSON array file (e.g., monsters.json).
     /// Each element must be an object with an "id" field (string).
     fn load_array_file(&mut self, collection: &str, path: &Path) -> Result<(), ScriptError> {
@@ -96,7 +110,7 @@ impl ContentRegistry {
         let col = self
             .collections
             .entry(collection.to_string())
-            .or_insert_with(BTreeMap::new);
+            .or_default();
 
         for (i, item) in arr.iter().enumerate() {
             let obj = item.as_object().ok_or_else(|| {
@@ -155,7 +169,7 @@ impl ContentRegistry {
         let col = self
             .collections
             .entry(collection.to_string())
-            .or_insert_with(BTreeMap::new);
+            .or_default();
 
         for entry in entries {
             let file_path = entry.path();
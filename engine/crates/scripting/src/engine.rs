@@ -1,24 +1,33 @@
 use std::path::Path;
+use std::sync::atomic::Ordering;
 
 use ecs_adapter::{EcsAdapter, EntityId};
 use mlua::{AppDataRef, Function, Lua, LuaSerdeExt};
 use session::{SessionId, SessionManager, SessionOutput};
 use space::model::SpaceModel;
-use tracing::{info, warn};
+use tracing::info;
 
 use crate::api::auth::AuthProxy;
+use crate::api::cooldown::{register_cooldown_api, Cooldowns};
 use crate::api::ecs::EcsProxy;
 use crate::api::log::register_log_api;
 use crate::api::output::OutputProxy;
 use crate::api::session::SessionProxy;
 use crate::api::space::{IntoSpaceKind, SpaceProxy};
+use crate::api::require::register_require_api;
+use crate::api::strutil::register_strutil_api;
+use crate::api::time::register_time_api;
 use crate::auth::AuthProvider;
 use crate::component_registry::ScriptComponentRegistry;
 use crate::content::ContentRegistry;
-use crate::error::ScriptError;
-use crate::hooks::{self, HookRegistry};
+use crate::error::{HookError, ScriptError};
+use crate::hooks::{self, hook_ctx, record_hook_error, HookErrorLog, HookErrorPolicy, HookRegistry};
 use crate::sandbox::{self, ScriptConfig};
 
+/// How many times one action may be rewritten by on_action hooks returning
+/// `{action = ..., args = ...}` before further rewrites are refused.
+pub const MAX_ACTION_REWRITES: usize = 1;
+
 /// Context passed to script execution methods.
 /// Holds mutable references to the game state that Lua scripts can access.
 pub struct ScriptContext<'a, S: SpaceModel> {
@@ -59,7 +68,13 @@ impl ScriptEngine {
         let lua = sandbox::create_sandboxed_lua(&config)?;
 
         // Store HookRegistry in Lua app data so callbacks can access it
-        lua.set_app_data(HookRegistry::new());
+        let mut registry = HookRegistry::new();
+        registry.error_policy = HookErrorPolicy {
+            threshold: config.hook_error_threshold,
+            window_ticks: config.hook_error_window_ticks,
+        };
+        lua.set_app_data(registry);
+        lua.set_app_data(HookErrorLog::default());
 
         // Register hooks.* API
         hooks::register_hooks_api(&lua)?;
@@ -67,6 +82,18 @@ impl ScriptEngine {
         // Register log.* API
         register_log_api(&lua)?;
 
+        // Register strutil.* API
+        register_strutil_api(&lua)?;
+
+        // Register cooldown.* API
+        register_cooldown_api(&lua)?;
+
+        // Register now() wall clock
+        register_time_api(&lua)?;
+
+        // Register sandboxed require() for shared modules in lib_dir
+        register_require_api(&lua, config.lib_dir.clone())?;
+
         info!(
             "ScriptEngine initialized (memory_limit={}KB, instruction_limit={})",
             config.memory_limit / 1024,
@@ -81,6 +108,41 @@ impl ScriptEngine {
         })
     }
 
+    /// Drain the hook errors recorded since the last call.
+    ///
+    /// Every `run_on_*` method keeps going when a callback raises; the failure
+    /// is queued here so callers can alert on it or count it. Servers drain
+    /// this once per tick; only errors beyond the pending-queue limit are
+    /// logged and counted by the engine itself.
+    pub fn take_hook_errors(&self) -> Vec<HookError> {
+        self.lua
+            .app_data_mut::<HookErrorLog>()
+            .map(|mut log| std::mem::take(&mut log.errors))
+            .unwrap_or_default()
+    }
+
+    /// Re-enable hook callbacks that were disabled for repeated errors and
+    /// clear their failure counts. Returns how many were re-enabled.
+    pub fn reenable_hooks(&self) -> usize {
+        let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
+        let count = hooks.reenable_all();
+        if count > 0 {
+            info!(count, "Re-enabled disabled hooks");
+        }
+        count
+    }
+
+    /// Record the tick of the hook pass about to run (for error windows and
+    /// `cooldown.*` timers).
+    fn begin_hook_pass(&self, tick: u64) {
+        if let Some(mut hooks) = self.lua.app_data_mut::<HookRegistry>() {
+            hooks.current_tick = tick;
+        }
+        if let Some(mut cooldowns) = self.lua.app_data_mut::<Cooldowns>() {
+            cooldowns.advance(tick);
+        }
+    }
+
     /// Get a mutable reference to the component registry for registration.
     pub fn component_registry_mut(&mut self) -> &mut ScriptComponentRegistry {
         &mut self.component_registry
@@ -95,6 +157,34 @@ impl ScriptEngine {
     /// Called once at startup, before loading scripts.
     /// Content is read-only — no proxy needed, just plain Lua tables.
     pub fn register_content(&self, registry: &ContentRegistry) -> Result<(), ScriptError> {
+        let content_table = self.build_content_tables(registry)?;
+        self.lua.globals().set("content", content_table)?;
+
+        Ok(())
+    }
+
+    /// Replace the `content` global with freshly loaded data without a restart.
+    ///
+    /// Every entry is converted before anything is touched, so a conversion
+    /// error leaves the old content in place. The existing tables are then
+    /// refilled in place down to the entry level: Luau resolves constant
+    /// paths like `content.items.potion` once when a script is loaded, and
+    /// scripts may cache `content.items` in a local, so swapping in new
+    /// tables would leave those readers on stale data.
+    pub fn reload_content(&self, registry: &ContentRegistry) -> Result<(), ScriptError> {
+        let fresh = self.build_content_tables(registry)?;
+
+        match self.lua.globals().get::<mlua::Value>("content")? {
+            // content -> collection -> entry
+            mlua::Value::Table(current) => refill_table(&self.lua, &current, &fresh, 2)?,
+            _ => self.lua.globals().set("content", fresh)?,
+        }
+
+        info!("Content reloaded ({} entries)", registry.total_count());
+        Ok(())
+    }
+
+    fn build_content_tables(&self, registry: &ContentRegistry) -> Result<mlua::Table, ScriptError> {
         let content_table = self.lua.create_table()?;
 
         for (collection_name, items) in registry.collections() {
@@ -106,8 +196,30 @@ impl ScriptEngine {
             content_table.set(collection_name.as_str(), col_table)?;
         }
 
-        self.lua.globals().set("content", content_table)?;
+        Ok(content_table)
+    }
+
+    /// Reseed Lua's `math.random` with the engine's per-tick seed so script
+    /// randomness is reproducible from the `TickLoop` master seed. Call once
+    /// per tick before running any hook.
+    pub fn set_tick_seed(&self, seed: u64) -> Result<(), ScriptError> {
+        // Luau's randomseed takes a 32-bit int; fold both halves into it.
+        let folded = ((seed >> 32) ^ seed) as u32 as i32;
+        let math: mlua::Table = self.lua.globals().get("math")?;
+        let randomseed: Function = math.get("randomseed")?;
+        randomseed.call::<()>(folded)?;
+        Ok(())
+    }
 
+    /// Expose server settings to scripts as a read-only `config` global.
+    /// Called once at startup, before loading scripts. The table and any
+    /// nested tables are frozen, so assignments from Lua raise an error.
+    pub fn set_config_globals(&self, values: &serde_json::Value) -> Result<(), ScriptError> {
+        let value: mlua::Value = self.lua.to_value(values)?;
+        if let mlua::Value::Table(ref table) = value {
+            freeze_table(table)?;
+        }
+        self.lua.globals().set("config", value)?;
         Ok(())
     }
 
@@ -165,6 +277,11 @@ impl ScriptEngine {
 
     /// Run all on_init hooks (called once at startup).
     /// Returns collected session outputs from Lua scripts.
+    ///
+    /// The pass runs under `init_instruction_limit` instead of the per-tick
+    /// limit. A hook that exhausts it aborts init with
+    /// `ScriptError::InitInstructionLimitExceeded`; ordinary hook errors are
+    /// recorded and the remaining hooks still run.
     pub fn run_on_init<S: SpaceModel + IntoSpaceKind>(
         &self,
         ctx: &mut ScriptContext<'_, S>,
@@ -177,7 +294,10 @@ impl ScriptEngine {
 
         let mut outputs = Vec::new();
 
-        sandbox::reset_instruction_counter(&self.lua, &self.config);
+        self.begin_hook_pass(ctx.tick);
+        let limit = self.config.init_instruction_limit;
+        let budget = sandbox::set_instruction_budget(&self.lua, limit);
+        let mut exhausted_by = None;
 
         self.lua.scope(|scope| {
             let ecs_proxy = unsafe {
@@ -187,8 +307,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -201,35 +324,45 @@ impl ScriptEngine {
             self.lua.globals().set("sessions", session_ud)?;
 
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_init {
-                let func: Function = self.lua.registry_value(key)?;
+            for entry in &hooks.on_init {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
                 if let Err(e) = func.call::<()>(()) {
-                    warn!("on_init hook error: {}", e);
+                    if budget.load(Ordering::Relaxed) >= limit {
+                        exhausted_by = Some(entry.source.clone());
+                        break;
+                    }
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_init", &e);
                 }
             }
 
             Ok(())
         })?;
 
+        if let Some(script) = exhausted_by {
+            return Err(ScriptError::InitInstructionLimitExceeded { script, limit });
+        }
         Ok(outputs)
     }
 
-    /// Run all on_tick hooks.
+    /// Run all on_tick hooks, then any on_interval hooks due this tick.
     /// Returns collected session outputs from Lua scripts.
     pub fn run_on_tick<S: SpaceModel + IntoSpaceKind>(
         &self,
         ctx: &mut ScriptContext<'_, S>,
     ) -> Result<Vec<SessionOutput>, ScriptError> {
+        let tick = ctx.tick;
         let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-        if hooks.on_tick.is_empty() {
+        if !hooks.has_tick_work(tick) {
             return Ok(Vec::new());
         }
-
-        let tick = ctx.tick;
         drop(hooks);
 
         let mut outputs = Vec::new();
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -240,8 +373,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -254,10 +390,32 @@ impl ScriptEngine {
             self.lua.globals().set("sessions", session_ud)?;
 
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_tick {
-                let func: Function = self.lua.registry_value(key)?;
-                if let Err(e) = func.call::<()>(tick) {
-                    warn!("on_tick hook error: {}", e);
+            for entry in &hooks.on_tick {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                let started = std::time::Instant::now();
+                let result = func.call::<()>(tick);
+                observability::record_script_cost(&entry.source, started.elapsed());
+                if let Err(e) = result {
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_tick", &e);
+                }
+            }
+            for entry in &hooks.on_interval {
+                if !tick.is_multiple_of(entry.interval) {
+                    continue;
+                }
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                let started = std::time::Instant::now();
+                let result = func.call::<()>(tick);
+                observability::record_script_cost(&entry.source, started.elapsed());
+                if let Err(e) = result {
+                    let hook = format!("on_interval({})", entry.interval);
+                    record_hook_error(&self.lua, &entry.health, &entry.source, &hook, &e);
                 }
             }
 
@@ -269,6 +427,12 @@ impl ScriptEngine {
 
     /// Run on_action hooks for a specific action.
     /// Returns (outputs, consumed) where consumed=true means the action was handled by Lua.
+    ///
+    /// A hook may instead return `{action = ..., args = ...}` to rewrite the
+    /// action: dispatch restarts with the new name and args (the same
+    /// session/entity). Only `MAX_ACTION_REWRITES` rewrites are followed; a
+    /// further rewrite is dropped with a warning and the action counts as
+    /// unconsumed, so alias loops can't spin.
     pub fn run_on_action<S: SpaceModel + IntoSpaceKind>(
         &self,
         ctx: &mut ScriptContext<'_, S>,
@@ -284,6 +448,7 @@ impl ScriptEngine {
         let mut outputs = Vec::new();
         let mut consumed = false;
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -294,8 +459,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -307,28 +475,60 @@ impl ScriptEngine {
             self.lua.globals().set("output", output_ud)?;
             self.lua.globals().set("sessions", session_ud)?;
 
-            // Build context table for the callback
-            let action_ctx = self.lua.create_table()?;
-            action_ctx.set("session_id", action.session_id.0)?;
-            action_ctx.set("entity", action.entity.to_u64())?;
-            action_ctx.set("action", action.action_name.as_str())?;
-            action_ctx.set("args", action.args.as_str())?;
-
-            let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            if let Some(callbacks) = hooks.on_action.get(&action.action_name) {
-                for key in callbacks {
-                    let func: Function = self.lua.registry_value(key)?;
-                    match func.call::<mlua::Value>(action_ctx.clone()) {
-                        Ok(mlua::Value::Boolean(true)) => {
-                            consumed = true;
-                            break;
+            let mut action_name = action.action_name.clone();
+            let mut args = action.args.clone();
+            let mut rewrites = 0;
+            loop {
+                // Build context table for the callback
+                let action_ctx = hook_ctx(&self.lua, ctx.tick)?;
+                action_ctx.set("session_id", action.session_id.0)?;
+                action_ctx.set("entity", action.entity.to_u64())?;
+                action_ctx.set("action", action_name.as_str())?;
+                action_ctx.set("args", args.as_str())?;
+
+                let mut rewrite = None;
+                let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
+                if let Some(callbacks) = hooks.on_action.get(&action_name) {
+                    for entry in callbacks {
+                        if entry.health.is_disabled() {
+                            continue;
                         }
-                        Ok(_) => {}
-                        Err(e) => {
-                            warn!("on_action('{}') hook error: {}", action.action_name, e);
+                        let func: Function = self.lua.registry_value(&entry.callback)?;
+                        match func.call::<mlua::Value>(action_ctx.clone()) {
+                            Ok(mlua::Value::Boolean(true)) => {
+                                consumed = true;
+                                break;
+                            }
+                            Ok(mlua::Value::Table(t)) => {
+                                if let Ok(Some(next)) = t.get::<Option<String>>("action") {
+                                    let next_args = t.get::<Option<String>>("args").ok().flatten();
+                                    rewrite = Some((next, next_args.unwrap_or_default()));
+                                    break;
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                let hook = format!("on_action('{}')", action_name);
+                                record_hook_error(&self.lua, &entry.health, &entry.source, &hook, &e);
+                            }
                         }
                     }
                 }
+                drop(hooks);
+
+                let Some((next, next_args)) = rewrite else { break };
+                if rewrites >= MAX_ACTION_REWRITES {
+                    tracing::warn!(
+                        from = %action_name,
+                        to = %next,
+                        original = %action.action_name,
+                        "on_action rewrite limit reached, dropping action"
+                    );
+                    break;
+                }
+                rewrites += 1;
+                action_name = next;
+                args = next_args;
             }
 
             Ok(())
@@ -353,6 +553,7 @@ impl ScriptEngine {
 
         let mut outputs = Vec::new();
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -363,8 +564,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -383,11 +587,15 @@ impl ScriptEngine {
                 None => mlua::Value::Nil,
             };
 
+            let ctx_table = hook_ctx(&self.lua, ctx.tick)?;
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_enter_room {
-                let func: Function = self.lua.registry_value(key)?;
-                if let Err(e) = func.call::<()>((entity_u64, room_u64, old_room_val.clone())) {
-                    warn!("on_enter_room hook error: {}", e);
+            for entry in &hooks.on_enter_room {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                if let Err(e) = func.call::<()>((entity_u64, room_u64, old_room_val.clone(), ctx_table.clone())) {
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_enter_room", &e);
                 }
             }
 
@@ -411,6 +619,7 @@ impl ScriptEngine {
 
         let mut outputs = Vec::new();
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -421,8 +630,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -434,11 +646,15 @@ impl ScriptEngine {
             self.lua.globals().set("output", output_ud)?;
             self.lua.globals().set("sessions", session_ud)?;
 
+            let ctx_table = hook_ctx(&self.lua, ctx.tick)?;
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_connect {
-                let func: Function = self.lua.registry_value(key)?;
-                if let Err(e) = func.call::<()>(session_id.0) {
-                    warn!("on_connect hook error: {}", e);
+            for entry in &hooks.on_connect {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                if let Err(e) = func.call::<()>((session_id.0, ctx_table.clone())) {
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_connect", &e);
                 }
             }
 
@@ -466,6 +682,7 @@ impl ScriptEngine {
         let mut outputs = Vec::new();
         let mut handled = false;
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -476,8 +693,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -489,7 +709,7 @@ impl ScriptEngine {
             self.lua.globals().set("output", output_ud)?;
             self.lua.globals().set("sessions", session_ud)?;
 
-            let admin_ctx = self.lua.create_table()?;
+            let admin_ctx = hook_ctx(&self.lua, ctx.tick)?;
             admin_ctx.set("session_id", admin.session_id.0)?;
             admin_ctx.set("entity", admin.entity.to_u64())?;
             admin_ctx.set("command", admin.command.as_str())?;
@@ -503,6 +723,9 @@ impl ScriptEngine {
                     if admin.permission < entry.min_permission {
                         continue;
                     }
+                    if entry.health.is_disabled() {
+                        continue;
+                    }
                     let func: Function = self.lua.registry_value(&entry.callback)?;
                     match func.call::<mlua::Value>(admin_ctx.clone()) {
                         Ok(mlua::Value::Boolean(true)) => {
@@ -513,7 +736,8 @@ impl ScriptEngine {
                             handled = true;
                         }
                         Err(e) => {
-                            warn!("on_admin('{}') hook error: {}", admin.command, e);
+                            let hook = format!("on_admin('{}')", admin.command);
+                            record_hook_error(&self.lua, &entry.health, &entry.source, &hook, &e);
                         }
                     }
                 }
@@ -551,6 +775,7 @@ impl ScriptEngine {
                 as *const dyn AuthProvider
         });
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -561,8 +786,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -580,11 +808,15 @@ impl ScriptEngine {
                 self.lua.globals().set("auth", auth_ud)?;
             }
 
+            let ctx_table = hook_ctx(&self.lua, ctx.tick)?;
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_input {
-                let func: Function = self.lua.registry_value(key)?;
-                if let Err(e) = func.call::<()>((session_id.0, line.to_string())) {
-                    warn!("on_input hook error: {}", e);
+            for entry in &hooks.on_input {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                if let Err(e) = func.call::<()>((session_id.0, line.to_string(), ctx_table.clone())) {
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_input", &e);
                 }
             }
 
@@ -618,6 +850,7 @@ impl ScriptEngine {
                 as *const dyn AuthProvider
         });
 
+        self.begin_hook_pass(ctx.tick);
         sandbox::reset_instruction_counter(&self.lua, &self.config);
 
         self.lua.scope(|scope| {
@@ -628,8 +861,11 @@ impl ScriptEngine {
                 )
             };
             let space_proxy = unsafe { SpaceProxy::from_space(ctx.space as *mut S) };
-            let output_proxy = unsafe { OutputProxy::new(&mut outputs as *mut Vec<SessionOutput>) };
-            let session_proxy = unsafe { SessionProxy::new(ctx.sessions as *mut SessionManager) };
+            let sessions_ptr = ctx.sessions as *mut SessionManager;
+            let output_proxy = unsafe {
+                OutputProxy::with_sessions(&mut outputs as *mut Vec<SessionOutput>, sessions_ptr)
+            };
+            let session_proxy = unsafe { SessionProxy::new(sessions_ptr) };
 
             let ecs_ud = scope.create_userdata(ecs_proxy)?;
             let space_ud = scope.create_userdata(space_proxy)?;
@@ -647,11 +883,15 @@ impl ScriptEngine {
                 self.lua.globals().set("auth", auth_ud)?;
             }
 
+            let ctx_table = hook_ctx(&self.lua, ctx.tick)?;
             let hooks = self.lua.app_data_ref::<HookRegistry>().unwrap();
-            for key in &hooks.on_disconnect {
-                let func: Function = self.lua.registry_value(key)?;
-                if let Err(e) = func.call::<()>(session_id.0) {
-                    warn!("on_disconnect hook error: {}", e);
+            for entry in &hooks.on_disconnect {
+                if entry.health.is_disabled() {
+                    continue;
+                }
+                let func: Function = self.lua.registry_value(&entry.callback)?;
+                if let Err(e) = func.call::<()>((session_id.0, ctx_table.clone())) {
+                    record_hook_error(&self.lua, &entry.health, &entry.source, "on_disconnect", &e);
                 }
             }
 
@@ -684,6 +924,45 @@ impl ScriptEngine {
     }
 }
 
+/// Make `dst` hold exactly the keys of `src`. Table values present in both
+/// are refilled recursively (up to `depth` levels) instead of replaced, so
+/// existing references to them observe the new contents; tables that
+/// disappear are emptied the same way before being unlinked.
+fn refill_table(lua: &Lua, dst: &mlua::Table, src: &mlua::Table, depth: usize) -> mlua::Result<()> {
+    let old: Vec<(mlua::Value, mlua::Value)> = dst.pairs().collect::<mlua::Result<_>>()?;
+    for (key, old_value) in old {
+        let new_value: mlua::Value = src.raw_get(key.clone())?;
+        match (old_value, new_value) {
+            (mlua::Value::Table(o), mlua::Value::Table(n)) if depth > 0 => {
+                refill_table(lua, &o, &n, depth - 1)?;
+            }
+            (mlua::Value::Table(o), mlua::Value::Nil) if depth > 0 => {
+                refill_table(lua, &o, &lua.create_table()?, depth - 1)?;
+                dst.raw_set(key, mlua::Value::Nil)?;
+            }
+            (_, new_value) => dst.raw_set(key, new_value)?,
+        }
+    }
+    for pair in src.pairs::<mlua::Value, mlua::Value>() {
+        let (key, value) = pair?;
+        if dst.raw_get::<mlua::Value>(key.clone())?.is_nil() {
+            dst.raw_set(key, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Mark a table and every table nested in it read-only.
+fn freeze_table(table: &mlua::Table) -> mlua::Result<()> {
+    for pair in table.pairs::<mlua::Value, mlua::Value>() {
+        if let (_, mlua::Value::Table(child)) = pair? {
+            freeze_table(&child)?;
+        }
+    }
+    table.set_readonly(true);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -894,186 +1173,327 @@ mod tests {
     }
 
     #[test]
-    fn test_run_on_action_consumed() {
+    fn test_run_on_interval() {
         let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
 
         engine
             .load_script(
-                "action_test",
+                "interval_test",
                 r#"
-                hooks.on_action("dance", function(ctx)
-                    output:send(ctx.session_id, "You dance!")
-                    return true
+                hooks.on_interval(10, function(tick)
+                    output:send(1, "weather " .. tostring(tick))
+                end)
+            "#,
+            )
+            .unwrap();
+        assert_eq!(engine.hook_registry().on_interval_count(), 1);
+        assert_eq!(engine.hook_registry().on_tick_count(), 0);
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut fired = Vec::new();
+        for tick in 1..=35 {
+            let mut ctx = ScriptContext {
+                ecs: &mut ecs,
+                space: &mut space,
+                sessions: &mut sessions,
+                tick,
+            };
+            for out in engine.run_on_tick(&mut ctx).unwrap() {
+                fired.push(out.text);
+            }
+        }
+        assert_eq!(fired, vec!["weather 10", "weather 20", "weather 30"]);
+    }
+
+    #[test]
+    fn test_cooldown_follows_hook_tick() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "cooldown_test",
+                r#"
+                hooks.on_tick(function(tick)
+                    if cooldown.ready("shout", 4) then
+                        output:send(1, "shout " .. tostring(tick))
+                    end
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut fired = Vec::new();
+        for tick in 1..=10 {
+            let mut ctx = ScriptContext {
+                ecs: &mut ecs,
+                space: &mut space,
+                sessions: &mut sessions,
+                tick,
+            };
+            for out in engine.run_on_tick(&mut ctx).unwrap() {
+                fired.push(out.text);
+            }
+        }
+        assert_eq!(fired, vec!["shout 1", "shout 5", "shout 9"]);
+    }
+
+    #[test]
+    fn test_on_interval_rejects_zero() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        let result = engine.load_script("bad_interval", "hooks.on_interval(0, function() end)");
+        assert!(result.is_err());
+        assert_eq!(engine.hook_registry().on_interval_count(), 0);
+    }
+
+    #[test]
+    fn test_on_tick_error_returned_with_source() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "healthy",
+                r#"
+                hooks.on_tick(function(tick) end)
+            "#,
+            )
+            .unwrap();
+        engine
+            .load_script(
+                "broken_tick",
+                r#"
+                hooks.on_tick(function(tick)
+                    error("boom at " .. tick)
                 end)
             "#,
             )
             .unwrap();
 
         let (mut ecs, mut space, mut sessions) = setup_world();
-        let entity = ecs.spawn_entity();
         let mut ctx = ScriptContext {
             ecs: &mut ecs,
             space: &mut space,
             sessions: &mut sessions,
-            tick: 1,
+            tick: 7,
         };
 
-        let action = ActionInfo {
-            action_name: "dance".to_string(),
-            args: String::new(),
-            session_id: SessionId(42),
-            entity,
-        };
+        // The failing hook does not fail the whole call
+        engine.run_on_tick(&mut ctx).unwrap();
 
-        let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
-        assert!(consumed);
-        assert_eq!(outputs.len(), 1);
-        assert_eq!(outputs[0].text, "You dance!");
+        let errors = engine.take_hook_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].source, "broken_tick");
+        assert_eq!(errors[0].hook, "on_tick");
+        assert!(errors[0].message.contains("boom at 7"));
+
+        // Drained
+        assert!(engine.take_hook_errors().is_empty());
     }
 
     #[test]
-    fn test_run_on_action_not_consumed() {
+    fn test_on_action_error_names_action() {
         let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
 
         engine
             .load_script(
-                "action_test",
+                "combat",
                 r#"
-                hooks.on_action("dance", function(ctx)
-                    -- do something but don't consume
-                    return false
+                hooks.on_action("attack", function(ctx)
+                    local missing = nil
+                    return missing.field
                 end)
             "#,
             )
             .unwrap();
 
         let (mut ecs, mut space, mut sessions) = setup_world();
-        let entity = ecs.spawn_entity();
         let mut ctx = ScriptContext {
             ecs: &mut ecs,
             space: &mut space,
             sessions: &mut sessions,
             tick: 1,
         };
-
         let action = ActionInfo {
-            action_name: "dance".to_string(),
-            args: String::new(),
-            session_id: SessionId(42),
-            entity,
+            action_name: "attack".to_string(),
+            args: "goblin".to_string(),
+            session_id: SessionId(1),
+            entity: EntityId::new(1, 0),
         };
 
-        let (_outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        let (_, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
         assert!(!consumed);
+
+        let errors = engine.take_hook_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].source, "combat");
+        assert_eq!(errors[0].hook, "on_action('attack')");
     }
 
     #[test]
-    fn test_run_on_action_no_handler() {
-        let engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    fn test_hooks_receive_context_tick() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        engine
+            .load_script(
+                "tick_probe",
+                r#"
+                hooks.on_action("look", function(ctx)
+                    output:send(ctx.session_id, "action " .. tostring(ctx.tick))
+                    return true
+                end)
+                hooks.on_connect(function(session_id, ctx)
+                    output:send(session_id, "connect " .. tostring(ctx.tick))
+                end)
+                hooks.on_enter_room(function(entity, room, old_room, ctx)
+                    output:send(1, "enter " .. tostring(ctx.tick))
+                end)
+                hooks.on_admin("uptime", 0, function(ctx)
+                    output:send(ctx.session_id, "admin " .. tostring(ctx.tick))
+                    return true
+                end)
+            "#,
+            )
+            .unwrap();
 
         let (mut ecs, mut space, mut sessions) = setup_world();
         let entity = ecs.spawn_entity();
+        let room = EntityId::new(100, 0);
         let mut ctx = ScriptContext {
             ecs: &mut ecs,
             space: &mut space,
             sessions: &mut sessions,
-            tick: 1,
+            tick: 42,
         };
-
         let action = ActionInfo {
-            action_name: "nonexistent".to_string(),
+            action_name: "look".to_string(),
             args: String::new(),
             session_id: SessionId(1),
-            entity,
+            entity: EntityId::new(1, 0),
         };
 
         let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
-        assert!(!consumed);
-        assert!(outputs.is_empty());
+        assert!(consumed);
+        assert_eq!(outputs[0].text, "action 42");
+
+        ctx.tick = 43;
+        let outputs = engine.run_on_connect(&mut ctx, SessionId(1)).unwrap();
+        assert_eq!(outputs[0].text, "connect 43");
+
+        let outputs = engine.run_on_enter_room(&mut ctx, entity, room, None).unwrap();
+        assert_eq!(outputs[0].text, "enter 43");
+
+        let admin = AdminInfo {
+            command: "uptime".to_string(),
+            args: String::new(),
+            session_id: SessionId(1),
+            entity: EntityId::new(1, 0),
+            permission: 0,
+        };
+        let (outputs, handled) = engine.run_on_admin(&mut ctx, &admin).unwrap();
+        assert!(handled);
+        assert_eq!(outputs[0].text, "admin 43");
     }
 
     #[test]
-    fn test_run_on_enter_room() {
-        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    fn test_failing_hook_disabled_after_threshold() {
+        let config = ScriptConfig {
+            hook_error_threshold: 3,
+            hook_error_window_ticks: 100,
+            ..Default::default()
+        };
+        let mut engine = ScriptEngine::new(config).unwrap();
 
         engine
             .load_script(
-                "enter_room_test",
+                "always_fails",
                 r#"
-                hooks.on_enter_room(function(entity, room, old_room)
-                    output:send(1, "Entity entered room")
+                calls = 0
+                hooks.on_tick(function(tick)
+                    calls = calls + 1
+                    error("broken")
                 end)
             "#,
             )
             .unwrap();
 
         let (mut ecs, mut space, mut sessions) = setup_world();
-        let entity = EntityId::new(1, 0);
-        let room = EntityId::new(100, 0);
+        for tick in 1..=10 {
+            let mut ctx = ScriptContext {
+                ecs: &mut ecs,
+                space: &mut space,
+                sessions: &mut sessions,
+                tick,
+            };
+            engine.run_on_tick(&mut ctx).unwrap();
+        }
 
+        // Invoked until it exceeded the threshold (3), then skipped
+        let calls: u32 = engine.lua().globals().get("calls").unwrap();
+        assert_eq!(calls, 4);
+        assert_eq!(engine.take_hook_errors().len(), 4);
+
+        // Recovery
+        assert_eq!(engine.reenable_hooks(), 1);
         let mut ctx = ScriptContext {
             ecs: &mut ecs,
             space: &mut space,
             sessions: &mut sessions,
-            tick: 1,
+            tick: 11,
         };
-
-        let outputs = engine
-            .run_on_enter_room(&mut ctx, entity, room, None)
-            .unwrap();
-        assert_eq!(outputs.len(), 1);
-        assert_eq!(outputs[0].text, "Entity entered room");
+        engine.run_on_tick(&mut ctx).unwrap();
+        let calls: u32 = engine.lua().globals().get("calls").unwrap();
+        assert_eq!(calls, 5);
     }
 
     #[test]
-    fn test_run_on_connect() {
-        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    fn test_hook_errors_outside_window_do_not_disable() {
+        let config = ScriptConfig {
+            hook_error_threshold: 2,
+            hook_error_window_ticks: 5,
+            ..Default::default()
+        };
+        let mut engine = ScriptEngine::new(config).unwrap();
 
         engine
             .load_script(
-                "connect_test",
+                "sometimes_fails",
                 r#"
-                hooks.on_connect(function(session_id)
-                    output:send(session_id, "Welcome!")
+                calls = 0
+                hooks.on_tick(function(tick)
+                    calls = calls + 1
+                    if tick % 5 == 0 then error("periodic") end
                 end)
             "#,
             )
             .unwrap();
 
         let (mut ecs, mut space, mut sessions) = setup_world();
-        let mut ctx = ScriptContext {
-            ecs: &mut ecs,
-            space: &mut space,
-            sessions: &mut sessions,
-            tick: 1,
-        };
+        for tick in 1..=30 {
+            let mut ctx = ScriptContext {
+                ecs: &mut ecs,
+                space: &mut space,
+                sessions: &mut sessions,
+                tick,
+            };
+            engine.run_on_tick(&mut ctx).unwrap();
+        }
 
-        let outputs = engine.run_on_connect(&mut ctx, SessionId(7)).unwrap();
-        assert_eq!(outputs.len(), 1);
-        assert_eq!(outputs[0].session_id, SessionId(7));
-        assert_eq!(outputs[0].text, "Welcome!");
+        // One error per window never exceeds the threshold
+        let calls: u32 = engine.lua().globals().get("calls").unwrap();
+        assert_eq!(calls, 30);
+        assert_eq!(engine.reenable_hooks(), 0);
     }
 
     #[test]
-    fn test_on_tick_ecs_access() {
+    fn test_run_on_action_consumed() {
         let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
-        engine
-            .component_registry_mut()
-            .register(Box::new(HealthHandler));
 
         engine
             .load_script(
-                "ecs_access",
+                "action_test",
                 r#"
-                hooks.on_tick(function(tick)
-                    local entities = ecs:query("Health")
-                    for _, eid in ipairs(entities) do
-                        local hp = ecs:get(eid, "Health")
-                        if hp then
-                            hp.current = hp.current - 1
-                            ecs:set(eid, "Health", hp)
-                        end
-                    end
+                hooks.on_action("dance", function(ctx)
+                    output:send(ctx.session_id, "You dance!")
+                    return true
                 end)
             "#,
             )
@@ -1081,9 +1501,6 @@ mod tests {
 
         let (mut ecs, mut space, mut sessions) = setup_world();
         let entity = ecs.spawn_entity();
-        ecs.set_component(entity, Health { current: 10, max: 10 })
-            .unwrap();
-
         let mut ctx = ScriptContext {
             ecs: &mut ecs,
             space: &mut space,
@@ -1091,12 +1508,425 @@ mod tests {
             tick: 1,
         };
 
-        engine.run_on_tick(&mut ctx).unwrap();
-
-        // Health should have been decremented
-        let hp = ctx.ecs.get_component::<Health>(entity).unwrap();
-        assert_eq!(hp.current, 9);
-        assert_eq!(hp.max, 10);
+        let action = ActionInfo {
+            action_name: "dance".to_string(),
+            args: String::new(),
+            session_id: SessionId(42),
+            entity,
+        };
+
+        let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(consumed);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].text, "You dance!");
+    }
+
+    #[test]
+    fn test_run_on_action_not_consumed() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "action_test",
+                r#"
+                hooks.on_action("dance", function(ctx)
+                    -- do something but don't consume
+                    return false
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = ecs.spawn_entity();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let action = ActionInfo {
+            action_name: "dance".to_string(),
+            args: String::new(),
+            session_id: SessionId(42),
+            entity,
+        };
+
+        let (_outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(!consumed);
+    }
+
+    #[test]
+    fn test_run_on_action_rewrite_redirects() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "rewrite_test",
+                r#"
+                hooks.on_action("attack", function(ctx)
+                    if ctx.args == "dragon" then
+                        return { action = "flee", args = "north" }
+                    end
+                    output:send(ctx.session_id, "You attack " .. ctx.args)
+                    return true
+                end)
+                hooks.on_action("flee", function(ctx)
+                    output:send(ctx.session_id, ctx.action .. " " .. ctx.args)
+                    return true
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = ecs.spawn_entity();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let action = ActionInfo {
+            action_name: "attack".to_string(),
+            args: "dragon".to_string(),
+            session_id: SessionId(42),
+            entity,
+        };
+        let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(consumed);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].text, "flee north");
+
+        let action = ActionInfo {
+            args: "goblin".to_string(),
+            ..action
+        };
+        let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(consumed);
+        assert_eq!(outputs[0].text, "You attack goblin");
+    }
+
+    #[test]
+    fn test_run_on_action_rewrite_loop_is_cut_off() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "rewrite_loop",
+                r#"
+                calls = 0
+                hooks.on_action("ping", function(ctx)
+                    calls = calls + 1
+                    return { action = "pong" }
+                end)
+                hooks.on_action("pong", function(ctx)
+                    calls = calls + 1
+                    return { action = "ping" }
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = ecs.spawn_entity();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let action = ActionInfo {
+            action_name: "ping".to_string(),
+            args: String::new(),
+            session_id: SessionId(42),
+            entity,
+        };
+        let (_outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(!consumed);
+        let calls: i64 = engine.lua.globals().get("calls").unwrap();
+        assert_eq!(calls, 1 + MAX_ACTION_REWRITES as i64);
+    }
+
+    #[test]
+    fn test_run_on_action_no_handler() {
+        let engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = ecs.spawn_entity();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let action = ActionInfo {
+            action_name: "nonexistent".to_string(),
+            args: String::new(),
+            session_id: SessionId(1),
+            entity,
+        };
+
+        let (outputs, consumed) = engine.run_on_action(&mut ctx, &action).unwrap();
+        assert!(!consumed);
+        assert!(outputs.is_empty());
+    }
+
+    #[test]
+    fn test_run_on_enter_room() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "enter_room_test",
+                r#"
+                hooks.on_enter_room(function(entity, room, old_room)
+                    output:send(1, "Entity entered room")
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = EntityId::new(1, 0);
+        let room = EntityId::new(100, 0);
+
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let outputs = engine
+            .run_on_enter_room(&mut ctx, entity, room, None)
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].text, "Entity entered room");
+    }
+
+    #[test]
+    fn test_run_on_connect() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "connect_test",
+                r#"
+                hooks.on_connect(function(session_id)
+                    output:send(session_id, "Welcome!")
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let outputs = engine.run_on_connect(&mut ctx, SessionId(7)).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].session_id, SessionId(7));
+        assert_eq!(outputs[0].text, "Welcome!");
+        assert!(!outputs[0].disconnect);
+    }
+
+    #[test]
+    fn test_hook_requests_disconnect() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "kick_test",
+                r#"
+                hooks.on_connect(function(session_id)
+                    output:send_and_disconnect(session_id, "Banned.")
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let outputs = engine.run_on_connect(&mut ctx, SessionId(7)).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].session_id, SessionId(7));
+        assert_eq!(outputs[0].text, "Banned.");
+        assert!(outputs[0].disconnect);
+    }
+
+    #[test]
+    fn test_on_tick_ecs_access() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        engine
+            .component_registry_mut()
+            .register(Box::new(HealthHandler));
+
+        engine
+            .load_script(
+                "ecs_access",
+                r#"
+                hooks.on_tick(function(tick)
+                    local entities = ecs:query("Health")
+                    for _, eid in ipairs(entities) do
+                        local hp = ecs:get(eid, "Health")
+                        if hp then
+                            hp.current = hp.current - 1
+                            ecs:set(eid, "Health", hp)
+                        end
+                    end
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let entity = ecs.spawn_entity();
+        ecs.set_component(entity, Health { current: 10, max: 10 })
+            .unwrap();
+
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        engine.run_on_tick(&mut ctx).unwrap();
+
+        // Health should have been decremented
+        let hp = ctx.ecs.get_component::<Health>(entity).unwrap();
+        assert_eq!(hp.current, 9);
+        assert_eq!(hp.max, 10);
+    }
+
+    #[test]
+    fn test_config_globals_read_only() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        engine
+            .set_config_globals(&serde_json::json!({
+                "mode": "mud",
+                "tps": 20,
+                "grid": { "width": 64 },
+            }))
+            .unwrap();
+
+        engine
+            .load_script(
+                "config_test",
+                r#"
+                hooks.on_init(function()
+                    output:send(1, config.mode .. ":" .. tostring(config.tps) .. ":" .. tostring(config.grid.width))
+                    local ok = pcall(function() config.tps = 1 end)
+                    local nested_ok = pcall(function() config.grid.width = 1 end)
+                    output:send(1, tostring(ok) .. ":" .. tostring(nested_ok) .. ":" .. tostring(config.tps))
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 0,
+        };
+        let outputs = engine.run_on_init(&mut ctx).unwrap();
+        assert_eq!(outputs[0].text, "mud:20:64");
+        assert_eq!(outputs[1].text, "false:false:20");
+    }
+
+    #[test]
+    fn test_on_init_uses_separate_instruction_budget() {
+        let config = ScriptConfig {
+            instruction_limit: 1_000,
+            init_instruction_limit: 1_000_000,
+            ..Default::default()
+        };
+        let mut engine = ScriptEngine::new(config).unwrap();
+        engine
+            .load_script(
+                "heavy_init",
+                r#"
+                hooks.on_init(function()
+                    local n = 0
+                    for i = 1, 50000 do n = n + i end
+                    output:send(1, tostring(n))
+                end)
+                hooks.on_tick(function()
+                    while true do end
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 0,
+        };
+        // Well over the per-tick limit, well under the init limit.
+        let outputs = engine.run_on_init(&mut ctx).unwrap();
+        assert_eq!(outputs[0].text, "1250025000");
+
+        // The per-tick limit still applies afterwards.
+        ctx.tick = 1;
+        engine.run_on_tick(&mut ctx).unwrap();
+        assert_eq!(engine.take_hook_errors().len(), 1);
+    }
+
+    #[test]
+    fn test_on_init_infinite_loop_fails_with_clear_error() {
+        let config = ScriptConfig {
+            init_instruction_limit: 100_000,
+            ..Default::default()
+        };
+        let mut engine = ScriptEngine::new(config).unwrap();
+        engine
+            .load_script(
+                "stuck_init",
+                r#"
+                hooks.on_init(function()
+                    while true do end
+                end)
+                hooks.on_init(function()
+                    output:send(1, "unreachable")
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 0,
+        };
+        let err = engine.run_on_init(&mut ctx).unwrap_err();
+        assert!(
+            matches!(&err, ScriptError::InitInstructionLimitExceeded { script, limit: 100_000 } if script == "stuck_init"),
+            "{:?}",
+            err
+        );
+        assert!(err.to_string().contains("init instruction limit"), "{}", err);
     }
 
     #[test]
@@ -1217,6 +2047,69 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_reload_content_updates_cached_tables() {
+        let dir = std::env::temp_dir().join("engine_content_test_reload");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("items.json"), r#"[{"id":"potion","heal":50}]"#).unwrap();
+        std::fs::write(dir.join("old.json"), r#"[{"id":"x"}]"#).unwrap();
+
+        let mut registry = ContentRegistry::load_dir(&dir).unwrap();
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        engine.register_content(&registry).unwrap();
+
+        // `items` is captured at load time and must still see reloaded data.
+        engine
+            .load_script(
+                "test",
+                r#"
+                local items = content.items
+                hooks.on_tick(function(tick)
+                    output:send(1, tostring(items.potion.heal) .. ":" .. tostring(content.items.potion.heal)
+                        .. ":" .. tostring(rawget(content, "old") ~= nil) .. ":" .. tostring(content.new ~= nil))
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+        let outputs = engine.run_on_tick(&mut ctx).unwrap();
+        assert_eq!(outputs[0].text, "50:50:true:false");
+
+        std::fs::write(dir.join("items.json"), r#"[{"id":"potion","heal":75}]"#).unwrap();
+        std::fs::remove_file(dir.join("old.json")).unwrap();
+        std::fs::write(dir.join("new.json"), r#"[{"id":"y"}]"#).unwrap();
+        registry.reload_dir(&dir).unwrap();
+        engine.reload_content(&registry).unwrap();
+
+        let outputs = engine.run_on_tick(&mut ctx).unwrap();
+        assert_eq!(outputs[0].text, "75:75:false:true");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reload_content_failure_keeps_old_data() {
+        let dir = std::env::temp_dir().join("engine_content_test_reload_bad");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("items.json"), r#"[{"id":"potion","heal":50}]"#).unwrap();
+
+        let mut registry = ContentRegistry::load_dir(&dir).unwrap();
+        std::fs::write(dir.join("items.json"), "not json").unwrap();
+        assert!(registry.reload_dir(&dir).is_err());
+        assert_eq!(registry.get("items", "potion").unwrap()["heal"], 50);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_run_on_tick_with_grid_space() {
         use space::grid_space::{GridConfig, GridSpace};
@@ -1257,4 +2150,132 @@ mod tests {
         assert_eq!(outputs.len(), 1);
         assert_eq!(outputs[0].text, "entities: 1");
     }
+
+    /// In-memory AuthProvider holding a single character's script data.
+    struct ScriptDataAuth {
+        script_data: std::cell::RefCell<serde_json::Value>,
+    }
+
+    impl AuthProvider for ScriptDataAuth {
+        fn check_account(
+            &self,
+            _username: &str,
+        ) -> Result<Option<crate::auth::AuthAccountInfo>, crate::auth::AuthError> {
+            Ok(None)
+        }
+
+        fn authenticate(
+            &self,
+            username: &str,
+            _password: &str,
+        ) -> Result<crate::auth::AuthAccountInfo, crate::auth::AuthError> {
+            Err(crate::auth::AuthError::AccountNotFound(username.to_string()))
+        }
+
+        fn create_account(
+            &self,
+            _username: &str,
+            _password: &str,
+        ) -> Result<crate::auth::AuthAccountInfo, crate::auth::AuthError> {
+            Err(crate::auth::AuthError::Internal("unsupported".into()))
+        }
+
+        fn list_characters(
+            &self,
+            _account_id: i64,
+        ) -> Result<Vec<crate::auth::AuthCharacterSummary>, crate::auth::AuthError> {
+            Ok(Vec::new())
+        }
+
+        fn create_character(
+            &self,
+            _account_id: i64,
+            _name: &str,
+            _defaults: &serde_json::Value,
+        ) -> Result<crate::auth::AuthCharacterDetail, crate::auth::AuthError> {
+            Err(crate::auth::AuthError::Internal("unsupported".into()))
+        }
+
+        fn load_character(
+            &self,
+            character_id: i64,
+        ) -> Result<crate::auth::AuthCharacterDetail, crate::auth::AuthError> {
+            if character_id != 1 {
+                return Err(crate::auth::AuthError::CharacterNotFound(character_id));
+            }
+            Ok(crate::auth::AuthCharacterDetail {
+                id: 1,
+                account_id: 1,
+                name: "Hero".into(),
+                components: serde_json::json!({}),
+                script_data: self.script_data.borrow().clone(),
+                room_id: None,
+                position_x: None,
+                position_y: None,
+            })
+        }
+
+        fn save_character(
+            &self,
+            _character_id: i64,
+            _components: &serde_json::Value,
+            _room_id: Option<u64>,
+            _position: Option<(i32, i32)>,
+        ) -> Result<(), crate::auth::AuthError> {
+            Ok(())
+        }
+
+        fn save_script_data(
+            &self,
+            character_id: i64,
+            script_data: &serde_json::Value,
+        ) -> Result<(), crate::auth::AuthError> {
+            if character_id != 1 {
+                return Err(crate::auth::AuthError::CharacterNotFound(character_id));
+            }
+            *self.script_data.borrow_mut() = script_data.clone();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_auth_script_data_roundtrip() {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+
+        engine
+            .load_script(
+                "script_data_test",
+                r#"
+                hooks.on_input(function(session_id, line)
+                    auth:save_script_data(1, { quests = { rats = { stage = 2 } } })
+                    local data = auth:get_script_data(1)
+                    local loaded = auth:load_character(1)
+                    output:send(session_id, "stage=" .. data.quests.rats.stage
+                        .. " loaded=" .. loaded.script_data.quests.rats.stage)
+                end)
+            "#,
+            )
+            .unwrap();
+
+        let auth = ScriptDataAuth {
+            script_data: std::cell::RefCell::new(serde_json::json!({})),
+        };
+        let (mut ecs, mut space, mut sessions) = setup_world();
+        let mut ctx = ScriptContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 1,
+        };
+
+        let outputs = engine
+            .run_on_input(&mut ctx, SessionId(1), "x", Some(&auth))
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].text, "stage=2 loaded=2");
+        assert_eq!(
+            *auth.script_data.borrow(),
+            serde_json::json!({"quests": {"rats": {"stage": 2}}})
+        );
+    }
 }
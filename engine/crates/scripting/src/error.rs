@@ -20,9 +20,32 @@ pub enum ScriptError {
     #[error("instruction limit exceeded")]
     InstructionLimitExceeded,
 
+    #[error("on_init hook in '{script}' exceeded the init instruction limit ({limit})")]
+    InitInstructionLimitExceeded { script: String, limit: u32 },
+
     #[error("content load error: {0}")]
     ContentLoad(String),
 
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 }
+
+/// A Lua hook callback that raised an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookError {
+    /// Script name the callback was defined in.
+    pub source: String,
+    /// Hook kind, e.g. `on_tick` or `on_action('attack')`.
+    pub hook: String,
+    /// Lua error message.
+    pub message: String,
+}
+
+// Manual impls: thiserror would treat the `source` field as an error cause.
+impl std::fmt::Display for HookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} hook error in '{}': {}", self.hook, self.source, self.message)
+    }
+}
+
+impl std::error::Error for HookError {}
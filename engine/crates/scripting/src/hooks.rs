@@ -1,31 +1,173 @@
-use mlua::{Function, Lua, RegistryKey, Result as LuaResult};
+use mlua::{Function, Lua, RegistryKey, Result as LuaResult, Table};
+use std::cell::Cell;
 use std::collections::HashMap;
-use tracing::warn;
+use tracing::{debug, error, warn};
+
+use crate::error::HookError;
+
+/// When a repeatedly failing callback gets disabled.
+#[derive(Debug, Clone, Copy)]
+pub struct HookErrorPolicy {
+    /// Disable after more than this many errors in one window (0 = never).
+    pub threshold: u32,
+    /// Window length in ticks.
+    pub window_ticks: u64,
+}
+
+impl Default for HookErrorPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: 10,
+            window_ticks: 100,
+        }
+    }
+}
+
+/// Per-callback failure tracking. Interior-mutable so it can be updated while
+/// the registry is borrowed for iteration.
+#[derive(Debug, Default)]
+pub struct HookHealth {
+    failures: Cell<u32>,
+    window_start: Cell<u64>,
+    disabled: Cell<bool>,
+}
+
+impl HookHealth {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.get()
+    }
+
+    /// Count a failure at `tick`. Returns true if this failure disabled the callback.
+    fn record_failure(&self, tick: u64, policy: HookErrorPolicy) -> bool {
+        if tick.saturating_sub(self.window_start.get()) >= policy.window_ticks {
+            self.window_start.set(tick);
+            self.failures.set(0);
+        }
+        self.failures.set(self.failures.get() + 1);
+        if policy.threshold > 0 && self.failures.get() > policy.threshold && !self.is_disabled() {
+            self.disabled.set(true);
+            return true;
+        }
+        false
+    }
+
+    fn reset(&self) {
+        self.failures.set(0);
+        self.window_start.set(0);
+        self.disabled.set(false);
+    }
+}
+
+/// A hook entry: callback + name of the script that defined it.
+pub struct HookEntry {
+    pub callback: RegistryKey,
+    pub source: String,
+    pub health: HookHealth,
+}
 
 /// An admin hook entry: callback + minimum required permission level.
 pub struct AdminHookEntry {
     pub callback: RegistryKey,
+    pub source: String,
+    pub health: HookHealth,
     pub min_permission: i32,
 }
 
+/// An interval hook entry: callback fired every `interval` ticks.
+pub struct IntervalHookEntry {
+    pub callback: RegistryKey,
+    pub source: String,
+    pub health: HookHealth,
+    pub interval: u64,
+}
+
+/// Hook errors recorded since the last `ScriptEngine::take_hook_errors`.
+/// Stored in Lua app data next to the HookRegistry.
+#[derive(Default)]
+pub struct HookErrorLog {
+    pub errors: Vec<HookError>,
+}
+
+/// Upper bound on undrained hook errors; later errors are logged and counted
+/// here instead of being queued.
+const MAX_PENDING_HOOK_ERRORS: usize = 1024;
+
+/// Queue a failed hook call for the caller (see
+/// `ScriptEngine::take_hook_errors`) and disable the callback if it keeps
+/// failing (see `HookErrorPolicy`).
+pub(crate) fn record_hook_error(
+    lua: &Lua,
+    health: &HookHealth,
+    source: &str,
+    hook: &str,
+    err: &mlua::Error,
+) {
+    let error = HookError {
+        source: source.to_string(),
+        hook: hook.to_string(),
+        message: err.to_string(),
+    };
+    debug!(source = %error.source, hook = %error.hook, "{}", error);
+    if let Some(registry) = lua.app_data_ref::<HookRegistry>() {
+        if health.record_failure(registry.current_tick, registry.error_policy) {
+            error!(
+                source = %error.source,
+                hook = %error.hook,
+                "hook disabled after {} errors within {} ticks",
+                health.failures.get(),
+                registry.error_policy.window_ticks
+            );
+        }
+    }
+    if let Some(mut log) = lua.app_data_mut::<HookErrorLog>() {
+        if log.errors.len() < MAX_PENDING_HOOK_ERRORS {
+            log.errors.push(error);
+        } else {
+            // Never drained: report it here instead.
+            warn!(source = %error.source, hook = %error.hook, "{}", error);
+            observability::metrics::record_script_error(hook);
+        }
+    }
+}
+
+/// Name of the script chunk that defined `func` (as passed to `load_script`).
+fn hook_source(func: &Function) -> String {
+    let info = func.info();
+    info.source
+        .map(|s| s.trim_start_matches(['=', '@']).to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 /// Registry of Lua callbacks organized by event type.
 pub struct HookRegistry {
     /// on_init callbacks — called once at startup
-    pub on_init: Vec<RegistryKey>,
+    pub on_init: Vec<HookEntry>,
     /// on_tick callbacks — called every tick with (tick_number)
-    pub on_tick: Vec<RegistryKey>,
+    pub on_tick: Vec<HookEntry>,
+    /// on_interval callbacks — called with (tick_number) when tick % interval == 0
+    pub on_interval: Vec<IntervalHookEntry>,
     /// on_action callbacks — keyed by action name, called with (ctx table)
-    pub on_action: HashMap<String, Vec<RegistryKey>>,
+    pub on_action: HashMap<String, Vec<HookEntry>>,
     /// on_enter_room callbacks — called with (entity_id, room_id, old_room_id)
-    pub on_enter_room: Vec<RegistryKey>,
+    pub on_enter_room: Vec<HookEntry>,
     /// on_connect callbacks — called with (session_id)
-    pub on_connect: Vec<RegistryKey>,
+    pub on_connect: Vec<HookEntry>,
     /// on_admin callbacks — keyed by command name, with min permission
     pub on_admin: HashMap<String, Vec<AdminHookEntry>>,
     /// on_input callbacks — called with (session_id, line) for Login-state input
-    pub on_input: Vec<RegistryKey>,
+    pub on_input: Vec<HookEntry>,
     /// on_disconnect callbacks — called with (session_id)
-    pub on_disconnect: Vec<RegistryKey>,
+    pub on_disconnect: Vec<HookEntry>,
+    /// When failing callbacks get disabled.
+    pub error_policy: HookErrorPolicy,
+    /// Tick of the hook pass in progress (for the error window).
+    pub current_tick: u64,
+}
+
+impl Default for HookRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HookRegistry {
@@ -33,18 +175,22 @@ impl HookRegistry {
         Self {
             on_init: Vec::new(),
             on_tick: Vec::new(),
+            on_interval: Vec::new(),
             on_action: HashMap::new(),
             on_enter_room: Vec::new(),
             on_connect: Vec::new(),
             on_admin: HashMap::new(),
             on_input: Vec::new(),
             on_disconnect: Vec::new(),
+            error_policy: HookErrorPolicy::default(),
+            current_tick: 0,
         }
     }
 
     pub fn clear(&mut self) {
         self.on_init.clear();
         self.on_tick.clear();
+        self.on_interval.clear();
         self.on_action.clear();
         self.on_enter_room.clear();
         self.on_connect.clear();
@@ -53,6 +199,30 @@ impl HookRegistry {
         self.on_disconnect.clear();
     }
 
+    /// Clear failure counts and re-enable every disabled callback.
+    /// Returns how many callbacks were re-enabled.
+    pub fn reenable_all(&self) -> usize {
+        let mut healths: Vec<&HookHealth> = Vec::new();
+        healths.extend(self.on_init.iter().map(|e| &e.health));
+        healths.extend(self.on_tick.iter().map(|e| &e.health));
+        healths.extend(self.on_interval.iter().map(|e| &e.health));
+        healths.extend(self.on_action.values().flatten().map(|e| &e.health));
+        healths.extend(self.on_enter_room.iter().map(|e| &e.health));
+        healths.extend(self.on_connect.iter().map(|e| &e.health));
+        healths.extend(self.on_admin.values().flatten().map(|e| &e.health));
+        healths.extend(self.on_input.iter().map(|e| &e.health));
+        healths.extend(self.on_disconnect.iter().map(|e| &e.health));
+
+        let mut reenabled = 0;
+        for health in healths {
+            if health.is_disabled() {
+                reenabled += 1;
+            }
+            health.reset();
+        }
+        reenabled
+    }
+
     pub fn on_init_count(&self) -> usize {
         self.on_init.len()
     }
@@ -61,6 +231,15 @@ impl HookRegistry {
         self.on_tick.len()
     }
 
+    pub fn on_interval_count(&self) -> usize {
+        self.on_interval.len()
+    }
+
+    /// Whether any on_tick or on_interval hook should run on this tick.
+    pub fn has_tick_work(&self, tick: u64) -> bool {
+        !self.on_tick.is_empty() || self.on_interval.iter().any(|e| tick.is_multiple_of(e.interval))
+    }
+
     pub fn on_action_count(&self) -> usize {
         self.on_action.values().map(|v| v.len()).sum()
     }
@@ -86,6 +265,15 @@ impl HookRegistry {
     }
 }
 
+/// Context table handed to a hook, holding the current `tick`. Hooks with
+/// positional arguments get it as their last argument, and on_action/on_admin
+/// add their fields to it, so scripts always read the tick as `ctx.tick`.
+pub(crate) fn hook_ctx(lua: &Lua, tick: u64) -> LuaResult<Table> {
+    let ctx = lua.create_table()?;
+    ctx.set("tick", tick)?;
+    Ok(ctx)
+}
+
 /// Register hooks.* API functions on the Lua global table.
 /// The HookRegistry is stored in Lua app data for callback access.
 pub fn register_hooks_api(lua: &Lua) -> LuaResult<()> {
@@ -93,63 +281,109 @@ pub fn register_hooks_api(lua: &Lua) -> LuaResult<()> {
 
     // hooks.on_init(fn)
     let on_init_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_init
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_init", on_init_fn)?;
 
     // hooks.on_tick(fn)
     let on_tick_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_tick
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_tick", on_tick_fn)?;
 
+    // hooks.on_interval(interval_ticks, fn)
+    let on_interval_fn = lua.create_function(|lua, (interval, func): (u64, Function)| {
+        if interval == 0 {
+            return Err(mlua::Error::runtime("on_interval requires interval_ticks > 0"));
+        }
+        let source = hook_source(&func);
+        let key = lua.create_registry_value(func)?;
+        lua.app_data_mut::<HookRegistry>()
+            .expect("HookRegistry not set")
+            .on_interval
+            .push(IntervalHookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+                interval,
+            });
+        Ok(())
+    })?;
+    hooks_table.set("on_interval", on_interval_fn)?;
+
     // hooks.on_action(action_name, fn)
     let on_action_fn = lua.create_function(|lua, (action, func): (String, Function)| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_action
             .entry(action)
             .or_default()
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_action", on_action_fn)?;
 
     // hooks.on_enter_room(fn)
     let on_enter_room_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_enter_room
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_enter_room", on_enter_room_fn)?;
 
     // hooks.on_connect(fn)
     let on_connect_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_connect
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_connect", on_connect_fn)?;
 
     // hooks.on_admin(command_name, min_permission, fn)
     let on_admin_fn = lua.create_function(|lua, (command, min_perm, func): (String, i32, Function)| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
@@ -158,6 +392,8 @@ pub fn register_hooks_api(lua: &Lua) -> LuaResult<()> {
             .or_default()
             .push(AdminHookEntry {
                 callback: key,
+                source,
+                health: HookHealth::default(),
                 min_permission: min_perm,
             });
         Ok(())
@@ -166,22 +402,32 @@ pub fn register_hooks_api(lua: &Lua) -> LuaResult<()> {
 
     // hooks.on_input(fn)
     let on_input_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_input
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_input", on_input_fn)?;
 
     // hooks.on_disconnect(fn)
     let on_disconnect_fn = lua.create_function(|lua, func: Function| {
+        let source = hook_source(&func);
         let key = lua.create_registry_value(func)?;
         lua.app_data_mut::<HookRegistry>()
             .expect("HookRegistry not set")
             .on_disconnect
-            .push(key);
+            .push(HookEntry {
+                callback: key,
+                source,
+                health: HookHealth::default(),
+            });
         Ok(())
     })?;
     hooks_table.set("on_disconnect", on_disconnect_fn)?;
@@ -191,19 +437,26 @@ pub fn register_hooks_api(lua: &Lua) -> LuaResult<()> {
     let fire_enter_room_fn =
         lua.create_function(|lua, (entity_u64, room_u64, old_room_u64): (u64, u64, Option<u64>)| {
             // Collect functions first, then drop the borrow before calling them.
-            let funcs: Vec<Function> = {
+            let (funcs, tick): (Vec<(usize, Function)>, u64) = {
                 let hooks = lua
                     .app_data_ref::<HookRegistry>()
                     .expect("HookRegistry not set");
-                hooks
+                let funcs = hooks
                     .on_enter_room
                     .iter()
-                    .filter_map(|key| lua.registry_value(key).ok())
-                    .collect()
+                    .enumerate()
+                    .filter(|(_, entry)| !entry.health.is_disabled())
+                    .filter_map(|(i, entry)| Some((i, lua.registry_value(&entry.callback).ok()?)))
+                    .collect();
+                (funcs, hooks.current_tick)
             };
-            for func in funcs {
-                if let Err(e) = func.call::<()>((entity_u64, room_u64, old_room_u64)) {
-                    warn!("on_enter_room hook error: {}", e);
+            for (index, func) in funcs {
+                if let Err(e) = func.call::<()>((entity_u64, room_u64, old_room_u64, hook_ctx(lua, tick)?)) {
+                    let hooks = lua
+                        .app_data_ref::<HookRegistry>()
+                        .expect("HookRegistry not set");
+                    let entry = &hooks.on_enter_room[index];
+                    record_hook_error(lua, &entry.health, &entry.source, "on_enter_room", &e);
                 }
             }
             Ok(())
@@ -223,6 +476,7 @@ mod tests {
         let registry = HookRegistry::new();
         assert_eq!(registry.on_init_count(), 0);
         assert_eq!(registry.on_tick_count(), 0);
+        assert_eq!(registry.on_interval_count(), 0);
         assert_eq!(registry.on_action_count(), 0);
         assert_eq!(registry.on_enter_room_count(), 0);
         assert_eq!(registry.on_connect_count(), 0);
@@ -9,7 +9,7 @@ pub mod content;
 pub mod auth;
 
 pub use engine::ScriptEngine;
-pub use error::ScriptError;
+pub use error::{HookError, ScriptError};
 pub use sandbox::ScriptConfig;
 pub use hooks::HookRegistry;
 pub use content::ContentRegistry;
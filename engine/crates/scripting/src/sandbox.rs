@@ -1,5 +1,6 @@
 use crate::error::ScriptError;
 use mlua::Lua;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -10,6 +11,16 @@ pub struct ScriptConfig {
     pub memory_limit: usize,
     /// Instruction limit per execution (default 1_000_000).
     pub instruction_limit: u32,
+    /// Instruction limit for the whole `on_init` pass, which builds the world
+    /// and legitimately does far more work than a tick (default 100_000_000).
+    pub init_instruction_limit: u32,
+    /// A hook callback is disabled once it errors more than this many times
+    /// within `hook_error_window_ticks` (0 = never disable; default 10).
+    pub hook_error_threshold: u32,
+    /// Length of the error-counting window in ticks (default 100).
+    pub hook_error_window_ticks: u64,
+    /// Directory `require(name)` loads modules from (None = `require` disabled).
+    pub lib_dir: Option<PathBuf>,
 }
 
 impl Default for ScriptConfig {
@@ -17,6 +28,10 @@ impl Default for ScriptConfig {
         Self {
             memory_limit: 16 * 1024 * 1024, // 16 MB
             instruction_limit: 1_000_000,
+            init_instruction_limit: 100_000_000,
+            hook_error_threshold: 10,
+            hook_error_window_ticks: 100,
+            lib_dir: None,
         }
     }
 }
@@ -49,7 +64,12 @@ pub fn create_sandboxed_lua(config: &ScriptConfig) -> Result<Lua, ScriptError> {
 /// Reset the instruction counter for a new execution pass.
 /// Called before each hook execution batch.
 pub fn reset_instruction_counter(lua: &Lua, config: &ScriptConfig) {
-    let limit = config.instruction_limit;
+    set_instruction_budget(lua, config.instruction_limit);
+}
+
+/// Start a fresh pass with an explicit instruction `limit`. The returned
+/// counter lets the caller tell whether a failed call ran out of budget.
+pub fn set_instruction_budget(lua: &Lua, limit: u32) -> Arc<AtomicU32> {
     let counter = Arc::new(AtomicU32::new(0));
     let counter_clone = counter.clone();
     lua.set_interrupt(move |_| {
@@ -59,6 +79,7 @@ pub fn reset_instruction_counter(lua: &Lua, config: &ScriptConfig) {
         }
         Ok(mlua::VmState::Continue)
     });
+    counter
 }
 
 #[cfg(test)]
@@ -101,6 +122,7 @@ mod tests {
         let config = ScriptConfig {
             memory_limit: 1024 * 64, // 64 KB — very small
             instruction_limit: 10_000_000,
+            ..Default::default()
         };
         let lua = create_sandboxed_lua(&config).unwrap();
 
@@ -121,6 +143,7 @@ mod tests {
         let config = ScriptConfig {
             memory_limit: 8 * 1024 * 1024,
             instruction_limit: 500_000,
+            ..Default::default()
         };
         let lua = create_sandboxed_lua(&config).unwrap();
 
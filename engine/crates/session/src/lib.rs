@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::SystemTime;
 
 use ecs_adapter::EntityId;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct SessionId(pub u64);
@@ -12,6 +15,14 @@ pub struct SessionOutput {
     /// When true, the output router will close the session's write channel
     /// after delivering this message, causing the TCP connection to shut down.
     pub disconnect: bool,
+    /// When true, `text` is a JSON `ServerMessage` rather than game text.
+    /// Transports deliver it only to clients that can parse it (GMCP over
+    /// Telnet, WebSocket) and drop it for everyone else.
+    pub structured: bool,
+    /// Pre-encoded binary form of `text` (a `StateDelta` frame). WebSocket
+    /// sessions that negotiated binary deltas get these bytes; everyone else
+    /// gets `text`.
+    pub binary: Option<Vec<u8>>,
 }
 
 impl SessionOutput {
@@ -20,6 +31,8 @@ impl SessionOutput {
             session_id,
             text: text.into(),
             disconnect: false,
+            structured: false,
+            binary: None,
         }
     }
 
@@ -29,14 +42,34 @@ impl SessionOutput {
             session_id,
             text: text.into(),
             disconnect: true,
+            structured: false,
+            binary: None,
         }
     }
+
+    /// Create an out-of-band structured message (see `structured`).
+    pub fn structured(session_id: SessionId, json: impl Into<String>) -> Self {
+        Self {
+            session_id,
+            text: json.into(),
+            disconnect: false,
+            structured: true,
+            binary: None,
+        }
+    }
+
+    /// Attach a binary encoding of the message (see `binary`).
+    pub fn with_binary(mut self, bytes: Vec<u8>) -> Self {
+        self.binary = Some(bytes);
+        self
+    }
 }
 
 /// Permission levels matching player_db::PermissionLevel.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(i32)]
 pub enum PermissionLevel {
+    #[default]
     Player = 0,
     Builder = 1,
     Admin = 2,
@@ -58,12 +91,6 @@ impl PermissionLevel {
     }
 }
 
-impl Default for PermissionLevel {
-    fn default() -> Self {
-        Self::Player
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SessionState {
     Login,
@@ -74,30 +101,90 @@ pub enum SessionState {
 #[derive(Debug, Clone)]
 pub struct PlayerSession {
     pub session_id: SessionId,
+    /// Terminal columns used when word-wrapping output for this session.
+    pub line_width: usize,
     pub state: SessionState,
     pub entity: Option<EntityId>,
     pub player_name: Option<String>,
     pub account_id: Option<i64>,
     pub character_id: Option<i64>,
     pub permission: PermissionLevel,
+    /// Free-form key/value tags set by game scripts (client kind, locale,
+    /// flags). Lives only as long as the session and is never persisted.
+    pub metadata: BTreeMap<String, String>,
+    /// Peer address and accept time; None for sessions not created by the
+    /// network layer (tests, local tools).
+    pub connection: Option<ConnectionInfo>,
+    /// Tick the session was created on (set by the server loop).
+    pub connected_tick: u64,
+    /// Input lines received, including login input.
+    pub input_count: u64,
+    /// Bytes of input received (line text, without line endings).
+    pub input_bytes: u64,
 }
 
 impl PlayerSession {
     pub fn new(session_id: SessionId) -> Self {
         Self {
             session_id,
+            line_width: DEFAULT_LINE_WIDTH,
             state: SessionState::Login,
             entity: None,
             player_name: None,
             account_id: None,
             character_id: None,
             permission: PermissionLevel::Player,
+            metadata: BTreeMap::new(),
+            connection: None,
+            connected_tick: 0,
+            input_count: 0,
+            input_bytes: 0,
         }
     }
+
+    /// True if this session's permission is at least `required`.
+    pub fn can(&self, required: PermissionLevel) -> bool {
+        self.permission >= required
+    }
+}
+
+/// Per-session activity counters for moderation tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStats {
+    pub input_count: u64,
+    pub input_bytes: u64,
+    pub connected_tick: u64,
 }
 
+impl SessionStats {
+    /// Ticks since the session connected, as of `current_tick`.
+    pub fn uptime_ticks(&self, current_tick: u64) -> u64 {
+        current_tick.saturating_sub(self.connected_tick)
+    }
+}
+
+/// Where and when the network layer accepted a session's connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub peer_addr: SocketAddr,
+    pub connected_at: SystemTime,
+}
+
+impl ConnectionInfo {
+    /// Connection from `peer_addr`, accepted now.
+    pub fn new(peer_addr: SocketAddr) -> Self {
+        Self {
+            peer_addr,
+            connected_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Terminal columns assumed until a session sets its own width.
+pub const DEFAULT_LINE_WIDTH: usize = 80;
+
 /// A player entity that remains in-world after disconnect, awaiting reconnection.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LingeringEntity {
     pub entity: EntityId,
     pub character_id: i64,
@@ -105,12 +192,24 @@ pub struct LingeringEntity {
     pub disconnect_tick: u64,
 }
 
+/// A lingering character whose entity was saved to the DB and despawned to
+/// free memory. Reconnecting rebuilds the character from the DB; the record
+/// only remembers who it was and when they left.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HibernatedCharacter {
+    pub character_id: i64,
+    pub account_id: i64,
+    /// Tick of the original disconnect, so the linger timeout still applies.
+    pub disconnect_tick: u64,
+}
+
 /// Manages active player sessions.
 #[derive(Debug, Default)]
 pub struct SessionManager {
     sessions: BTreeMap<SessionId, PlayerSession>,
     entity_to_session: BTreeMap<EntityId, SessionId>,
     lingering: BTreeMap<i64, LingeringEntity>, // character_id -> LingeringEntity
+    hibernated: BTreeMap<i64, HibernatedCharacter>, // character_id -> record
     next_id: u64,
 }
 
@@ -135,6 +234,14 @@ impl SessionManager {
         }
     }
 
+    /// Create a session with a specific ID, recording its connection details.
+    pub fn create_session_with_connection(&mut self, id: SessionId, connection: ConnectionInfo) {
+        self.create_session_with_id(id);
+        if let Some(session) = self.sessions.get_mut(&id) {
+            session.connection = Some(connection);
+        }
+    }
+
     /// Get a session by ID.
     pub fn get_session(&self, id: SessionId) -> Option<&PlayerSession> {
         self.sessions.get(&id)
@@ -145,6 +252,11 @@ impl SessionManager {
         self.sessions.get_mut(&id)
     }
 
+    /// Permission level of a session, or None if it does not exist.
+    pub fn session_permission(&self, id: SessionId) -> Option<PermissionLevel> {
+        self.sessions.get(&id).map(|s| s.permission)
+    }
+
     /// Get session by entity.
     pub fn session_for_entity(&self, entity: EntityId) -> Option<&PlayerSession> {
         let sid = self.entity_to_session.get(&entity)?;
@@ -178,6 +290,41 @@ impl SessionManager {
         None
     }
 
+    /// Set a metadata tag on a session, overwriting any previous value.
+    /// Returns false if the session does not exist.
+    pub fn set_meta(&mut self, session_id: SessionId, key: impl Into<String>, value: impl Into<String>) -> bool {
+        match self.sessions.get_mut(&session_id) {
+            Some(session) => {
+                session.metadata.insert(key.into(), value.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_meta(&self, session_id: SessionId, key: &str) -> Option<&str> {
+        self.sessions
+            .get(&session_id)
+            .and_then(|s| s.metadata.get(key))
+            .map(String::as_str)
+    }
+
+    /// Remove one metadata tag, or all of them when `key` is None.
+    /// Returns true if anything was removed.
+    pub fn clear_meta(&mut self, session_id: SessionId, key: Option<&str>) -> bool {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            return false;
+        };
+        match key {
+            Some(key) => session.metadata.remove(key).is_some(),
+            None => {
+                let had_any = !session.metadata.is_empty();
+                session.metadata.clear();
+                had_any
+            }
+        }
+    }
+
     /// Remove a disconnected session entirely.
     pub fn remove_session(&mut self, session_id: SessionId) {
         if let Some(session) = self.sessions.remove(&session_id) {
@@ -187,14 +334,65 @@ impl SessionManager {
         }
     }
 
+    /// All sessions whose state matches `pred` (sorted by session ID).
+    pub fn sessions_in_state(&self, pred: impl Fn(&SessionState) -> bool) -> Vec<&PlayerSession> {
+        self.sessions.values().filter(|s| pred(&s.state)).collect()
+    }
+
     /// All sessions in Playing state (sorted by session ID).
     pub fn playing_sessions(&self) -> Vec<&PlayerSession> {
+        self.sessions_in_state(|state| *state == SessionState::Playing)
+    }
+
+    /// All sessions still in the login flow — entering credentials or
+    /// choosing a character (sorted by session ID).
+    pub fn authenticating_sessions(&self) -> Vec<&PlayerSession> {
+        self.sessions_in_state(|state| *state == SessionState::Login)
+    }
+
+    /// Build a private message for the playing session whose player name matches
+    /// `target_name` (case-insensitive). Returns None if no such player is playing.
+    pub fn tell(&self, target_name: &str, text: impl Into<String>) -> Option<SessionOutput> {
+        let target = self.sessions.values().find(|s| {
+            s.state == SessionState::Playing
+                && s.player_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(target_name))
+        })?;
+        Some(SessionOutput::new(target.session_id, text))
+    }
+
+    /// Sessions connected from `ip` (any port), in session id order.
+    pub fn sessions_from_ip(&self, ip: IpAddr) -> Vec<SessionId> {
         self.sessions
             .values()
-            .filter(|s| s.state == SessionState::Playing)
+            .filter(|s| s.connection.is_some_and(|c| c.peer_addr.ip() == ip))
+            .map(|s| s.session_id)
             .collect()
     }
 
+    /// Count one line of input (`bytes` long) against a session.
+    /// Returns false for unknown sessions.
+    pub fn record_input(&mut self, session_id: SessionId, bytes: usize) -> bool {
+        match self.sessions.get_mut(&session_id) {
+            Some(session) => {
+                session.input_count += 1;
+                session.input_bytes += bytes as u64;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Activity counters for a session.
+    pub fn session_stats(&self, session_id: SessionId) -> Option<SessionStats> {
+        self.sessions.get(&session_id).map(|s| SessionStats {
+            input_count: s.input_count,
+            input_bytes: s.input_bytes,
+            connected_tick: s.connected_tick,
+        })
+    }
+
     /// All session IDs.
     pub fn all_session_ids(&self) -> Vec<SessionId> {
         self.sessions.keys().copied().collect()
@@ -237,6 +435,71 @@ impl SessionManager {
         self.lingering.values().collect()
     }
 
+    /// Copy of all lingering records, ordered by character ID, for persisting
+    /// alongside a world snapshot.
+    pub fn export_lingering(&self) -> Vec<LingeringEntity> {
+        self.lingering.values().cloned().collect()
+    }
+
+    /// Rebuild lingering records (e.g. after restoring a snapshot).
+    /// A record for a character that is already lingering replaces it.
+    pub fn import_lingering(&mut self, records: Vec<LingeringEntity>) {
+        for linger in records {
+            self.add_lingering(linger);
+        }
+    }
+
+    /// Lingering characters to hibernate so at most `max_resident` keep
+    /// their entity in memory: the longest-disconnected first (ties by
+    /// character ID).
+    pub fn hibernation_candidates(&self, max_resident: usize) -> Vec<i64> {
+        let excess = self.lingering.len().saturating_sub(max_resident);
+        let mut by_age: Vec<&LingeringEntity> = self.lingering.values().collect();
+        by_age.sort_by_key(|l| (l.disconnect_tick, l.character_id));
+        by_age.into_iter().take(excess).map(|l| l.character_id).collect()
+    }
+
+    /// Turn a lingering record into a hibernated one. Returns the lingering
+    /// record so the caller can save and despawn its entity.
+    pub fn hibernate_lingering(&mut self, character_id: i64) -> Option<LingeringEntity> {
+        let linger = self.lingering.remove(&character_id)?;
+        self.hibernated.insert(
+            character_id,
+            HibernatedCharacter {
+                character_id,
+                account_id: linger.account_id,
+                disconnect_tick: linger.disconnect_tick,
+            },
+        );
+        Some(linger)
+    }
+
+    /// Find a hibernated character by character ID.
+    pub fn find_hibernated(&self, character_id: i64) -> Option<&HibernatedCharacter> {
+        self.hibernated.get(&character_id)
+    }
+
+    /// Remove a hibernated record when its character reconnects (and is
+    /// rebuilt from the DB) or is purged.
+    pub fn wake_hibernated(&mut self, character_id: i64) -> Option<HibernatedCharacter> {
+        self.hibernated.remove(&character_id)
+    }
+
+    /// Character IDs of hibernated records past the linger timeout. Their
+    /// state is already in the DB, so purging just drops the record.
+    pub fn expired_hibernated(&self, current_tick: u64, timeout_ticks: u64) -> Vec<i64> {
+        self.hibernated
+            .values()
+            .filter(|h| current_tick.saturating_sub(h.disconnect_tick) >= timeout_ticks)
+            .map(|h| h.character_id)
+            .collect()
+    }
+
+    /// Number of hibernated characters.
+    pub fn hibernated_count(&self) -> usize {
+        self.hibernated.len()
+    }
+
     /// Rebind a lingering entity to a new session (seamless reconnection).
     pub fn rebind_lingering(&mut self, session_id: SessionId, character_id: i64) -> Option<EntityId> {
         let linger = self.lingering.remove(&character_id)?;
@@ -255,6 +518,100 @@ impl SessionManager {
 mod tests {
     use super::*;
 
+    fn linger(character_id: i64, disconnect_tick: u64) -> LingeringEntity {
+        LingeringEntity {
+            entity: EntityId::new(character_id as u32, 0),
+            character_id,
+            account_id: character_id * 10,
+            disconnect_tick,
+        }
+    }
+
+    #[test]
+    fn hibernation_candidates_oldest_first() {
+        let mut mgr = SessionManager::new();
+        mgr.add_lingering(linger(1, 30));
+        mgr.add_lingering(linger(2, 10));
+        mgr.add_lingering(linger(3, 20));
+
+        assert_eq!(mgr.hibernation_candidates(3), Vec::<i64>::new());
+        assert_eq!(mgr.hibernation_candidates(1), vec![2, 3]);
+        assert_eq!(mgr.hibernation_candidates(0), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn hibernate_then_wake() {
+        let mut mgr = SessionManager::new();
+        mgr.add_lingering(linger(7, 100));
+
+        let record = mgr.hibernate_lingering(7).unwrap();
+        assert_eq!(record.entity, EntityId::new(7, 0));
+        assert!(mgr.find_lingering(7).is_none());
+        assert_eq!(mgr.find_hibernated(7).unwrap().account_id, 70);
+        assert_eq!(mgr.hibernated_count(), 1);
+        assert!(mgr.hibernate_lingering(7).is_none());
+
+        let woken = mgr.wake_hibernated(7).unwrap();
+        assert_eq!(woken.disconnect_tick, 100);
+        assert!(mgr.find_hibernated(7).is_none());
+    }
+
+    #[test]
+    fn hibernated_expire_on_original_disconnect_tick() {
+        let mut mgr = SessionManager::new();
+        mgr.add_lingering(linger(1, 100));
+        mgr.add_lingering(linger(2, 150));
+        mgr.hibernate_lingering(1);
+        mgr.hibernate_lingering(2);
+
+        assert!(mgr.expired_hibernated(199, 100).is_empty());
+        assert_eq!(mgr.expired_hibernated(200, 100), vec![1]);
+        assert_eq!(mgr.expired_hibernated(250, 100), vec![1, 2]);
+    }
+
+    #[test]
+    fn connection_info_recorded_and_searchable() {
+        let mut mgr = SessionManager::new();
+        let addr: SocketAddr = "203.0.113.7:50000".parse().unwrap();
+        mgr.create_session_with_connection(SessionId(3), ConnectionInfo::new(addr));
+        mgr.create_session_with_connection(
+            SessionId(5),
+            ConnectionInfo::new("203.0.113.7:50001".parse().unwrap()),
+        );
+        mgr.create_session_with_id(SessionId(4));
+
+        let conn = mgr.get_session(SessionId(3)).unwrap().connection.unwrap();
+        assert_eq!(conn.peer_addr, addr);
+        assert!(conn.connected_at <= SystemTime::now());
+        assert!(mgr.get_session(SessionId(4)).unwrap().connection.is_none());
+        assert_eq!(mgr.sessions_from_ip(addr.ip()), vec![SessionId(3), SessionId(5)]);
+        assert!(mgr.sessions_from_ip("198.51.100.1".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn session_metadata_set_overwrite_clear() {
+        let mut mgr = SessionManager::new();
+        let sid = mgr.create_session();
+        assert_eq!(mgr.get_meta(sid, "client"), None);
+
+        assert!(mgr.set_meta(sid, "client", "mudlet"));
+        assert!(mgr.set_meta(sid, "locale", "ko"));
+        assert_eq!(mgr.get_meta(sid, "client"), Some("mudlet"));
+
+        mgr.set_meta(sid, "client", "tintin");
+        assert_eq!(mgr.get_meta(sid, "client"), Some("tintin"));
+
+        assert!(mgr.clear_meta(sid, Some("client")));
+        assert!(!mgr.clear_meta(sid, Some("client")));
+        assert_eq!(mgr.get_meta(sid, "client"), None);
+        assert_eq!(mgr.get_meta(sid, "locale"), Some("ko"));
+
+        assert!(mgr.clear_meta(sid, None));
+        assert!(mgr.get_session(sid).unwrap().metadata.is_empty());
+
+        assert!(!mgr.set_meta(SessionId(999), "k", "v"));
+    }
+
     #[test]
     fn create_session_increments_id() {
         let mut mgr = SessionManager::new();
@@ -306,6 +663,34 @@ mod tests {
         assert_eq!(playing[0].session_id, s1);
     }
 
+    #[test]
+    fn sessions_in_state_filters() {
+        let mut mgr = SessionManager::new();
+        let login = mgr.create_session();
+        let playing_a = mgr.create_session();
+        let gone = mgr.create_session();
+        let playing_b = mgr.create_session();
+
+        mgr.bind_entity(playing_a, EntityId::new(1, 0));
+        mgr.bind_entity(playing_b, EntityId::new(2, 0));
+        mgr.bind_entity(gone, EntityId::new(3, 0));
+        mgr.disconnect(gone);
+
+        let ids = |v: Vec<&PlayerSession>| v.iter().map(|s| s.session_id).collect::<Vec<_>>();
+
+        assert_eq!(ids(mgr.authenticating_sessions()), vec![login]);
+        assert_eq!(ids(mgr.playing_sessions()), vec![playing_a, playing_b]);
+        assert_eq!(
+            ids(mgr.sessions_in_state(|s| *s == SessionState::Disconnected)),
+            vec![gone]
+        );
+        assert_eq!(
+            ids(mgr.sessions_in_state(|s| *s != SessionState::Disconnected)),
+            vec![login, playing_a, playing_b]
+        );
+        assert!(mgr.sessions_in_state(|_| false).is_empty());
+    }
+
     #[test]
     fn remove_session_cleans_up() {
         let mut mgr = SessionManager::new();
@@ -318,6 +703,43 @@ mod tests {
         assert!(mgr.session_id_for_entity(eid).is_none());
     }
 
+    #[test]
+    fn tell_found_target() {
+        let mut mgr = SessionManager::new();
+        let s1 = mgr.create_session();
+        let s2 = mgr.create_session();
+        mgr.bind_entity(s1, EntityId::new(1, 0));
+        mgr.bind_entity(s2, EntityId::new(2, 0));
+        mgr.get_session_mut(s1).unwrap().player_name = Some("Alice".to_string());
+        mgr.get_session_mut(s2).unwrap().player_name = Some("Bob".to_string());
+
+        let out = mgr.tell("bob", "psst").unwrap();
+        assert_eq!(out.session_id, s2);
+        assert_eq!(out.text, "psst");
+        assert!(!out.disconnect);
+    }
+
+    #[test]
+    fn tell_unknown_target() {
+        let mut mgr = SessionManager::new();
+        let s1 = mgr.create_session();
+        mgr.bind_entity(s1, EntityId::new(1, 0));
+        mgr.get_session_mut(s1).unwrap().player_name = Some("Alice".to_string());
+
+        assert!(mgr.tell("Carol", "hello?").is_none());
+    }
+
+    #[test]
+    fn tell_disconnected_target() {
+        let mut mgr = SessionManager::new();
+        let s1 = mgr.create_session();
+        mgr.bind_entity(s1, EntityId::new(1, 0));
+        mgr.get_session_mut(s1).unwrap().player_name = Some("Alice".to_string());
+        mgr.disconnect(s1);
+
+        assert!(mgr.tell("Alice", "still there?").is_none());
+    }
+
     #[test]
     fn active_count() {
         let mut mgr = SessionManager::new();
@@ -351,6 +773,47 @@ mod tests {
         assert!(PermissionLevel::Admin < PermissionLevel::Owner);
     }
 
+    #[test]
+    fn can_compares_by_rank() {
+        let levels = [
+            PermissionLevel::Player,
+            PermissionLevel::Builder,
+            PermissionLevel::Admin,
+            PermissionLevel::Owner,
+        ];
+        let mut mgr = SessionManager::new();
+        for (i, &have) in levels.iter().enumerate() {
+            let sid = mgr.create_session();
+            mgr.get_session_mut(sid).unwrap().permission = have;
+            assert_eq!(mgr.session_permission(sid), Some(have));
+
+            let session = mgr.get_session(sid).unwrap();
+            for (j, &required) in levels.iter().enumerate() {
+                assert_eq!(session.can(required), i >= j, "{:?} can {:?}", have, required);
+            }
+        }
+        assert_eq!(mgr.session_permission(SessionId(999)), None);
+    }
+
+    #[test]
+    fn session_stats_count_inputs_and_uptime() {
+        let mut mgr = SessionManager::new();
+        let sid = mgr.create_session();
+        mgr.get_session_mut(sid).unwrap().connected_tick = 40;
+
+        assert!(mgr.record_input(sid, 5));
+        assert!(mgr.record_input(sid, 12));
+        assert!(!mgr.record_input(SessionId(999), 3));
+
+        let stats = mgr.session_stats(sid).unwrap();
+        assert_eq!(stats.input_count, 2);
+        assert_eq!(stats.input_bytes, 17);
+        assert_eq!(stats.connected_tick, 40);
+        assert_eq!(stats.uptime_ticks(100), 60);
+        assert_eq!(stats.uptime_ticks(10), 0);
+        assert_eq!(mgr.session_stats(SessionId(999)), None);
+    }
+
     #[test]
     fn lingering_add_find_remove() {
         let mut mgr = SessionManager::new();
@@ -372,6 +835,43 @@ mod tests {
         assert!(mgr.find_lingering(42).is_none());
     }
 
+    #[test]
+    fn lingering_export_import_roundtrip() {
+        let mut mgr = SessionManager::new();
+        mgr.add_lingering(LingeringEntity {
+            entity: EntityId::new(7, 1),
+            character_id: 20,
+            account_id: 2,
+            disconnect_tick: 200,
+        });
+        mgr.add_lingering(LingeringEntity {
+            entity: EntityId::new(5, 0),
+            character_id: 10,
+            account_id: 1,
+            disconnect_tick: 100,
+        });
+
+        let exported = mgr.export_lingering();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].character_id, 10);
+        assert_eq!(exported[1].character_id, 20);
+
+        // Fresh manager, as after a restart
+        let mut restored = SessionManager::new();
+        restored.import_lingering(exported.clone());
+        assert_eq!(restored.export_lingering(), exported);
+        assert_eq!(restored.find_lingering(20).unwrap().entity, EntityId::new(7, 1));
+        assert_eq!(restored.expired_lingering(250, 100), vec![10]);
+
+        let sid = restored.create_session();
+        assert_eq!(restored.rebind_lingering(sid, 10), Some(EntityId::new(5, 0)));
+        assert!(restored.find_lingering(10).is_none());
+        let session = restored.get_session(sid).unwrap();
+        assert_eq!(session.entity, Some(EntityId::new(5, 0)));
+        assert_eq!(session.account_id, Some(1));
+        assert_eq!(restored.session_id_for_entity(EntityId::new(5, 0)), Some(sid));
+    }
+
     #[test]
     fn lingering_expired() {
         let mut mgr = SessionManager::new();
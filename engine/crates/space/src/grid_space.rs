@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::model::{MoveError, SpaceModel};
 
+/// Side length, in cells, of the square buckets used by the chunk index.
+const CHUNK_SIZE: i32 = 16;
+
 /// Sentinel generation value for synthetic cell EntityIds.
 /// EntityAllocator never produces this generation, so no collision with real entities.
 const GRID_CELL_GENERATION: u32 = u32::MAX;
@@ -69,6 +72,44 @@ pub fn entity_id_to_cell(id: EntityId) -> Option<GridPos> {
     Some(GridPos::new(x, y))
 }
 
+/// Cardinal direction an entity is facing, derived from its last step.
+///
+/// The grid's y axis grows downward (`origin_y` is the top edge), so a step
+/// with a negative y delta faces `North`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Facing {
+    North,
+    #[default]
+    South,
+    East,
+    West,
+}
+
+impl Facing {
+    /// Direction of a single step, or `None` for a zero delta. Diagonal steps
+    /// resolve to their horizontal component so sprites turn left/right.
+    pub fn from_delta(dx: i32, dy: i32) -> Option<Facing> {
+        match (dx.signum(), dy.signum()) {
+            (1, _) => Some(Facing::East),
+            (-1, _) => Some(Facing::West),
+            (0, -1) => Some(Facing::North),
+            (0, 1) => Some(Facing::South),
+            _ => None,
+        }
+    }
+
+    /// Lowercase wire name (`"north"`, `"south"`, ...).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Facing::North => "north",
+            Facing::South => "south",
+            Facing::East => "east",
+            Facing::West => "west",
+        }
+    }
+}
+
 /// 2D coordinate-based spatial model.
 ///
 /// Entities are placed on integer grid cells. The grid has fixed bounds
@@ -81,6 +122,17 @@ pub struct GridSpace {
     entity_to_pos: BTreeMap<EntityId, GridPos>,
     /// Spatial index: position → set of entities at that cell.
     cell_occupants: BTreeMap<GridPos, BTreeSet<EntityId>>,
+    /// Coarse index over `cell_occupants`: (chunk_y, chunk_x) → occupied
+    /// cells in that `CHUNK_SIZE` square, so area queries skip whole chunks.
+    /// Keyed row-first so one chunk row is a contiguous range.
+    chunk_cells: BTreeMap<(i32, i32), BTreeSet<GridPos>>,
+    /// Facing set by the last `move_to`. Entities without an entry face
+    /// `Facing::default()`. Not part of the snapshot: it is presentation
+    /// state and resets to the default on restore.
+    facing: BTreeMap<EntityId, Facing>,
+    /// Impassable cells (walls, water). Terrain is set up by scripts at
+    /// startup, so it is neither snapshotted nor cleared on restore.
+    blocked: BTreeSet<GridPos>,
 }
 
 impl GridSpace {
@@ -89,6 +141,45 @@ impl GridSpace {
             config,
             entity_to_pos: BTreeMap::new(),
             cell_occupants: BTreeMap::new(),
+            chunk_cells: BTreeMap::new(),
+            facing: BTreeMap::new(),
+            blocked: BTreeSet::new(),
+        }
+    }
+
+    fn chunk_of(pos: GridPos) -> (i32, i32) {
+        (pos.y.div_euclid(CHUNK_SIZE), pos.x.div_euclid(CHUNK_SIZE))
+    }
+
+    /// Add `entity` at `pos` to the cell and chunk indexes.
+    fn index_insert(&mut self, entity: EntityId, pos: GridPos) {
+        let occupants = self.cell_occupants.entry(pos).or_default();
+        if occupants.is_empty() {
+            self.chunk_cells
+                .entry(Self::chunk_of(pos))
+                .or_default()
+                .insert(pos);
+        }
+        occupants.insert(entity);
+    }
+
+    /// Remove `entity` at `pos` from the cell and chunk indexes, dropping
+    /// cells and chunks that become empty.
+    fn index_remove(&mut self, entity: EntityId, pos: GridPos) {
+        let Some(set) = self.cell_occupants.get_mut(&pos) else {
+            return;
+        };
+        set.remove(&entity);
+        if !set.is_empty() {
+            return;
+        }
+        self.cell_occupants.remove(&pos);
+        let chunk = Self::chunk_of(pos);
+        if let Some(cells) = self.chunk_cells.get_mut(&chunk) {
+            cells.remove(&pos);
+            if cells.is_empty() {
+                self.chunk_cells.remove(&chunk);
+            }
         }
     }
 
@@ -105,13 +196,62 @@ impl GridSpace {
             && y < self.config.origin_y + self.config.height as i32
     }
 
+    /// Mark or clear an in-bounds cell as impassable. Returns false (and
+    /// changes nothing) for out-of-bounds cells.
+    pub fn set_blocked(&mut self, x: i32, y: i32, blocked: bool) -> bool {
+        if !self.in_bounds(x, y) {
+            return false;
+        }
+        let pos = GridPos::new(x, y);
+        if blocked {
+            self.blocked.insert(pos);
+        } else {
+            self.blocked.remove(&pos);
+        }
+        true
+    }
+
+    /// Whether a cell is impassable. Out-of-bounds cells are not "blocked";
+    /// check `in_bounds` separately.
+    pub fn is_blocked(&self, x: i32, y: i32) -> bool {
+        self.blocked.contains(&GridPos::new(x, y))
+    }
+
+    /// In-bounds, non-blocked cells adjacent to `pos`: the four cardinal
+    /// neighbors, plus the four diagonals when `diagonal` is set. Occupancy
+    /// is ignored. Returned in row-major order (top-left first).
+    pub fn walkable_neighbors(&self, pos: GridPos, diagonal: bool) -> Vec<GridPos> {
+        let mut result = Vec::new();
+        for dy in -1..=1_i32 {
+            for dx in -1..=1_i32 {
+                if (dx == 0 && dy == 0) || (!diagonal && dx != 0 && dy != 0) {
+                    continue;
+                }
+                let (nx, ny) = (pos.x + dx, pos.y + dy);
+                if self.in_bounds(nx, ny) && !self.is_blocked(nx, ny) {
+                    result.push(GridPos::new(nx, ny));
+                }
+            }
+        }
+        result
+    }
+
     /// Get the position of an entity.
     pub fn get_position(&self, entity: EntityId) -> Option<GridPos> {
         self.entity_to_pos.get(&entity).copied()
     }
 
+    /// Direction a placed entity is facing, or `None` if it is not in the grid.
+    pub fn facing(&self, entity: EntityId) -> Option<Facing> {
+        self.entity_to_pos
+            .contains_key(&entity)
+            .then(|| self.facing.get(&entity).copied().unwrap_or_default())
+    }
+
     /// Set (teleport) an entity to an arbitrary in-bounds position.
     /// If the entity is already placed, it is moved; otherwise it is placed.
+    /// Teleports do not change facing: a moved entity keeps its direction and
+    /// a newly placed one faces the default.
     pub fn set_position(&mut self, entity: EntityId, x: i32, y: i32) -> Result<(), MoveError> {
         if !self.in_bounds(x, y) {
             return Err(MoveError::OutOfBounds { x, y });
@@ -120,23 +260,26 @@ impl GridSpace {
 
         // Remove from old cell if present
         if let Some(old_pos) = self.entity_to_pos.get(&entity).copied() {
-            if let Some(set) = self.cell_occupants.get_mut(&old_pos) {
-                set.remove(&entity);
-                if set.is_empty() {
-                    self.cell_occupants.remove(&old_pos);
-                }
-            }
+            self.index_remove(entity, old_pos);
         }
 
         self.entity_to_pos.insert(entity, new_pos);
-        self.cell_occupants
-            .entry(new_pos)
-            .or_default()
-            .insert(entity);
+        self.index_insert(entity, new_pos);
         Ok(())
     }
 
-    /// Move an entity to a specific position (must be adjacent — Chebyshev distance 1).
+    /// Teleport many entities at once (scatter, party-to-arena). Each move is
+    /// validated and applied independently in order, so a failing entry is
+    /// reported without aborting the rest of the batch.
+    pub fn set_positions(&mut self, moves: &[(EntityId, i32, i32)]) -> Vec<(EntityId, Result<(), MoveError>)> {
+        moves
+            .iter()
+            .map(|&(entity, x, y)| (entity, self.set_position(entity, x, y)))
+            .collect()
+    }
+
+    /// Move an entity to a specific position (must be adjacent — Chebyshev
+    /// distance 1 — and not blocked).
     pub fn move_to(&mut self, entity: EntityId, x: i32, y: i32) -> Result<(), MoveError> {
         let current = self
             .entity_to_pos
@@ -150,7 +293,11 @@ impl GridSpace {
 
         let dx = (x - current.x).abs();
         let dy = (y - current.y).abs();
-        if dx > 1 || dy > 1 || (dx == 0 && dy == 0) {
+        let Some(facing) = Facing::from_delta(x - current.x, y - current.y) else {
+            let cell = cell_to_entity_id(x, y);
+            return Err(MoveError::NoExit { from: cell, to: cell });
+        };
+        if dx > 1 || dy > 1 {
             let target = cell_to_entity_id(x, y);
             let from = cell_to_entity_id(current.x, current.y);
             return Err(MoveError::NoExit {
@@ -158,47 +305,68 @@ impl GridSpace {
                 to: target,
             });
         }
+        if self.is_blocked(x, y) {
+            return Err(MoveError::Blocked { x, y });
+        }
 
         let new_pos = GridPos::new(x, y);
 
         // Remove from old cell
-        if let Some(set) = self.cell_occupants.get_mut(&current) {
-            set.remove(&entity);
-            if set.is_empty() {
-                self.cell_occupants.remove(&current);
-            }
-        }
+        self.index_remove(entity, current);
 
         self.entity_to_pos.insert(entity, new_pos);
-        self.cell_occupants
-            .entry(new_pos)
-            .or_default()
-            .insert(entity);
+        self.index_insert(entity, new_pos);
+        self.facing.insert(entity, facing);
         Ok(())
     }
 
     /// Find all entities within a given radius (Chebyshev distance) of a point.
     /// Results are sorted by EntityId for determinism.
     pub fn entities_in_radius(&self, x: i32, y: i32, radius: u32) -> Vec<EntityId> {
-        let r = radius as i32;
-        let mut result = Vec::new();
+        self.entities_in_rect_counted(x, y, radius).0
+    }
 
-        let min_x = x.saturating_sub(r);
-        let max_x = x.saturating_add(r);
-        let min_y = y.saturating_sub(r);
-        let max_y = y.saturating_add(r);
+    /// `entities_in_radius` plus the number of occupied cells examined.
+    /// Only chunks overlapping the query square (clamped to the grid) are
+    /// scanned, so the cost follows the query area, not the map size.
+    fn entities_in_rect_counted(&self, x: i32, y: i32, radius: u32) -> (Vec<EntityId>, usize) {
+        let r = radius as i64;
+        let grid_max_x = self.config.origin_x as i64 + self.config.width as i64 - 1;
+        let grid_max_y = self.config.origin_y as i64 + self.config.height as i64 - 1;
+        let min_x = (x as i64 - r).max(self.config.origin_x as i64);
+        let max_x = (x as i64 + r).min(grid_max_x);
+        let min_y = (y as i64 - r).max(self.config.origin_y as i64);
+        let max_y = (y as i64 + r).min(grid_max_y);
+        if min_x > max_x || min_y > max_y {
+            return (Vec::new(), 0);
+        }
+        // Clamped to the grid, so every bound fits in i32.
+        let (min_x, max_x, min_y, max_y) = (min_x as i32, max_x as i32, min_y as i32, max_y as i32);
 
-        let range_start = GridPos::new(min_x, min_y);
-        let range_end = GridPos::new(max_x + 1, max_y + 1);
+        let (min_cy, min_cx) = Self::chunk_of(GridPos::new(min_x, min_y));
+        let (max_cy, max_cx) = Self::chunk_of(GridPos::new(max_x, max_y));
 
-        for (pos, entities) in self.cell_occupants.range(range_start..range_end) {
-            if pos.x >= min_x && pos.x <= max_x && pos.y >= min_y && pos.y <= max_y {
-                result.extend(entities.iter());
+        let mut result = Vec::new();
+        let mut visited = 0;
+        for cy in min_cy..=max_cy {
+            for (&(_, cx), cells) in self.chunk_cells.range((cy, min_cx)..=(cy, max_cx)) {
+                // Cells order by x then y, so each column inside the query
+                // is one contiguous range of the chunk's set.
+                let col_start = (cx * CHUNK_SIZE).max(min_x);
+                let col_end = (cx * CHUNK_SIZE + CHUNK_SIZE - 1).min(max_x);
+                for col in col_start..=col_end {
+                    for pos in cells.range(GridPos::new(col, min_y)..=GridPos::new(col, max_y)) {
+                        visited += 1;
+                        if let Some(entities) = self.cell_occupants.get(pos) {
+                            result.extend(entities.iter());
+                        }
+                    }
+                }
             }
         }
 
         result.sort();
-        result
+        (result, visited)
     }
 
     /// Get all entity positions (for state broadcast).
@@ -224,17 +392,20 @@ impl GridSpace {
     }
 
     /// Restore grid state from a snapshot, replacing all current data.
+    /// Only the config and positions are stored; the `cell_occupants` and
+    /// `chunk_cells` indexes are rebuilt here, and cell ids stay stable
+    /// because they are a pure function of the position (see
+    /// `GRID_CELL_GENERATION`).
     pub fn restore_from_snapshot(&mut self, snapshot: GridSpaceSnapshot) {
         self.config = snapshot.config;
         self.entity_to_pos.clear();
         self.cell_occupants.clear();
+        self.chunk_cells.clear();
+        self.facing.clear();
 
         for entry in snapshot.entities {
             self.entity_to_pos.insert(entry.entity, entry.pos);
-            self.cell_occupants
-                .entry(entry.pos)
-                .or_default()
-                .insert(entry.entity);
+            self.index_insert(entry.entity, entry.pos);
         }
     }
 }
@@ -299,10 +470,7 @@ impl SpaceModel for GridSpace {
             return Err(MoveError::OutOfBounds { x: pos.x, y: pos.y });
         }
         self.entity_to_pos.insert(entity, pos);
-        self.cell_occupants
-            .entry(pos)
-            .or_default()
-            .insert(entity);
+        self.index_insert(entity, pos);
         Ok(())
     }
 
@@ -311,12 +479,8 @@ impl SpaceModel for GridSpace {
             .entity_to_pos
             .remove(&entity)
             .ok_or(MoveError::EntityNotInRoom(entity))?;
-        if let Some(set) = self.cell_occupants.get_mut(&pos) {
-            set.remove(&entity);
-            if set.is_empty() {
-                self.cell_occupants.remove(&pos);
-            }
-        }
+        self.facing.remove(&entity);
+        self.index_remove(entity, pos);
         Ok(())
     }
 }
@@ -488,6 +652,73 @@ mod tests {
         assert!(grid.remove_entity(entity(99)).is_err());
     }
 
+    // --- facing ---
+
+    #[test]
+    fn facing_follows_each_cardinal_move() {
+        let mut grid = default_grid();
+        let e1 = entity(1);
+        grid.set_position(e1, 5, 5).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::South));
+
+        grid.move_to(e1, 6, 5).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::East));
+        grid.move_to(e1, 6, 4).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::North));
+        grid.move_to(e1, 5, 4).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::West));
+        grid.move_to(e1, 5, 5).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::South));
+    }
+
+    #[test]
+    fn facing_diagonal_prefers_horizontal() {
+        let mut grid = default_grid();
+        let e1 = entity(1);
+        grid.set_position(e1, 5, 5).unwrap();
+        grid.move_to(e1, 4, 4).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::West));
+        grid.move_to(e1, 5, 5).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::East));
+    }
+
+    #[test]
+    fn facing_unchanged_on_zero_delta() {
+        let mut grid = default_grid();
+        let e1 = entity(1);
+        grid.set_position(e1, 5, 5).unwrap();
+        grid.move_to(e1, 5, 4).unwrap();
+
+        assert!(grid.move_to(e1, 5, 4).is_err());
+        assert_eq!(grid.facing(e1), Some(Facing::North));
+        assert_eq!(grid.get_position(e1), Some(GridPos::new(5, 4)));
+    }
+
+    #[test]
+    fn facing_kept_on_teleport_and_cleared_on_remove() {
+        let mut grid = default_grid();
+        let e1 = entity(1);
+        grid.set_position(e1, 5, 5).unwrap();
+        grid.move_to(e1, 4, 5).unwrap();
+
+        grid.set_position(e1, 8, 8).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::West));
+
+        grid.remove_entity(e1).unwrap();
+        assert_eq!(grid.facing(e1), None);
+        grid.set_position(e1, 5, 5).unwrap();
+        assert_eq!(grid.facing(e1), Some(Facing::South));
+    }
+
+    #[test]
+    fn facing_wire_names() {
+        assert_eq!(Facing::North.as_str(), "north");
+        assert_eq!(Facing::South.as_str(), "south");
+        assert_eq!(Facing::East.as_str(), "east");
+        assert_eq!(Facing::West.as_str(), "west");
+        assert_eq!(Facing::default(), Facing::South);
+    }
+
     // --- move_entity (SpaceModel) ---
 
     #[test]
@@ -589,6 +820,26 @@ mod tests {
         assert!(grid.set_position(e1, 100, 100).is_err());
     }
 
+    #[test]
+    fn set_positions_reports_per_entity() {
+        let mut grid = default_grid();
+        let (e1, e2, e3) = (entity(1), entity(2), entity(3));
+        grid.set_position(e2, 5, 5).unwrap();
+
+        let results = grid.set_positions(&[(e1, 1, 1), (e2, 10, 0), (e3, 9, 9)]);
+        assert_eq!(results.len(), 3);
+        let ids: Vec<_> = results.iter().map(|(e, _)| *e).collect();
+        assert_eq!(ids, vec![e1, e2, e3]);
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(MoveError::OutOfBounds { x: 10, y: 0 })));
+        assert!(results[2].1.is_ok());
+
+        assert_eq!(grid.get_position(e1), Some(GridPos::new(1, 1)));
+        // A rejected move leaves the entity where it was
+        assert_eq!(grid.get_position(e2), Some(GridPos::new(5, 5)));
+        assert_eq!(grid.get_position(e3), Some(GridPos::new(9, 9)));
+    }
+
     // --- entities_in_same_area ---
 
     #[test]
@@ -636,6 +887,57 @@ mod tests {
         assert_eq!(neighbors.len(), 5);
     }
 
+    #[test]
+    fn walkable_neighbors_in_the_open() {
+        let grid = default_grid();
+        let pos = GridPos::new(5, 5);
+        assert_eq!(grid.walkable_neighbors(pos, true).len(), 8);
+        assert_eq!(
+            grid.walkable_neighbors(pos, false),
+            vec![GridPos::new(5, 4), GridPos::new(4, 5), GridPos::new(6, 5), GridPos::new(5, 6)]
+        );
+    }
+
+    #[test]
+    fn walkable_neighbors_at_corner() {
+        let grid = default_grid();
+        assert_eq!(grid.walkable_neighbors(GridPos::new(0, 0), true).len(), 3);
+        assert_eq!(grid.walkable_neighbors(GridPos::new(0, 0), false).len(), 2);
+        assert_eq!(grid.walkable_neighbors(GridPos::new(9, 9), false).len(), 2);
+    }
+
+    #[test]
+    fn walkable_neighbors_skip_blocked_cells() {
+        let mut grid = default_grid();
+        assert!(grid.set_blocked(6, 5, true));
+        assert!(grid.set_blocked(6, 6, true));
+        assert!(!grid.set_blocked(20, 20, true));
+
+        let pos = GridPos::new(5, 5);
+        let around = grid.walkable_neighbors(pos, true);
+        assert_eq!(around.len(), 6);
+        assert!(!around.contains(&GridPos::new(6, 5)));
+        assert_eq!(grid.walkable_neighbors(pos, false).len(), 3);
+
+        grid.set_blocked(6, 5, false);
+        assert!(!grid.is_blocked(6, 5));
+        assert_eq!(grid.walkable_neighbors(pos, false).len(), 4);
+    }
+
+    #[test]
+    fn move_to_blocked_cell_fails() {
+        let mut grid = default_grid();
+        let e = entity(1);
+        grid.set_position(e, 5, 5).unwrap();
+        grid.set_blocked(6, 5, true);
+
+        assert!(matches!(grid.move_to(e, 6, 5), Err(MoveError::Blocked { x: 6, y: 5 })));
+        assert_eq!(grid.get_position(e), Some(GridPos::new(5, 5)));
+        // Teleports ignore terrain.
+        grid.set_position(e, 6, 5).unwrap();
+        assert_eq!(grid.get_position(e), Some(GridPos::new(6, 5)));
+    }
+
     // --- entities_in_radius ---
 
     #[test]
@@ -685,6 +987,75 @@ mod tests {
         assert_eq!(grid.entity_count(), 1);
     }
 
+    // --- chunk index ---
+
+    #[test]
+    fn entities_in_radius_matches_naive_scan_on_large_grid() {
+        let mut grid = GridSpace::new(GridConfig {
+            width: 512,
+            height: 512,
+            origin_x: -256,
+            origin_y: -256,
+        });
+        // Deterministic LCG scatter, with a dense cluster near the origin.
+        let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = |m: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % m
+        };
+        for i in 0..3000u32 {
+            let (x, y) = if i % 3 == 0 {
+                (next(40) as i32 - 20, next(40) as i32 - 20)
+            } else {
+                (next(512) as i32 - 256, next(512) as i32 - 256)
+            };
+            grid.set_position(entity(i), x, y).unwrap();
+        }
+
+        let queries = [(0, 0, 5), (-250, -250, 10), (100, -30, 0), (255, 255, 40), (0, 0, 1000)];
+        for &(x, y, r) in &queries {
+            let mut naive: Vec<EntityId> = grid
+                .all_entity_positions()
+                .iter()
+                .filter(|(_, p)| (p.x - x).abs() <= r as i32 && (p.y - y).abs() <= r as i32)
+                .map(|(&e, _)| e)
+                .collect();
+            naive.sort();
+            assert_eq!(grid.entities_in_radius(x, y, r), naive, "query {:?}", (x, y, r));
+        }
+
+        // Only occupied cells inside the square are visited; a single ordered
+        // range over `cell_occupants` walks every occupied cell in the
+        // query's columns, whatever its y.
+        let (found, visited) = grid.entities_in_rect_counted(0, 0, 5);
+        let row_scan = grid
+            .cell_occupants
+            .range(GridPos::new(-5, -5)..GridPos::new(6, 6))
+            .count();
+        assert!(!found.is_empty());
+        assert!(visited < row_scan, "chunked {} vs row scan {}", visited, row_scan);
+    }
+
+    #[test]
+    fn chunk_index_follows_moves_and_removals() {
+        let mut grid = GridSpace::new(GridConfig {
+            width: 32,
+            height: 32,
+            origin_x: 0,
+            origin_y: 0,
+        });
+        let e = entity(1);
+        grid.set_position(e, 15, 15).unwrap();
+        grid.move_to(e, 16, 16).unwrap();
+        assert_eq!(grid.chunk_cells.len(), 1);
+        assert_eq!(grid.entities_in_radius(16, 16, 0), vec![e]);
+        assert!(grid.entities_in_radius(15, 15, 0).is_empty());
+
+        grid.remove_entity(e).unwrap();
+        assert!(grid.chunk_cells.is_empty());
+        assert!(grid.cell_occupants.is_empty());
+    }
+
     // --- all_entity_positions ---
 
     #[test]
@@ -737,6 +1108,43 @@ mod tests {
         assert_eq!(decoded.config.width, 10);
     }
 
+    #[test]
+    fn snapshot_bincode_roundtrip_rebuilds_index() {
+        let mut grid = default_grid();
+        let e1 = entity(1);
+        let e2 = entity(2);
+        let e3 = entity(3);
+        let e4 = entity(4);
+        grid.set_position(e1, 2, 2).unwrap();
+        grid.set_position(e2, 2, 2).unwrap();
+        grid.set_position(e3, 3, 2).unwrap();
+        grid.set_position(e4, 9, 9).unwrap();
+
+        let bytes = bincode::serialize(&grid.snapshot_state()).unwrap();
+        let mut restored = GridSpace::new(GridConfig::default());
+        restored.restore_from_snapshot(bincode::deserialize(&bytes).unwrap());
+
+        assert_eq!(restored.entity_count(), 4);
+        assert_eq!(restored.config().width, grid.config().width);
+        for e in [e1, e2, e3, e4] {
+            assert_eq!(restored.get_position(e), grid.get_position(e));
+            assert_eq!(restored.entity_room(e), grid.entity_room(e));
+        }
+        assert_eq!(
+            restored.entities_in_radius(2, 2, 0),
+            vec![e1, e2]
+        );
+        assert_eq!(
+            restored.entities_in_radius(2, 2, 1),
+            grid.entities_in_radius(2, 2, 1)
+        );
+        assert_eq!(
+            restored.entities_in_same_area(e1).unwrap(),
+            grid.entities_in_same_area(e1).unwrap()
+        );
+        assert_eq!(restored.entities_in_radius(9, 9, 0), vec![e4]);
+    }
+
     // --- broadcast_targets ---
 
     #[test]
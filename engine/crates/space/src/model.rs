@@ -16,6 +16,9 @@ pub enum MoveError {
 
     #[error("position ({x}, {y}) is out of bounds")]
     OutOfBounds { x: i32, y: i32 },
+
+    #[error("position ({x}, {y}) is blocked")]
+    Blocked { x: i32, y: i32 },
 }
 
 /// Trait abstracting spatial models (room-based, grid-based, etc.)
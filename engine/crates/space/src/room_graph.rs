@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
 
 use ecs_adapter::EntityId;
 use serde::{Deserialize, Serialize};
@@ -13,9 +14,43 @@ pub struct RoomExits {
     pub east: Option<EntityId>,
     pub west: Option<EntityId>,
     pub custom: HashMap<String, EntityId>,
+    /// Travel cost per direction ("north", ..., or a custom exit name).
+    /// Exits without an entry cost `DEFAULT_EXIT_COST`.
+    pub costs: HashMap<String, u32>,
 }
 
+/// Cost of an exit with no explicit entry in `RoomExits::costs`.
+pub const DEFAULT_EXIT_COST: u32 = 1;
+
 impl RoomExits {
+    /// Travel cost of the exit in `direction`.
+    pub fn cost(&self, direction: &str) -> u32 {
+        self.costs.get(direction).copied().unwrap_or(DEFAULT_EXIT_COST)
+    }
+
+    /// (target, cost) for every exit, sorted by target. When several exits
+    /// lead to the same room only the cheapest one is kept.
+    pub fn weighted_exits(&self) -> Vec<(EntityId, u32)> {
+        let mut best: BTreeMap<EntityId, u32> = BTreeMap::new();
+        let cardinal = [
+            ("north", self.north),
+            ("south", self.south),
+            ("east", self.east),
+            ("west", self.west),
+        ];
+        let named = cardinal
+            .into_iter()
+            .filter_map(|(dir, target)| target.map(|t| (dir, t)))
+            .chain(self.custom.iter().map(|(dir, t)| (dir.as_str(), *t)));
+        for (dir, target) in named {
+            let cost = self.cost(dir);
+            best.entry(target)
+                .and_modify(|c| *c = (*c).min(cost))
+                .or_insert(cost);
+        }
+        best.into_iter().collect()
+    }
+
     pub fn all_exits(&self) -> Vec<EntityId> {
         let mut exits = Vec::new();
         if let Some(id) = self.north {
@@ -48,6 +83,8 @@ pub struct RoomGraphSpace {
     entity_to_room: HashMap<EntityId, EntityId>,
     /// Room ID → exits.
     room_exits: HashMap<EntityId, RoomExits>,
+    /// Room ID → zone it belongs to (rooms without an entry are unzoned).
+    room_zones: BTreeMap<EntityId, String>,
 }
 
 impl RoomGraphSpace {
@@ -61,6 +98,39 @@ impl RoomGraphSpace {
         self.room_exits.insert(room_id, exits);
     }
 
+    /// Register a room with its exits as part of `zone`.
+    pub fn register_room_in_zone(&mut self, room_id: EntityId, exits: RoomExits, zone: impl Into<String>) {
+        self.register_room(room_id, exits);
+        self.room_zones.insert(room_id, zone.into());
+    }
+
+    /// Assign a registered room to a zone, or clear it with None.
+    /// Returns false if the room does not exist.
+    pub fn set_zone(&mut self, room_id: EntityId, zone: Option<String>) -> bool {
+        if !self.room_exists(room_id) {
+            return false;
+        }
+        match zone {
+            Some(z) => self.room_zones.insert(room_id, z),
+            None => self.room_zones.remove(&room_id),
+        };
+        true
+    }
+
+    /// Zone a room belongs to, if any.
+    pub fn zone_of(&self, room_id: EntityId) -> Option<&str> {
+        self.room_zones.get(&room_id).map(String::as_str)
+    }
+
+    /// Rooms assigned to `zone` (sorted).
+    pub fn rooms_in_zone(&self, zone: &str) -> Vec<EntityId> {
+        self.room_zones
+            .iter()
+            .filter(|(_, z)| z.as_str() == zone)
+            .map(|(&room, _)| room)
+            .collect()
+    }
+
     /// Check if a room exists.
     pub fn room_exists(&self, room_id: EntityId) -> bool {
         self.room_occupants.contains_key(&room_id)
@@ -95,6 +165,59 @@ impl RoomGraphSpace {
             .unwrap_or_default()
     }
 
+    /// Cheapest route from `from` to `to` following exit costs, including both
+    /// endpoints. Returns None if either room is unknown or `to` is unreachable.
+    pub fn path(&self, from: EntityId, to: EntityId) -> Option<Vec<EntityId>> {
+        self.shortest_route(from, to).map(|(_, path)| path)
+    }
+
+    /// Total exit cost of the cheapest route from `from` to `to`.
+    pub fn distance(&self, from: EntityId, to: EntityId) -> Option<u64> {
+        self.shortest_route(from, to).map(|(cost, _)| cost)
+    }
+
+    /// Dijkstra over the room graph. Ties are broken by room ID so the chosen
+    /// route is deterministic.
+    fn shortest_route(&self, from: EntityId, to: EntityId) -> Option<(u64, Vec<EntityId>)> {
+        if !self.room_exists(from) || !self.room_exists(to) {
+            return None;
+        }
+
+        let mut dist: BTreeMap<EntityId, u64> = BTreeMap::new();
+        let mut prev: BTreeMap<EntityId, EntityId> = BTreeMap::new();
+        let mut heap = BinaryHeap::new();
+        dist.insert(from, 0);
+        heap.push(Reverse((0u64, from)));
+
+        while let Some(Reverse((cost, room))) = heap.pop() {
+            if room == to {
+                let mut path = vec![to];
+                let mut cur = to;
+                while let Some(&p) = prev.get(&cur) {
+                    path.push(p);
+                    cur = p;
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            if dist.get(&room).is_some_and(|&d| cost > d) {
+                continue;
+            }
+            let Some(exits) = self.room_exits.get(&room) else {
+                continue;
+            };
+            for (next, edge) in exits.weighted_exits() {
+                let next_cost = cost + edge as u64;
+                if dist.get(&next).is_none_or(|&d| next_cost < d) {
+                    dist.insert(next, next_cost);
+                    prev.insert(next, room);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        None
+    }
+
     /// Capture the full space state as a serializable snapshot.
     pub fn snapshot_state(&self) -> SpaceSnapshot {
         let mut rooms = Vec::new();
@@ -112,6 +235,7 @@ impl RoomGraphSpace {
                 room_id,
                 exits,
                 occupants,
+                zone: self.room_zones.get(&room_id).cloned(),
             });
         }
 
@@ -123,8 +247,12 @@ impl RoomGraphSpace {
         self.room_occupants.clear();
         self.entity_to_room.clear();
         self.room_exits.clear();
+        self.room_zones.clear();
 
         for room_snap in snapshot.rooms {
+            if let Some(zone) = room_snap.zone {
+                self.room_zones.insert(room_snap.room_id, zone);
+            }
             let mut occupant_set = HashSet::new();
             for &entity in &room_snap.occupants {
                 occupant_set.insert(entity);
@@ -142,6 +270,8 @@ pub struct RoomSnapshot {
     pub room_id: EntityId,
     pub exits: RoomExits,
     pub occupants: Vec<EntityId>,
+    #[serde(default)]
+    pub zone: Option<String>,
 }
 
 /// Serializable snapshot of the entire space.
@@ -335,4 +465,114 @@ mod tests {
         let neighbors = space.neighbors(room_a).unwrap();
         assert_eq!(neighbors, vec![room_b]);
     }
+
+    #[test]
+    fn cheaper_multi_hop_path_beats_expensive_edge() {
+        let mut space = RoomGraphSpace::new();
+        let room_a = EntityId::new(100, 0);
+        let room_b = EntityId::new(101, 0);
+        let room_c = EntityId::new(102, 0);
+        let room_d = EntityId::new(103, 0);
+
+        // a --east(10)--> d, and a -> b -> c -> d at cost 1 each.
+        space.register_room(
+            room_a,
+            RoomExits {
+                east: Some(room_d),
+                north: Some(room_b),
+                costs: HashMap::from([("east".to_string(), 10)]),
+                ..Default::default()
+            },
+        );
+        space.register_room(
+            room_b,
+            RoomExits {
+                north: Some(room_c),
+                ..Default::default()
+            },
+        );
+        space.register_room(
+            room_c,
+            RoomExits {
+                east: Some(room_d),
+                ..Default::default()
+            },
+        );
+        space.register_room(room_d, RoomExits::default());
+
+        assert_eq!(
+            space.path(room_a, room_d).unwrap(),
+            vec![room_a, room_b, room_c, room_d]
+        );
+        assert_eq!(space.distance(room_a, room_d), Some(3));
+
+        // Making the direct edge cheap flips the choice.
+        let mut exits = space.room_exits(room_a).unwrap().clone();
+        exits.costs.insert("east".to_string(), 2);
+        space.register_room(room_a, exits);
+        assert_eq!(space.path(room_a, room_d).unwrap(), vec![room_a, room_d]);
+        assert_eq!(space.distance(room_a, room_d), Some(2));
+    }
+
+    #[test]
+    fn path_unreachable_or_unknown() {
+        let (space, room_a, room_b) = setup_two_rooms();
+        assert_eq!(space.path(room_a, room_a).unwrap(), vec![room_a]);
+        assert_eq!(space.distance(room_b, room_a), Some(1));
+        assert!(space.path(room_a, EntityId::new(999, 0)).is_none());
+
+        let mut space = RoomGraphSpace::new();
+        space.register_room(room_a, RoomExits::default());
+        space.register_room(room_b, RoomExits::default());
+        assert!(space.path(room_a, room_b).is_none());
+        assert!(space.distance(room_a, room_b).is_none());
+    }
+
+    #[test]
+    fn weighted_exits_keep_cheapest_duplicate() {
+        let room_b = EntityId::new(101, 0);
+        let exits = RoomExits {
+            north: Some(room_b),
+            custom: HashMap::from([("portal".to_string(), room_b)]),
+            costs: HashMap::from([("north".to_string(), 5), ("portal".to_string(), 3)]),
+            ..Default::default()
+        };
+        assert_eq!(exits.weighted_exits(), vec![(room_b, 3)]);
+        assert_eq!(exits.cost("south"), DEFAULT_EXIT_COST);
+    }
+
+    #[test]
+    fn rooms_grouped_by_zone() {
+        let (mut space, room_a, room_b) = setup_two_rooms();
+        let room_c = EntityId::new(102, 0);
+        let unzoned = EntityId::new(103, 0);
+        space.register_room_in_zone(room_c, RoomExits::default(), "forest");
+        space.register_room(unzoned, RoomExits::default());
+        assert!(space.set_zone(room_b, Some("town".to_string())));
+        assert!(space.set_zone(room_a, Some("town".to_string())));
+        assert!(!space.set_zone(EntityId::new(999, 0), Some("town".to_string())));
+
+        assert_eq!(space.rooms_in_zone("town"), vec![room_a, room_b]);
+        assert_eq!(space.rooms_in_zone("forest"), vec![room_c]);
+        assert!(space.rooms_in_zone("desert").is_empty());
+        assert_eq!(space.zone_of(unzoned), None);
+        assert_eq!(space.zone_of(room_c), Some("forest"));
+
+        // Re-registering exits keeps the zone; clearing removes the room
+        space.register_room(room_c, RoomExits::default());
+        assert_eq!(space.zone_of(room_c), Some("forest"));
+        assert!(space.set_zone(room_a, None));
+        assert_eq!(space.rooms_in_zone("town"), vec![room_b]);
+    }
+
+    #[test]
+    fn zones_survive_snapshot_roundtrip() {
+        let (mut space, room_a, room_b) = setup_two_rooms();
+        space.set_zone(room_a, Some("town".to_string()));
+
+        let mut restored = RoomGraphSpace::new();
+        restored.restore_from_snapshot(space.snapshot_state());
+        assert_eq!(restored.rooms_in_zone("town"), vec![room_a]);
+        assert_eq!(restored.zone_of(room_b), None);
+    }
 }
@@ -50,6 +50,11 @@ fn emit_command(cmd: &WasmCommand) -> i32 {
 
 // --- Plugin entry points ---
 
+#[no_mangle]
+pub extern "C" fn abi_version() -> u64 {
+    plugin_abi::ABI_VERSION
+}
+
 #[no_mangle]
 pub extern "C" fn on_load() -> i32 {
     RESULT_OK
@@ -1,5 +1,10 @@
 #![no_std]
 
+#[no_mangle]
+pub extern "C" fn abi_version() -> u64 {
+    plugin_abi::ABI_VERSION
+}
+
 #[no_mangle]
 pub extern "C" fn on_load() -> i32 {
     0
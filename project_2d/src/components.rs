@@ -3,3 +3,11 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Name(pub String);
+
+/// How the web client should draw an entity. `sprite` is a client-side asset
+/// key; `color` is a CSS color string overriding the default tint.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Appearance {
+    pub sprite: String,
+    pub color: Option<String>,
+}
@@ -1,8 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::Deserialize;
 
 use engine_core::tick::TickConfig;
+use net::ip_filter::{IpFilter, IpFilterError};
+use net::ws_server::{Heartbeat, WsHandshake};
 use scripting::ScriptConfig;
 use space::grid_space::GridConfig;
 
@@ -12,6 +15,12 @@ pub struct NetConfig {
     pub ws_addr: String,
     pub max_connections: usize,
     pub web_static_dir: String,
+    /// `/readyz` reports 503 once the last tick is older than this.
+    pub readiness_max_tick_age_ms: u64,
+    /// WebSocket ping interval (0 = no pings, no timeout).
+    pub ws_ping_interval_ms: u64,
+    /// Unanswered pings before a WebSocket client is disconnected (0 acts as 1).
+    pub ws_max_missed_pongs: u32,
 }
 
 impl Default for NetConfig {
@@ -20,6 +29,9 @@ impl Default for NetConfig {
             ws_addr: "0.0.0.0:4001".to_string(),
             max_connections: 1000,
             web_static_dir: "web_dist".to_string(),
+            readiness_max_tick_age_ms: 5000,
+            ws_ping_interval_ms: 30_000,
+            ws_max_missed_pongs: 2,
         }
     }
 }
@@ -28,11 +40,20 @@ impl Default for NetConfig {
 #[serde(default)]
 pub struct TickSection {
     pub tps: u32,
+    /// Master seed for plugin and script randomness. Set it to make a run
+    /// reproducible; unset keeps Lua's `math.random` unseeded.
+    pub seed: Option<u64>,
+    /// Stop cleanly once this tick is reached, as on shutdown (0 = run
+    /// forever). For bounded load-test and CI runs.
+    pub max_ticks: u64,
+    /// Pace ticks to `tps` in wall time. False steps back-to-back (offline
+    /// simulation, tests).
+    pub realtime: bool,
 }
 
 impl Default for TickSection {
     fn default() -> Self {
-        Self { tps: 10 }
+        Self { tps: 10, seed: None, max_ticks: 0, realtime: true }
     }
 }
 
@@ -44,6 +65,12 @@ pub struct ScriptSection {
     pub content_dir: String,
     pub memory_limit_kb: usize,
     pub instruction_limit: u32,
+    /// Budget for the whole `on_init` pass (world setup).
+    pub init_instruction_limit: u32,
+    pub hook_error_threshold: u32,
+    pub hook_error_window_ticks: u64,
+    /// Directory for `require` modules (default: `<grid_scripts_dir>/lib`).
+    pub lib_dir: Option<String>,
 }
 
 impl Default for ScriptSection {
@@ -54,6 +81,10 @@ impl Default for ScriptSection {
             content_dir: "content".to_string(),
             memory_limit_kb: 16384,       // 16 MB
             instruction_limit: 1_000_000,
+            init_instruction_limit: 100_000_000,
+            hook_error_threshold: 10,
+            hook_error_window_ticks: 100,
+            lib_dir: None,
         }
     }
 }
@@ -66,6 +97,9 @@ pub struct GridSection {
     pub origin_x: i32,
     pub origin_y: i32,
     pub aoi_radius: u32,
+    /// Idle sessions get a keepalive instead of an empty delta every this
+    /// many ticks. 0 sends a (possibly empty) delta every tick.
+    pub delta_keepalive_ticks: u64,
 }
 
 impl Default for GridSection {
@@ -76,6 +110,7 @@ impl Default for GridSection {
             origin_x: 0,
             origin_y: 0,
             aoi_radius: 32,
+            delta_keepalive_ticks: 50,
         }
     }
 }
@@ -87,6 +122,10 @@ pub struct SecuritySection {
     pub max_connections_per_ip: usize,
     pub max_commands_per_second: u32,
     pub max_input_length: usize,
+    pub ip_allow: Vec<String>,
+    pub ip_deny: Vec<String>,
+    /// Origins allowed to open the game WebSocket; empty allows any.
+    pub allowed_origins: Vec<String>,
 }
 
 impl Default for SecuritySection {
@@ -96,12 +135,15 @@ impl Default for SecuritySection {
             max_connections_per_ip: 5,
             max_commands_per_second: 20,
             max_input_length: 4096,
+            ip_allow: Vec::new(),
+            ip_deny: Vec::new(),
+            allowed_origins: Vec::new(),
         }
     }
 }
 
 /// Top-level Grid server configuration.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
 pub struct ServerConfig {
     pub net: NetConfig,
@@ -111,18 +153,6 @@ pub struct ServerConfig {
     pub security: SecuritySection,
 }
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
-            net: NetConfig::default(),
-            tick: TickSection::default(),
-            scripting: ScriptSection::default(),
-            grid: GridSection::default(),
-            security: SecuritySection::default(),
-        }
-    }
-}
-
 impl ServerConfig {
     /// Load configuration from an optional TOML file path.
     pub fn load(config_path: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
@@ -140,15 +170,58 @@ impl ServerConfig {
     pub fn to_tick_config(&self) -> TickConfig {
         TickConfig {
             tps: self.tick.tps,
-            max_ticks: 0,
+            max_ticks: self.tick.max_ticks,
+            realtime: self.tick.realtime,
+            ..TickConfig::default()
         }
     }
 
+    /// Settings exposed to Lua as the read-only `config` global.
+    pub fn to_script_globals(&self) -> serde_json::Value {
+        serde_json::json!({
+            "mode": "grid",
+            "tps": self.tick.tps,
+            "grid": {
+                "width": self.grid.width,
+                "height": self.grid.height,
+                "origin_x": self.grid.origin_x,
+                "origin_y": self.grid.origin_y,
+            },
+            "aoi_radius": self.grid.aoi_radius,
+            "delta_keepalive_ticks": self.grid.delta_keepalive_ticks,
+        })
+    }
+
+    /// WebSocket handshake policy from the security section.
+    pub fn to_ws_handshake(&self) -> WsHandshake {
+        WsHandshake::new(self.security.allowed_origins.clone())
+    }
+
+    /// WebSocket keepalive from the net section.
+    pub fn to_heartbeat(&self) -> Heartbeat {
+        Heartbeat::new(
+            Duration::from_millis(self.net.ws_ping_interval_ms),
+            self.net.ws_max_missed_pongs,
+        )
+    }
+
+    /// Build the accept-time IP filter from the security section.
+    pub fn to_ip_filter(&self) -> Result<IpFilter, IpFilterError> {
+        IpFilter::from_lists(&self.security.ip_allow, &self.security.ip_deny)
+    }
+
     /// Convert scripting section to scripting crate's ScriptConfig.
     pub fn to_script_config(&self) -> ScriptConfig {
         ScriptConfig {
             memory_limit: self.scripting.memory_limit_kb * 1024,
             instruction_limit: self.scripting.instruction_limit,
+            init_instruction_limit: self.scripting.init_instruction_limit,
+            hook_error_threshold: self.scripting.hook_error_threshold,
+            hook_error_window_ticks: self.scripting.hook_error_window_ticks,
+            lib_dir: Some(match &self.scripting.lib_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => Path::new(&self.scripting.grid_scripts_dir).join("lib"),
+            }),
         }
     }
 
@@ -165,7 +238,8 @@ impl ServerConfig {
 
 /// Parse CLI arguments and load config.
 /// Supports: --config <path>
-pub fn parse_cli_args() -> ServerConfig {
+/// Returns the config and the path it was loaded from (for hot reload).
+pub fn parse_cli_args() -> (ServerConfig, Option<String>) {
     let args: Vec<String> = std::env::args().collect();
     let mut config_path: Option<&str> = None;
 
@@ -189,7 +263,7 @@ pub fn parse_cli_args() -> ServerConfig {
     }
 
     match ServerConfig::load(config_path) {
-        Ok(c) => c,
+        Ok(c) => (c, config_path.map(str::to_string)),
         Err(e) => {
             eprintln!("Failed to load config: {}", e);
             std::process::exit(1);
@@ -207,6 +281,7 @@ mod tests {
     fn default_config_matches_hardcoded_values() {
         let config = ServerConfig::default();
         assert_eq!(config.net.ws_addr, "0.0.0.0:4001");
+        assert_eq!(config.net.readiness_max_tick_age_ms, 5000);
         assert_eq!(config.tick.tps, 10);
         assert_eq!(config.scripting.scripts_dir, "scripts");
         assert_eq!(config.scripting.grid_scripts_dir, "scripts_grid");
@@ -214,7 +289,10 @@ mod tests {
         assert_eq!(config.grid.width, 256);
         assert_eq!(config.grid.height, 256);
         assert_eq!(config.grid.aoi_radius, 32);
+        assert_eq!(config.grid.delta_keepalive_ticks, 50);
         assert_eq!(config.security.max_connections_per_ip, 5);
+        assert!(config.security.allowed_origins.is_empty());
+        assert_eq!(config.to_heartbeat(), Heartbeat::default());
     }
 
     #[test]
@@ -231,6 +309,7 @@ mod tests {
         let sc = config.to_script_config();
         assert_eq!(sc.memory_limit, 16384 * 1024);
         assert_eq!(sc.instruction_limit, 1_000_000);
+        assert_eq!(sc.init_instruction_limit, 100_000_000);
     }
 
     #[test]
@@ -261,6 +340,7 @@ mod tests {
         write!(f, r#"
 [tick]
 tps = 20
+seed = 42
 
 [grid]
 width = 512
@@ -268,8 +348,24 @@ width = 512
 
         let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
         assert_eq!(config.tick.tps, 20);
+        assert_eq!(config.tick.seed, Some(42));
         assert_eq!(config.grid.width, 512);
         assert_eq!(config.grid.height, 256);
         assert_eq!(config.net.ws_addr, "0.0.0.0:4001");
     }
+
+    #[test]
+    fn zero_ping_interval_disables_heartbeat() {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, r#"
+[net]
+ws_ping_interval_ms = 0
+ws_max_missed_pongs = 5
+"#).unwrap();
+
+        let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
+        let heartbeat = config.to_heartbeat();
+        assert!(!heartbeat.is_enabled());
+        assert_eq!(heartbeat.max_missed, 5);
+    }
 }
@@ -1,36 +1,56 @@
 mod config;
 mod shutdown;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use ecs_adapter::EcsAdapter;
-use engine_core::tick::TickLoop;
+use engine_core::despawn::despawn_with_space;
+use engine_core::tick::{TickLoop, TickPacer};
 use net::channels::{NetToTick, OutputTx, PlayerRx};
-use net::protocol::{EntityMovedWire, EntityWire, GridConfigWire, ServerMessage};
+use net::health::{ReadinessProbe, TickHeartbeat};
+use net::ip_filter::IpFilterHandle;
+use net::protocol::{
+    DeltaPacer, EntityMovedWire, EntityWire, ErrorCode, GridConfigWire, ServerClock,
+    ServerMessage,
+};
+use observability::{TickPhase, TickProfiler};
 use scripting::engine::{ScriptContext, ScriptEngine};
 use scripting::ContentRegistry;
-use session::{SessionId, SessionManager, SessionOutput, SessionState};
+use session::{ConnectionInfo, SessionId, SessionManager, SessionOutput, SessionState};
 use space::grid_space::GridConfig;
-use space::SpaceModel;
+use space::model::MoveError;
 
 use crate::config::{parse_cli_args, ServerConfig};
 use crate::shutdown::{shutdown_channel, ShutdownRx};
 
-pub use project_2d::components::Name;
+pub use project_2d::components::{Appearance, Name};
 
 #[tokio::main]
 async fn main() {
     observability::init_logging();
 
-    let config = parse_cli_args();
+    let (config, config_path) = parse_cli_args();
+    let ip_filter = IpFilterHandle::start(
+        &config.security.ip_allow,
+        &config.security.ip_deny,
+        config_path.map(PathBuf::from),
+        |p| {
+            let config = ServerConfig::load(p.to_str()).map_err(|e| e.to_string())?;
+            config.to_ip_filter().map_err(|e| e.to_string())
+        },
+    )
+    .unwrap_or_else(|e| {
+        tracing::error!("Invalid IP filter config: {}", e);
+        std::process::exit(1);
+    });
     tracing::info!("Grid Server starting...");
 
     let (shutdown_tx, shutdown_rx) = shutdown_channel();
 
     let config_clone = config.clone();
     let server_future = async move {
-        run_grid_server(config_clone, shutdown_rx).await;
+        run_grid_server(config_clone, shutdown_rx, ip_filter).await;
     };
 
     tokio::select! {
@@ -45,7 +65,7 @@ async fn main() {
     tracing::info!("Server stopped.");
 }
 
-async fn run_grid_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
+async fn run_grid_server(config: ServerConfig, shutdown_rx: ShutdownRx, ip_filter: IpFilterHandle) {
     // Channels between async and tick thread (same pattern as MUD mode)
     let (player_tx, player_rx) = tokio::sync::mpsc::unbounded_channel();
     let (output_tx, output_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -68,6 +88,13 @@ async fn run_grid_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
         if p.is_dir() { Some(p) } else { None }
     };
     let ws_shutdown = shutdown_rx.clone();
+    let handshake = config.to_ws_handshake();
+    let ws_heartbeat = config.to_heartbeat();
+    let heartbeat = TickHeartbeat::new();
+    let readiness = ReadinessProbe::new(
+        heartbeat.clone(),
+        Duration::from_millis(config.net.readiness_max_tick_age_ms),
+    );
     tokio::spawn(async move {
         if let Err(e) = net::web_server::run_web_server_with_shutdown(
             ws_addr,
@@ -76,6 +103,10 @@ async fn run_grid_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
             unregister_tx_clone,
             static_dir,
             ws_shutdown.into_inner(),
+            Some(readiness),
+            Some(ip_filter),
+            handshake,
+            ws_heartbeat,
         )
         .await
         {
@@ -88,20 +119,29 @@ async fn run_grid_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
     // Tick thread (blocking)
     let tick_shutdown = shutdown_rx;
     let tick_handle = std::thread::spawn(move || {
-        run_grid_tick_thread(player_rx, output_tx, config, tick_shutdown);
+        run_grid_tick_thread(player_rx, output_tx, config, tick_shutdown, heartbeat);
     });
 
     // Wait for tick thread
     let _ = tick_handle.join();
 }
 
-fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: ServerConfig, shutdown_rx: ShutdownRx) {
+fn run_grid_tick_thread(
+    mut player_rx: PlayerRx,
+    output_tx: OutputTx,
+    config: ServerConfig,
+    shutdown_rx: ShutdownRx,
+    heartbeat: TickHeartbeat,
+) {
     let tick_config = config.to_tick_config();
     let grid_config = config.to_grid_config();
     let grid = space::GridSpace::new(grid_config.clone());
     let mut tick_loop = TickLoop::new(tick_config, grid);
+    if let Some(seed) = config.tick.seed {
+        tick_loop.set_master_seed(seed);
+    }
     let mut sessions = SessionManager::new();
-    let mut aoi = AoiTracker::new(config.grid.aoi_radius);
+    let mut aoi = AoiTracker::new(config.grid.aoi_radius, config.grid.delta_keepalive_ticks);
 
     // Initialize scripting engine for grid mode
     let mut script_engine = match ScriptEngine::new(config.to_script_config()) {
@@ -111,6 +151,9 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
             std::process::exit(1);
         }
     };
+    if let Err(e) = script_engine.set_config_globals(&config.to_script_globals()) {
+        tracing::warn!("Failed to expose config to Lua: {}", e);
+    }
 
     // Load content from content/ directory if it exists
     let content_path = Path::new(&config.scripting.content_dir);
@@ -174,6 +217,7 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
             }
             Err(e) => {
                 tracing::error!("Lua on_init error: {}", e);
+                std::process::exit(1);
             }
         }
     }
@@ -182,29 +226,43 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
 
     tracing::info!("Grid tick loop running (Ctrl+C to stop)");
 
+    let mut pacer = TickPacer::new(&tick_loop.config);
+
     loop {
-        if shutdown_rx.is_shutdown() {
-            tracing::info!("Grid tick loop: shutdown signal received");
+        // Waits out the rest of the tick, or returns at once while catching up
+        let paced = pacer.next_tick();
+        let max_ticks = tick_loop.config.max_ticks;
+        let max_ticks_reached = max_ticks > 0 && tick_loop.current_tick >= max_ticks;
+        if shutdown_rx.is_shutdown() || max_ticks_reached {
+            if max_ticks_reached {
+                tracing::info!(max_ticks, "Grid tick loop: max_ticks reached");
+            } else {
+                tracing::info!("Grid tick loop: shutdown signal received");
+            }
             // Send shutdown message to all connected sessions
             for session in sessions.playing_sessions() {
                 let _ = output_tx.send(SessionOutput::with_disconnect(
                     session.session_id,
-                    serde_json::to_string(&ServerMessage::Error {
-                        message: "Server is shutting down.".to_string(),
-                    })
-                    .unwrap(),
+                    ServerMessage::error(ErrorCode::ShuttingDown, "Server is shutting down.")
+                        .to_json(),
                 ));
             }
             break;
         }
 
-        let tick_start = std::time::Instant::now();
+        let mut profiler = TickProfiler::start();
+
+        if let Some(seed) = tick_loop.tick_seed() {
+            if let Err(e) = script_engine.set_tick_seed(seed) {
+                tracing::warn!("Failed to seed script RNG: {}", e);
+            }
+        }
 
         // 1. Process network messages
         while let Ok(msg) = player_rx.try_recv() {
             match msg {
-                NetToTick::NewConnection { session_id } => {
-                    handle_grid_new_connection(&mut sessions, &output_tx, session_id);
+                NetToTick::NewConnection { session_id, connection } => {
+                    handle_grid_new_connection(&mut sessions, &output_tx, session_id, connection);
                 }
                 NetToTick::PlayerInput { session_id, line } => {
                     handle_grid_player_input(
@@ -231,11 +289,15 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
             }
         }
 
-        // 2. Run engine tick (WASM plugins, command stream)
-        let _metrics = tick_loop.step();
+        profiler.lap(TickPhase::Network);
 
-        // 3. Run Lua on_tick hooks
-        {
+        // 2. Run engine tick (WASM plugins, command stream); skipped while paused
+        let paused = tick_loop.step_if_running().is_none();
+        heartbeat.beat();
+        profiler.lap(TickPhase::EngineStep);
+
+        // 3. Run Lua on_tick hooks (not while paused)
+        if !paused {
             let mut script_ctx = ScriptContext {
                 ecs: &mut tick_loop.ecs,
                 space: &mut tick_loop.space,
@@ -254,6 +316,11 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
             }
         }
 
+        profiler.lap(TickPhase::ScriptTick);
+
+        // Surface the hook errors queued by this tick's hook passes
+        report_hook_errors(&script_engine);
+
         // 4. Broadcast delta to all playing sessions (AOI filtering)
         broadcast_delta(
             &tick_loop.ecs,
@@ -263,28 +330,38 @@ fn run_grid_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Se
             tick_loop.current_tick,
             &mut aoi,
         );
-
-        // Sleep for remainder of tick
-        let elapsed = tick_start.elapsed();
-        if elapsed < tick_duration {
-            std::thread::sleep(tick_duration - elapsed);
-        }
+        tick_loop.ecs.clear_change_tracking();
+        profiler.lap(TickPhase::Broadcast);
+        let mut profile = profiler.finish(tick_loop.current_tick);
+        profile.skipped_ticks = paced.skipped;
+        profile.log(tick_duration);
+        observability::slow_tick_sampler().finish_tick(&profile, tick_duration);
     }
 
     tracing::info!("Grid tick loop stopped");
 }
 
+/// Log and count the Lua hook errors queued since the last call.
+fn report_hook_errors(script_engine: &ScriptEngine) {
+    for err in script_engine.take_hook_errors() {
+        tracing::error!(source = %err.source, hook = %err.hook, "Lua hook error: {}", err.message);
+        observability::metrics::record_script_error(&err.hook);
+    }
+}
+
 fn handle_grid_new_connection(
     sessions: &mut SessionManager,
     output_tx: &OutputTx,
     session_id: SessionId,
+    connection: ConnectionInfo,
 ) {
-    sessions.create_session_with_id(session_id);
+    sessions.create_session_with_connection(session_id, connection);
     tracing::info!(?session_id, "Grid: new connection (awaiting login)");
     // No welcome message yet — client sends Connect with name
     let _ = output_tx;
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_grid_player_input(
     ecs: &mut EcsAdapter,
     space: &mut space::GridSpace,
@@ -316,12 +393,11 @@ fn handle_grid_player_input(
             if let Err(e) = space.set_position(entity, center_x, center_y) {
                 tracing::error!(?entity, "Failed to place entity on grid: {}", e);
                 let _ = ecs.despawn_entity(entity);
-                let err_msg = ServerMessage::Error {
-                    message: format!("Failed to spawn: {}", e),
-                };
+                let err_msg =
+                    ServerMessage::error(ErrorCode::Internal, format!("Failed to spawn: {}", e));
                 let _ = output_tx.send(SessionOutput::new(
                     session_id,
-                    serde_json::to_string(&err_msg).unwrap(),
+                    err_msg.to_json(),
                 ));
                 return;
             }
@@ -337,6 +413,7 @@ fn handle_grid_player_input(
                 session_id: session_id.0,
                 entity_id: entity.to_u64(),
                 tick,
+                server_time_ms: aoi.clock.now_ms(),
                 grid_config: GridConfigWire {
                     width: grid_config.width,
                     height: grid_config.height,
@@ -346,7 +423,7 @@ fn handle_grid_player_input(
             };
             let _ = output_tx.send(SessionOutput::new(
                 session_id,
-                serde_json::to_string(&welcome).unwrap(),
+                welcome.to_json(),
             ));
 
             tracing::info!(?session_id, ?entity, "Grid: player spawned");
@@ -361,11 +438,16 @@ fn handle_grid_player_input(
                 let pong = ServerMessage::Pong;
                 let _ = output_tx.send(SessionOutput::new(
                     session_id,
-                    serde_json::to_string(&pong).unwrap(),
+                    pong.to_json(),
                 ));
                 return;
             }
 
+            if line == "__resync" {
+                aoi.resync(session_id);
+                return;
+            }
+
             if let Some(rest) = line.strip_prefix("__grid_move ") {
                 let parts: Vec<&str> = rest.split_whitespace().collect();
                 if parts.len() == 2 {
@@ -374,12 +456,14 @@ fn handle_grid_player_input(
                             let new_x = pos.x + dx;
                             let new_y = pos.y + dy;
                             if let Err(e) = space.move_to(entity, new_x, new_y) {
-                                let err_msg = ServerMessage::Error {
-                                    message: format!("{}", e),
+                                let code = match e {
+                                    MoveError::OutOfBounds { .. } => ErrorCode::OutOfBounds,
+                                    _ => ErrorCode::InvalidMove,
                                 };
+                                let err_msg = ServerMessage::error(code, e.to_string());
                                 let _ = output_tx.send(SessionOutput::new(
                                     session_id,
-                                    serde_json::to_string(&err_msg).unwrap(),
+                                    err_msg.to_json(),
                                 ));
                             }
                         }
@@ -403,8 +487,7 @@ fn handle_grid_disconnect(
     aoi: &mut AoiTracker,
 ) {
     if let Some(entity) = sessions.disconnect(session_id) {
-        let _ = space.remove_entity(entity);
-        let _ = ecs.despawn_entity(entity);
+        despawn_with_space(ecs, space, entity);
     }
     aoi.on_session_removed(session_id);
     sessions.remove_session(session_id);
@@ -412,18 +495,28 @@ fn handle_grid_disconnect(
 
 struct SessionAoiState {
     known: std::collections::BTreeMap<ecs_adapter::EntityId, space::grid_space::GridPos>,
+    pacer: DeltaPacer,
 }
 
 struct AoiTracker {
     sessions: std::collections::BTreeMap<SessionId, SessionAoiState>,
     radius: u32,
+    /// Ticks between keepalives for idle sessions (0 = send every delta).
+    keepalive_ticks: u64,
+    /// Name cache kept across ticks; see `invalidate_names`.
+    names: std::collections::BTreeMap<ecs_adapter::EntityId, Option<String>>,
+    /// Source of `server_time_ms` in Welcome/StateDelta.
+    clock: ServerClock,
 }
 
 impl AoiTracker {
-    fn new(radius: u32) -> Self {
+    fn new(radius: u32, keepalive_ticks: u64) -> Self {
         Self {
             sessions: std::collections::BTreeMap::new(),
             radius,
+            keepalive_ticks,
+            names: std::collections::BTreeMap::new(),
+            clock: ServerClock::new(),
         }
     }
 
@@ -432,6 +525,7 @@ impl AoiTracker {
             session_id,
             SessionAoiState {
                 known: std::collections::BTreeMap::new(),
+                pacer: DeltaPacer::new(self.keepalive_ticks),
             },
         );
     }
@@ -439,6 +533,26 @@ impl AoiTracker {
     fn on_session_removed(&mut self, session_id: SessionId) {
         self.sessions.remove(&session_id);
     }
+
+    /// Drop cached names that may be stale: the entity's `Name` was set
+    /// since the last tick, its `Name` was removed, or it left the grid
+    /// (e.g. despawned).
+    fn invalidate_names(&mut self, ecs: &EcsAdapter, space: &space::GridSpace) {
+        for eid in ecs.changed_this_tick::<Name>() {
+            self.names.remove(&eid);
+        }
+        self.names.retain(|&eid, cached| {
+            cached.is_some() == ecs.has_component::<Name>(eid) && space.get_position(eid).is_some()
+        });
+    }
+
+    /// Forget what the session has seen so the next delta re-sends its whole
+    /// AOI as `entered`.
+    fn resync(&mut self, session_id: SessionId) {
+        if let Some(state) = self.sessions.get_mut(&session_id) {
+            state.known.clear();
+        }
+    }
 }
 
 fn broadcast_delta(
@@ -449,16 +563,17 @@ fn broadcast_delta(
     tick: u64,
     aoi: &mut AoiTracker,
 ) {
+    // Only re-read names that changed since the last tick
+    aoi.invalidate_names(ecs, space);
+
     let playing = sessions.playing_sessions();
     if playing.is_empty() {
         return;
     }
 
     let all_positions = space.all_entity_positions();
-
-    // Name cache to avoid repeated ECS lookups
-    let mut name_cache: std::collections::BTreeMap<ecs_adapter::EntityId, Option<String>> =
-        std::collections::BTreeMap::new();
+    let server_time_ms = aoi.clock.now_ms();
+    let name_cache = &mut aoi.names;
 
     for session in &playing {
         let self_entity = match session.entity {
@@ -508,12 +623,16 @@ fn broadcast_delta(
                             ecs.get_component::<Name>(eid).ok().map(|n| n.0.clone())
                         })
                         .clone();
+                    let appearance = ecs.get_component::<Appearance>(eid).ok();
                     entered.push(EntityWire {
                         id: eid.to_u64(),
                         x: pos.x,
                         y: pos.y,
                         name,
                         is_self: eid == self_entity,
+                        facing: space.facing(eid).unwrap_or_default().as_str(),
+                        sprite: appearance.map(|a| a.sprite.clone()),
+                        color: appearance.and_then(|a| a.color.clone()),
                     });
                 }
                 Some(old_pos) => {
@@ -523,6 +642,7 @@ fn broadcast_delta(
                             id: eid.to_u64(),
                             x: pos.x,
                             y: pos.y,
+                            facing: space.facing(eid).unwrap_or_default().as_str(),
                         });
                     }
                 }
@@ -535,13 +655,51 @@ fn broadcast_delta(
         // Send StateDelta
         let delta = ServerMessage::StateDelta {
             tick,
+            server_time_ms,
             entered,
             moved,
             left,
         };
-        let _ = output_tx.send(SessionOutput::new(
-            session.session_id,
-            serde_json::to_string(&delta).unwrap(),
-        ));
+        if let Some(msg) = aoi_state.pacer.pace(tick, delta) {
+            // Both encodings are rendered here, once; the session's writer
+            // sends whichever its client negotiated.
+            let mut output = SessionOutput::new(session.session_id, msg.to_json());
+            if let Some(bytes) = msg.to_binary() {
+                output = output.with_binary(bytes);
+            }
+            let _ = output_tx.send(output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use space::model::SpaceModel;
+
+    #[test]
+    fn name_cache_drops_removed_and_despawned_names() {
+        let mut ecs = EcsAdapter::new();
+        let mut space = space::GridSpace::new(GridConfig::default());
+        let renamed = ecs.spawn_entity();
+        let unnamed = ecs.spawn_entity();
+        let gone = ecs.spawn_entity();
+        for (i, e) in [renamed, unnamed, gone].into_iter().enumerate() {
+            ecs.set_component(e, Name(format!("n{}", i))).unwrap();
+            space.set_position(e, i as i32, 0).unwrap();
+        }
+        ecs.clear_change_tracking();
+        let mut aoi = AoiTracker::new(5, 0);
+        for e in [renamed, unnamed, gone] {
+            aoi.names.insert(e, Some("cached".into()));
+        }
+
+        ecs.set_component(renamed, Name("new".into())).unwrap();
+        ecs.remove_component::<Name>(unnamed).unwrap();
+        space.remove_entity(gone).unwrap();
+        ecs.despawn_entity(gone).unwrap();
+        aoi.invalidate_names(&ecs, &space);
+
+        assert!(aoi.names.is_empty());
     }
 }
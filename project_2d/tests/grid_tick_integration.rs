@@ -6,9 +6,16 @@ use space::grid_space::{cell_to_entity_id, GridConfig, GridPos, GridSpace};
 use space::SpaceModel;
 
 fn make_tick_loop() -> TickLoop<GridSpace> {
+    make_tick_loop_until(0)
+}
+
+/// Non-realtime loop, so `run` steps `max_ticks` ticks without sleeping.
+fn make_tick_loop_until(max_ticks: u64) -> TickLoop<GridSpace> {
     let config = TickConfig {
         tps: 30,
-        max_ticks: 0,
+        max_ticks,
+        realtime: false,
+        ..Default::default()
     };
     let grid = GridSpace::new(GridConfig {
         width: 20,
@@ -30,11 +37,10 @@ fn grid_tick_loop_basic_step() {
 
 #[test]
 fn grid_tick_loop_multiple_steps() {
-    let mut tick_loop = make_tick_loop();
-    for i in 1..=5 {
-        let metrics = tick_loop.step();
-        assert_eq!(metrics.tick_number, i);
-    }
+    let mut tick_loop = make_tick_loop_until(5);
+    let metrics = tick_loop.run();
+    let ticks: Vec<u64> = metrics.iter().map(|m| m.tick_number).collect();
+    assert_eq!(ticks, vec![1, 2, 3, 4, 5]);
     assert_eq!(tick_loop.current_tick, 5);
 }
 
@@ -43,6 +49,8 @@ fn grid_tick_loop_run_limited() {
     let config = TickConfig {
         tps: 1000, // fast for testing
         max_ticks: 10,
+        realtime: false,
+        ..Default::default()
     };
     let grid = GridSpace::new(GridConfig {
         width: 10,
@@ -83,7 +91,7 @@ fn move_entity_via_engine_command() {
 
 #[test]
 fn spawn_place_step_integration() {
-    let mut tick_loop = make_tick_loop();
+    let mut tick_loop = make_tick_loop_until(3);
 
     // Spawn entity via ECS
     let e1 = tick_loop.ecs.spawn_entity();
@@ -94,10 +102,9 @@ fn spawn_place_step_integration() {
     assert_eq!(tick_loop.space.entity_count(), 1);
     assert_eq!(tick_loop.space.entity_room(e1), Some(cell));
 
-    // Step a few times — entity stays put
-    for _ in 0..3 {
-        tick_loop.step();
-    }
+    // Run a few ticks — entity stays put
+    tick_loop.run();
+    assert_eq!(tick_loop.current_tick, 3);
 
     assert_eq!(tick_loop.space.get_position(e1), Some(GridPos::new(10, 10)));
     assert_eq!(tick_loop.ecs.entity_count(), 1);
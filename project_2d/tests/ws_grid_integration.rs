@@ -8,9 +8,11 @@ use tokio_tungstenite::tungstenite::Message;
 
 use ecs_adapter::EntityId;
 use engine_core::tick::{TickConfig, TickLoop};
-use project_2d::components::Name;
+use project_2d::components::{Appearance, Name};
 use net::channels::{NetToTick, OutputTx, PlayerRx};
-use net::protocol::{EntityMovedWire, EntityWire, GridConfigWire, ServerMessage};
+use net::protocol::{
+    DeltaPacer, EntityMovedWire, EntityWire, GridConfigWire, ServerClock, ServerMessage,
+};
 use session::{SessionId, SessionManager, SessionOutput, SessionState};
 use space::grid_space::{GridConfig, GridPos};
 use space::{GridSpace, SpaceModel};
@@ -20,18 +22,28 @@ const AOI_RADIUS: u32 = 32;
 /// Per-session AOI tracking state (mirrors main.rs AoiTracker).
 struct TestAoiState {
     known: BTreeMap<EntityId, GridPos>,
+    pacer: DeltaPacer,
 }
 
 struct TestAoiTracker {
     sessions: BTreeMap<SessionId, TestAoiState>,
     radius: u32,
+    keepalive_ticks: u64,
+    clock: ServerClock,
 }
 
 impl TestAoiTracker {
+    /// Tracker with delta coalescing disabled: every tick sends a delta.
     fn new(radius: u32) -> Self {
+        Self::with_keepalive(radius, 0)
+    }
+
+    fn with_keepalive(radius: u32, keepalive_ticks: u64) -> Self {
         Self {
             sessions: BTreeMap::new(),
             radius,
+            keepalive_ticks,
+            clock: ServerClock::new(),
         }
     }
 
@@ -40,6 +52,7 @@ impl TestAoiTracker {
             session_id,
             TestAoiState {
                 known: BTreeMap::new(),
+                pacer: DeltaPacer::new(self.keepalive_ticks),
             },
         );
     }
@@ -47,6 +60,12 @@ impl TestAoiTracker {
     fn on_session_removed(&mut self, session_id: SessionId) {
         self.sessions.remove(&session_id);
     }
+
+    fn resync(&mut self, session_id: SessionId) {
+        if let Some(state) = self.sessions.get_mut(&session_id) {
+            state.known.clear();
+        }
+    }
 }
 
 /// Run one grid tick: process network messages, step engine, broadcast AOI delta.
@@ -61,8 +80,8 @@ fn run_grid_tick(
     // Process network messages
     while let Ok(msg) = player_rx.try_recv() {
         match msg {
-            NetToTick::NewConnection { session_id } => {
-                sessions.create_session_with_id(session_id);
+            NetToTick::NewConnection { session_id, connection } => {
+                sessions.create_session_with_connection(session_id, connection);
             }
             NetToTick::PlayerInput { session_id, line } => {
                 let state = match sessions.get_session(session_id) {
@@ -92,6 +111,7 @@ fn run_grid_tick(
                             session_id: session_id.0,
                             entity_id: entity.to_u64(),
                             tick: tick_loop.current_tick,
+                            server_time_ms: aoi.clock.now_ms(),
                             grid_config: GridConfigWire {
                                 width: grid_config.width,
                                 height: grid_config.height,
@@ -101,7 +121,7 @@ fn run_grid_tick(
                         };
                         let _ = output_tx.send(SessionOutput::new(
                             session_id,
-                            serde_json::to_string(&welcome).unwrap(),
+                            welcome.to_json(),
                         ));
                     }
                     SessionState::Playing => {
@@ -114,10 +134,14 @@ fn run_grid_tick(
                             let pong = ServerMessage::Pong;
                             let _ = output_tx.send(SessionOutput::new(
                                 session_id,
-                                serde_json::to_string(&pong).unwrap(),
+                                pong.to_json(),
                             ));
                             continue;
                         }
+                        if line == "__resync" {
+                            aoi.resync(session_id);
+                            continue;
+                        }
                         if let Some(rest) = line.strip_prefix("__grid_move ") {
                             let parts: Vec<&str> = rest.split_whitespace().collect();
                             if parts.len() == 2 {
@@ -205,12 +229,16 @@ fn run_grid_tick(
                                 .map(|n| n.0.clone())
                         })
                         .clone();
+                    let appearance = tick_loop.ecs.get_component::<Appearance>(eid).ok();
                     entered.push(EntityWire {
                         id: eid.to_u64(),
                         x: pos.x,
                         y: pos.y,
                         name,
                         is_self: eid == self_entity,
+                        facing: tick_loop.space.facing(eid).unwrap_or_default().as_str(),
+                        sprite: appearance.map(|a| a.sprite.clone()),
+                        color: appearance.and_then(|a| a.color.clone()),
                     });
                 }
                 Some(old_pos) => {
@@ -219,6 +247,7 @@ fn run_grid_tick(
                             id: eid.to_u64(),
                             x: pos.x,
                             y: pos.y,
+                            facing: tick_loop.space.facing(eid).unwrap_or_default().as_str(),
                         });
                     }
                 }
@@ -229,14 +258,17 @@ fn run_grid_tick(
 
         let delta = ServerMessage::StateDelta {
             tick: tick_loop.current_tick,
+            server_time_ms: aoi.clock.now_ms(),
             entered,
             moved,
             left,
         };
-        let _ = output_tx.send(SessionOutput::new(
-            session.session_id,
-            serde_json::to_string(&delta).unwrap(),
-        ));
+        if let Some(msg) = aoi_state.pacer.pace(tick_loop.current_tick, delta) {
+            let _ = output_tx.send(SessionOutput::new(
+                session.session_id,
+                msg.to_json(),
+            ));
+        }
     }
 }
 
@@ -277,6 +309,7 @@ async fn ws_connect_and_welcome() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -301,7 +334,7 @@ async fn ws_connect_and_welcome() {
 
     // Send connect message
     let connect_msg = serde_json::to_string(&serde_json::json!({"type":"connect","name":"Alice"})).unwrap();
-    ws.send(Message::Text(connect_msg.into())).await.unwrap();
+    ws.send(Message::Text(connect_msg)).await.unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     // Process login
@@ -320,6 +353,7 @@ async fn ws_connect_and_welcome() {
     let text = msg.into_text().unwrap();
     let server_msg: serde_json::Value = serde_json::from_str(&text).unwrap();
     assert_eq!(server_msg["type"], "welcome");
+    assert_eq!(server_msg["v"], net::protocol::PROTOCOL_VERSION);
     assert!(server_msg["session_id"].as_u64().unwrap() >= 1_000_000);
     assert!(server_msg["entity_id"].is_u64());
     assert_eq!(server_msg["grid_config"]["width"], 100);
@@ -373,6 +407,7 @@ async fn ws_move_changes_position() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -395,7 +430,7 @@ async fn ws_move_changes_position() {
 
     // Connect
     let connect = serde_json::to_string(&serde_json::json!({"type":"connect","name":"Bob"})).unwrap();
-    ws.send(Message::Text(connect.into())).await.unwrap();
+    ws.send(Message::Text(connect)).await.unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     run_grid_tick(
@@ -414,7 +449,7 @@ async fn ws_move_changes_position() {
 
     // Send move command (dx=1, dy=0)
     let move_msg = serde_json::to_string(&serde_json::json!({"type":"move","dx":1,"dy":0})).unwrap();
-    ws.send(Message::Text(move_msg.into())).await.unwrap();
+    ws.send(Message::Text(move_msg)).await.unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     run_grid_tick(
@@ -436,6 +471,7 @@ async fn ws_move_changes_position() {
     assert_eq!(moved.len(), 1);
     assert_eq!(moved[0]["x"], 51); // moved from 50 to 51
     assert_eq!(moved[0]["y"], 50);
+    assert_eq!(moved[0]["facing"], "east");
 
     ws.close(None).await.unwrap();
 }
@@ -474,6 +510,7 @@ async fn ws_disconnect_removes_entity() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -494,7 +531,7 @@ async fn ws_disconnect_removes_entity() {
     );
     tokio::time::sleep(Duration::from_millis(50)).await;
 
-    ws1.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"P1"})).unwrap().into()))
+    ws1.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"P1"})).unwrap()))
         .await
         .unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -535,7 +572,7 @@ async fn ws_disconnect_removes_entity() {
     assert_eq!(p1_interim_val["type"], "state_delta");
 
     // Now send P2's connect (login) message
-    ws2.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"P2"})).unwrap().into()))
+    ws2.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"P2"})).unwrap()))
         .await
         .unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -620,6 +657,7 @@ async fn ws_ping_pong() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -640,7 +678,7 @@ async fn ws_ping_pong() {
     );
     tokio::time::sleep(Duration::from_millis(50)).await;
 
-    ws.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"PingPlayer"})).unwrap().into()))
+    ws.send(Message::Text(serde_json::to_string(&serde_json::json!({"type":"connect","name":"PingPlayer"})).unwrap()))
         .await
         .unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -660,7 +698,7 @@ async fn ws_ping_pong() {
     let _delta = ws.next().await.unwrap().unwrap();
 
     // Send ping
-    ws.send(Message::Text(r#"{"type":"ping"}"#.to_string().into()))
+    ws.send(Message::Text(r#"{"type":"ping"}"#.to_string()))
         .await
         .unwrap();
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -699,6 +737,7 @@ async fn ws_aoi_filters_distant_entity() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -763,6 +802,73 @@ async fn ws_aoi_filters_distant_entity() {
     assert!(!entered_ids.contains(&distant_entity.to_u64()));
 }
 
+#[tokio::test]
+async fn ws_entered_carries_appearance() {
+    let (_player_tx, mut player_rx) = mpsc::unbounded_channel();
+    let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+
+    let grid_config = GridConfig {
+        width: 256,
+        height: 256,
+        origin_x: 0,
+        origin_y: 0,
+    };
+    let config = TickConfig {
+        tps: 10,
+        max_ticks: 0,
+        ..Default::default()
+    };
+    let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
+    let mut sessions = SessionManager::new();
+    let mut aoi = TestAoiTracker::new(AOI_RADIUS);
+
+    let session_id = SessionId(1_000_010);
+    sessions.create_session_with_id(session_id);
+    let player_entity = tick_loop.ecs.spawn_entity();
+    tick_loop.space.set_position(player_entity, 128, 128).unwrap();
+    sessions.bind_entity(session_id, player_entity);
+    aoi.on_session_playing(session_id);
+
+    let knight = tick_loop.ecs.spawn_entity();
+    tick_loop
+        .ecs
+        .set_component(
+            knight,
+            Appearance {
+                sprite: "knight".to_string(),
+                color: Some("#c0392b".to_string()),
+            },
+        )
+        .unwrap();
+    tick_loop.space.set_position(knight, 129, 128).unwrap();
+
+    run_grid_tick(
+        &mut tick_loop,
+        &mut sessions,
+        &mut player_rx,
+        &output_tx,
+        &grid_config,
+        &mut aoi,
+    );
+
+    let out = output_rx.try_recv().unwrap();
+    let delta: serde_json::Value = serde_json::from_str(&out.text).unwrap();
+    let entered = delta["entered"].as_array().unwrap();
+    let find = |id: EntityId| {
+        entered
+            .iter()
+            .find(|e| e["id"].as_u64() == Some(id.to_u64()))
+            .unwrap()
+    };
+
+    assert_eq!(find(knight)["sprite"], "knight");
+    assert_eq!(find(knight)["color"], "#c0392b");
+    // No Appearance component: fields are present but null
+    assert!(find(player_entity)["sprite"].is_null());
+    assert!(find(player_entity)["color"].is_null());
+    assert!(find(player_entity).as_object().unwrap().contains_key("sprite"));
+}
+
 #[tokio::test]
 async fn ws_aoi_enter_leave_on_move() {
     // Test: when player moves, entities enter/leave AOI correctly
@@ -778,6 +884,7 @@ async fn ws_aoi_enter_leave_on_move() {
     let config = TickConfig {
         tps: 10,
         max_ticks: 0,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
     let mut sessions = SessionManager::new();
@@ -878,3 +985,249 @@ async fn ws_aoi_enter_leave_on_move() {
     let moved3 = delta3["moved"].as_array().unwrap();
     assert!(moved3.iter().any(|m| m["id"].as_u64().unwrap() == player_entity.to_u64()));
 }
+
+#[tokio::test]
+async fn ws_rejects_unsupported_protocol_version() {
+    let (player_tx, mut player_rx) = mpsc::unbounded_channel();
+    let (_output_tx, output_rx) = mpsc::unbounded_channel();
+    let (register_tx, register_rx) = mpsc::unbounded_channel();
+    let (unregister_tx, unregister_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(net::output_router::run_output_router(
+        output_rx,
+        register_rx,
+        unregister_rx,
+    ));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    tokio::spawn(net::ws_server::run_ws_server(
+        addr.to_string(),
+        player_tx,
+        register_tx,
+        unregister_tx,
+    ));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let url = format!("ws://{}", addr);
+    let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    ws.send(Message::Text(r#"{"v":99,"type":"connect","name":"Future"}"#.to_string()))
+        .await
+        .unwrap();
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), ws.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_str(&msg.into_text().unwrap()).unwrap();
+    assert_eq!(val["type"], "error");
+    assert!(val["message"]
+        .as_str()
+        .unwrap()
+        .contains("unsupported protocol version 99"));
+
+    // The rejected login never reaches the tick thread
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut saw_input = false;
+    while let Ok(msg) = player_rx.try_recv() {
+        if matches!(msg, NetToTick::PlayerInput { .. }) {
+            saw_input = true;
+        }
+    }
+    assert!(!saw_input);
+}
+
+#[tokio::test]
+async fn ws_idle_ticks_coalesce_deltas() {
+    let (player_tx, mut player_rx) = mpsc::unbounded_channel();
+    let (output_tx, output_rx) = mpsc::unbounded_channel();
+    let (register_tx, register_rx) = mpsc::unbounded_channel();
+    let (unregister_tx, unregister_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(net::output_router::run_output_router(
+        output_rx,
+        register_rx,
+        unregister_rx,
+    ));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    tokio::spawn(net::ws_server::run_ws_server(
+        addr.to_string(),
+        player_tx,
+        register_tx,
+        unregister_tx,
+    ));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let grid_config = GridConfig {
+        width: 100,
+        height: 100,
+        origin_x: 0,
+        origin_y: 0,
+    };
+    let config = TickConfig {
+        tps: 10,
+        max_ticks: 0,
+        ..Default::default()
+    };
+    let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
+    let mut sessions = SessionManager::new();
+    let mut aoi = TestAoiTracker::with_keepalive(AOI_RADIUS, 100);
+
+    let url = format!("ws://{}", addr);
+    let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    run_grid_tick(
+        &mut tick_loop,
+        &mut sessions,
+        &mut player_rx,
+        &output_tx,
+        &grid_config,
+        &mut aoi,
+    );
+
+    let connect = serde_json::to_string(&serde_json::json!({"type":"connect","name":"Idle"})).unwrap();
+    ws.send(Message::Text(connect)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    run_grid_tick(
+        &mut tick_loop,
+        &mut sessions,
+        &mut player_rx,
+        &output_tx,
+        &grid_config,
+        &mut aoi,
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Welcome + initial full delta
+    let welcome: serde_json::Value =
+        serde_json::from_str(&ws.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+    assert!(welcome["server_time_ms"].is_u64());
+    let delta1: serde_json::Value =
+        serde_json::from_str(&ws.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+    assert_eq!(delta1["type"], "state_delta");
+    assert_eq!(delta1["entered"].as_array().unwrap().len(), 1);
+
+    // Idle ticks: nothing should be sent
+    for _ in 0..5 {
+        run_grid_tick(
+            &mut tick_loop,
+            &mut sessions,
+            &mut player_rx,
+            &output_tx,
+            &grid_config,
+            &mut aoi,
+        );
+    }
+    let idle = tokio::time::timeout(Duration::from_millis(200), ws.next()).await;
+    assert!(idle.is_err(), "idle ticks should not produce state_delta");
+
+    // Movement produces a delta again
+    let move_msg = serde_json::to_string(&serde_json::json!({"type":"move","dx":1,"dy":0})).unwrap();
+    ws.send(Message::Text(move_msg)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    run_grid_tick(
+        &mut tick_loop,
+        &mut sessions,
+        &mut player_rx,
+        &output_tx,
+        &grid_config,
+        &mut aoi,
+    );
+
+    let delta2: serde_json::Value =
+        serde_json::from_str(&ws.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+    assert_eq!(delta2["type"], "state_delta");
+    // Server clock advanced with the idle ticks in between
+    assert!(
+        delta2["server_time_ms"].as_u64().unwrap() > delta1["server_time_ms"].as_u64().unwrap()
+    );
+    assert_eq!(delta2["moved"].as_array().unwrap().len(), 1);
+
+    ws.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn ws_resync_resends_full_aoi() {
+    // Test: after a resync request, the next delta re-reports every in-AOI entity as entered
+    let (player_tx, mut player_rx) = mpsc::unbounded_channel();
+    let (output_tx, mut output_rx) = mpsc::unbounded_channel();
+
+    let grid_config = GridConfig {
+        width: 256,
+        height: 256,
+        origin_x: 0,
+        origin_y: 0,
+    };
+    let config = TickConfig {
+        tps: 10,
+        max_ticks: 0,
+        ..Default::default()
+    };
+    let mut tick_loop = TickLoop::new(config, GridSpace::new(grid_config.clone()));
+    let mut sessions = SessionManager::new();
+    let mut aoi = TestAoiTracker::new(AOI_RADIUS);
+
+    let session_id = SessionId(1_000_001);
+    sessions.create_session_with_id(session_id);
+    let player_entity = tick_loop.ecs.spawn_entity();
+    tick_loop.space.set_position(player_entity, 128, 128).unwrap();
+    sessions.bind_entity(session_id, player_entity);
+    aoi.on_session_playing(session_id);
+
+    let near_entity = tick_loop.ecs.spawn_entity();
+    tick_loop.space.set_position(near_entity, 130, 130).unwrap();
+    let distant_entity = tick_loop.ecs.spawn_entity();
+    tick_loop.space.set_position(distant_entity, 200, 200).unwrap();
+
+    let mut tick_and_collect = |tick_loop: &mut TickLoop<GridSpace>,
+                                sessions: &mut SessionManager,
+                                aoi: &mut TestAoiTracker| {
+        run_grid_tick(tick_loop, sessions, &mut player_rx, &output_tx, &grid_config, aoi);
+        let mut deltas = Vec::new();
+        while let Ok(out) = output_rx.try_recv() {
+            deltas.push(serde_json::from_str::<serde_json::Value>(&out.text).unwrap());
+        }
+        deltas
+    };
+
+    // Initial delta: everything in AOI enters
+    let deltas = tick_and_collect(&mut tick_loop, &mut sessions, &mut aoi);
+    assert_eq!(deltas[0]["entered"].as_array().unwrap().len(), 2);
+
+    // Nothing changed: the following delta carries no entities
+    let deltas = tick_and_collect(&mut tick_loop, &mut sessions, &mut aoi);
+    assert!(deltas[0].get("entered").is_none());
+
+    // Resync: the next delta re-includes every in-AOI entity
+    player_tx
+        .send(NetToTick::PlayerInput {
+            session_id,
+            line: "__resync".to_string(),
+        })
+        .unwrap();
+    let deltas = tick_and_collect(&mut tick_loop, &mut sessions, &mut aoi);
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0]["type"], "state_delta");
+    let entered_ids: Vec<u64> = deltas[0]["entered"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(entered_ids.len(), 2);
+    assert!(entered_ids.contains(&player_entity.to_u64()));
+    assert!(entered_ids.contains(&near_entity.to_u64()));
+    assert!(!entered_ids.contains(&distant_entity.to_u64()));
+}
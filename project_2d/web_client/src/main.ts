@@ -79,8 +79,12 @@ connection.onMessage = (msg: ServerMessage) => {
       renderer.syncEntities(state);
       break;
 
+    case "keepalive":
+      state.tick = msg.tick;
+      break;
+
     case "error":
-      console.error("Server error:", msg.message);
+      console.error(`Server error [${msg.code}]:`, msg.message);
       break;
 
     case "pong":
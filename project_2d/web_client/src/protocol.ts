@@ -1,3 +1,6 @@
+// Protocol version sent as `v` on every client message (server echoes its own `v`).
+export const PROTOCOL_VERSION = 1;
+
 // Client → Server messages
 
 export interface ConnectMessage {
@@ -21,11 +24,18 @@ export interface PingMessage {
   type: "ping";
 }
 
+// Ask for the full AOI again; the next state_delta lists every visible entity
+// as entered, so drop local entity state before sending.
+export interface ResyncMessage {
+  type: "resync";
+}
+
 export type ClientMessage =
   | ConnectMessage
   | MoveMessage
   | ActionMessage
-  | PingMessage;
+  | PingMessage
+  | ResyncMessage;
 
 // Server → Client messages
 
@@ -42,12 +52,18 @@ export interface EntityWire {
   y: number;
   name?: string;
   is_self: boolean;
+  facing: Facing;
+  sprite: string | null;
+  color: string | null;
 }
 
+export type Facing = "north" | "south" | "east" | "west";
+
 export interface EntityMovedWire {
   id: number;
   x: number;
   y: number;
+  facing: Facing;
 }
 
 export interface WelcomeMessage {
@@ -55,19 +71,38 @@ export interface WelcomeMessage {
   session_id: number;
   entity_id: number;
   tick: number;
+  /** Monotonic server clock in ms (absent from older servers). */
+  server_time_ms?: number;
   grid_config: GridConfig;
 }
 
 export interface StateDeltaMessage {
   type: "state_delta";
   tick: number;
+  /** Monotonic server clock in ms (absent from older servers). */
+  server_time_ms?: number;
   entered?: EntityWire[];
   moved?: EntityMovedWire[];
   left?: number[];
 }
 
+export interface KeepaliveMessage {
+  type: "keepalive";
+  tick: number;
+}
+
+export type ErrorCode =
+  | "out_of_bounds"
+  | "invalid_move"
+  | "not_authenticated"
+  | "rate_limited"
+  | "unsupported_version"
+  | "shutting_down"
+  | "internal";
+
 export interface ErrorMessage {
   type: "error";
+  code: ErrorCode;
   message: string;
 }
 
@@ -78,5 +113,6 @@ export interface PongMessage {
 export type ServerMessage =
   | WelcomeMessage
   | StateDeltaMessage
+  | KeepaliveMessage
   | ErrorMessage
   | PongMessage;
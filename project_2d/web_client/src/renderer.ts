@@ -80,7 +80,7 @@ export class GameRenderer {
     const container = new Container();
 
     const circle = new Graphics();
-    const color = ent.isSelf ? SELF_COLOR : OTHER_COLOR;
+    const color = ent.color ?? (ent.isSelf ? SELF_COLOR : OTHER_COLOR);
     circle.circle(0, 0, ENTITY_RADIUS);
     circle.fill({ color });
 
@@ -2,6 +2,7 @@ import type {
   GridConfig,
   EntityWire,
   EntityMovedWire,
+  Facing,
 } from "./protocol";
 
 export interface EntityState {
@@ -10,6 +11,9 @@ export interface EntityState {
   y: number;
   name: string;
   isSelf: boolean;
+  facing: Facing;
+  sprite: string | null;
+  color: string | null;
   // Rendering interpolation positions (float)
   renderX: number;
   renderY: number;
@@ -40,6 +44,9 @@ export class GameState {
         y: e.y,
         name: e.name ?? `Entity ${e.id}`,
         isSelf: e.is_self,
+        facing: e.facing,
+        sprite: e.sprite ?? null,
+        color: e.color ?? null,
         renderX: e.x,
         renderY: e.y,
       });
@@ -52,6 +59,7 @@ export class GameState {
       if (ent) {
         ent.x = m.x;
         ent.y = m.y;
+        ent.facing = m.facing;
       }
     }
   }
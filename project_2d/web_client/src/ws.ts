@@ -1,3 +1,4 @@
+import { PROTOCOL_VERSION } from "./protocol";
 import type { ClientMessage, ServerMessage } from "./protocol";
 
 export class GameConnection {
@@ -8,7 +9,7 @@ export class GameConnection {
   onOpen: (() => void) | null = null;
 
   connect(url: string): void {
-    this.ws = new WebSocket(url);
+    this.ws = new WebSocket(url, "project-g.v1");
 
     this.ws.onopen = () => {
       this.onOpen?.();
@@ -35,7 +36,7 @@ export class GameConnection {
 
   send(msg: ClientMessage): void {
     if (this.ws && this.ws.readyState === WebSocket.OPEN) {
-      this.ws.send(JSON.stringify(msg));
+      this.ws.send(JSON.stringify({ v: PROTOCOL_VERSION, ...msg }));
     }
   }
 
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use ecs_adapter::Component;
 use serde::{Deserialize, Serialize};
 
@@ -36,6 +38,27 @@ impl Default for Inventory {
     }
 }
 
+/// Equipped items keyed by slot name ("weapon", "armor", ...).
+/// Stat bonuses are applied by scripts on equip/unequip.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Equipment {
+    pub slots: BTreeMap<String, ecs_adapter::EntityId>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for Equipment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Component, Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct PlayerTag;
 
@@ -72,8 +95,9 @@ pub struct Mana {
 #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Experience(pub i64);
 
-#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum CharacterPosition {
+    #[default]
     Standing,
     Sitting,
     Resting,
@@ -82,28 +106,37 @@ pub enum CharacterPosition {
     Incapacitated,
 }
 
-impl Default for CharacterPosition {
-    fn default() -> Self {
-        Self::Standing
-    }
-}
-
 #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Skills {
     pub learned: Vec<String>,
 }
 
+/// Most characters (players and NPCs; items don't count) a room holds.
+/// Rooms without it are unlimited.
 #[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Gold(pub i64);
+pub struct RoomCapacity(pub u32);
+
+/// A character's gold, behind the `ecs:money`/`ecs:add_money`/
+/// `ecs:spend_money` script helpers. Scripts should go through those helpers
+/// rather than `ecs:set`, which does no balance checks. Replaces the old
+/// `Gold` component; see `persistence_setup::migrate_legacy_gold`.
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Wallet {
+    pub copper: i64,
+}
 
 /// Generic ECS component holding arbitrary JSON data.
 /// Custom Serialize/Deserialize implementation to work with bincode:
 /// bincode stores the JSON as a string, then deserializes back.
+/// Human-readable formats (JSON export) get the value as-is.
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct GameData(pub serde_json::Value);
 
 impl Serialize for GameData {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return self.0.serialize(serializer);
+        }
         let json_str = serde_json::to_string(&self.0).map_err(serde::ser::Error::custom)?;
         serializer.serialize_str(&json_str)
     }
@@ -111,6 +144,9 @@ impl Serialize for GameData {
 
 impl<'de> Deserialize<'de> for GameData {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return serde_json::Value::deserialize(deserializer).map(GameData);
+        }
         let s = String::deserialize(deserializer)?;
         let value: serde_json::Value =
             serde_json::from_str(&s).map_err(serde::de::Error::custom)?;
@@ -130,6 +166,16 @@ mod tests {
         assert_eq!(name, decoded);
     }
 
+    #[test]
+    fn description_serde_roundtrip() {
+        let desc = Description("녹슨 단검을 든 고블린".to_string());
+        let bytes = bincode::serialize(&desc).unwrap();
+        assert_eq!(bincode::deserialize::<Description>(&bytes).unwrap(), desc);
+        let json = serde_json::to_value(&desc).unwrap();
+        assert_eq!(json, serde_json::json!("녹슨 단검을 든 고블린"));
+        assert_eq!(serde_json::from_value::<Description>(json).unwrap(), desc);
+    }
+
     #[test]
     fn health_bincode_roundtrip() {
         let hp = Health { current: 80, max: 100 };
@@ -151,6 +197,18 @@ mod tests {
         assert_eq!(inv, decoded);
     }
 
+    #[test]
+    fn equipment_bincode_roundtrip() {
+        let mut eq = Equipment::new();
+        eq.slots
+            .insert("weapon".to_string(), ecs_adapter::EntityId::new(3, 1));
+        eq.slots
+            .insert("armor".to_string(), ecs_adapter::EntityId::new(7, 0));
+        let bytes = bincode::serialize(&eq).unwrap();
+        let decoded: Equipment = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(eq, decoded);
+    }
+
     #[test]
     fn combat_target_bincode_roundtrip() {
         let ct = CombatTarget(ecs_adapter::EntityId::new(42, 1));
@@ -216,11 +274,11 @@ mod tests {
     }
 
     #[test]
-    fn gold_bincode_roundtrip() {
-        let gold = Gold(150);
-        let bytes = bincode::serialize(&gold).unwrap();
-        let decoded: Gold = bincode::deserialize(&bytes).unwrap();
-        assert_eq!(gold, decoded);
+    fn wallet_bincode_roundtrip() {
+        let wallet = Wallet { copper: 1234 };
+        let bytes = bincode::serialize(&wallet).unwrap();
+        let decoded: Wallet = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(wallet, decoded);
     }
 
     #[test]
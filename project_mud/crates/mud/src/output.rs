@@ -1 +1,314 @@
-pub use session::{SessionId, SessionOutput};
+use std::collections::BTreeMap;
+
+use unicode_width::UnicodeWidthChar;
+
+pub use session::{SessionId, SessionOutput, DEFAULT_LINE_WIDTH};
+
+use scripting::ContentRegistry;
+use session::SessionManager;
+
+/// Display width of `text` in terminal cells. CJK characters count as two
+/// cells; ANSI escape sequences (colors from scripts) count as zero.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            width += c.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+/// Consume the rest of an escape sequence after ESC. CSI sequences
+/// (`ESC [ ... final`) end at the first byte in 0x40..=0x7E.
+fn skip_escape(chars: &mut std::str::Chars<'_>) {
+    if chars.next() != Some('[') {
+        return;
+    }
+    for c in chars.by_ref() {
+        if ('\x40'..='\x7e').contains(&c) {
+            break;
+        }
+    }
+}
+
+/// Word-wrap `text` to `width` terminal cells.
+///
+/// Explicit newlines and the spacing between words are kept; spaces at a
+/// break point are dropped. Words wider than a full line are split between
+/// characters. A width of 0 disables wrapping.
+pub fn wrap(text: &str, width: usize) -> String {
+    if width == 0 {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let (line, cr) = match line.strip_suffix('\r') {
+            Some(l) => (l, "\r"),
+            None => (line, ""),
+        };
+        wrap_line(line, width, &mut out);
+        out.push_str(cr);
+    }
+    out
+}
+
+fn wrap_line(line: &str, width: usize, out: &mut String) {
+    if display_width(line) <= width {
+        out.push_str(line);
+        return;
+    }
+    let mut col = 0;
+    let mut rest = line;
+    while !rest.is_empty() {
+        // Spacing before the word is kept as-is unless the line breaks there
+        let gap = rest.len() - rest.trim_start_matches(' ').len();
+        rest = &rest[gap..];
+        let end = rest.find(' ').unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(end);
+        rest = tail;
+        if word.is_empty() {
+            break;
+        }
+        let w = display_width(word);
+        if col + gap + w <= width {
+            out.push_str(&" ".repeat(gap));
+            out.push_str(word);
+            col += gap + w;
+        } else if w <= width {
+            out.push('\n');
+            out.push_str(word);
+            col = w;
+        } else {
+            if col > 0 && col + 1 < width {
+                out.push(' ');
+                col += 1;
+            }
+            col = push_split_word(word, width, col, out);
+        }
+    }
+}
+
+/// Emit an over-long word character by character, breaking lines as needed.
+/// Returns the column after the last character.
+fn push_split_word(word: &str, width: usize, mut col: usize, out: &mut String) -> usize {
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Keep escape sequences intact
+            let rest = chars.as_str();
+            let mut probe = chars.clone();
+            skip_escape(&mut probe);
+            let consumed = rest.len() - probe.as_str().len();
+            out.push(c);
+            out.push_str(&rest[..consumed]);
+            chars = probe;
+            continue;
+        }
+        let cw = c.width().unwrap_or(0);
+        if col > 0 && col + cw > width {
+            out.push('\n');
+            col = 0;
+        }
+        out.push(c);
+        col += cw;
+    }
+    col
+}
+
+/// Wrap an output's text to its session's `line_width`. Outputs for unknown
+/// sessions and structured outputs are returned unchanged.
+pub fn format_for_session(sessions: &SessionManager, mut output: SessionOutput) -> SessionOutput {
+    if output.structured {
+        return output;
+    }
+    if let Some(session) = sessions.get_session(output.session_id) {
+        output.text = wrap(&output.text, session.line_width);
+    }
+    output
+}
+
+/// Built-in message templates, used when the `messages` content collection
+/// does not define a template of the same name.
+const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    ("room_leave", "{name}님이 {dir}쪽으로 떠났습니다."),
+    ("room_arrive", "{name}님이 {dir}쪽에서 도착했습니다."),
+];
+
+/// Player-facing message templates with `{key}` placeholders.
+///
+/// Builders override or add templates in `content/messages.json`, one entry
+/// per template: `{"id": "room_leave", "text": "..."}`.
+#[derive(Debug, Clone, Default)]
+pub struct MessageTemplates {
+    templates: BTreeMap<String, String>,
+}
+
+impl MessageTemplates {
+    /// Only the built-in templates.
+    pub fn new() -> Self {
+        let templates = DEFAULT_TEMPLATES
+            .iter()
+            .map(|(name, text)| (name.to_string(), text.to_string()))
+            .collect();
+        Self { templates }
+    }
+
+    /// Built-in templates overlaid with the `messages` content collection.
+    /// Entries without a string `text` field are ignored.
+    pub fn from_content(registry: &ContentRegistry) -> Self {
+        let mut templates = Self::new();
+        for (id, def) in registry.all("messages").into_iter().flatten() {
+            if let Some(text) = def.get("text").and_then(|t| t.as_str()) {
+                templates.insert(id, text);
+            }
+        }
+        templates
+    }
+
+    pub fn insert(&mut self, name: &str, text: &str) {
+        self.templates.insert(name.to_string(), text.to_string());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.templates.get(name).map(String::as_str)
+    }
+
+    /// Render template `name` with `params`. An unknown template renders as
+    /// its own name so the gap is visible in game rather than silent.
+    pub fn render_template(&self, name: &str, params: &BTreeMap<String, String>) -> String {
+        match self.get(name) {
+            Some(text) => fill_placeholders(text, params),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Replace each `{key}` in `template` with `params[key]`. Placeholders with
+/// no matching parameter, and unclosed braces, are left as written.
+pub fn fill_placeholders(template: &str, params: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find(['{', '}']) {
+            Some(end) if after[end..].starts_with('}') => {
+                let key = &after[..end];
+                match params.get(key) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            // A brace that never closes before the next one opens is literal
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let p = params(&[("name", "철수"), ("dir", "동")]);
+        assert_eq!(fill_placeholders("{name}님이 {dir}쪽으로", &p), "철수님이 동쪽으로");
+        assert_eq!(fill_placeholders("{name}{name}", &p), "철수철수");
+        assert_eq!(fill_placeholders("no placeholders", &p), "no placeholders");
+    }
+
+    #[test]
+    fn leaves_missing_keys_and_stray_braces() {
+        let p = params(&[("name", "철수")]);
+        assert_eq!(fill_placeholders("{name} -> {target}", &p), "철수 -> {target}");
+        assert_eq!(fill_placeholders("open { brace {name}", &p), "open { brace 철수");
+        assert_eq!(fill_placeholders("{}{name", &p), "{}{name");
+    }
+
+    #[test]
+    fn content_overrides_defaults_and_missing_falls_back() {
+        let dir = std::env::temp_dir().join("mud_message_templates_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("messages.json"),
+            r#"[{"id": "room_leave", "text": "{name} leaves {dir}."}, {"id": "greet", "text": "Hi {name}"}, {"id": "bad"}]"#,
+        )
+        .unwrap();
+        let registry = ContentRegistry::load_dir(&dir).unwrap();
+        let templates = MessageTemplates::from_content(&registry);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let p = params(&[("name", "Bob"), ("dir", "north")]);
+        assert_eq!(templates.render_template("room_leave", &p), "Bob leaves north.");
+        assert_eq!(templates.render_template("greet", &p), "Hi Bob");
+        // Not in content: the built-in default is used.
+        assert_eq!(
+            templates.render_template("room_arrive", &p),
+            "Bob님이 north쪽에서 도착했습니다."
+        );
+        // Unknown anywhere (or without text): rendered as the name.
+        assert_eq!(templates.render_template("bad", &p), "bad");
+        assert_eq!(templates.render_template("nope", &p), "nope");
+    }
+
+    #[test]
+    fn wraps_ascii_on_word_boundaries() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(wrap(text, 10), "The quick\nbrown fox\njumps over\nthe lazy\ndog");
+        assert_eq!(wrap(text, 80), text);
+        assert_eq!(wrap(text, 0), text);
+        assert_eq!(wrap("  indented  gap words", 12), "  indented\ngap words");
+    }
+
+    #[test]
+    fn counts_cjk_as_double_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("마을 광장"), 9);
+        assert_eq!(display_width("\x1b[1;33m광장\x1b[0m"), 4);
+
+        // Each word is 4 cells; two fit in 9 columns, three don't.
+        assert_eq!(wrap("광장 북쪽 남쪽", 9), "광장 북쪽\n남쪽");
+        // A word wider than the line breaks between characters.
+        assert_eq!(wrap("가나다라마", 6), "가나다\n라마");
+    }
+
+    #[test]
+    fn preserves_explicit_newlines() {
+        assert_eq!(wrap("one two\n\nthree four", 7), "one two\n\nthree\nfour");
+        assert_eq!(wrap("a b\r\nc d", 80), "a b\r\nc d");
+        assert_eq!(wrap("\x1b[32mgreen words\x1b[0m here", 11), "\x1b[32mgreen words\x1b[0m\nhere");
+    }
+
+    #[test]
+    fn formats_to_session_width() {
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        sessions.get_session_mut(sid).unwrap().line_width = 5;
+
+        let out = format_for_session(&sessions, SessionOutput::new(sid, "aaa bbb"));
+        assert_eq!(out.text, "aaa\nbbb");
+        let out = format_for_session(&sessions, SessionOutput::new(SessionId(99), "aaa bbb"));
+        assert_eq!(out.text, "aaa bbb");
+    }
+}
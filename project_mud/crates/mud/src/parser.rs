@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::fmt;
 
+use scripting::ContentRegistry;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,6 +37,8 @@ impl fmt::Display for Direction {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PlayerAction {
     Look,
+    /// Examine a specific entity (`goblin look`).
+    LookAt(String),
     Move(Direction),
     Attack(String),
     Get(String),
@@ -49,23 +53,217 @@ pub enum PlayerAction {
     Gold,
     SkillList,
     UseSkill(String),
+    /// Freeform action text, e.g. `emote waves cheerfully`.
+    Emote(String),
+    /// Trail another character between rooms (`hero follow`).
+    Follow(String),
+    Unfollow,
+    /// A content-defined social (`smile`, `goblin bow`), by social id.
+    Social { name: String, target: Option<String> },
     Unknown(String),
 }
 
+/// Command words that trigger socials, mapped to the social's content id.
+/// Built from the `socials` content collection: each entry's `id` plus any
+/// strings in its `aliases` array.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SocialTable {
+    commands: BTreeMap<String, String>,
+}
+
+impl SocialTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_content(registry: &ContentRegistry) -> Self {
+        let mut table = Self::new();
+        for (id, def) in registry.all("socials").into_iter().flatten() {
+            table.insert(id, id);
+            let aliases = def.get("aliases").and_then(|a| a.as_array());
+            for alias in aliases.into_iter().flatten().filter_map(|a| a.as_str()) {
+                table.insert(alias, id);
+            }
+        }
+        table
+    }
+
+    /// Register `command` as a trigger for social `id`. Commands are
+    /// matched case-insensitively.
+    pub fn insert(&mut self, command: &str, id: &str) {
+        self.commands.insert(command.to_lowercase(), id.to_string());
+    }
+
+    /// Social id triggered by a (lowercased) command word.
+    pub fn lookup(&self, command: &str) -> Option<&str> {
+        self.commands.get(command).map(String::as_str)
+    }
+
+    /// Every command word that triggers a social.
+    pub fn commands(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// A built-in command, before its argument is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Look,
+    Move(Direction),
+    Attack,
+    Get,
+    Drop,
+    Inventory,
+    Say,
+    Who,
+    Quit,
+    Help,
+    Status,
+    Gold,
+    Skill,
+    Emote,
+    Follow,
+    Unfollow,
+}
+
+/// Every built-in command word, with aliases and jamo shortcuts. The parser
+/// and [`command_words`] both read this table.
+const COMMANDS: &[(&[&str], Command)] = &[
+    (&["look", "l", "보기", "\u{3142}"], Command::Look), // ㅂ
+    (&["north", "n", "북"], Command::Move(Direction::North)),
+    (&["south", "s", "남"], Command::Move(Direction::South)),
+    (&["east", "e", "동"], Command::Move(Direction::East)),
+    (&["west", "w", "서"], Command::Move(Direction::West)),
+    (&["attack", "kill", "k", "공격", "\u{3131}"], Command::Attack), // ㄱ
+    (&["get", "take", "pick", "줍기", "\u{3148}"], Command::Get), // ㅈ
+    (&["drop", "버리기", "\u{3142}\u{3139}"], Command::Drop), // ㅂㄹ
+    (&["inventory", "inv", "i", "가방", "인벤"], Command::Inventory),
+    (&["say", "말", "\u{3141}"], Command::Say), // ㅁ
+    (&["who", "접속자"], Command::Who),
+    (&["quit", "exit", "종료"], Command::Quit),
+    (&["help", "?", "도움말", "\u{3137}"], Command::Help), // ㄷ
+    (&["status", "stat", "상태"], Command::Status),
+    (&["gold", "골드", "\u{3131}\u{3137}"], Command::Gold), // ㄱㄷ
+    (&["skill", "스킬"], Command::Skill),
+    (&["emote", "em", "감정"], Command::Emote),
+    (&["follow", "fol", "따라가기"], Command::Follow),
+    (&["unfollow", "그만따라가기"], Command::Unfollow),
+];
+
+/// The built-in command triggered by a (lowercased) word.
+fn lookup_command(word: &str) -> Option<Command> {
+    COMMANDS
+        .iter()
+        .find(|(words, _)| words.contains(&word))
+        .map(|&(_, command)| command)
+}
+
+/// Command words offered by [`suggest`] for mistyped commands: every
+/// built-in word and alias, then the social commands. One-character words
+/// and jamo shortcuts are left out: nearly any short typo is one edit away
+/// from them.
+pub fn command_words(socials: &SocialTable) -> Vec<&str> {
+    let is_shortcut = |word: &str| {
+        word.chars().count() < 2 || word.chars().all(|c| ('\u{3131}'..='\u{318E}').contains(&c))
+    };
+    COMMANDS
+        .iter()
+        .flat_map(|(words, _)| words.iter().copied())
+        .chain(socials.commands())
+        .filter(|word| !is_shortcut(word))
+        .collect()
+}
+
+/// Nearest entry of `candidates` to `input` by Levenshtein distance, if it
+/// is close enough to be a plausible typo: at most one edit per three
+/// characters of input (minimum one). Ties go to the earlier candidate.
+pub fn suggest(input: &str, candidates: &[&str]) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    let max_distance = (input.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|c| (levenshtein(&input, c), *c))
+        .filter(|&(d, _)| d <= max_distance)
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, c)| c.to_string())
+}
+
+/// Edit distance over chars (not bytes), so Hangul counts one per syllable.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Split a line into chained commands on `separator` (e.g. `줍기 검; 보기`).
+/// Separators inside `"quoted spans"` are kept, blank pieces are dropped,
+/// and admin commands (`/...`) are never split so their arguments pass
+/// through verbatim. A line with no pieces yields itself, so an empty line
+/// still parses as a look.
+pub fn split_commands(input: &str, separator: char) -> Vec<&str> {
+    if input.trim_start().starts_with('/') {
+        return vec![input];
+    }
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_quote = false;
+    for (i, c) in input.char_indices() {
+        if c == '"' {
+            in_quote = !in_quote;
+        } else if c == separator && !in_quote {
+            pieces.push(&input[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    pieces.push(&input[start..]);
+    pieces.retain(|p| !p.trim().is_empty());
+    if pieces.is_empty() {
+        pieces.push(input);
+    }
+    pieces
+}
+
 /// Parse raw user input into a PlayerAction.
 ///
 /// Format: `[argument] [command]` — the last word is the command, preceding words are the argument.
 /// Single-word commands (e.g. "보기", "북", "도움말") work as before.
 /// Admin commands (/command args) keep the original order.
+/// `"quoted spans"` count as a single word; if the last word is quoted the
+/// order flips to `[command] [argument]` (e.g. `say "hello world"`).
 pub fn parse_input(input: &str) -> PlayerAction {
+    parse_input_with_socials(input, &SocialTable::new())
+}
+
+/// `parse_input`, additionally recognizing the socials in `socials`.
+/// Built-in commands take precedence over a social with the same word.
+pub fn parse_input_with_socials(input: &str, socials: &SocialTable) -> PlayerAction {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return PlayerAction::Look;
     }
 
     // Admin commands start with / — keep [command] [args] order
-    if trimmed.starts_with('/') {
-        let without_slash = &trimmed[1..];
+    if let Some(without_slash) = trimmed.strip_prefix('/') {
         let mut parts = without_slash.splitn(2, ' ');
         let command = parts.next().unwrap_or("").to_lowercase();
         let args = parts.next().unwrap_or("").trim().to_string();
@@ -75,88 +273,220 @@ pub fn parse_input(input: &str) -> PlayerAction {
         return PlayerAction::Admin { command, args };
     }
 
+    // `emote <text>` keeps the command first and the text's original casing.
+    if let Some((first, rest)) = trimmed.split_once(char::is_whitespace) {
+        if lookup_command(&first.to_lowercase()) == Some(Command::Emote) && !rest.trim().is_empty() {
+            return PlayerAction::Emote(rest.trim().to_string());
+        }
+    }
+
     let lower = trimmed.to_lowercase();
-    let words: Vec<&str> = lower.split_whitespace().collect();
+    // Unterminated quote: fall back to plain whitespace splitting.
+    let words = tokenize(&lower).unwrap_or_else(|| {
+        lower
+            .split_whitespace()
+            .map(|w| Token {
+                text: w.to_string(),
+                quoted: false,
+            })
+            .collect()
+    });
     if words.is_empty() {
         return PlayerAction::Look;
     }
 
-    // Last word = command, preceding words = argument
-    let cmd = words[words.len() - 1];
-    let arg = if words.len() >= 2 {
-        words[..words.len() - 1].join(" ")
+    // Last word = command, preceding words = argument.
+    // A quoted word can't be a command, so `say "hello world"` falls back to
+    // [command] [argument] order.
+    let (cmd, arg_words) = if words.len() >= 2 && words[words.len() - 1].quoted {
+        (&words[0], &words[1..])
     } else {
-        String::new()
+        (&words[words.len() - 1], &words[..words.len() - 1])
     };
+    if cmd.quoted {
+        return PlayerAction::Unknown(trimmed.to_string());
+    }
+    let cmd = cmd.text.as_str();
+    let arg = arg_words
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
 
-    match cmd {
-        // look  (ㅂ)
-        "look" | "l" | "보기" | "\u{3142}" => PlayerAction::Look,
-        // movement
-        "north" | "n" | "북" => PlayerAction::Move(Direction::North),
-        "south" | "s" | "남" => PlayerAction::Move(Direction::South),
-        "east" | "e" | "동" => PlayerAction::Move(Direction::East),
-        "west" | "w" | "서" => PlayerAction::Move(Direction::West),
-        // attack  (ㄱ)
-        "attack" | "kill" | "k" | "공격" | "\u{3131}" => {
+    let Some(command) = lookup_command(cmd) else {
+        return match socials.lookup(cmd) {
+            Some(name) => PlayerAction::Social {
+                name: name.to_string(),
+                target: (!arg.is_empty()).then_some(arg),
+            },
+            None => PlayerAction::Unknown(trimmed.to_string()),
+        };
+    };
+    match command {
+        Command::Look => {
+            if arg.is_empty() {
+                PlayerAction::Look
+            } else {
+                PlayerAction::LookAt(arg)
+            }
+        }
+        Command::Move(dir) => PlayerAction::Move(dir),
+        Command::Attack => {
             if arg.is_empty() {
                 PlayerAction::Unknown("누구를 공격할까요?".to_string())
             } else {
                 PlayerAction::Attack(arg)
             }
         }
-        // get  (ㅈ)
-        "get" | "take" | "pick" | "줍기" | "\u{3148}" => {
+        Command::Get => {
             if arg.is_empty() {
                 PlayerAction::Unknown("무엇을 주울까요?".to_string())
             } else {
                 PlayerAction::Get(arg)
             }
         }
-        // drop  (ㅂㄹ)
-        "drop" | "버리기" | "\u{3142}\u{3139}" => {
+        Command::Drop => {
             if arg.is_empty() {
                 PlayerAction::Unknown("무엇을 버릴까요?".to_string())
             } else {
                 PlayerAction::Drop(arg)
             }
         }
-        // inventory
-        "inventory" | "inv" | "i" | "가방" | "인벤" => PlayerAction::InventoryList,
-        // say  (ㅁ)
-        "say" | "말" | "\u{3141}" => {
+        Command::Inventory => PlayerAction::InventoryList,
+        Command::Say => {
             if arg.is_empty() {
                 PlayerAction::Unknown("무엇을 말할까요?".to_string())
             } else {
                 PlayerAction::Say(arg)
             }
         }
-        // who
-        "who" | "접속자" => PlayerAction::Who,
-        // quit
-        "quit" | "exit" | "종료" => PlayerAction::Quit,
-        // help  (ㄷ)
-        "help" | "?" | "도움말" | "\u{3137}" => PlayerAction::Help,
-        // status
-        "status" | "stat" | "상태" => PlayerAction::Status,
-        // gold  (ㄱㄷ)
-        "gold" | "골드" | "\u{3131}\u{3137}" => PlayerAction::Gold,
-        // skill
-        "skill" | "스킬" => {
+        Command::Who => PlayerAction::Who,
+        Command::Quit => PlayerAction::Quit,
+        Command::Help => PlayerAction::Help,
+        Command::Status => PlayerAction::Status,
+        Command::Gold => PlayerAction::Gold,
+        Command::Skill => {
             if arg.is_empty() {
                 PlayerAction::SkillList
             } else {
                 PlayerAction::UseSkill(arg)
             }
         }
-        _ => PlayerAction::Unknown(trimmed.to_string()),
+        // [text] emote form
+        Command::Emote => {
+            if arg.is_empty() {
+                PlayerAction::Unknown("어떤 행동을 할까요?".to_string())
+            } else {
+                PlayerAction::Emote(arg)
+            }
+        }
+        Command::Follow => {
+            if arg.is_empty() {
+                PlayerAction::Unknown("누구를 따라갈까요?".to_string())
+            } else {
+                PlayerAction::Follow(arg)
+            }
+        }
+        Command::Unfollow => PlayerAction::Unfollow,
     }
 }
 
+/// A whitespace-separated word; `quoted` words came from a `"..."` span.
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Split on whitespace, keeping `"quoted spans"` intact as single words.
+/// Returns None if a quote is left unterminated.
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_quote = false;
+
+    for c in input.chars() {
+        if c == '"' {
+            in_quote = !in_quote;
+            quoted = true;
+        } else if c.is_whitespace() && !in_quote {
+            if !current.is_empty() {
+                tokens.push(Token {
+                    text: std::mem::take(&mut current),
+                    quoted,
+                });
+            }
+            quoted = false;
+        } else {
+            current.push(c);
+        }
+    }
+    if in_quote {
+        return None;
+    }
+    if !current.is_empty() {
+        tokens.push(Token {
+            text: current,
+            quoted,
+        });
+    }
+    Some(tokens)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn suggest_close_typo() {
+        let socials = SocialTable::new();
+        let words = command_words(&socials);
+        assert_eq!(suggest("lok", &words), Some("look".to_string()));
+        assert_eq!(suggest("ATACK", &words), Some("attack".to_string()));
+        assert_eq!(suggest("도움알", &words), Some("도움말".to_string()));
+    }
+
+    #[test]
+    fn suggest_nothing_for_unrelated_input() {
+        let socials = SocialTable::new();
+        let words = command_words(&socials);
+        assert_eq!(suggest("xyzzyplugh", &words), None);
+        assert_eq!(suggest("q", &words), None);
+        assert_eq!(suggest("", &words), None);
+    }
+
+    #[test]
+    fn command_words_cover_aliases_and_socials() {
+        let mut socials = SocialTable::new();
+        socials.insert("smile", "smile");
+        socials.insert("grin", "smile");
+        let words = command_words(&socials);
+        for word in ["fol", "inv", "stat", "em", "kill", "smile", "grin"] {
+            assert!(words.contains(&word), "{}", word);
+        }
+        for shortcut in ["l", "?", "\u{3142}", "\u{3142}\u{3139}"] {
+            assert!(!words.contains(&shortcut), "{}", shortcut);
+        }
+        assert_eq!(suggest("smil", &words), Some("smile".to_string()));
+    }
+
+    #[test]
+    fn every_command_word_parses() {
+        for (words, _) in COMMANDS {
+            for word in *words {
+                let input = format!("goblin {}", word);
+                assert_ne!(parse_input(&input), PlayerAction::Unknown(input.clone()), "{}", word);
+            }
+        }
+    }
+
+    #[test]
+    fn levenshtein_counts_chars() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("공격", "공겨"), 1);
+    }
+
     #[test]
     fn parse_look() {
         assert_eq!(parse_input("보기"), PlayerAction::Look);
@@ -166,6 +496,22 @@ mod tests {
         assert_eq!(parse_input(""), PlayerAction::Look);
     }
 
+    #[test]
+    fn parse_follow() {
+        assert_eq!(parse_input("hero follow"), PlayerAction::Follow("hero".to_string()));
+        assert_eq!(parse_input("영웅 따라가기"), PlayerAction::Follow("영웅".to_string()));
+        assert!(matches!(parse_input("follow"), PlayerAction::Unknown(_)));
+        assert_eq!(parse_input("unfollow"), PlayerAction::Unfollow);
+        assert_eq!(parse_input("그만따라가기"), PlayerAction::Unfollow);
+    }
+
+    #[test]
+    fn parse_look_at_target() {
+        assert_eq!(parse_input("고블린 보기"), PlayerAction::LookAt("고블린".to_string()));
+        assert_eq!(parse_input("goblin look"), PlayerAction::LookAt("goblin".to_string()));
+        assert_eq!(parse_input("look \"red potion\""), PlayerAction::LookAt("red potion".to_string()));
+    }
+
     #[test]
     fn parse_movement() {
         assert_eq!(parse_input("북"), PlayerAction::Move(Direction::North));
@@ -242,6 +588,76 @@ mod tests {
         assert_eq!(parse_input("안녕 \u{3141}"), PlayerAction::Say("안녕".to_string()));
     }
 
+    #[test]
+    fn parse_quoted_say() {
+        assert_eq!(
+            parse_input("say \"hello   world\""),
+            PlayerAction::Say("hello   world".to_string()),
+        );
+        assert_eq!(
+            parse_input("\"안녕 하세요\" 말"),
+            PlayerAction::Say("안녕 하세요".to_string()),
+        );
+    }
+
+    #[test]
+    fn parse_quoted_attack_target() {
+        assert_eq!(
+            parse_input("\"goblin chief\" attack"),
+            PlayerAction::Attack("goblin chief".to_string()),
+        );
+        assert_eq!(
+            parse_input("attack \"Goblin Chief\""),
+            PlayerAction::Attack("goblin chief".to_string()),
+        );
+        // A lone quoted word is never a command.
+        assert_eq!(
+            parse_input("\"attack\""),
+            PlayerAction::Unknown("\"attack\"".to_string()),
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_quote_falls_back() {
+        assert_eq!(
+            parse_input("\"hello world say"),
+            PlayerAction::Say("\"hello world".to_string()),
+        );
+        assert_eq!(
+            parse_input("goblin \"chief attack"),
+            PlayerAction::Attack("goblin \"chief".to_string()),
+        );
+    }
+
+    #[test]
+    fn split_three_command_chain() {
+        let actions: Vec<_> = split_commands("검 줍기; 검 장착 ;보기", ';')
+            .into_iter()
+            .map(parse_input)
+            .collect();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(actions[0], PlayerAction::Get("검".to_string()));
+        assert_eq!(actions[1], PlayerAction::Unknown("검 장착".to_string()));
+        assert_eq!(actions[2], PlayerAction::Look);
+    }
+
+    #[test]
+    fn split_keeps_separator_inside_quotes() {
+        let pieces = split_commands(r#"say "hi; bye"; north"#, ';');
+        assert_eq!(pieces, vec![r#"say "hi; bye""#, " north"]);
+        assert_eq!(parse_input(pieces[0]), PlayerAction::Say("hi; bye".to_string()));
+        assert_eq!(parse_input(pieces[1]), PlayerAction::Move(Direction::North));
+    }
+
+    #[test]
+    fn split_edge_cases() {
+        assert_eq!(split_commands("look", ';'), vec!["look"]);
+        assert_eq!(split_commands("n;;  ;s;", ';'), vec!["n", "s"]);
+        assert_eq!(split_commands("", ';'), vec![""]);
+        assert_eq!(split_commands("/announce a; b", ';'), vec!["/announce a; b"]);
+        assert_eq!(split_commands("n|s", '|'), vec!["n", "s"]);
+    }
+
     #[test]
     fn parse_who_quit_help() {
         assert_eq!(parse_input("접속자"), PlayerAction::Who);
@@ -335,6 +751,69 @@ mod tests {
         assert_eq!(parse_input("fireball skill"), PlayerAction::UseSkill("fireball".to_string()));
     }
 
+    #[test]
+    fn parse_freeform_emote() {
+        assert_eq!(
+            parse_input("emote waves cheerfully"),
+            PlayerAction::Emote("waves cheerfully".to_string())
+        );
+        // Command-first form keeps the original casing
+        assert_eq!(
+            parse_input("EM Waves At Bob"),
+            PlayerAction::Emote("Waves At Bob".to_string())
+        );
+        assert_eq!(
+            parse_input("손을 흔든다 감정"),
+            PlayerAction::Emote("손을 흔든다".to_string())
+        );
+        assert!(matches!(parse_input("emote"), PlayerAction::Unknown(_)));
+    }
+
+    #[test]
+    fn parse_social_with_optional_target() {
+        let mut socials = SocialTable::new();
+        socials.insert("smile", "smile");
+        socials.insert("미소", "smile");
+        socials.insert("Bow", "bow");
+
+        assert_eq!(
+            parse_input_with_socials("smile", &socials),
+            PlayerAction::Social { name: "smile".to_string(), target: None }
+        );
+        assert_eq!(
+            parse_input_with_socials("고블린 미소", &socials),
+            PlayerAction::Social { name: "smile".to_string(), target: Some("고블린".to_string()) }
+        );
+        assert_eq!(
+            parse_input_with_socials("old man BOW", &socials),
+            PlayerAction::Social { name: "bow".to_string(), target: Some("old man".to_string()) }
+        );
+        // Without the table the word is unknown; built-ins still win
+        assert!(matches!(parse_input("smile"), PlayerAction::Unknown(_)));
+        socials.insert("look", "stare");
+        assert_eq!(parse_input_with_socials("look", &socials), PlayerAction::Look);
+    }
+
+    #[test]
+    fn social_table_from_content() {
+        let dir = std::env::temp_dir().join(format!("mud_socials_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("socials.json"),
+            r#"[{"id": "smile", "aliases": ["미소", "grin"]}, {"id": "bow"}]"#,
+        )
+        .unwrap();
+        let registry = ContentRegistry::load_dir(&dir).unwrap();
+        let table = SocialTable::from_content(&registry);
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.lookup("grin"), Some("smile"));
+        assert_eq!(table.lookup("bow"), Some("bow"));
+        assert_eq!(table.lookup("wave"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(SocialTable::from_content(&ContentRegistry::new()).is_empty());
+    }
+
     #[test]
     fn direction_opposite() {
         assert_eq!(Direction::North.opposite(), Direction::South);
@@ -1,4 +1,4 @@
-use ecs_adapter::{Component, EcsAdapter, EntityId};
+use ecs_adapter::{Component, EcsAdapter, EntityId, Tags};
 use persistence::error::PersistenceError;
 use persistence::registry::{PersistenceRegistry, PersistentComponent};
 use serde::{de::DeserializeOwned, Serialize};
@@ -34,6 +34,12 @@ where
             .and_then(|c| bincode::serialize(c).ok())
     }
 
+    fn capture_json(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<serde_json::Value> {
+        ecs.get_component::<C>(eid)
+            .ok()
+            .and_then(|c| serde_json::to_value(c).ok())
+    }
+
     fn restore(
         &self,
         ecs: &mut EcsAdapter,
@@ -62,6 +68,7 @@ pub fn register_mud_components(registry: &mut PersistenceRegistry) {
     register::<Attack>(registry, "Attack");
     register::<Defense>(registry, "Defense");
     register::<Inventory>(registry, "Inventory");
+    register::<Equipment>(registry, "Equipment");
     register::<PlayerTag>(registry, "PlayerTag");
     register::<NpcTag>(registry, "NpcTag");
     register::<ItemTag>(registry, "ItemTag");
@@ -75,6 +82,64 @@ pub fn register_mud_components(registry: &mut PersistenceRegistry) {
     register::<Experience>(registry, "Experience");
     register::<CharacterPosition>(registry, "Position");
     register::<Skills>(registry, "Skills");
-    register::<Gold>(registry, "Gold");
+    register::<RoomCapacity>(registry, "RoomCapacity");
+    register::<Wallet>(registry, "Wallet");
     register::<GameData>(registry, "GameData");
+    register::<Tags>(registry, "Tags");
+}
+
+/// Components left out of a character's saved state. Inventory, Equipment,
+/// InRoom and CombatTarget hold runtime `EntityId`s that mean nothing after
+/// a restart; the rest are markers or values login and combat set anew.
+pub const CHARACTER_RUNTIME_TAGS: &[&str] = &[
+    "Name",
+    "Inventory",
+    "Equipment",
+    "PlayerTag",
+    "NpcTag",
+    "ItemTag",
+    "InRoom",
+    "CombatTarget",
+    "Dead",
+    "RoomCapacity",
+];
+
+/// Drop [`CHARACTER_RUNTIME_TAGS`] from a character's component object.
+/// Also applied on load, since older saves exported every component.
+pub fn retain_character_tags(components: &mut serde_json::Value) {
+    if let Some(map) = components.as_object_mut() {
+        map.retain(|tag, _| !CHARACTER_RUNTIME_TAGS.contains(&tag.as_str()));
+    }
+}
+
+/// Fold a pre-`Wallet` `"Gold"` balance into `"Wallet"`. Characters saved
+/// before the two currencies were merged still carry it.
+pub fn migrate_legacy_gold(components: &mut serde_json::Value) {
+    let Some(map) = components.as_object_mut() else {
+        return;
+    };
+    let Some(gold) = map.remove("Gold") else {
+        return;
+    };
+    let gold = gold.as_i64().unwrap_or(0);
+    let copper = map
+        .get("Wallet")
+        .and_then(|w| w.get("copper"))
+        .and_then(|c| c.as_i64())
+        .unwrap_or(0);
+    map.insert(
+        "Wallet".to_string(),
+        serde_json::json!({"copper": copper.saturating_add(gold)}),
+    );
+}
+
+/// The components of `eid` worth saving to the player DB, keyed by tag.
+pub fn export_character_json(
+    registry: &PersistenceRegistry,
+    ecs: &EcsAdapter,
+    eid: EntityId,
+) -> serde_json::Value {
+    let mut components = registry.export_entity_json(ecs, eid);
+    retain_character_tags(&mut components);
+    components
 }
@@ -1,11 +1,16 @@
-use ecs_adapter::{Component, EcsAdapter, EntityId};
-use scripting::component_registry::{ScriptComponent, ScriptComponentRegistry};
+use ecs_adapter::{Component, EcsAdapter, EntityId, Tags};
+use scripting::component_registry::{CurrencyComponent, ScriptComponent, ScriptComponentRegistry};
 use scripting::error::ScriptError;
 use scripting::mlua;
 use scripting::mlua::{Lua, LuaSerdeExt};
 use serde::{de::DeserializeOwned, Serialize};
 
+use std::collections::BTreeMap;
+
 use crate::components::*;
+use crate::output::MessageTemplates;
+use crate::systems::follow::FollowRegistry;
+use crate::systems::party::{LeaveOutcome, PartyId, PartyRegistry};
 
 /// Generic handler for any Component that implements Serialize + DeserializeOwned.
 /// Converts between Rust components and Lua values via serde_json.
@@ -337,6 +342,76 @@ impl ScriptComponent for InventoryHandler {
     }
 }
 
+/// Handler for Equipment { slots: BTreeMap<String, EntityId> } — exposes
+/// `{slots = {weapon = <eid>, ...}}` to Lua with raw u64 entity ids.
+struct EquipmentHandler;
+
+impl ScriptComponent for EquipmentHandler {
+    fn tag(&self) -> &str {
+        "Equipment"
+    }
+
+    fn get_as_lua(
+        &self,
+        ecs: &EcsAdapter,
+        eid: EntityId,
+        lua: &Lua,
+    ) -> Result<Option<mlua::Value>, ScriptError> {
+        match ecs.get_component::<Equipment>(eid) {
+            Ok(eq) => {
+                let table = lua.create_table().map_err(ScriptError::Lua)?;
+                let slots = lua.create_table().map_err(ScriptError::Lua)?;
+                for (slot, item_id) in &eq.slots {
+                    slots
+                        .set(slot.as_str(), item_id.to_u64())
+                        .map_err(ScriptError::Lua)?;
+                }
+                table.set("slots", slots).map_err(ScriptError::Lua)?;
+                Ok(Some(mlua::Value::Table(table)))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn set_from_lua(
+        &self,
+        ecs: &mut EcsAdapter,
+        eid: EntityId,
+        value: mlua::Value,
+        _lua: &Lua,
+    ) -> Result<(), ScriptError> {
+        let table = match value {
+            mlua::Value::Table(t) => t,
+            _ => return Err(ScriptError::Lua(mlua::Error::runtime("Equipment expects a table with slots field"))),
+        };
+        let slots_table: Option<mlua::Table> = table.get("slots").map_err(ScriptError::Lua)?;
+        let mut eq = Equipment::new();
+        if let Some(slots_table) = slots_table {
+            for pair in slots_table.pairs::<String, u64>() {
+                let (slot, id) = pair.map_err(ScriptError::Lua)?;
+                eq.slots.insert(slot, EntityId::from_u64(id));
+            }
+        }
+        ecs.set_component(eid, eq)
+            .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))?;
+        Ok(())
+    }
+
+    fn has(&self, ecs: &EcsAdapter, eid: EntityId) -> bool {
+        ecs.has_component::<Equipment>(eid)
+    }
+
+    fn remove(&self, ecs: &mut EcsAdapter, eid: EntityId) -> Result<(), ScriptError> {
+        ecs.remove_component::<Equipment>(eid)
+            .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))?;
+        Ok(())
+    }
+
+    fn entities_with(&self, ecs: &EcsAdapter) -> Vec<EntityId> {
+        ecs.entities_with::<Equipment>()
+    }
+}
+
 /// Handler for Skills { learned: Vec<String> } — explicitly handles sequence conversion.
 struct SkillsHandler;
 
@@ -486,6 +561,25 @@ impl ScriptComponent for CharacterPositionHandler {
     }
 }
 
+/// Backs the `ecs:money`/`add_money`/`spend_money` helpers with `Wallet`.
+struct WalletCurrency;
+
+impl CurrencyComponent for WalletCurrency {
+    fn balance(&self, ecs: &EcsAdapter, eid: EntityId) -> Option<i64> {
+        ecs.get_component::<Wallet>(eid).ok().map(|w| w.copper)
+    }
+
+    fn set_balance(&self, ecs: &mut EcsAdapter, eid: EntityId, amount: i64) -> Result<(), ScriptError> {
+        ecs.set_component(eid, Wallet { copper: amount })
+            .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))
+    }
+
+    fn remove_wallet(&self, ecs: &mut EcsAdapter, eid: EntityId) -> Result<(), ScriptError> {
+        ecs.remove_component::<Wallet>(eid)
+            .map_err(|e| ScriptError::Lua(mlua::Error::runtime(e.to_string())))
+    }
+}
+
 /// Register all MUD component types with the script component registry.
 pub fn register_mud_script_components(registry: &mut ScriptComponentRegistry) {
     register::<Name>(registry, "Name");
@@ -494,6 +588,7 @@ pub fn register_mud_script_components(registry: &mut ScriptComponentRegistry) {
     register::<Attack>(registry, "Attack");
     register::<Defense>(registry, "Defense");
     registry.register(Box::new(InventoryHandler));
+    registry.register(Box::new(EquipmentHandler));
     register_tag::<PlayerTag>(registry, "PlayerTag");
     register_tag::<NpcTag>(registry, "NpcTag");
     register_tag::<ItemTag>(registry, "ItemTag");
@@ -507,8 +602,165 @@ pub fn register_mud_script_components(registry: &mut ScriptComponentRegistry) {
     register::<Experience>(registry, "Experience");
     registry.register(Box::new(CharacterPositionHandler));
     registry.register(Box::new(SkillsHandler));
-    register::<Gold>(registry, "Gold");
+    register::<RoomCapacity>(registry, "RoomCapacity");
+    register::<Wallet>(registry, "Wallet");
+    registry.set_currency(Box::new(WalletCurrency));
     registry.register(Box::new(GameDataHandler));
+    register::<Tags>(registry, "Tags");
+}
+
+/// Install a `PartyRegistry` in the Lua app data and register the `party.*`
+/// API over it. Invalid operations (joining twice, leaving while ungrouped)
+/// raise a Lua error; check `party.of` first or wrap the call in `pcall`.
+pub fn register_party_api(lua: &Lua) -> mlua::Result<()> {
+    fn with_parties<R>(lua: &Lua, f: impl FnOnce(&mut PartyRegistry) -> R) -> mlua::Result<R> {
+        let mut reg = lua
+            .app_data_mut::<PartyRegistry>()
+            .ok_or_else(|| mlua::Error::runtime("party registry not installed"))?;
+        Ok(f(&mut reg))
+    }
+
+    lua.set_app_data(PartyRegistry::new());
+    let party = lua.create_table()?;
+
+    // party.create(leader_id) -> party_id
+    party.set("create", lua.create_function(|lua, leader: u64| {
+        with_parties(lua, |reg| reg.create_party(EntityId::from_u64(leader)))?
+            .map(|id| id.0)
+            .map_err(|e| mlua::Error::runtime(e.to_string()))
+    })?)?;
+
+    // party.join(party_id, entity_id)
+    party.set("join", lua.create_function(|lua, (party_id, eid): (u64, u64)| {
+        with_parties(lua, |reg| reg.join(PartyId(party_id), EntityId::from_u64(eid)))?
+            .map_err(|e| mlua::Error::runtime(e.to_string()))
+    })?)?;
+
+    // party.leave(entity_id) -> "left" | "promoted", new_leader_id | "disbanded"
+    party.set("leave", lua.create_function(|lua, eid: u64| {
+        let outcome = with_parties(lua, |reg| reg.leave(EntityId::from_u64(eid)))?
+            .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+        Ok(match outcome {
+            LeaveOutcome::Left => ("left", None),
+            LeaveOutcome::LeaderPromoted(leader) => ("promoted", Some(leader.to_u64())),
+            LeaveOutcome::Disbanded => ("disbanded", None),
+        })
+    })?)?;
+
+    // party.of(entity_id) -> party_id | nil
+    party.set("of", lua.create_function(|lua, eid: u64| {
+        with_parties(lua, |reg| reg.party_of(EntityId::from_u64(eid)).map(|id| id.0))
+    })?)?;
+
+    // party.members(entity_id) -> {entity_id, ...} in join order (empty if ungrouped)
+    party.set("members", lua.create_function(|lua, eid: u64| {
+        let members = with_parties(lua, |reg| reg.party_members(EntityId::from_u64(eid)))?;
+        lua.create_sequence_from(members.into_iter().map(|m| m.to_u64()))
+    })?)?;
+
+    // party.leader(entity_id) -> leader_id | nil
+    party.set("leader", lua.create_function(|lua, eid: u64| {
+        with_parties(lua, |reg| reg.leader_of(EntityId::from_u64(eid)).map(|l| l.to_u64()))
+    })?)?;
+
+    lua.globals().set("party", party)?;
+    Ok(())
+}
+
+/// Install a `FollowRegistry` in the Lua app data and register the
+/// `follow.*` API over it. `follow.start` raises a Lua error for self-follow
+/// and cycles.
+pub fn register_follow_api(lua: &Lua) -> mlua::Result<()> {
+    fn with_follows<R>(lua: &Lua, f: impl FnOnce(&mut FollowRegistry) -> R) -> mlua::Result<R> {
+        let mut reg = lua
+            .app_data_mut::<FollowRegistry>()
+            .ok_or_else(|| mlua::Error::runtime("follow registry not installed"))?;
+        Ok(f(&mut reg))
+    }
+
+    lua.set_app_data(FollowRegistry::new());
+    let follow = lua.create_table()?;
+
+    // follow.start(follower_id, leader_id) -> previous_leader_id | nil
+    follow.set("start", lua.create_function(|lua, (follower, leader): (u64, u64)| {
+        with_follows(lua, |reg| reg.follow(EntityId::from_u64(follower), EntityId::from_u64(leader)))?
+            .map(|prev| prev.map(|l| l.to_u64()))
+            .map_err(|e| mlua::Error::runtime(e.to_string()))
+    })?)?;
+
+    // follow.stop(follower_id) -> previous_leader_id | nil
+    follow.set("stop", lua.create_function(|lua, follower: u64| {
+        with_follows(lua, |reg| reg.unfollow(EntityId::from_u64(follower)).map(|l| l.to_u64()))
+    })?)?;
+
+    // follow.leader(follower_id) -> leader_id | nil
+    follow.set("leader", lua.create_function(|lua, follower: u64| {
+        with_follows(lua, |reg| reg.leader_of(EntityId::from_u64(follower)).map(|l| l.to_u64()))
+    })?)?;
+
+    // follow.followers(leader_id) -> {entity_id, ...} in entity order
+    follow.set("followers", lua.create_function(|lua, leader: u64| {
+        let followers = with_follows(lua, |reg| reg.followers_of(EntityId::from_u64(leader)))?;
+        lua.create_sequence_from(followers.into_iter().map(|f| f.to_u64()))
+    })?)?;
+
+    // follow.forget(entity_id) -> {former_follower_id, ...}
+    follow.set("forget", lua.create_function(|lua, eid: u64| {
+        let followers = with_follows(lua, |reg| reg.forget(EntityId::from_u64(eid)))?;
+        lua.create_sequence_from(followers.into_iter().map(|f| f.to_u64()))
+    })?)?;
+
+    lua.globals().set("follow", follow)?;
+    Ok(())
+}
+
+/// Drop a despawned entity from the party and follow registries installed
+/// by `register_party_api` / `register_follow_api`. A party it led passes to
+/// the next member; entities following it stop following.
+pub fn forget_entity(lua: &Lua, entity: EntityId) {
+    if let Some(mut parties) = lua.app_data_mut::<PartyRegistry>() {
+        let _ = parties.leave(entity);
+    }
+    if let Some(mut follows) = lua.app_data_mut::<FollowRegistry>() {
+        follows.forget(entity);
+    }
+}
+
+/// Install `templates` in the Lua app data and register
+/// `message.render(name, params?)`, which fills a content-defined template.
+/// Parameter values may be strings or numbers.
+pub fn register_message_api(lua: &Lua, templates: MessageTemplates) -> mlua::Result<()> {
+    lua.set_app_data(templates);
+    let message = lua.create_table()?;
+
+    // message.render(name, {key = value, ...}?) -> string
+    message.set("render", lua.create_function(|lua, (name, params): (String, Option<mlua::Table>)| {
+        let mut values = BTreeMap::new();
+        let params = params.unwrap_or(lua.create_table()?);
+        for pair in params.pairs::<String, mlua::Value>() {
+            let (key, value) = pair?;
+            let value = match value {
+                mlua::Value::String(s) => s.to_string_lossy(),
+                mlua::Value::Integer(i) => i.to_string(),
+                mlua::Value::Number(n) => n.to_string(),
+                other => {
+                    return Err(mlua::Error::runtime(format!(
+                        "message param '{}' must be a string or number, got {}",
+                        key,
+                        other.type_name()
+                    )))
+                }
+            };
+            values.insert(key, value);
+        }
+        let templates = lua
+            .app_data_ref::<MessageTemplates>()
+            .ok_or_else(|| mlua::Error::runtime("message templates not installed"))?;
+        Ok(templates.render_template(&name, &values))
+    })?)?;
+
+    lua.globals().set("message", message)?;
+    Ok(())
 }
 
 /// Handler for GameData(serde_json::Value) — directly passes JSON value without
@@ -562,3 +814,157 @@ impl ScriptComponent for GameDataHandler {
         ecs.entities_with::<GameData>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn description_script_roundtrip() {
+        let mut registry = ScriptComponentRegistry::new();
+        register_mud_script_components(&mut registry);
+        let handler = registry.get("Description").unwrap();
+
+        let lua = Lua::new();
+        let mut ecs = EcsAdapter::new();
+        let eid = ecs.spawn_entity();
+        ecs.set_component(eid, Name("고블린".to_string())).unwrap();
+        assert!(handler.get_as_lua(&ecs, eid, &lua).unwrap().is_none());
+
+        let value = lua.to_value("녹슨 단검을 든 고블린").unwrap();
+        handler.set_from_lua(&mut ecs, eid, value, &lua).unwrap();
+        assert_eq!(
+            ecs.get_component::<Description>(eid).unwrap(),
+            &Description("녹슨 단검을 든 고블린".to_string())
+        );
+        // Description is independent of Name
+        assert_eq!(ecs.get_component::<Name>(eid).unwrap().0, "고블린");
+
+        let back = handler.get_as_lua(&ecs, eid, &lua).unwrap().unwrap();
+        assert_eq!(back.as_string().unwrap().to_str().unwrap(), "녹슨 단검을 든 고블린");
+    }
+
+    #[test]
+    fn wallet_backs_money_helpers() {
+        use scripting::api::ecs::EcsProxy;
+
+        let mut registry = ScriptComponentRegistry::new();
+        register_mud_script_components(&mut registry);
+        let lua = Lua::new();
+        let mut ecs = EcsAdapter::new();
+        let eid = ecs.spawn_entity();
+
+        let proxy = unsafe { EcsProxy::new(&mut ecs as *mut _, &registry as *const _) };
+        lua.scope(|scope| {
+            lua.globals().set("ecs", scope.create_userdata(proxy)?)?;
+            let (ok, short, wallet): (bool, bool, i64) = lua
+                .load(format!(
+                    r#"
+                    local e = {}
+                    ecs:add_money(e, 50)
+                    local ok = ecs:spend_money(e, 20)
+                    local short = ecs:spend_money(e, 31)
+                    return ok, short, ecs:get(e, "Wallet").copper
+                    "#,
+                    eid.to_u64()
+                ))
+                .eval()?;
+            assert!(ok);
+            assert!(!short);
+            assert_eq!(wallet, 30);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(ecs.get_component::<Wallet>(eid).unwrap(), &Wallet { copper: 30 });
+    }
+
+    #[test]
+    fn party_api_roundtrip() {
+        let lua = Lua::new();
+        register_party_api(&lua).unwrap();
+
+        let members: Vec<u64> = lua
+            .load("local p = party.create(1); party.join(p, 2); party.join(p, 3); return party.members(3)")
+            .eval()
+            .unwrap();
+        assert_eq!(members, vec![1, 2, 3]);
+
+        let (outcome, leader): (String, Option<u64>) = lua.load("return party.leave(1)").eval().unwrap();
+        assert_eq!((outcome.as_str(), leader), ("promoted", Some(2)));
+        let leader: u64 = lua.load("return party.leader(3)").eval().unwrap();
+        assert_eq!(leader, 2);
+        let none: Option<u64> = lua.load("return party.of(1)").eval().unwrap();
+        assert!(none.is_none());
+
+        let err = lua.load("party.join(party.of(2), 3)").exec().unwrap_err();
+        assert!(err.to_string().contains("already in a party"));
+        assert!(lua.load("party.leave(1)").exec().is_err());
+    }
+
+    #[test]
+    fn follow_api_roundtrip() {
+        let lua = Lua::new();
+        register_follow_api(&lua).unwrap();
+
+        let prev: Option<u64> = lua.load("follow.start(2, 1); return follow.start(3, 1)").eval().unwrap();
+        assert!(prev.is_none());
+        let followers: Vec<u64> = lua.load("return follow.followers(1)").eval().unwrap();
+        assert_eq!(followers, vec![2, 3]);
+        let leader: u64 = lua.load("return follow.stop(2)").eval().unwrap();
+        assert_eq!(leader, 1);
+
+        let err = lua.load("follow.start(1, 3)").exec().unwrap_err();
+        assert!(err.to_string().contains("already follows"), "{}", err);
+        let forgotten: Vec<u64> = lua.load("return follow.forget(1)").eval().unwrap();
+        assert_eq!(forgotten, vec![3]);
+        let none: Option<u64> = lua.load("return follow.leader(3)").eval().unwrap();
+        assert!(none.is_none());
+    }
+
+    #[test]
+    fn forget_entity_clears_party_and_follow_links() {
+        let lua = Lua::new();
+        register_party_api(&lua).unwrap();
+        register_follow_api(&lua).unwrap();
+        lua.load("local p = party.create(1); party.join(p, 2); follow.start(2, 1); follow.start(1, 3)")
+            .exec()
+            .unwrap();
+
+        forget_entity(&lua, EntityId::from_u64(1));
+        let (party_of, leader, follows, followers): (Option<u64>, u64, Option<u64>, Vec<u64>) = lua
+            .load("return party.of(1), party.leader(2), follow.leader(2), follow.followers(3)")
+            .eval()
+            .unwrap();
+        assert_eq!(party_of, None);
+        assert_eq!(leader, 2);
+        assert_eq!(follows, None);
+        assert!(followers.is_empty());
+
+        // Entities in neither registry are ignored.
+        forget_entity(&lua, EntityId::from_u64(99));
+    }
+
+    #[test]
+    fn message_api_renders_templates() {
+        let lua = Lua::new();
+        let mut templates = MessageTemplates::new();
+        templates.insert("gain", "{name} gains {amount} gold.");
+        register_message_api(&lua, templates).unwrap();
+
+        let text: String = lua
+            .load(r#"return message.render("gain", {name = "Bob", amount = 12})"#)
+            .eval()
+            .unwrap();
+        assert_eq!(text, "Bob gains 12 gold.");
+        let text: String = lua.load(r#"return message.render("gain")"#).eval().unwrap();
+        assert_eq!(text, "{name} gains {amount} gold.");
+        let text: String = lua.load(r#"return message.render("missing", {})"#).eval().unwrap();
+        assert_eq!(text, "missing");
+
+        let err = lua
+            .load(r#"message.render("gain", {name = {}})"#)
+            .exec()
+            .unwrap_err();
+        assert!(err.to_string().contains("must be a string or number"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use ecs_adapter::EntityId;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum FollowError {
+    #[error("entity {0:?} cannot follow itself")]
+    SelfFollow(EntityId),
+    #[error("entity {leader:?} already follows {follower:?}")]
+    Cycle { follower: EntityId, leader: EntityId },
+}
+
+/// Who follows whom. Each entity follows at most one leader; a leader may
+/// have any number of followers, who may in turn lead others. Moving
+/// followers along is left to scripts (`on_enter_room`).
+#[derive(Debug, Default)]
+pub struct FollowRegistry {
+    leader_of: BTreeMap<EntityId, EntityId>,
+}
+
+impl FollowRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `follower` follow `leader`, replacing any previous leader, which
+    /// is returned. Refuses chains that would lead back to `follower`.
+    pub fn follow(
+        &mut self,
+        follower: EntityId,
+        leader: EntityId,
+    ) -> Result<Option<EntityId>, FollowError> {
+        if follower == leader {
+            return Err(FollowError::SelfFollow(follower));
+        }
+        let mut next = Some(leader);
+        while let Some(current) = next {
+            if current == follower {
+                return Err(FollowError::Cycle { follower, leader });
+            }
+            next = self.leader_of.get(&current).copied();
+        }
+        Ok(self.leader_of.insert(follower, leader))
+    }
+
+    /// Stop `follower` following anyone. Returns the leader it had.
+    pub fn unfollow(&mut self, follower: EntityId) -> Option<EntityId> {
+        self.leader_of.remove(&follower)
+    }
+
+    pub fn leader_of(&self, follower: EntityId) -> Option<EntityId> {
+        self.leader_of.get(&follower).copied()
+    }
+
+    /// Direct followers of `leader`, in entity order.
+    pub fn followers_of(&self, leader: EntityId) -> Vec<EntityId> {
+        self.leader_of
+            .iter()
+            .filter(|&(_, &l)| l == leader)
+            .map(|(&f, _)| f)
+            .collect()
+    }
+
+    /// Drop every relationship involving `entity` (e.g. on logout). Returns
+    /// the entities that were following it.
+    pub fn forget(&mut self, entity: EntityId) -> Vec<EntityId> {
+        self.leader_of.remove(&entity);
+        let followers = self.followers_of(entity);
+        for follower in &followers {
+            self.leader_of.remove(follower);
+        }
+        followers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e(id: u32) -> EntityId {
+        EntityId::new(id, 0)
+    }
+
+    #[test]
+    fn follow_switch_and_unfollow() {
+        let mut reg = FollowRegistry::new();
+        assert_eq!(reg.follow(e(2), e(1)), Ok(None));
+        assert_eq!(reg.follow(e(3), e(1)), Ok(None));
+        assert_eq!(reg.followers_of(e(1)), vec![e(2), e(3)]);
+
+        assert_eq!(reg.follow(e(3), e(4)), Ok(Some(e(1))));
+        assert_eq!(reg.followers_of(e(1)), vec![e(2)]);
+        assert_eq!(reg.leader_of(e(3)), Some(e(4)));
+
+        assert_eq!(reg.unfollow(e(2)), Some(e(1)));
+        assert_eq!(reg.unfollow(e(2)), None);
+        assert!(reg.followers_of(e(1)).is_empty());
+    }
+
+    #[test]
+    fn refuses_self_follow_and_cycles() {
+        let mut reg = FollowRegistry::new();
+        assert_eq!(reg.follow(e(1), e(1)), Err(FollowError::SelfFollow(e(1))));
+
+        reg.follow(e(2), e(1)).unwrap();
+        reg.follow(e(3), e(2)).unwrap();
+        assert_eq!(
+            reg.follow(e(1), e(3)),
+            Err(FollowError::Cycle { follower: e(1), leader: e(3) })
+        );
+        assert_eq!(reg.leader_of(e(1)), None);
+    }
+
+    #[test]
+    fn forget_drops_both_directions() {
+        let mut reg = FollowRegistry::new();
+        reg.follow(e(2), e(1)).unwrap();
+        reg.follow(e(3), e(2)).unwrap();
+        reg.follow(e(4), e(2)).unwrap();
+
+        assert_eq!(reg.forget(e(2)), vec![e(3), e(4)]);
+        assert!(reg.followers_of(e(1)).is_empty());
+        assert_eq!(reg.leader_of(e(3)), None);
+    }
+}
@@ -1,10 +1,14 @@
+pub mod follow;
+pub mod party;
+pub mod status;
+
 use ecs_adapter::{EcsAdapter, EntityId};
 use scripting::engine::{ActionInfo, ScriptContext, ScriptEngine};
 use session::SessionId;
 use space::RoomGraphSpace;
 
 use crate::output::SessionOutput;
-use crate::parser::PlayerAction;
+use crate::parser::{command_words, suggest, PlayerAction, SocialTable};
 use crate::session::SessionManager;
 
 /// Type alias for MUD-specific ScriptContext (always RoomGraphSpace).
@@ -26,58 +30,125 @@ pub struct GameContext<'a> {
     pub tick: u64,
 }
 
+/// Whether any script handled a player action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemOutcome {
+    /// An on_action hook consumed the action.
+    Consumed,
+    /// No script consumed the action; the caller decides the fallback.
+    Unhandled,
+}
+
 /// Process all player inputs via Lua on_action hooks, returning outputs.
+///
+/// Unhandled actions get the default "알 수 없는 명령어" reply; use
+/// `run_game_systems_with` to customize it.
 pub fn run_game_systems(
     ctx: &mut GameContext<'_>,
     inputs: Vec<PlayerInput>,
     script_engine: Option<&ScriptEngine>,
 ) -> Vec<SessionOutput> {
+    run_game_systems_with(ctx, inputs, script_engine, default_unhandled_message)
+}
+
+/// Like `run_game_systems`, but `on_unhandled` builds the reply for actions
+/// no script consumed. Returning None sends nothing.
+pub fn run_game_systems_with<F>(
+    ctx: &mut GameContext<'_>,
+    inputs: Vec<PlayerInput>,
+    script_engine: Option<&ScriptEngine>,
+    mut on_unhandled: F,
+) -> Vec<SessionOutput>
+where
+    F: FnMut(&PlayerInput) -> Option<String>,
+{
     let mut outputs = Vec::new();
 
     for input in inputs {
-        if let Some(engine) = script_engine {
-            let (action_name, args) = action_to_lua_info(&input.action);
-            let action_info = ActionInfo {
-                action_name: action_name.clone(),
-                args,
-                session_id: input.session_id,
-                entity: input.entity,
-            };
+        let (script_outputs, outcome) = dispatch_action(ctx, &input, script_engine);
+        outputs.extend(script_outputs);
+        if outcome == SystemOutcome::Unhandled {
+            if let Some(text) = on_unhandled(&input) {
+                outputs.push(SessionOutput::new(input.session_id, text));
+            }
+        }
+    }
 
-            let mut script_ctx: MudScriptContext<'_> = ScriptContext {
-                ecs: ctx.ecs,
-                space: ctx.space,
-                sessions: &mut *ctx.sessions,
-                tick: ctx.tick,
-            };
+    outputs
+}
 
-            match engine.run_on_action(&mut script_ctx, &action_info) {
-                Ok((script_outputs, consumed)) => {
-                    outputs.extend(script_outputs);
-                    if consumed {
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Script on_action error for '{}': {}", action_name, e);
-                }
-            }
+/// Run the on_action hooks for a single input.
+pub fn dispatch_action(
+    ctx: &mut GameContext<'_>,
+    input: &PlayerInput,
+    script_engine: Option<&ScriptEngine>,
+) -> (Vec<SessionOutput>, SystemOutcome) {
+    let Some(engine) = script_engine else {
+        return (Vec::new(), SystemOutcome::Unhandled);
+    };
+
+    let (action_name, args) = action_to_lua_info(&input.action);
+    let action_info = ActionInfo {
+        action_name: action_name.clone(),
+        args,
+        session_id: input.session_id,
+        entity: input.entity,
+    };
+
+    let mut script_ctx: MudScriptContext<'_> = ScriptContext {
+        ecs: ctx.ecs,
+        space: ctx.space,
+        sessions: &mut *ctx.sessions,
+        tick: ctx.tick,
+    };
+
+    match engine.run_on_action(&mut script_ctx, &action_info) {
+        Ok((script_outputs, consumed)) => {
+            let outcome = if consumed {
+                SystemOutcome::Consumed
+            } else {
+                SystemOutcome::Unhandled
+            };
+            (script_outputs, outcome)
+        }
+        Err(e) => {
+            tracing::warn!("Script on_action error for '{}': {}", action_name, e);
+            (Vec::new(), SystemOutcome::Unhandled)
         }
+    }
+}
+
+/// Default reply for an action no script consumed. Unrecognized commands
+/// get a "did you mean" hint when their command word is a near miss of one
+/// of the built-in [`command_words`].
+pub fn default_unhandled_message(input: &PlayerInput) -> Option<String> {
+    unhandled_message(input, &SocialTable::new())
+}
 
-        // Fallback: if no script engine or script didn't consume
-        outputs.push(SessionOutput::new(
-            input.session_id,
-            format!("알 수 없는 명령어: {:?}", input.action),
-        ));
+/// `default_unhandled_message`, also suggesting the commands in `socials`.
+pub fn unhandled_message(input: &PlayerInput, socials: &SocialTable) -> Option<String> {
+    match &input.action {
+        PlayerAction::Unknown(text) => Some(unknown_command_message(text, &command_words(socials))),
+        action => Some(format!("알 수 없는 명령어: {:?}", action)),
     }
+}
 
-    outputs
+/// "Unknown command" reply for `text`, suggesting the nearest of `commands`.
+/// The command word is the last word of the input, matching the parser's
+/// `[argument] [command]` order.
+pub fn unknown_command_message(text: &str, commands: &[&str]) -> String {
+    let word = text.split_whitespace().last().unwrap_or("");
+    match suggest(word, commands) {
+        Some(hint) => format!("알 수 없는 명령어: {} (혹시 '{}'?)", text, hint),
+        None => format!("알 수 없는 명령어: {}", text),
+    }
 }
 
 /// Convert a PlayerAction to a Lua action name and args string.
 fn action_to_lua_info(action: &PlayerAction) -> (String, String) {
     match action {
         PlayerAction::Look => ("look".to_string(), String::new()),
+        PlayerAction::LookAt(target) => ("look".to_string(), target.clone()),
         PlayerAction::Move(dir) => ("move".to_string(), format!("{:?}", dir).to_lowercase()),
         PlayerAction::Attack(target) => ("attack".to_string(), target.clone()),
         PlayerAction::Get(item) => ("get".to_string(), item.clone()),
@@ -92,6 +163,13 @@ fn action_to_lua_info(action: &PlayerAction) -> (String, String) {
         PlayerAction::Gold => ("gold".to_string(), String::new()),
         PlayerAction::SkillList => ("skill_list".to_string(), String::new()),
         PlayerAction::UseSkill(ref name) => ("use_skill".to_string(), name.clone()),
+        PlayerAction::Emote(text) => ("emote".to_string(), text.clone()),
+        PlayerAction::Follow(target) => ("follow".to_string(), target.clone()),
+        PlayerAction::Unfollow => ("unfollow".to_string(), String::new()),
+        PlayerAction::Social { name, target } => match target {
+            Some(target) => ("social".to_string(), format!("{} {}", name, target)),
+            None => ("social".to_string(), name.clone()),
+        },
         PlayerAction::Unknown(text) => ("unknown".to_string(), text.clone()),
     }
 }
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use ecs_adapter::EntityId;
+
+/// Identifier of a party, unique for the lifetime of a `PartyRegistry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartyId(pub u64);
+
+/// A group of entities with a leader. `members` keeps join order and always
+/// contains the leader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub leader: EntityId,
+    pub members: Vec<EntityId>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PartyError {
+    #[error("entity {0:?} is already in a party")]
+    AlreadyInParty(EntityId),
+    #[error("entity {0:?} is not in a party")]
+    NotInParty(EntityId),
+    #[error("party {0:?} not found")]
+    PartyNotFound(PartyId),
+}
+
+/// What happened to the party when a member left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveOutcome {
+    /// A non-leader left; the party is otherwise unchanged.
+    Left,
+    /// The leader left and the longest-standing member took over.
+    LeaderPromoted(EntityId),
+    /// The last member left and the party no longer exists.
+    Disbanded,
+}
+
+/// Party membership bookkeeping. Only tracks who is grouped with whom;
+/// shared XP, follow and loot rules live in scripts.
+#[derive(Debug, Default)]
+pub struct PartyRegistry {
+    parties: BTreeMap<PartyId, Party>,
+    member_of: BTreeMap<EntityId, PartyId>,
+    next_id: u64,
+}
+
+impl PartyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Form a new party led by `leader`.
+    pub fn create_party(&mut self, leader: EntityId) -> Result<PartyId, PartyError> {
+        if self.member_of.contains_key(&leader) {
+            return Err(PartyError::AlreadyInParty(leader));
+        }
+        self.next_id += 1;
+        let id = PartyId(self.next_id);
+        self.parties.insert(
+            id,
+            Party {
+                leader,
+                members: vec![leader],
+            },
+        );
+        self.member_of.insert(leader, id);
+        Ok(id)
+    }
+
+    /// Add `entity` to an existing party.
+    pub fn join(&mut self, party: PartyId, entity: EntityId) -> Result<(), PartyError> {
+        if self.member_of.contains_key(&entity) {
+            return Err(PartyError::AlreadyInParty(entity));
+        }
+        let p = self
+            .parties
+            .get_mut(&party)
+            .ok_or(PartyError::PartyNotFound(party))?;
+        p.members.push(entity);
+        self.member_of.insert(entity, party);
+        Ok(())
+    }
+
+    /// Remove `entity` from its party. A departing leader hands over to the
+    /// next member in join order; the last member leaving disbands the party.
+    pub fn leave(&mut self, entity: EntityId) -> Result<LeaveOutcome, PartyError> {
+        let id = self
+            .member_of
+            .remove(&entity)
+            .ok_or(PartyError::NotInParty(entity))?;
+        let party = self
+            .parties
+            .get_mut(&id)
+            .ok_or(PartyError::PartyNotFound(id))?;
+        party.members.retain(|&m| m != entity);
+
+        let Some(&next) = party.members.first() else {
+            self.parties.remove(&id);
+            return Ok(LeaveOutcome::Disbanded);
+        };
+        if party.leader == entity {
+            party.leader = next;
+            return Ok(LeaveOutcome::LeaderPromoted(next));
+        }
+        Ok(LeaveOutcome::Left)
+    }
+
+    /// The party `entity` belongs to, if any.
+    pub fn party_of(&self, entity: EntityId) -> Option<PartyId> {
+        self.member_of.get(&entity).copied()
+    }
+
+    pub fn party(&self, id: PartyId) -> Option<&Party> {
+        self.parties.get(&id)
+    }
+
+    /// Everyone in `entity`'s party (including `entity`), in join order.
+    /// Empty if `entity` is not grouped.
+    pub fn party_members(&self, entity: EntityId) -> Vec<EntityId> {
+        self.party_of(entity)
+            .and_then(|id| self.parties.get(&id))
+            .map(|p| p.members.clone())
+            .unwrap_or_default()
+    }
+
+    /// Leader of `entity`'s party, if it is grouped.
+    pub fn leader_of(&self, entity: EntityId) -> Option<EntityId> {
+        self.party_of(entity)
+            .and_then(|id| self.parties.get(&id))
+            .map(|p| p.leader)
+    }
+
+    pub fn party_count(&self) -> usize {
+        self.parties.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e(id: u32) -> EntityId {
+        EntityId::new(id, 0)
+    }
+
+    #[test]
+    fn form_party_and_list_members() {
+        let mut reg = PartyRegistry::new();
+        let id = reg.create_party(e(1)).unwrap();
+        reg.join(id, e(2)).unwrap();
+        reg.join(id, e(3)).unwrap();
+
+        assert_eq!(reg.party_members(e(2)), vec![e(1), e(2), e(3)]);
+        assert_eq!(reg.leader_of(e(3)), Some(e(1)));
+        assert_eq!(reg.party_of(e(1)), Some(id));
+        assert!(reg.party_members(e(9)).is_empty());
+    }
+
+    #[test]
+    fn cannot_be_in_two_parties() {
+        let mut reg = PartyRegistry::new();
+        let a = reg.create_party(e(1)).unwrap();
+        let b = reg.create_party(e(2)).unwrap();
+        assert_eq!(reg.join(b, e(1)), Err(PartyError::AlreadyInParty(e(1))));
+        assert_eq!(reg.create_party(e(2)), Err(PartyError::AlreadyInParty(e(2))));
+        assert_eq!(reg.join(PartyId(99), e(3)), Err(PartyError::PartyNotFound(PartyId(99))));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn member_leaving_keeps_party() {
+        let mut reg = PartyRegistry::new();
+        let id = reg.create_party(e(1)).unwrap();
+        reg.join(id, e(2)).unwrap();
+
+        assert_eq!(reg.leave(e(2)), Ok(LeaveOutcome::Left));
+        assert_eq!(reg.party_members(e(1)), vec![e(1)]);
+        assert_eq!(reg.party_of(e(2)), None);
+        assert_eq!(reg.leave(e(2)), Err(PartyError::NotInParty(e(2))));
+    }
+
+    #[test]
+    fn leader_leaving_promotes_next_member() {
+        let mut reg = PartyRegistry::new();
+        let id = reg.create_party(e(1)).unwrap();
+        reg.join(id, e(2)).unwrap();
+        reg.join(id, e(3)).unwrap();
+
+        assert_eq!(reg.leave(e(1)), Ok(LeaveOutcome::LeaderPromoted(e(2))));
+        assert_eq!(reg.leader_of(e(3)), Some(e(2)));
+        assert_eq!(reg.party_members(e(3)), vec![e(2), e(3)]);
+    }
+
+    #[test]
+    fn last_member_leaving_disbands() {
+        let mut reg = PartyRegistry::new();
+        let id = reg.create_party(e(1)).unwrap();
+        reg.join(id, e(2)).unwrap();
+
+        reg.leave(e(1)).unwrap();
+        assert_eq!(reg.leave(e(2)), Ok(LeaveOutcome::Disbanded));
+        assert_eq!(reg.party(id), None);
+        assert_eq!(reg.party_count(), 0);
+        // Former members can form a new party.
+        assert!(reg.create_party(e(2)).is_ok());
+    }
+}
@@ -0,0 +1,129 @@
+use ecs_adapter::{EcsAdapter, EntityId};
+use session::SessionId;
+
+use crate::components::{Health, Inventory, Name};
+use crate::session::SessionManager;
+
+/// Vitals and inventory of one player, as shown to structured clients.
+/// The server turns it into the wire `StatusUpdate` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStatus {
+    pub health: i32,
+    pub max_health: i32,
+    pub inventory: Vec<StatusItem>,
+}
+
+/// An inventory entry of [`PlayerStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusItem {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Current vitals and inventory of `entity`, or None if it has no Health.
+pub fn player_status(ecs: &EcsAdapter, entity: EntityId) -> Option<PlayerStatus> {
+    let health = ecs.get_component::<Health>(entity).ok()?;
+    let inventory = ecs
+        .get_component::<Inventory>(entity)
+        .map(|inv| {
+            inv.items
+                .iter()
+                .map(|&item| StatusItem {
+                    id: item.to_u64(),
+                    name: ecs
+                        .get_component::<Name>(item)
+                        .map(|n| n.0.clone())
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(PlayerStatus {
+        health: health.current,
+        max_health: health.max,
+        inventory,
+    })
+}
+
+/// Status of every session-bound player whose Health or Inventory changed
+/// since the last `clear_change_tracking`, keyed by session.
+pub fn status_updates(ecs: &EcsAdapter, sessions: &SessionManager) -> Vec<(SessionId, PlayerStatus)> {
+    let mut changed = ecs.changed_this_tick::<Health>();
+    changed.extend(ecs.changed_this_tick::<Inventory>());
+    changed.sort();
+    changed.dedup();
+
+    changed
+        .into_iter()
+        .filter_map(|entity| {
+            let session_id = sessions.session_id_for_entity(entity)?;
+            Some((session_id, player_status(ecs, entity)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(ecs: &mut EcsAdapter, sessions: &mut SessionManager) -> (EntityId, SessionId) {
+        let eid = ecs.spawn_entity();
+        ecs.set_component(eid, Health { current: 10, max: 10 }).unwrap();
+        let sid = sessions.create_session();
+        sessions.bind_entity(sid, eid);
+        (eid, sid)
+    }
+
+    #[test]
+    fn health_change_pushes_status_update() {
+        let mut ecs = EcsAdapter::new();
+        let mut sessions = SessionManager::new();
+        let (eid, sid) = player(&mut ecs, &mut sessions);
+        ecs.clear_change_tracking();
+        assert!(status_updates(&ecs, &sessions).is_empty());
+
+        ecs.set_component(eid, Health { current: 4, max: 10 }).unwrap();
+        let updates = status_updates(&ecs, &sessions);
+        assert_eq!(
+            updates,
+            vec![(
+                sid,
+                PlayerStatus {
+                    health: 4,
+                    max_health: 10,
+                    inventory: Vec::new(),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn inventory_change_lists_item_names() {
+        let mut ecs = EcsAdapter::new();
+        let mut sessions = SessionManager::new();
+        let (eid, _) = player(&mut ecs, &mut sessions);
+        let potion = ecs.spawn_entity();
+        ecs.set_component(potion, Name("물약".to_string())).unwrap();
+        ecs.clear_change_tracking();
+
+        ecs.set_component(eid, Inventory { items: vec![potion] }).unwrap();
+        let updates = status_updates(&ecs, &sessions);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(
+            updates[0].1.inventory,
+            vec![StatusItem {
+                id: potion.to_u64(),
+                name: "물약".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unbound_entities_are_skipped() {
+        let mut ecs = EcsAdapter::new();
+        let sessions = SessionManager::new();
+        let npc = ecs.spawn_entity();
+        ecs.set_component(npc, Health { current: 5, max: 5 }).unwrap();
+        assert!(status_updates(&ecs, &sessions).is_empty());
+    }
+}
@@ -1,9 +1,11 @@
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
-use password_hash::rand_core::OsRng;
-use password_hash::SaltString;
-use rusqlite::Connection;
+use std::time::Duration;
+
+use password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
 use crate::error::PlayerDbError;
+use crate::hashing::{verify_password, HashParams};
+use crate::pool::PooledConnection;
 
 /// Permission levels for accounts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -38,16 +40,64 @@ pub struct Account {
     pub permission: PermissionLevel,
     pub created_at: String,
     pub last_login: Option<String>,
+    /// Contact address for password recovery, if the player set one.
+    pub email: Option<String>,
+}
+
+/// Category of a security-relevant event in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditKind {
+    Login,
+    LoginFailed,
+    Ban,
+    PermissionChange,
+}
+
+impl AuditKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::LoginFailed => "login_failed",
+            Self::Ban => "ban",
+            Self::PermissionChange => "permission_change",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "login" => Self::Login,
+            "login_failed" => Self::LoginFailed,
+            "ban" => Self::Ban,
+            "permission_change" => Self::PermissionChange,
+            _ => return None,
+        })
+    }
+}
+
+/// One row of the append-only audit log.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub id: i64,
+    /// `None` when the event names no existing account (e.g. a failed login
+    /// for an unknown username).
+    pub account_id: Option<i64>,
+    pub kind: AuditKind,
+    pub detail: String,
+    pub created_at: String,
 }
 
+/// How long a recovery token stays valid.
+pub const RECOVERY_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+
 /// Repository for account operations.
 pub struct AccountRepo<'a> {
-    conn: &'a Connection,
+    conn: PooledConnection<'a>,
+    hash_params: HashParams,
 }
 
 impl<'a> AccountRepo<'a> {
-    pub(crate) fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+    pub(crate) fn new(conn: PooledConnection<'a>, hash_params: HashParams) -> Self {
+        Self { conn, hash_params }
     }
 
     /// Create a new account with the given username and password.
@@ -57,7 +107,7 @@ impl<'a> AccountRepo<'a> {
             return Err(PlayerDbError::AccountExists(username.to_string()));
         }
 
-        let password_hash = hash_password(password)?;
+        let password_hash = self.hash_params.hash(password)?;
 
         self.conn.execute(
             "INSERT INTO accounts (username, password_hash) VALUES (?1, ?2)",
@@ -72,13 +122,16 @@ impl<'a> AccountRepo<'a> {
             permission: PermissionLevel::Player,
             created_at: String::new(), // Will be filled by DB default
             last_login: None,
+            email: None,
         })
     }
 
     /// Authenticate with username and password. Returns the account on success.
+    /// A hash cheaper than the configured parameters is replaced after the
+    /// password checks out; if that fails the login still succeeds.
     pub fn authenticate(&self, username: &str, password: &str) -> Result<Account, PlayerDbError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, username, password_hash, permission, created_at, last_login FROM accounts WHERE username = ?1",
+            "SELECT id, username, password_hash, permission, created_at, last_login, email, banned FROM accounts WHERE username = ?1",
         )?;
 
         let result = stmt.query_row(rusqlite::params![username], |row| {
@@ -89,24 +142,42 @@ impl<'a> AccountRepo<'a> {
                 row.get::<_, i32>(3)?,
                 row.get::<_, String>(4)?,
                 row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, bool>(7)?,
             ))
         });
 
-        let (id, username, password_hash, permission, created_at, last_login) = match result {
+        let (id, username, password_hash, permission, created_at, last_login, email, banned) = match result {
             Ok(row) => row,
             Err(rusqlite::Error::QueryReturnedNoRows) => {
+                self.audit(None, AuditKind::LoginFailed, &format!("unknown account '{}'", username));
                 return Err(PlayerDbError::AccountNotFound(username.to_string()));
             }
             Err(e) => return Err(e.into()),
         };
 
-        verify_password(password, &password_hash)?;
+        if let Err(e) = verify_password(password, &password_hash) {
+            self.audit(Some(id), AuditKind::LoginFailed, "invalid password");
+            return Err(e);
+        }
+
+        if banned {
+            self.audit(Some(id), AuditKind::LoginFailed, "account banned");
+            return Err(PlayerDbError::AccountBanned(username));
+        }
+
+        if self.hash_params.needs_rehash(&password_hash) {
+            if let Err(e) = self.rehash(id, password) {
+                tracing::warn!(account_id = id, error = %e, "failed to upgrade password hash");
+            }
+        }
 
         // Update last_login
         self.conn.execute(
             "UPDATE accounts SET last_login = datetime('now') WHERE id = ?1",
             rusqlite::params![id],
         )?;
+        self.audit(Some(id), AuditKind::Login, "");
 
         Ok(Account {
             id,
@@ -114,13 +185,14 @@ impl<'a> AccountRepo<'a> {
             permission: PermissionLevel::from_i32(permission),
             created_at,
             last_login,
+            email,
         })
     }
 
     /// Get an account by username (case-insensitive).
     pub fn get_by_username(&self, username: &str) -> Result<Option<Account>, PlayerDbError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, username, permission, created_at, last_login FROM accounts WHERE username = ?1",
+            "SELECT id, username, permission, created_at, last_login, email FROM accounts WHERE username = ?1",
         )?;
 
         let result = stmt.query_row(rusqlite::params![username], |row| {
@@ -130,6 +202,7 @@ impl<'a> AccountRepo<'a> {
                 permission: PermissionLevel::from_i32(row.get(2)?),
                 created_at: row.get(3)?,
                 last_login: row.get(4)?,
+                email: row.get(5)?,
             })
         });
 
@@ -140,31 +213,193 @@ impl<'a> AccountRepo<'a> {
         }
     }
 
-    /// Set the permission level of an account.
+    /// Set the permission level of an account. The change is audited.
     pub fn set_permission(&self, id: i64, level: PermissionLevel) -> Result<(), PlayerDbError> {
-        let rows = self.conn.execute(
+        let tx = self.conn.unchecked_transaction()?;
+        let previous = tx
+            .query_row(
+                "SELECT permission FROM accounts WHERE id = ?1",
+                rusqlite::params![id],
+                |row| row.get::<_, i32>(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => PlayerDbError::AccountNotFound(id.to_string()),
+                other => other.into(),
+            })?;
+        tx.execute(
             "UPDATE accounts SET permission = ?1 WHERE id = ?2",
             rusqlite::params![level.as_i32(), id],
         )?;
+        self.log_event(
+            Some(id),
+            AuditKind::PermissionChange,
+            &format!("{:?} -> {:?}", PermissionLevel::from_i32(previous), level),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Ban an account: later logins are refused. The ban is audited with
+    /// `reason` as its detail.
+    pub fn ban(&self, id: i64, reason: &str) -> Result<(), PlayerDbError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let rows = tx.execute(
+            "UPDATE accounts SET banned = 1 WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
         if rows == 0 {
             return Err(PlayerDbError::AccountNotFound(id.to_string()));
         }
+        self.log_event(Some(id), AuditKind::Ban, reason)?;
+        tx.commit()?;
         Ok(())
     }
-}
 
-fn hash_password(password: &str) -> Result<String, PlayerDbError> {
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    argon2
-        .hash_password(password.as_bytes(), &salt)
-        .map(|h| h.to_string())
-        .map_err(|e| PlayerDbError::HashError(e.to_string()))
+    /// Replace an account's hash with one made with the configured parameters.
+    fn rehash(&self, id: i64, password: &str) -> Result<(), PlayerDbError> {
+        self.conn.execute(
+            "UPDATE accounts SET password_hash = ?1 WHERE id = ?2",
+            rusqlite::params![self.hash_params.hash(password)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// `log_event` for paths whose outcome must not hinge on the audit
+    /// write (logins): a failed insert is logged and otherwise ignored.
+    fn audit(&self, account_id: Option<i64>, kind: AuditKind, detail: &str) {
+        if let Err(e) = self.log_event(account_id, kind, detail) {
+            tracing::warn!(?account_id, kind = kind.as_str(), error = %e, "failed to write audit log entry");
+        }
+    }
+
+    /// Append an entry to the audit log. Rows are never updated or deleted.
+    pub fn log_event(&self, account_id: Option<i64>, kind: AuditKind, detail: &str) -> Result<(), PlayerDbError> {
+        self.conn.execute(
+            "INSERT INTO audit_log (account_id, kind, detail) VALUES (?1, ?2, ?3)",
+            rusqlite::params![account_id, kind.as_str(), detail],
+        )?;
+        Ok(())
+    }
+
+    /// The newest `limit` audit entries, newest first.
+    pub fn recent_events(&self, limit: usize) -> Result<Vec<AuditEvent>, PlayerDbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, account_id, kind, detail, created_at FROM audit_log ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        let mut events = Vec::new();
+        for row in rows {
+            let (id, account_id, kind, detail, created_at) = row?;
+            // Kinds written by a newer build are skipped rather than failing the read.
+            let Some(kind) = AuditKind::parse(&kind) else {
+                continue;
+            };
+            events.push(AuditEvent {
+                id,
+                account_id,
+                kind,
+                detail,
+                created_at,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Set or clear the recovery email of an account.
+    pub fn set_email(&self, id: i64, email: Option<&str>) -> Result<(), PlayerDbError> {
+        let rows = self.conn.execute(
+            "UPDATE accounts SET email = ?1 WHERE id = ?2",
+            rusqlite::params![email.map(str::trim), id],
+        )?;
+        if rows == 0 {
+            return Err(PlayerDbError::AccountNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Issue a one-time password recovery token valid for `RECOVERY_TOKEN_TTL`.
+    /// Any earlier unused tokens for the account are revoked. Only a hash of
+    /// the token is stored; the returned string is the only copy.
+    pub fn create_recovery_token(&self, id: i64) -> Result<String, PlayerDbError> {
+        self.create_recovery_token_with_ttl(id, RECOVERY_TOKEN_TTL)
+    }
+
+    /// `create_recovery_token` with an explicit lifetime.
+    pub fn create_recovery_token_with_ttl(&self, id: i64, ttl: Duration) -> Result<String, PlayerDbError> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM accounts WHERE id = ?1)",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(PlayerDbError::AccountNotFound(id.to_string()));
+        }
+
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM recovery_tokens WHERE account_id = ?1 AND used_at IS NULL",
+            rusqlite::params![id],
+        )?;
+        tx.execute(
+            "INSERT INTO recovery_tokens (token_hash, account_id, expires_at)
+             VALUES (?1, ?2, unixepoch() + ?3)",
+            rusqlite::params![hash_token(&token), id, ttl.as_secs() as i64],
+        )?;
+        tx.commit()?;
+        Ok(token)
+    }
+
+    /// Redeem a recovery token, returning the account id it was issued for.
+    /// A token works once; expired and already-used tokens are rejected.
+    pub fn consume_recovery_token(&self, token: &str) -> Result<i64, PlayerDbError> {
+        let token_hash = hash_token(token.trim());
+        let row = self.conn.query_row(
+            "SELECT account_id, expires_at <= unixepoch(), used_at IS NOT NULL
+             FROM recovery_tokens WHERE token_hash = ?1",
+            rusqlite::params![token_hash],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?, row.get::<_, bool>(2)?)),
+        );
+        let (account_id, expired, used) = match row {
+            Ok(row) => row,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(PlayerDbError::InvalidRecoveryToken);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if used {
+            return Err(PlayerDbError::RecoveryTokenUsed);
+        }
+        if expired {
+            return Err(PlayerDbError::RecoveryTokenExpired);
+        }
+
+        // Guarded on used_at so a concurrent redeem can't succeed twice.
+        let rows = self.conn.execute(
+            "UPDATE recovery_tokens SET used_at = unixepoch() WHERE token_hash = ?1 AND used_at IS NULL",
+            rusqlite::params![token_hash],
+        )?;
+        if rows == 0 {
+            return Err(PlayerDbError::RecoveryTokenUsed);
+        }
+        Ok(account_id)
+    }
 }
 
-fn verify_password(password: &str, hash: &str) -> Result<(), PlayerDbError> {
-    let parsed = PasswordHash::new(hash).map_err(|e| PlayerDbError::HashError(e.to_string()))?;
-    Argon2::default()
-        .verify_password(password.as_bytes(), &parsed)
-        .map_err(|_| PlayerDbError::InvalidPassword)
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
@@ -1,7 +1,7 @@
-use rusqlite::Connection;
 use serde_json::Value;
 
 use crate::error::PlayerDbError;
+use crate::pool::PooledConnection;
 
 /// A character record from the database.
 #[derive(Debug, Clone)]
@@ -10,6 +10,9 @@ pub struct CharacterRecord {
     pub account_id: i64,
     pub name: String,
     pub components: Value,
+    /// Free-form state owned by game scripts (quest flags, counters, ...).
+    /// Stored separately from `components` so engine saves never clobber it.
+    pub script_data: Value,
     pub room_id: Option<u64>,
     pub position_x: Option<i32>,
     pub position_y: Option<i32>,
@@ -17,13 +20,71 @@ pub struct CharacterRecord {
     pub last_played: Option<String>,
 }
 
+/// A character row as stored, before its JSON columns are parsed.
+struct CharacterRow {
+    id: i64,
+    account_id: i64,
+    name: String,
+    components: String,
+    room_id: Option<u64>,
+    position_x: Option<i32>,
+    position_y: Option<i32>,
+    created_at: String,
+    last_played: Option<String>,
+    script_data: String,
+}
+
+impl CharacterRow {
+    /// Read a row selected as `id, account_id, name, components, room_id,
+    /// position_x, position_y, created_at, last_played, script_data`.
+    fn read(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            name: row.get(2)?,
+            components: row.get(3)?,
+            room_id: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
+            position_x: row.get(5)?,
+            position_y: row.get(6)?,
+            created_at: row.get(7)?,
+            last_played: row.get(8)?,
+            script_data: row.get(9)?,
+        })
+    }
+
+    /// Parse the JSON columns. A corrupt column is an error rather than an
+    /// empty value, so a later save can't overwrite the stored data.
+    fn into_record(self) -> Result<CharacterRecord, PlayerDbError> {
+        let id = self.id;
+        let parse = |column: &'static str, text: &str| {
+            serde_json::from_str(text).map_err(|e| PlayerDbError::CorruptCharacterData {
+                id,
+                column,
+                reason: e.to_string(),
+            })
+        };
+        Ok(CharacterRecord {
+            id,
+            account_id: self.account_id,
+            components: parse("components", &self.components)?,
+            script_data: parse("script_data", &self.script_data)?,
+            name: self.name,
+            room_id: self.room_id,
+            position_x: self.position_x,
+            position_y: self.position_y,
+            created_at: self.created_at,
+            last_played: self.last_played,
+        })
+    }
+}
+
 /// Repository for character operations.
 pub struct CharacterRepo<'a> {
-    conn: &'a Connection,
+    conn: PooledConnection<'a>,
 }
 
 impl<'a> CharacterRepo<'a> {
-    pub(crate) fn new(conn: &'a Connection) -> Self {
+    pub(crate) fn new(conn: PooledConnection<'a>) -> Self {
         Self { conn }
     }
 
@@ -54,6 +115,7 @@ impl<'a> CharacterRepo<'a> {
             account_id,
             name: name.to_string(),
             components: default_components.clone(),
+            script_data: Value::Object(Default::default()),
             room_id: None,
             position_x: None,
             position_y: None,
@@ -65,55 +127,30 @@ impl<'a> CharacterRepo<'a> {
     /// List all characters for an account.
     pub fn list_for_account(&self, account_id: i64) -> Result<Vec<CharacterRecord>, PlayerDbError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played
+            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played, script_data
              FROM characters WHERE account_id = ?1 ORDER BY id",
         )?;
 
-        let records = stmt
-            .query_map(rusqlite::params![account_id], |row| {
-                let components_str: String = row.get(3)?;
-                Ok(CharacterRecord {
-                    id: row.get(0)?,
-                    account_id: row.get(1)?,
-                    name: row.get(2)?,
-                    components: serde_json::from_str(&components_str).unwrap_or(Value::Object(Default::default())),
-                    room_id: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
-                    position_x: row.get(5)?,
-                    position_y: row.get(6)?,
-                    created_at: row.get(7)?,
-                    last_played: row.get(8)?,
-                })
-            })?
+        let rows = stmt
+            .query_map(rusqlite::params![account_id], CharacterRow::read)?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(records)
+        rows.into_iter().map(CharacterRow::into_record).collect()
     }
 
     /// Load a character by ID.
     pub fn load(&self, id: i64) -> Result<CharacterRecord, PlayerDbError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played
+            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played, script_data
              FROM characters WHERE id = ?1",
         )?;
 
-        stmt.query_row(rusqlite::params![id], |row| {
-            let components_str: String = row.get(3)?;
-            Ok(CharacterRecord {
-                id: row.get(0)?,
-                account_id: row.get(1)?,
-                name: row.get(2)?,
-                components: serde_json::from_str(&components_str).unwrap_or(Value::Object(Default::default())),
-                room_id: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
-                position_x: row.get(5)?,
-                position_y: row.get(6)?,
-                created_at: row.get(7)?,
-                last_played: row.get(8)?,
-            })
-        })
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => PlayerDbError::CharacterNotFound(id),
-            other => other.into(),
-        })
+        stmt.query_row(rusqlite::params![id], CharacterRow::read)
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => PlayerDbError::CharacterNotFound(id),
+                other => other.into(),
+            })?
+            .into_record()
     }
 
     /// Save character state (components JSON, position).
@@ -143,6 +180,24 @@ impl<'a> CharacterRepo<'a> {
         Ok(())
     }
 
+    /// Save the script-owned data blob for a character.
+    ///
+    /// Independent from `save_state`: neither call touches the other's column.
+    pub fn save_script_data(&self, id: i64, script_data: &Value) -> Result<(), PlayerDbError> {
+        let data_str = serde_json::to_string(script_data)
+            .unwrap_or_else(|_| "{}".to_string());
+
+        let rows = self.conn.execute(
+            "UPDATE characters SET script_data = ?1 WHERE id = ?2",
+            rusqlite::params![data_str, id],
+        )?;
+
+        if rows == 0 {
+            return Err(PlayerDbError::CharacterNotFound(id));
+        }
+        Ok(())
+    }
+
     /// Delete a character by ID.
     pub fn delete(&self, id: i64) -> Result<(), PlayerDbError> {
         let rows = self.conn.execute(
@@ -155,28 +210,63 @@ impl<'a> CharacterRepo<'a> {
         Ok(())
     }
 
+    /// Move a character to another account (admin operation). Only
+    /// `account_id` changes; components, script data and position are kept.
+    ///
+    /// Fails with `AccountNotFound` if the target account does not exist and
+    /// with `CharacterNameTaken` if it already owns a character of the same
+    /// name (case-insensitive). Names are globally unique in the current
+    /// schema, so the latter only guards databases without that constraint.
+    pub fn transfer(&self, id: i64, new_account_id: i64) -> Result<(), PlayerDbError> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let name: String = tx
+            .query_row(
+                "SELECT name FROM characters WHERE id = ?1",
+                rusqlite::params![id],
+                |row| row.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => PlayerDbError::CharacterNotFound(id),
+                other => other.into(),
+            })?;
+
+        let account_exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM accounts WHERE id = ?1)",
+            rusqlite::params![new_account_id],
+            |row| row.get(0),
+        )?;
+        if !account_exists {
+            return Err(PlayerDbError::AccountNotFound(new_account_id.to_string()));
+        }
+
+        let collides: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM characters
+                           WHERE account_id = ?1 AND name = ?2 COLLATE NOCASE AND id != ?3)",
+            rusqlite::params![new_account_id, name, id],
+            |row| row.get(0),
+        )?;
+        if collides {
+            return Err(PlayerDbError::CharacterNameTaken(name));
+        }
+
+        tx.execute(
+            "UPDATE characters SET account_id = ?1 WHERE id = ?2",
+            rusqlite::params![new_account_id, id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
     /// Get a character by name (case-insensitive).
     pub fn get_by_name(&self, name: &str) -> Result<Option<CharacterRecord>, PlayerDbError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played
+            "SELECT id, account_id, name, components, room_id, position_x, position_y, created_at, last_played, script_data
              FROM characters WHERE name = ?1",
         )?;
 
-        match stmt.query_row(rusqlite::params![name], |row| {
-            let components_str: String = row.get(3)?;
-            Ok(CharacterRecord {
-                id: row.get(0)?,
-                account_id: row.get(1)?,
-                name: row.get(2)?,
-                components: serde_json::from_str(&components_str).unwrap_or(Value::Object(Default::default())),
-                room_id: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
-                position_x: row.get(5)?,
-                position_y: row.get(6)?,
-                created_at: row.get(7)?,
-                last_played: row.get(8)?,
-            })
-        }) {
-            Ok(record) => Ok(Some(record)),
+        match stmt.query_row(rusqlite::params![name], CharacterRow::read) {
+            Ok(row) => row.into_record().map(Some),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
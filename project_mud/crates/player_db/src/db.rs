@@ -1,18 +1,46 @@
+use std::time::Duration;
+
 use rusqlite::Connection;
 
 use crate::account::AccountRepo;
 use crate::character::CharacterRepo;
 use crate::error::PlayerDbError;
-use crate::schema;
+use crate::hashing::HashParams;
+use crate::migrations;
+use crate::pool::ConnectionPool;
+#[cfg(test)]
+use crate::pool::PooledConnection;
+
+/// Connections opened for a file-backed database.
+pub const DEFAULT_POOL_SIZE: usize = 4;
 
-/// Main database handle wrapping a SQLite connection.
+/// How long a connection waits on another writer's lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Main database handle over a small pool of SQLite connections.
+///
+/// File databases run in WAL mode, so readers don't block on a writer and
+/// concurrent logins don't serialize on one connection. The handle is
+/// `Sync`; share it by reference (or `Arc`) across threads. Each
+/// `account()`/`character()` repository holds one connection until dropped.
 pub struct PlayerDb {
-    conn: Connection,
+    pool: ConnectionPool,
+    hash_params: HashParams,
 }
 
 impl PlayerDb {
-    /// Open (or create) a database at the given file path.
-    pub fn open(path: &str) -> Result<Self, PlayerDbError> {
+    /// Open (or create) a database at the given file path. New password
+    /// hashes use `hash_params`.
+    pub fn open(path: &str, hash_params: HashParams) -> Result<Self, PlayerDbError> {
+        Self::open_with_pool_size(path, DEFAULT_POOL_SIZE, hash_params)
+    }
+
+    /// Open (or create) a database with `pool_size` connections (at least one).
+    pub fn open_with_pool_size(
+        path: &str,
+        pool_size: usize,
+        hash_params: HashParams,
+    ) -> Result<Self, PlayerDbError> {
         // Ensure parent directory exists
         if let Some(parent) = std::path::Path::new(path).parent() {
             if !parent.exists() {
@@ -25,27 +53,65 @@ impl PlayerDb {
             }
         }
 
-        let conn = Connection::open(path)?;
-        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
-        schema::create_tables(&conn)?;
-        Ok(Self { conn })
+        let first = Connection::open(path)?;
+        first.busy_timeout(BUSY_TIMEOUT)?;
+        first.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
+        migrations::migrate(&first)?;
+
+        let mut connections = vec![first];
+        for _ in 1..pool_size.max(1) {
+            let conn = Connection::open(path)?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+            connections.push(conn);
+        }
+        Ok(Self {
+            pool: ConnectionPool::new(connections),
+            hash_params,
+        })
     }
 
-    /// Open an in-memory database (for testing).
+    /// Open an in-memory database (for testing) with default hash parameters.
+    /// In-memory databases are private to their connection, so the pool
+    /// holds exactly one.
     pub fn open_memory() -> Result<Self, PlayerDbError> {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
-        schema::create_tables(&conn)?;
-        Ok(Self { conn })
+        migrations::migrate(&conn)?;
+        Ok(Self {
+            pool: ConnectionPool::new(vec![conn]),
+            hash_params: HashParams::default(),
+        })
+    }
+
+    /// Parameters used for new password hashes.
+    pub fn hash_params(&self) -> HashParams {
+        self.hash_params
+    }
+
+    /// Number of pooled connections.
+    pub fn pool_size(&self) -> usize {
+        self.pool.size()
+    }
+
+    /// Applied schema version (see `migrations`).
+    pub fn schema_version(&self) -> Result<u32, PlayerDbError> {
+        migrations::current_version(&self.pool.get())
     }
 
     /// Get account repository.
     pub fn account(&self) -> AccountRepo<'_> {
-        AccountRepo::new(&self.conn)
+        AccountRepo::new(self.pool.get(), self.hash_params)
     }
 
     /// Get character repository.
     pub fn character(&self) -> CharacterRepo<'_> {
-        CharacterRepo::new(&self.conn)
+        CharacterRepo::new(self.pool.get())
+    }
+
+    /// Raw connection, for tests that tamper with the schema.
+    #[cfg(test)]
+    pub(crate) fn conn(&self) -> PooledConnection<'_> {
+        self.pool.get()
     }
 }
@@ -14,12 +14,27 @@ pub enum PlayerDbError {
     #[error("invalid password")]
     InvalidPassword,
 
+    #[error("account is banned: {0}")]
+    AccountBanned(String),
+
     #[error("character name already taken: {0}")]
     CharacterNameTaken(String),
 
     #[error("character not found: {0}")]
     CharacterNotFound(i64),
 
+    #[error("character {id} has corrupt {column} data: {reason}")]
+    CorruptCharacterData { id: i64, column: &'static str, reason: String },
+
     #[error("password hashing error: {0}")]
     HashError(String),
+
+    #[error("invalid recovery token")]
+    InvalidRecoveryToken,
+
+    #[error("recovery token expired")]
+    RecoveryTokenExpired,
+
+    #[error("recovery token already used")]
+    RecoveryTokenUsed,
 }
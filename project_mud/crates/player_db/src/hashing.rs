@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant};
+
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use password_hash::rand_core::OsRng;
+use password_hash::SaltString;
+
+use crate::error::PlayerDbError;
+
+/// Upper bound on the iteration count `HashParams::calibrate` will pick.
+pub const MAX_CALIBRATED_ITERATIONS: u32 = 64;
+
+/// Argon2id cost parameters for new password hashes.
+///
+/// Each hash is stored in PHC format (`$argon2id$v=19$m=..,t=..,p=..$salt$hash`),
+/// which carries the parameters it was made with, so existing hashes keep
+/// verifying after the configured parameters change. Accounts whose hash is
+/// cheaper than the current parameters are rehashed on their next successful
+/// login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashParams {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Time cost (passes over memory).
+    pub iterations: u32,
+    /// Degree of parallelism (lanes).
+    pub parallelism: u32,
+}
+
+impl Default for HashParams {
+    /// The argon2 crate's recommended defaults (19 MiB, 2 passes, 1 lane).
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl HashParams {
+    /// Validated parameters; argon2's own limits apply (e.g. memory must be
+    /// at least 8 KiB per lane).
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self, PlayerDbError> {
+        let params = Self {
+            memory_kib,
+            iterations,
+            parallelism,
+        };
+        params.argon2()?;
+        Ok(params)
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, PlayerDbError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| PlayerDbError::HashError(e.to_string()))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// Hash `password` with a fresh salt.
+    pub fn hash(&self, password: &str) -> Result<String, PlayerDbError> {
+        let salt = SaltString::generate(&mut OsRng);
+        self.argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|h| h.to_string())
+            .map_err(|e| PlayerDbError::HashError(e.to_string()))
+    }
+
+    /// Parameters a stored hash was made with.
+    pub fn of_hash(hash: &str) -> Result<Self, PlayerDbError> {
+        let parsed = PasswordHash::new(hash).map_err(|e| PlayerDbError::HashError(e.to_string()))?;
+        let params = Params::try_from(&parsed).map_err(|e| PlayerDbError::HashError(e.to_string()))?;
+        Ok(Self {
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+        })
+    }
+
+    /// Whether a stored hash is cheaper than these parameters (less memory or
+    /// fewer iterations), or can't be parsed, and should be replaced on the
+    /// next successful login. Calibrated parameters vary from run to run, so
+    /// a hash at least as costly as the current ones is kept.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        Self::of_hash(hash).map_or(true, |params| {
+            params.memory_kib < self.memory_kib || params.iterations < self.iterations
+        })
+    }
+
+    /// Wall time of hashing one password with these parameters on this host.
+    pub fn measure(&self) -> Result<Duration, PlayerDbError> {
+        let start = Instant::now();
+        self.hash("calibration-password")?;
+        Ok(start.elapsed())
+    }
+
+    /// Pick the iteration count that keeps one hash within `target` on this
+    /// host, for the given memory and parallelism. Never goes below one
+    /// iteration, so the result may still exceed a very small target; check
+    /// `measure` if that matters.
+    pub fn calibrate(target: Duration, memory_kib: u32, parallelism: u32) -> Result<Self, PlayerDbError> {
+        let mut params = Self::new(memory_kib, 1, parallelism)?;
+        let one_pass = params.measure()?.as_nanos().max(1);
+        // Hashing time grows roughly linearly with the iteration count.
+        let estimate = (target.as_nanos() / one_pass).clamp(1, MAX_CALIBRATED_ITERATIONS as u128);
+        params.iterations = estimate as u32;
+        while params.iterations > 1 && params.measure()? > target {
+            params.iterations -= 1;
+        }
+        Ok(params)
+    }
+}
+
+/// Check `password` against a stored hash, using the parameters recorded in
+/// the hash rather than the current configuration.
+pub(crate) fn verify_password(password: &str, hash: &str) -> Result<(), PlayerDbError> {
+    let parsed = PasswordHash::new(hash).map_err(|e| PlayerDbError::HashError(e.to_string()))?;
+    Argon2::default()
+        .verify_password(password.as_bytes(), &parsed)
+        .map_err(|_| PlayerDbError::InvalidPassword)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cheap(iterations: u32) -> HashParams {
+        HashParams::new(64, iterations, 1).unwrap()
+    }
+
+    #[test]
+    fn hash_records_its_params_and_verifies() {
+        let params = cheap(3);
+        let hash = params.hash("hunter2").unwrap();
+        assert!(hash.contains("m=64,t=3,p=1"), "{}", hash);
+        assert_eq!(HashParams::of_hash(&hash).unwrap(), params);
+        assert!(verify_password("hunter2", &hash).is_ok());
+        assert!(matches!(verify_password("wrong", &hash), Err(PlayerDbError::InvalidPassword)));
+    }
+
+    #[test]
+    fn old_hashes_verify_after_cost_change() {
+        let old = cheap(1).hash("hunter2").unwrap();
+        let current = cheap(4);
+        assert!(verify_password("hunter2", &old).is_ok());
+        assert!(current.needs_rehash(&old));
+        assert!(!current.needs_rehash(&current.hash("hunter2").unwrap()));
+        assert!(current.needs_rehash("not a phc string"));
+    }
+
+    #[test]
+    fn costlier_hashes_are_kept() {
+        let strong = cheap(4).hash("hunter2").unwrap();
+        assert!(!cheap(2).needs_rehash(&strong));
+        assert!(!HashParams::new(64, 4, 2).unwrap().needs_rehash(&strong));
+        assert!(HashParams::new(128, 1, 1).unwrap().needs_rehash(&strong));
+    }
+
+    #[test]
+    fn invalid_params_rejected() {
+        assert!(HashParams::new(64, 0, 1).is_err());
+        assert!(HashParams::new(1, 1, 1).is_err());
+        assert!(HashParams::new(64, 1, 0).is_err());
+    }
+
+    #[test]
+    fn calibrate_stays_within_bounds() {
+        let params = HashParams::calibrate(Duration::from_millis(20), 64, 1).unwrap();
+        assert!((1..=MAX_CALIBRATED_ITERATIONS).contains(&params.iterations));
+        assert_eq!((params.memory_kib, params.parallelism), (64, 1));
+
+        let floor = HashParams::calibrate(Duration::ZERO, 64, 1).unwrap();
+        assert_eq!(floor.iterations, 1);
+    }
+}
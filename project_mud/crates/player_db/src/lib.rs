@@ -2,12 +2,15 @@ pub mod account;
 pub mod character;
 pub mod db;
 pub mod error;
-mod schema;
+pub mod hashing;
+mod migrations;
+mod pool;
 
-pub use account::{Account, AccountRepo, PermissionLevel};
+pub use account::{Account, AccountRepo, AuditEvent, AuditKind, PermissionLevel, RECOVERY_TOKEN_TTL};
 pub use character::CharacterRecord;
 pub use db::PlayerDb;
 pub use error::PlayerDbError;
+pub use hashing::HashParams;
 
 #[cfg(test)]
 mod tests {
@@ -85,6 +88,99 @@ mod tests {
         assert_eq!(loaded.permission, PermissionLevel::Admin);
     }
 
+    #[test]
+    fn failed_and_successful_logins_are_audited() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Hero", "secret123").unwrap();
+        assert!(db.account().authenticate("Hero", "wrongpass").is_err());
+        assert!(db.account().authenticate("Ghost", "pass").is_err());
+        db.account().authenticate("Hero", "secret123").unwrap();
+
+        let events = db.account().recent_events(10).unwrap();
+        let kinds: Vec<_> = events.iter().map(|e| (e.kind, e.account_id)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (AuditKind::Login, Some(account.id)),
+                (AuditKind::LoginFailed, None),
+                (AuditKind::LoginFailed, Some(account.id)),
+            ]
+        );
+        assert!(events[1].detail.contains("Ghost"));
+        assert_eq!(db.account().recent_events(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn permission_change_is_audited() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Admin", "pass").unwrap();
+        db.account()
+            .set_permission(account.id, PermissionLevel::Builder)
+            .unwrap();
+        assert!(matches!(
+            db.account().set_permission(999, PermissionLevel::Owner),
+            Err(PlayerDbError::AccountNotFound(_))
+        ));
+
+        let events = db.account().recent_events(10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, AuditKind::PermissionChange);
+        assert_eq!(events[0].account_id, Some(account.id));
+        assert_eq!(events[0].detail, "Player -> Builder");
+    }
+
+    #[test]
+    fn ban_refuses_login_and_is_audited() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Spammer", "pass").unwrap();
+        db.account().ban(account.id, "spamming").unwrap();
+        assert!(matches!(
+            db.account().ban(999, "nobody"),
+            Err(PlayerDbError::AccountNotFound(_))
+        ));
+        assert!(matches!(
+            db.account().authenticate("Spammer", "pass"),
+            Err(PlayerDbError::AccountBanned(_))
+        ));
+
+        let events = db.account().recent_events(10).unwrap();
+        let kinds: Vec<_> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![AuditKind::LoginFailed, AuditKind::Ban]);
+        assert_eq!(events[1].detail, "spamming");
+    }
+
+    #[test]
+    fn login_succeeds_when_audit_write_fails() {
+        let db = PlayerDb::open_memory().unwrap();
+        db.account().create("Hero", "secret123").unwrap();
+        db.conn()
+            .execute_batch(
+                "CREATE TRIGGER audit_log_no_insert BEFORE INSERT ON audit_log
+                 BEGIN SELECT RAISE(ABORT, 'audit log offline'); END;",
+            )
+            .unwrap();
+
+        assert!(db.account().authenticate("Hero", "secret123").is_ok());
+        // Failed logins still report the real reason
+        assert!(matches!(
+            db.account().authenticate("Hero", "wrongpass"),
+            Err(PlayerDbError::InvalidPassword)
+        ));
+    }
+
+    #[test]
+    fn log_event_records_caller_supplied_kinds() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Mod", "pass").unwrap();
+        db.account()
+            .log_event(Some(account.id), AuditKind::Ban, "spamming")
+            .unwrap();
+
+        let events = db.account().recent_events(10).unwrap();
+        assert_eq!(events[0].kind, AuditKind::Ban);
+        assert_eq!(events[0].detail, "spamming");
+    }
+
     #[test]
     fn create_character() {
         let db = PlayerDb::open_memory().unwrap();
@@ -169,6 +265,88 @@ mod tests {
         assert_eq!(loaded.position_y, Some(256));
     }
 
+    #[test]
+    fn save_and_load_nested_script_data() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Quester", "pass").unwrap();
+        let character = db
+            .character()
+            .create(account.id, "QuestHero", &json!({}))
+            .unwrap();
+        assert_eq!(character.script_data, json!({}));
+
+        let data = json!({
+            "quests": {"rat_hunt": {"stage": 2, "kills": [3, 5]}},
+            "flags": ["met_elder"]
+        });
+        db.character()
+            .save_script_data(character.id, &data)
+            .unwrap();
+
+        let loaded = db.character().load(character.id).unwrap();
+        assert_eq!(loaded.script_data, data);
+        assert_eq!(loaded.script_data["quests"]["rat_hunt"]["kills"][1], 5);
+
+        let listed = db.character().list_for_account(account.id).unwrap();
+        assert_eq!(listed[0].script_data, data);
+    }
+
+    #[test]
+    fn script_data_independent_from_components() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Keeper", "pass").unwrap();
+        let defaults = json!({"Health": {"current": 100, "max": 100}});
+        let character = db
+            .character()
+            .create(account.id, "KeepHero", &defaults)
+            .unwrap();
+
+        db.character()
+            .save_script_data(character.id, &json!({"gold_found": 7}))
+            .unwrap();
+        // Saving components must not clobber script data...
+        db.character()
+            .save_state(character.id, &json!({"Health": {"current": 50, "max": 100}}), None, None)
+            .unwrap();
+        let loaded = db.character().load(character.id).unwrap();
+        assert_eq!(loaded.script_data, json!({"gold_found": 7}));
+        assert_eq!(loaded.components["Health"]["current"], 50);
+        assert!(loaded.components.get("gold_found").is_none());
+
+        // ...and vice versa.
+        db.character()
+            .save_script_data(character.id, &json!({"gold_found": 8}))
+            .unwrap();
+        let loaded = db.character().load(character.id).unwrap();
+        assert_eq!(loaded.components["Health"]["current"], 50);
+        assert_eq!(loaded.script_data["gold_found"], 8);
+    }
+
+    #[test]
+    fn corrupt_json_column_is_an_error() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Broken", "pass").unwrap();
+        let character = db.character().create(account.id, "BrokenHero", &json!({})).unwrap();
+        db.conn()
+            .execute("UPDATE characters SET script_data = '{not json' WHERE id = ?1", [character.id])
+            .unwrap();
+
+        let err = db.character().load(character.id).unwrap_err();
+        assert!(
+            matches!(err, PlayerDbError::CorruptCharacterData { id, column: "script_data", .. } if id == character.id),
+            "{err}"
+        );
+        assert!(db.character().get_by_name("BrokenHero").is_err());
+        assert!(db.character().list_for_account(account.id).is_err());
+    }
+
+    #[test]
+    fn save_script_data_unknown_character() {
+        let db = PlayerDb::open_memory().unwrap();
+        let result = db.character().save_script_data(999, &json!({}));
+        assert!(matches!(result, Err(PlayerDbError::CharacterNotFound(999))));
+    }
+
     #[test]
     fn delete_character() {
         let db = PlayerDb::open_memory().unwrap();
@@ -183,6 +361,269 @@ mod tests {
         assert!(matches!(result, Err(PlayerDbError::CharacterNotFound(_))));
     }
 
+    #[test]
+    fn transfer_character_keeps_state() {
+        let db = PlayerDb::open_memory().unwrap();
+        let from = db.account().create("Seller", "pass").unwrap();
+        let to = db.account().create("Buyer", "pass").unwrap();
+        let character = db.character().create(from.id, "Wanderer", &json!({"Level": 7})).unwrap();
+        db.character()
+            .save_state(character.id, &json!({"Level": 8}), Some(42), None)
+            .unwrap();
+        db.character()
+            .save_script_data(character.id, &json!({"quest": "done"}))
+            .unwrap();
+
+        db.character().transfer(character.id, to.id).unwrap();
+
+        let loaded = db.character().load(character.id).unwrap();
+        assert_eq!(loaded.account_id, to.id);
+        assert_eq!(loaded.components, json!({"Level": 8}));
+        assert_eq!(loaded.script_data, json!({"quest": "done"}));
+        assert_eq!(loaded.room_id, Some(42));
+        assert!(db.character().list_for_account(from.id).unwrap().is_empty());
+        assert_eq!(db.character().list_for_account(to.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn transfer_to_missing_account_or_character_rejected() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Owner", "pass").unwrap();
+        let character = db.character().create(account.id, "Stay", &json!({})).unwrap();
+
+        let result = db.character().transfer(character.id, 9999);
+        assert!(matches!(result, Err(PlayerDbError::AccountNotFound(_))));
+        assert_eq!(db.character().load(character.id).unwrap().account_id, account.id);
+
+        let result = db.character().transfer(9999, account.id);
+        assert!(matches!(result, Err(PlayerDbError::CharacterNotFound(9999))));
+    }
+
+    #[test]
+    fn transfer_name_collision_rejected() {
+        // The current schema makes names globally unique, so build a table
+        // without that constraint to exercise the per-account guard.
+        let dir = std::env::temp_dir().join(format!("mud_test_player_db_transfer_collision_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("players.db");
+        let path = path.to_str().unwrap();
+        {
+            let conn = rusqlite::Connection::open(path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE accounts (
+                     id            INTEGER PRIMARY KEY AUTOINCREMENT,
+                     username      TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                     password_hash TEXT NOT NULL,
+                     permission    INTEGER NOT NULL DEFAULT 0,
+                     created_at    TEXT NOT NULL DEFAULT (datetime('now')),
+                     last_login    TEXT
+                 );
+                 CREATE TABLE characters (
+                     id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                     account_id  INTEGER NOT NULL REFERENCES accounts(id),
+                     name        TEXT NOT NULL COLLATE NOCASE,
+                     components  TEXT NOT NULL DEFAULT '{}',
+                     room_id     INTEGER,
+                     position_x  INTEGER,
+                     position_y  INTEGER,
+                     created_at  TEXT NOT NULL DEFAULT (datetime('now')),
+                     last_played TEXT
+                 );
+                 INSERT INTO accounts (username, password_hash) VALUES ('Alpha', 'x'), ('Beta', 'x');
+                 INSERT INTO characters (account_id, name) VALUES (1, 'Twin'), (2, 'twin');",
+            )
+            .unwrap();
+        }
+
+        let db = PlayerDb::open(path, HashParams::default()).unwrap();
+        let result = db.character().transfer(1, 2);
+        assert!(matches!(result, Err(PlayerDbError::CharacterNameTaken(ref n)) if n == "Twin"));
+        assert_eq!(db.character().load(1).unwrap().account_id, 1);
+        drop(db);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn hash_params_change_keeps_old_logins_working() {
+        let dir = std::env::temp_dir().join(format!("mud_test_player_db_hash_params_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("players.db");
+        let path = path.to_str().unwrap();
+        let stored_hash = || -> String {
+            rusqlite::Connection::open(path)
+                .unwrap()
+                .query_row("SELECT password_hash FROM accounts WHERE username = 'Vet'", [], |r| r.get(0))
+                .unwrap()
+        };
+
+        let old = HashParams::new(64, 1, 1).unwrap();
+        let db = PlayerDb::open(path, old).unwrap();
+        db.account().create("Vet", "secret").unwrap();
+        assert!(db.account().authenticate("Vet", "secret").is_ok());
+        drop(db);
+        assert_eq!(HashParams::of_hash(&stored_hash()).unwrap(), old);
+
+        // Raising the cost: the old hash still verifies, then is upgraded.
+        let new = HashParams::new(128, 3, 1).unwrap();
+        let db = PlayerDb::open(path, new).unwrap();
+        assert!(matches!(
+            db.account().authenticate("Vet", "wrong"),
+            Err(PlayerDbError::InvalidPassword)
+        ));
+        assert_eq!(HashParams::of_hash(&stored_hash()).unwrap(), old);
+        assert!(db.account().authenticate("Vet", "secret").is_ok());
+        assert_eq!(HashParams::of_hash(&stored_hash()).unwrap(), new);
+        assert!(db.account().authenticate("Vet", "secret").is_ok());
+        drop(db);
+
+        // Lowering it again (e.g. a slower calibration run) keeps the hash.
+        let db = PlayerDb::open(path, old).unwrap();
+        assert!(db.account().authenticate("Vet", "secret").is_ok());
+        assert_eq!(HashParams::of_hash(&stored_hash()).unwrap(), new);
+        drop(db);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn login_succeeds_when_rehash_fails() {
+        let dir = std::env::temp_dir().join(format!("mud_test_player_db_rehash_fails_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("players.db");
+        let path = path.to_str().unwrap();
+
+        let old = HashParams::new(64, 1, 1).unwrap();
+        PlayerDb::open(path, old).unwrap().account().create("Vet", "secret").unwrap();
+
+        let db = PlayerDb::open(path, HashParams::new(128, 2, 1).unwrap()).unwrap();
+        db.conn()
+            .execute_batch(
+                "CREATE TRIGGER accounts_no_rehash BEFORE UPDATE OF password_hash ON accounts
+                 BEGIN SELECT RAISE(ABORT, 'read-only hashes'); END;",
+            )
+            .unwrap();
+        assert!(db.account().authenticate("Vet", "secret").is_ok());
+        let hash: String = db
+            .conn()
+            .query_row("SELECT password_hash FROM accounts WHERE username = 'Vet'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(HashParams::of_hash(&hash).unwrap(), old);
+        drop(db);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn set_and_clear_email() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Mailer", "pass").unwrap();
+        assert_eq!(account.email, None);
+
+        db.account().set_email(account.id, Some(" hero@example.com ")).unwrap();
+        let loaded = db.account().get_by_username("Mailer").unwrap().unwrap();
+        assert_eq!(loaded.email.as_deref(), Some("hero@example.com"));
+        let authed = db.account().authenticate("Mailer", "pass").unwrap();
+        assert_eq!(authed.email.as_deref(), Some("hero@example.com"));
+
+        db.account().set_email(account.id, None).unwrap();
+        assert_eq!(db.account().get_by_username("Mailer").unwrap().unwrap().email, None);
+
+        assert!(matches!(
+            db.account().set_email(9999, Some("x@example.com")),
+            Err(PlayerDbError::AccountNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn recovery_token_consumed_once() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Forgetful", "pass").unwrap();
+
+        let token = db.account().create_recovery_token(account.id).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, db.account().create_recovery_token(account.id).unwrap());
+
+        // Issuing a new token revoked the first one
+        assert!(matches!(
+            db.account().consume_recovery_token(&token),
+            Err(PlayerDbError::InvalidRecoveryToken)
+        ));
+
+        let token = db.account().create_recovery_token(account.id).unwrap();
+        assert_eq!(db.account().consume_recovery_token(&token).unwrap(), account.id);
+        assert!(matches!(
+            db.account().consume_recovery_token(&token),
+            Err(PlayerDbError::RecoveryTokenUsed)
+        ));
+    }
+
+    #[test]
+    fn recovery_token_expiry_and_unknown_rejected() {
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("Slowpoke", "pass").unwrap();
+
+        let token = db
+            .account()
+            .create_recovery_token_with_ttl(account.id, std::time::Duration::ZERO)
+            .unwrap();
+        assert!(matches!(
+            db.account().consume_recovery_token(&token),
+            Err(PlayerDbError::RecoveryTokenExpired)
+        ));
+        assert!(matches!(
+            db.account().consume_recovery_token("not-a-token"),
+            Err(PlayerDbError::InvalidRecoveryToken)
+        ));
+        assert!(matches!(
+            db.account().create_recovery_token(9999),
+            Err(PlayerDbError::AccountNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn concurrent_logins_share_the_pool() {
+        let dir = std::env::temp_dir().join(format!("mud_test_player_db_concurrent_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("players.db");
+        let db = PlayerDb::open(path.to_str().unwrap(), HashParams::default()).unwrap();
+        assert_eq!(db.pool_size(), db::DEFAULT_POOL_SIZE);
+
+        const USERS: usize = 6;
+        for i in 0..USERS {
+            db.account().create(&format!("user{}", i), "password123").unwrap();
+        }
+
+        // More threads than connections: some must wait for a checkout.
+        std::thread::scope(|scope| {
+            for i in 0..USERS {
+                let db = &db;
+                scope.spawn(move || {
+                    let account = db
+                        .account()
+                        .authenticate(&format!("user{}", i), "password123")
+                        .unwrap();
+                    let record = db
+                        .character()
+                        .create(account.id, &format!("hero{}", i), &json!({"Gold": i}))
+                        .unwrap();
+                    db.character()
+                        .save_state(record.id, &json!({"Gold": i * 10}), Some(1), None)
+                        .unwrap();
+                });
+            }
+        });
+
+        for i in 0..USERS {
+            let account = db.account().get_by_username(&format!("user{}", i)).unwrap().unwrap();
+            let chars = db.character().list_for_account(account.id).unwrap();
+            assert_eq!(chars.len(), 1);
+            assert_eq!(chars[0].components, json!({"Gold": i * 10}));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn permission_level_ordering() {
         assert!(PermissionLevel::Player < PermissionLevel::Builder);
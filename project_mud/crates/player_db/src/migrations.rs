@@ -0,0 +1,304 @@
+//! Versioned schema upgrades.
+//!
+//! The applied version is stored in `schema_version`. Opening a database runs
+//! every step newer than that version, in order, each in its own transaction.
+//! Steps are append-only: never edit a released step, add a new one instead.
+
+use rusqlite::Connection;
+
+use crate::error::PlayerDbError;
+
+/// Extra step logic run after a migration's SQL.
+pub(crate) type Fixup = fn(&Connection) -> Result<(), PlayerDbError>;
+
+/// One schema upgrade step.
+pub(crate) struct Migration {
+    pub version: u32,
+    pub sql: &'static str,
+    /// Runs after `sql`, in the same transaction, for changes SQLite can't
+    /// express conditionally (e.g. adding a column only if it is missing).
+    pub fixup: Option<Fixup>,
+}
+
+/// All schema steps, in ascending version order.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        sql: "
+            CREATE TABLE IF NOT EXISTS accounts (
+                id            INTEGER PRIMARY KEY AUTOINCREMENT,
+                username      TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                password_hash TEXT NOT NULL,
+                permission    INTEGER NOT NULL DEFAULT 0,
+                created_at    TEXT NOT NULL DEFAULT (datetime('now')),
+                last_login    TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS characters (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id  INTEGER NOT NULL REFERENCES accounts(id),
+                name        TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                components  TEXT NOT NULL DEFAULT '{}',
+                room_id     INTEGER,
+                position_x  INTEGER,
+                position_y  INTEGER,
+                created_at  TEXT NOT NULL DEFAULT (datetime('now')),
+                last_played TEXT,
+                script_data TEXT NOT NULL DEFAULT '{}'
+            );
+        ",
+        fixup: Some(add_script_data_column),
+    },
+    Migration {
+        version: 2,
+        sql: "
+            ALTER TABLE accounts ADD COLUMN email TEXT;
+
+            CREATE TABLE IF NOT EXISTS recovery_tokens (
+                token_hash  TEXT PRIMARY KEY,
+                account_id  INTEGER NOT NULL REFERENCES accounts(id),
+                expires_at  INTEGER NOT NULL,
+                used_at     INTEGER
+            );
+        ",
+        fixup: None,
+    },
+    Migration {
+        version: 3,
+        sql: "
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id  INTEGER,
+                kind        TEXT NOT NULL,
+                detail      TEXT NOT NULL DEFAULT '',
+                created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+        ",
+        fixup: None,
+    },
+    Migration {
+        version: 4,
+        sql: "
+            ALTER TABLE accounts ADD COLUMN banned INTEGER NOT NULL DEFAULT 0;
+        ",
+        fixup: None,
+    },
+];
+
+/// Schema version a fully migrated database reports.
+pub(crate) fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Bring the database up to the latest schema. Returns the resulting version.
+pub(crate) fn migrate(conn: &Connection) -> Result<u32, PlayerDbError> {
+    let version = apply(conn, MIGRATIONS)?;
+    if version > latest_version() {
+        tracing::warn!(
+            version,
+            latest = latest_version(),
+            "player_db schema is newer than this build"
+        );
+    }
+    Ok(version)
+}
+
+/// Current schema version (0 for a database that predates versioning).
+pub(crate) fn current_version(conn: &Connection) -> Result<u32, PlayerDbError> {
+    conn.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL);")?;
+    let version: Option<u32> = conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+        row.get(0)
+    })?;
+    Ok(version.unwrap_or(0))
+}
+
+fn apply(conn: &Connection, steps: &[Migration]) -> Result<u32, PlayerDbError> {
+    let start = current_version(conn)?;
+    let mut version = start;
+    for step in steps.iter().filter(|m| m.version > start) {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(step.sql)?;
+        if let Some(fixup) = step.fixup {
+            fixup(&tx)?;
+        }
+        tx.execute("INSERT INTO schema_version (version) VALUES (?1)", [step.version])?;
+        tx.commit()?;
+        tracing::info!(from = version, to = step.version, "player_db schema migrated");
+        version = step.version;
+    }
+    Ok(version)
+}
+
+/// Databases created before versioning may predate `characters.script_data`,
+/// and `CREATE TABLE IF NOT EXISTS` leaves their table as it was.
+fn add_script_data_column(conn: &Connection) -> Result<(), PlayerDbError> {
+    add_column_if_missing(conn, "characters", "script_data", "TEXT NOT NULL DEFAULT '{}'")
+}
+
+/// Add a column to a table created by an older build of this schema.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), PlayerDbError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            table, column, definition
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HashParams, PlayerDb};
+
+    /// Tables as created before schema versioning (and before script_data).
+    const VERSION_0_SCHEMA: &str = "
+        CREATE TABLE accounts (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            username      TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            password_hash TEXT NOT NULL,
+            permission    INTEGER NOT NULL DEFAULT 0,
+            created_at    TEXT NOT NULL DEFAULT (datetime('now')),
+            last_login    TEXT
+        );
+        CREATE TABLE characters (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id  INTEGER NOT NULL REFERENCES accounts(id),
+            name        TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            components  TEXT NOT NULL DEFAULT '{}',
+            room_id     INTEGER,
+            position_x  INTEGER,
+            position_y  INTEGER,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now')),
+            last_played TEXT
+        );
+        INSERT INTO accounts (username, password_hash) VALUES ('Veteran', 'x');
+        INSERT INTO characters (account_id, name) VALUES (1, 'OldHero');
+    ";
+
+    #[test]
+    fn fresh_database_is_at_latest_version() {
+        let db = PlayerDb::open_memory().unwrap();
+        assert_eq!(db.schema_version().unwrap(), latest_version());
+    }
+
+    #[test]
+    fn version_0_database_upgrades_on_open() {
+        let dir = std::env::temp_dir().join(format!("mud_test_player_db_migrate_v0_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("players.db");
+        let path = path.to_str().unwrap();
+
+        {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(VERSION_0_SCHEMA).unwrap();
+        }
+
+        let db = PlayerDb::open(path, HashParams::default()).unwrap();
+        assert_eq!(db.schema_version().unwrap(), latest_version());
+
+        // Existing rows survive and the added column is usable
+        let chars = db.character().list_for_account(1).unwrap();
+        assert_eq!(chars.len(), 1);
+        assert_eq!(chars[0].name, "OldHero");
+        db.character()
+            .save_script_data(chars[0].id, &serde_json::json!({"quest": 1}))
+            .unwrap();
+        drop(db);
+
+        // Re-opening is a no-op
+        let db = PlayerDb::open(path, HashParams::default()).unwrap();
+        assert_eq!(db.schema_version().unwrap(), latest_version());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn only_newer_steps_are_applied() {
+        let conn = Connection::open_in_memory().unwrap();
+        let latest = latest_version();
+        assert_eq!(migrate(&conn).unwrap(), latest);
+
+        let steps = [
+            Migration {
+                version: latest,
+                sql: "THIS WOULD FAIL IF RE-RUN;",
+                fixup: None,
+            },
+            Migration {
+                version: latest + 1,
+                sql: "ALTER TABLE accounts ADD COLUMN motto TEXT;",
+                fixup: None,
+            },
+        ];
+        assert_eq!(apply(&conn, &steps).unwrap(), latest + 1);
+        assert_eq!(apply(&conn, &steps).unwrap(), latest + 1);
+        assert_eq!(current_version(&conn).unwrap(), latest + 1);
+    }
+
+    #[test]
+    fn audit_log_is_append_only() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        conn.execute("INSERT INTO audit_log (kind) VALUES ('ban')", []).unwrap();
+
+        assert!(conn.execute("UPDATE audit_log SET detail = 'edited'", []).is_err());
+        assert!(conn.execute("DELETE FROM audit_log", []).is_err());
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn failed_step_rolls_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+
+        let steps = [Migration {
+            version: latest_version() + 1,
+            sql: "ALTER TABLE accounts ADD COLUMN note TEXT; BOGUS SQL;",
+            fixup: None,
+        }];
+        assert!(apply(&conn, &steps).is_err());
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        // The partial ALTER was rolled back too
+        assert!(conn.prepare("SELECT note FROM accounts").is_err());
+    }
+
+    #[test]
+    fn failing_fixup_rolls_back_its_step() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+
+        let steps = [Migration {
+            version: latest_version() + 1,
+            sql: "ALTER TABLE accounts ADD COLUMN note TEXT;",
+            fixup: Some(|conn| {
+                conn.execute_batch("BOGUS SQL;")?;
+                Ok(())
+            }),
+        }];
+        assert!(apply(&conn, &steps).is_err());
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(conn.prepare("SELECT note FROM accounts").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex};
+
+use rusqlite::Connection;
+
+/// A fixed set of SQLite connections handed out one per repository.
+///
+/// Checkout blocks until a connection is idle, so at most `size` queries run
+/// at once and a slow one (e.g. password hashing during login) doesn't stall
+/// every other caller behind a single connection.
+pub(crate) struct ConnectionPool {
+    idle: Mutex<Vec<Connection>>,
+    available: Condvar,
+    size: usize,
+}
+
+impl ConnectionPool {
+    pub(crate) fn new(connections: Vec<Connection>) -> Self {
+        let size = connections.len();
+        Self {
+            idle: Mutex::new(connections),
+            available: Condvar::new(),
+            size,
+        }
+    }
+
+    /// Check out a connection, waiting for one to be returned if all are in use.
+    pub(crate) fn get(&self) -> PooledConnection<'_> {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(conn) = idle.pop() {
+                return PooledConnection {
+                    pool: self,
+                    conn: Some(conn),
+                };
+            }
+            idle = self.available.wait(idle).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// A checked-out connection; returned to the pool on drop.
+pub(crate) struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let mut idle = self.pool.idle.lock().unwrap_or_else(|e| e.into_inner());
+            idle.push(conn);
+            self.pool.available.notify_one();
+        }
+    }
+}
@@ -65,6 +65,22 @@ function broadcast_room(room_id, text, exclude_eid)
     end
 end
 
+--- Whether a room has space for one more character. Rooms without a
+--- RoomCapacity component are unlimited; items don't count.
+function room_has_space(room_id)
+    local capacity = ecs:get(room_id, "RoomCapacity")
+    if not capacity then
+        return true
+    end
+    local count = 0
+    for _, occ in ipairs(space:room_occupants(room_id)) do
+        if ecs:has(occ, "PlayerTag") or ecs:has(occ, "NpcTag") then
+            count = count + 1
+        end
+    end
+    return count < capacity
+end
+
 --- Format exits for a room in deterministic order (북, 남, 동, 서).
 function format_exits(room_id)
     local exits_table = space:exits(room_id)
@@ -139,6 +155,39 @@ function format_room(room_id, viewer)
     return table.concat(lines, "\n")
 end
 
+--- Find an entity the viewer can see by (partial, case-insensitive) name:
+--- room occupants first, then the viewer's inventory.
+function find_visible_entity(viewer, room_id, query)
+    local needle = string.lower(query)
+    local function matches(eid)
+        local name = ecs:get(eid, "Name")
+        return name and string.find(string.lower(name), needle, 1, true)
+    end
+    for _, occ in ipairs(space:room_occupants(room_id)) do
+        if occ ~= room_id and matches(occ) then
+            return occ
+        end
+    end
+    local inv = ecs:get(viewer, "Inventory")
+    for _, item_id in ipairs(inv and inv.items or {}) do
+        if matches(item_id) then
+            return item_id
+        end
+    end
+    return nil
+end
+
+--- Format a close look at a single entity: its name and description.
+function format_entity(eid)
+    local name = ecs:get(eid, "Name") or "무언가"
+    local lines = {colors.bold .. name .. colors.reset}
+    table.insert(lines, ecs:get(eid, "Description") or "특별한 점은 보이지 않습니다.")
+    if ecs:has(eid, "Dead") then
+        table.insert(lines, "죽어 있습니다.")
+    end
+    return table.concat(lines, "\n")
+end
+
 --- Format inventory listing for an entity.
 function format_inventory(eid)
     local inv = ecs:get(eid, "Inventory")
@@ -163,6 +212,22 @@ function get_monster_def(id)
     return nil
 end
 
+--- Get a social definition from content registry by id.
+function get_social_def(id)
+    if content and content.socials then
+        return content.socials[id]
+    end
+    return nil
+end
+
+--- Fill {actor}/{target} placeholders in a social message.
+function format_social(template, actor, target)
+    return (string.gsub(template, "{(%w+)}", function(key)
+        if key == "actor" then return actor end
+        if key == "target" then return target end
+    end))
+end
+
 --- Get an item definition from content registry by id.
 function get_item_def(id)
     if content and content.items then
@@ -173,6 +238,52 @@ function get_item_def(id)
     return nil
 end
 
+--- Get an item definition by the item entity's display name.
+function get_item_def_by_name(name)
+    if content and content.items then
+        for _, item in ipairs(content.items) do
+            if item.name == name then return item end
+        end
+    end
+    return nil
+end
+
+--- Add (sign = 1) or remove (sign = -1) an equipped item's stat bonuses.
+function apply_equipment_bonus(eid, item_id, sign)
+    local def = get_item_def_by_name(ecs:get(item_id, "Name"))
+    if not def then return end
+    local atk_bonus = def.attack_bonus or 0
+    local def_bonus = def.defense_bonus or 0
+    if atk_bonus ~= 0 then
+        ecs:set(eid, "Attack", (ecs:get(eid, "Attack") or 0) + sign * atk_bonus)
+    end
+    if def_bonus ~= 0 then
+        ecs:set(eid, "Defense", (ecs:get(eid, "Defense") or 0) + sign * def_bonus)
+    end
+end
+
+--- Unequip the item in `slot`, removing its bonuses. Returns the item id or nil.
+function unequip_item(eid, slot)
+    local eq = ecs:get(eid, "Equipment")
+    if not eq or not eq.slots[slot] then return nil end
+    local item_id = eq.slots[slot]
+    eq.slots[slot] = nil
+    ecs:set(eid, "Equipment", eq)
+    apply_equipment_bonus(eid, item_id, -1)
+    return item_id
+end
+
+--- Equip `item_id` into `slot`, swapping out any current item.
+--- Returns the previously equipped item id or nil.
+function equip_item(eid, slot, item_id)
+    local previous = unequip_item(eid, slot)
+    local eq = ecs:get(eid, "Equipment") or { slots = {} }
+    eq.slots[slot] = item_id
+    ecs:set(eid, "Equipment", eq)
+    apply_equipment_bonus(eid, item_id, 1)
+    return previous
+end
+
 --- Calculate gold drop from a dead NPC by checking its content loot_table.
 --- Looks up the NPC's Name in content.monsters, then sums currency item values.
 function calc_gold_drop(dead_entity)
@@ -267,7 +378,7 @@ function format_status(eid)
         table.insert(lines, "마나: " .. colors.blue .. tostring(mp.current) .. "/" .. tostring(mp.max) .. colors.reset)
     end
 
-    local gold = ecs:get(eid, "Gold") or 0
+    local gold = ecs:money(eid)
     table.insert(lines, "골드: " .. colors.yellow .. tostring(gold) .. colors.reset)
     table.insert(lines, "공격력: " .. colors.red .. tostring(atk) .. colors.reset .. "  방어력: " .. colors.blue .. tostring(def) .. colors.reset)
 
@@ -282,6 +393,7 @@ end
 
 HELP_TEXT = [[사용 가능한 명령어:
   보기 (ㅂ)           - 주변을 둘러봅니다
+  <대상> 보기         - 대상을 자세히 살펴봅니다
   북                  - 북쪽으로 이동
   남                  - 남쪽으로 이동
   동                  - 동쪽으로 이동
@@ -295,6 +407,10 @@ HELP_TEXT = [[사용 가능한 명령어:
   스킬                - 보유 스킬 목록을 확인합니다
   <스킬이름> 스킬     - 스킬을 사용합니다
   <내용> 말 (ㅁ)      - 말을 합니다
+  감정 <행동>         - 자유롭게 행동을 표현합니다 (emote)
+  [대상] 미소/인사    - 정해진 감정 표현을 합니다
+  <대상> 따라가기     - 대상이 이동하면 따라갑니다
+  그만따라가기        - 따라가기를 멈춥니다
   접속자              - 접속 중인 플레이어 목록
   도움말 (ㄷ, ?)      - 이 도움말을 표시합니다
   종료                - 접속을 종료합니다]]
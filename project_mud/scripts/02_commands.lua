@@ -1,13 +1,23 @@
 -- 02_commands.lua: All player command handlers via on_action hooks
 
--- look
+-- look / look <target>
 hooks.on_action("look", function(ctx)
     local room = space:entity_room(ctx.entity)
     if not room then
         output:send(ctx.session_id, "현재 위치를 알 수 없습니다.")
         return true
     end
-    output:send(ctx.session_id, format_room(room, ctx.entity))
+    if ctx.args == "" then
+        output:send(ctx.session_id, format_room(room, ctx.entity))
+        return true
+    end
+
+    local target = find_visible_entity(ctx.entity, room, ctx.args)
+    if not target then
+        output:send(ctx.session_id, "여기에 '" .. ctx.args .. "'이(가) 보이지 않습니다.")
+        return true
+    end
+    output:send(ctx.session_id, format_entity(target))
     return true
 end)
 
@@ -38,6 +48,10 @@ hooks.on_action("move", function(ctx)
     end
 
     local target_room = exits[dir]
+    if not room_has_space(target_room) then
+        output:send(session_id, "그쪽 방은 이미 가득 찼습니다.")
+        return true
+    end
 
     -- Move entity
     local ok, err = pcall(function()
@@ -54,10 +68,10 @@ hooks.on_action("move", function(ctx)
     local opposite_ko = DIRECTION_KO[opposite] or opposite
 
     -- Notify old room occupants
-    broadcast_room(current_room, player_name .. "님이 " .. dir_ko .. "쪽으로 떠났습니다.", entity)
+    broadcast_room(current_room, message.render("room_leave", { name = player_name, dir = dir_ko }), entity)
 
     -- Notify new room occupants
-    broadcast_room(target_room, player_name .. "님이 " .. opposite_ko .. "쪽에서 도착했습니다.", entity)
+    broadcast_room(target_room, message.render("room_arrive", { name = player_name, dir = opposite_ko }), entity)
 
     -- Show new room to mover
     output:send(session_id, format_room(target_room, entity))
@@ -226,7 +240,7 @@ end)
 
 -- gold
 hooks.on_action("gold", function(ctx)
-    local gold = ecs:get(ctx.entity, "Gold") or 0
+    local gold = ecs:money(ctx.entity)
     output:send(ctx.session_id, colors.yellow .. "보유 골드: " .. tostring(gold) .. colors.reset)
     return true
 end)
@@ -257,6 +271,77 @@ hooks.on_action("say", function(ctx)
     return true
 end)
 
+-- emote (freeform)
+hooks.on_action("emote", function(ctx)
+    local entity = ctx.entity
+    local text = get_name(entity) .. " " .. ctx.args
+
+    output:send(ctx.session_id, text)
+    local room = space:entity_room(entity)
+    if room then
+        broadcast_room(room, text, entity)
+    end
+    return true
+end)
+
+-- social (content/socials.json); args = "<social id>[ <target>]"
+hooks.on_action("social", function(ctx)
+    local entity = ctx.entity
+    local session_id = ctx.session_id
+    local social_id, target_name = string.match(ctx.args, "^(%S+)%s*(.*)$")
+    local social = social_id and get_social_def(social_id)
+    if not social then
+        output:send(session_id, "알 수 없는 감정 표현입니다.")
+        return true
+    end
+
+    local actor = get_name(entity)
+    local room = space:entity_room(entity)
+
+    if target_name == "" then
+        output:send(session_id, format_social(social.self, actor))
+        if room and social.room then
+            broadcast_room(room, format_social(social.room, actor), entity)
+        end
+        return true
+    end
+
+    -- Find target by name in room
+    local target = nil
+    if room then
+        local target_lower = string.lower(target_name)
+        for _, occ in ipairs(space:room_occupants(room)) do
+            local name = ecs:get(occ, "Name")
+            if occ ~= entity and name and string.find(string.lower(name), target_lower, 1, true) then
+                target = occ
+                break
+            end
+        end
+    end
+    if not target then
+        output:send(session_id, target_name .. "을(를) 찾을 수 없습니다.")
+        return true
+    end
+
+    local target_display = get_name(target)
+    output:send(session_id, format_social(social.self_target, actor, target_display))
+    local target_sid = sessions:session_for(target)
+    if target_sid and social.target then
+        output:send(target_sid, format_social(social.target, actor, target_display))
+    end
+    if social.room_target then
+        for _, occ in ipairs(space:room_occupants(room)) do
+            if occ ~= entity and occ ~= target then
+                local sid = sessions:session_for(occ)
+                if sid then
+                    output:send(sid, format_social(social.room_target, actor, target_display))
+                end
+            end
+        end
+    end
+    return true
+end)
+
 -- who
 hooks.on_action("who", function(ctx)
     local playing = sessions:playing_list()
@@ -276,12 +361,5 @@ hooks.on_action("help", function(ctx)
     return true
 end)
 
--- unknown command
-hooks.on_action("unknown", function(ctx)
-    if ctx.args and ctx.args ~= "" then
-        output:send(ctx.session_id, "알 수 없는 명령어: " .. ctx.args)
-    else
-        output:send(ctx.session_id, "알 수 없는 명령어입니다.")
-    end
-    return true
-end)
+-- unknown commands fall through to the engine's reply, which suggests
+-- the nearest known command
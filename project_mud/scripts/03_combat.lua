@@ -139,8 +139,7 @@ hooks.on_tick(function(tick)
                 -- Award gold from loot_table
                 local gold_earned = calc_gold_drop(dead_entity)
                 if gold_earned > 0 then
-                    local current_gold = ecs:get(round.attacker, "Gold") or 0
-                    ecs:set(round.attacker, "Gold", current_gold + gold_earned)
+                    ecs:add_money(round.attacker, gold_earned)
                     if killer_sid then
                         output:send(killer_sid, colors.yellow .. "골드 +" .. tostring(gold_earned) .. colors.reset)
                     end
@@ -114,14 +114,40 @@ hooks.on_admin("stats", 1, function(ctx)
     return true
 end)
 
+-- /sessions — Per-session activity: inputs, bytes, uptime (Builder+)
+hooks.on_admin("sessions", 1, function(ctx)
+    local msg = "=== 세션 목록 ===\n"
+    local count = 0
+    for _, sid in ipairs(sessions:all_ids()) do
+        local st = sessions:stats(sid)
+        if st then
+            count = count + 1
+            local name = "(로그인 중)"
+            local entity = sessions:get_entity(sid)
+            if entity then
+                name = ecs:get(entity, "Name") or "???"
+            end
+            local conn = sessions:connection(sid)
+            local ip = conn and conn.ip or "local"
+            msg = msg .. string.format("  #%d %s [%s] 입력 %d회/%d바이트, 접속 %d틱\n",
+                sid, name, ip, st.inputs, st.bytes, ctx.tick - st.connected_tick)
+        end
+    end
+    msg = msg .. "총 " .. count .. "개 세션"
+    output:send(ctx.session_id, msg)
+    return true
+end)
+
 -- /help — Show admin help (Builder+)
 hooks.on_admin("help", 1, function(ctx)
     local msg = "=== 관리자 명령어 ===\n"
     msg = msg .. "  /stats          — 서버 통계 (Builder+)\n"
+    msg = msg .. "  /sessions       — 세션별 입력/접속 시간 (Builder+)\n"
     msg = msg .. "  /help           — 관리자 도움말 (Builder+)\n"
     msg = msg .. "  /kick <이름>    — 플레이어 추방 (Admin+)\n"
     msg = msg .. "  /announce <msg> — 전체 공지 (Admin+)\n"
     msg = msg .. "  /teleport <이름> <방> — 텔레포트 (Admin+)\n"
+    msg = msg .. "  /reenable_hooks — 에러로 꺼진 훅 다시 켜기 (Admin+)\n"
     output:send(ctx.session_id, msg)
     return true
 end)
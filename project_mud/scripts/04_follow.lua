@@ -0,0 +1,132 @@
+-- 04_follow.lua: follow/unfollow commands.
+-- When a leader walks through an exit, followers still in the room it left
+-- trail it through the same exit if the destination has space. Each trailing
+-- move fires on_enter_room again, so chains (A follows B follows C) move
+-- together. Numbered before 05_login so the disconnect cleanup runs while the
+-- session still knows its entity.
+
+local function send_to(eid, text)
+    local sid = sessions:session_for(eid)
+    if sid then
+        output:send(sid, text)
+    end
+end
+
+--- Direction of the exit from `from_room` that leads to `to_room`, if any.
+local function exit_towards(from_room, to_room)
+    local exits = space:exits(from_room) or {}
+    for _, dir in ipairs(DIRECTION_ORDER) do
+        if exits[dir] == to_room then
+            return dir
+        end
+    end
+    local custom = {}
+    for dir, room in pairs(exits) do
+        if room == to_room then
+            table.insert(custom, dir)
+        end
+    end
+    table.sort(custom)
+    return custom[1]
+end
+
+local function stop_following(entity, session_id)
+    local leader = follow.stop(entity)
+    if not leader then
+        output:send(session_id, "아무도 따라가고 있지 않습니다.")
+        return
+    end
+    output:send(session_id, get_name(leader) .. "님을 그만 따라갑니다.")
+    send_to(leader, get_name(entity) .. "님이 더 이상 당신을 따라가지 않습니다.")
+end
+
+local function trail(follower, leader, dir, from_room, to_room)
+    if not room_has_space(to_room) then
+        send_to(follower, get_name(leader) .. "님을 따라가려 했지만 방이 가득 찼습니다.")
+        return
+    end
+    local ok = pcall(function()
+        space:move_entity(follower, to_room)
+    end)
+    if not ok then
+        return
+    end
+
+    local name = get_name(follower)
+    local dir_ko = DIRECTION_KO[dir] or dir
+    local opposite = DIRECTION_OPPOSITE[dir]
+    local opposite_ko = DIRECTION_KO[opposite] or opposite
+    broadcast_room(from_room, message.render("room_leave", { name = name, dir = dir_ko }), follower)
+    broadcast_room(to_room, message.render("room_arrive", { name = name, dir = opposite_ko }), follower)
+    send_to(follower, get_name(leader) .. "님을 따라 " .. dir_ko .. "쪽으로 이동합니다.\n" .. format_room(to_room, follower))
+
+    hooks.fire_enter_room(follower, to_room, from_room)
+end
+
+-- follow <name>: start following a character in the same room.
+-- Following yourself stops following.
+hooks.on_action("follow", function(ctx)
+    local entity = ctx.entity
+    local session_id = ctx.session_id
+
+    local room = space:entity_room(entity)
+    if not room then
+        output:send(session_id, "현재 위치를 알 수 없습니다.")
+        return true
+    end
+
+    local target = find_visible_entity(entity, room, ctx.args)
+    local is_character = target and (ecs:has(target, "PlayerTag") or ecs:has(target, "NpcTag"))
+    if not is_character or space:entity_room(target) ~= room then
+        output:send(session_id, "그런 대상은 여기 없습니다.")
+        return true
+    end
+    if target == entity then
+        stop_following(entity, session_id)
+        return true
+    end
+
+    local ok, previous = pcall(follow.start, entity, target)
+    if not ok then
+        output:send(session_id, "서로를 따라갈 수는 없습니다.")
+        return true
+    end
+    if previous and previous ~= target then
+        send_to(previous, get_name(entity) .. "님이 더 이상 당신을 따라가지 않습니다.")
+    end
+    output:send(session_id, get_name(target) .. "님을 따라가기 시작합니다.")
+    send_to(target, get_name(entity) .. "님이 당신을 따라가기 시작합니다.")
+    return true
+end)
+
+hooks.on_action("unfollow", function(ctx)
+    stop_following(ctx.entity, ctx.session_id)
+    return true
+end)
+
+hooks.on_enter_room(function(leader, room, old_room)
+    if not old_room then
+        return
+    end
+    -- Teleports and recalls have no exit to trail through.
+    local dir = exit_towards(old_room, room)
+    if not dir then
+        return
+    end
+    for _, follower in ipairs(follow.followers(leader)) do
+        if space:entity_room(follower) == old_room and not ecs:has(follower, "Dead") then
+            trail(follower, leader, dir, old_room, room)
+        end
+    end
+end)
+
+hooks.on_disconnect(function(session_id)
+    local entity = sessions:get_entity(session_id)
+    if not entity then
+        return
+    end
+    local name = get_name(entity)
+    for _, follower in ipairs(follow.forget(entity)) do
+        send_to(follower, name .. "님이 떠나 더 이상 따라갈 수 없습니다.")
+    end
+end)
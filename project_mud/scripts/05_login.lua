@@ -2,6 +2,7 @@
 -- Manages the entire login state machine via on_input/on_connect/on_disconnect hooks.
 -- Auth mode: name -> password -> character selection -> race/class -> playing
 -- Quick-play mode: name -> race/class -> playing (no DB)
+-- Later scripts can add steps or divert transitions via `login_flow`.
 
 -- Per-session login sub-state tracking
 local login_state = {}
@@ -108,13 +109,6 @@ local function apply_race_class(entity, race_id, class_id)
         base_def = base_def + (class_def.defense_bonus or 0)
     end
 
-    ecs:set(entity, "Health", {current = base_hp, max = base_hp})
-    ecs:set(entity, "Attack", base_atk)
-    ecs:set(entity, "Defense", base_def)
-    ecs:set(entity, "Race", race_id)
-    ecs:set(entity, "Class", class_id)
-    ecs:set(entity, "Level", {level = 1, exp = 0, exp_next = 100})
-
     -- Collect skills (class starting skills + racial skill)
     local skills = {}
     if class_def and class_def.starting_skills then
@@ -133,15 +127,21 @@ local function apply_race_class(entity, race_id, class_id)
         end
     end
 
-    ecs:set(entity, "Skills", {learned = skills})
+    ecs:set_many(entity, {
+        Health = {current = base_hp, max = base_hp},
+        Attack = base_atk,
+        Defense = base_def,
+        Race = race_id,
+        Class = class_id,
+        Level = 1,
+        Skills = {learned = skills},
+    })
 end
 
 -- Spawn a new entity for quick-play mode (no auth/DB)
 local function spawn_quick_play(session_id, name, race_id, class_id)
     local entity = ecs:spawn()
-    ecs:set(entity, "Name", name)
-    ecs:set(entity, "PlayerTag", true)
-    ecs:set(entity, "Inventory", {items = {}})
+    ecs:set_many(entity, {Name = name, PlayerTag = true, Inventory = {items = {}}})
 
     apply_race_class(entity, race_id, class_id)
 
@@ -169,55 +169,32 @@ local function spawn_character(session_id, char_detail, account)
         end
     end
 
-    -- Spawn new entity
-    local entity = ecs:spawn()
-    ecs:set(entity, "Name", char_detail.name)
-    ecs:set(entity, "PlayerTag", true)
-
-    -- Restore components from DB (or apply defaults)
+    -- Hibernated: the entity was saved to the DB and despawned while
+    -- lingering, so char_detail already holds its latest state.
+    local woke = sessions:wake_hibernated(char_detail.id)
+
+    -- Restore every saved component on top of the defaults. The DB only
+    -- holds persistent components; runtime ones are dropped on save.
+    local values = {
+        Health = {current = 100, max = 100},
+        Attack = 10,
+        Defense = 5,
+        Level = 1,
+        Skills = {learned = {}},
+    }
     local comps = char_detail.components
     if comps and type(comps) == "table" then
-        if comps.Health then
-            ecs:set(entity, "Health", comps.Health)
-        else
-            ecs:set(entity, "Health", {current = 100, max = 100})
-        end
-        if comps.Attack then
-            ecs:set(entity, "Attack", comps.Attack)
-        else
-            ecs:set(entity, "Attack", 10)
-        end
-        if comps.Defense then
-            ecs:set(entity, "Defense", comps.Defense)
-        else
-            ecs:set(entity, "Defense", 5)
-        end
-        -- Restore new RPG components
-        if comps.Race then
-            ecs:set(entity, "Race", comps.Race)
+        for tag, value in pairs(comps) do
+            values[tag] = value
         end
-        if comps.Class then
-            ecs:set(entity, "Class", comps.Class)
-        end
-        if comps.Level then
-            ecs:set(entity, "Level", comps.Level)
-        else
-            ecs:set(entity, "Level", {level = 1, exp = 0, exp_next = 100})
-        end
-        if comps.Skills then
-            ecs:set(entity, "Skills", comps.Skills)
-        else
-            ecs:set(entity, "Skills", {learned = {}})
-        end
-    else
-        ecs:set(entity, "Health", {current = 100, max = 100})
-        ecs:set(entity, "Attack", 10)
-        ecs:set(entity, "Defense", 5)
-        ecs:set(entity, "Level", {level = 1, exp = 0, exp_next = 100})
-        ecs:set(entity, "Skills", {learned = {}})
     end
+    values.Name = char_detail.name
+    values.PlayerTag = true
+    values.Inventory = {items = {}}
 
-    ecs:set(entity, "Inventory", {items = {}})
+    -- Spawn new entity
+    local entity = ecs:spawn()
+    ecs:set_many(entity, values)
 
     place_in_room(entity, char_detail.room_id)
 
@@ -228,10 +205,78 @@ local function spawn_character(session_id, char_detail, account)
     sessions:set_character_id(session_id, char_detail.id)
     sessions:set_permission(session_id, account.permission)
 
-    log.info("Player '" .. char_detail.name .. "' entered the game")
+    if woke then
+        output:send(session_id, colors.green .. "이전 세션에 재접속했습니다." .. colors.reset)
+        log.info("Player '" .. char_detail.name .. "' reconnected (restored from hibernation)")
+    else
+        log.info("Player '" .. char_detail.name .. "' entered the game")
+    end
     return entity
 end
 
+-------------------------------------------------------
+-- Login flow state machine
+-------------------------------------------------------
+-- Each step has an optional enter(session_id, state) that prompts the
+-- player and an input(session_id, line, state) that handles a line. An
+-- enter that cannot set its step up returns a fallback step instead.
+-- Other scripts extend the flow through the `login_flow` global.
+
+local STEPS = {}
+local transition_hooks = {}
+
+-- Move a session to step `to`. Transition hooks run in registration order;
+-- the first one returning a step name diverts the transition there. The
+-- step is only committed once its enter succeeds; a fallback step returned
+-- by enter is entered instead.
+local function goto_step(session_id, state, to)
+    local from = state.step
+    for _, hook in ipairs(transition_hooks) do
+        local ok, diverted = pcall(hook, session_id, from, to, state)
+        if not ok then
+            log.warn("login_flow transition hook failed: " .. tostring(diverted))
+        elseif diverted then
+            to = diverted
+            break
+        end
+    end
+
+    local step = STEPS[to]
+    if not step then
+        log.warn("login_flow: unknown step '" .. tostring(to) .. "', staying at '" .. tostring(from) .. "'")
+        return
+    end
+    local fallback = step.enter and step.enter(session_id, state)
+    if fallback and fallback ~= to then
+        return goto_step(session_id, state, fallback)
+    end
+    state.step = to
+end
+
+login_flow = {}
+
+--- Register a transition hook: fn(session_id, from, to, state) -> step | nil.
+--- `from` is nil for the first step after connecting. Returning a step name
+--- sends the session there instead of `to`; the diverted transition is not
+--- passed to the remaining hooks.
+function login_flow.on_transition(fn)
+    table.insert(transition_hooks, fn)
+end
+
+--- Add (or replace) a step: {enter = fn(session_id, state) -> fallback | nil,
+--- input = fn(session_id, line, state) -> next_step | nil}.
+--- Returning a step name from input moves the session on; nil stays.
+--- Returning a step name from enter goes there instead of this step.
+function login_flow.register_step(name, step)
+    STEPS[name] = step
+end
+
+--- Current login step of a session, or nil once it is playing.
+function login_flow.step_of(session_id)
+    local state = login_state[session_id]
+    return state and state.step
+end
+
 -- Show character selection menu
 local function enter_character_selection(session_id, state)
     local ok, chars = pcall(function()
@@ -239,11 +284,10 @@ local function enter_character_selection(session_id, state)
     end)
     if not ok then
         output:send(session_id, colors.red .. "캐릭터 목록 조회 실패: " .. tostring(chars) .. colors.reset)
-        return
+        return "name"
     end
 
     state.characters = chars
-    state.step = "character_select"
 
     local lines = {colors.bold .. "=== 캐릭터 선택 ===" .. colors.reset}
 
@@ -286,187 +330,218 @@ local function handle_character_selection(session_id, line, state)
     end
 
     state.new_char_name = name
-    state.step = "race_select"
-    show_race_selection(session_id)
+    return "race_select"
 end
 
--- Welcome banner
-local WELCOME_MSG = colors.bold .. colors.cyan
-    .. "========================================\n"
-    .. "     환영합니다, 모험가여!\n"
-    .. "========================================"
-    .. colors.reset .. "\n"
-    .. "이름을 입력하세요: "
-
--------------------------------------------------------
--- Hook registrations
--------------------------------------------------------
+-- Create the chosen character (auth mode) or spawn it directly (quick-play)
+local function finish_class_selection(session_id, state, selected_class)
+    if not auth then
+        spawn_quick_play(session_id, state.player_name, state.selected_race, selected_class)
+        login_state[session_id] = nil
+        return
+    end
 
-hooks.on_tick(function(tick)
-    current_tick = tick
-end)
+    local race_id = state.selected_race
+    local class_id = selected_class
 
-hooks.on_connect(function(session_id)
-    login_state[session_id] = {step = "name"}
-    output:send(session_id, WELCOME_MSG)
-end)
+    -- Calculate stats for DB storage
+    local base_hp = 100
+    local base_atk = 10
+    local base_def = 5
+    local race_def = get_race_def(race_id)
+    local class_def = get_class_def(class_id)
+    if race_def then
+        base_hp = base_hp + (race_def.hp_bonus or 0)
+        base_atk = base_atk + (race_def.attack_bonus or 0)
+        base_def = base_def + (race_def.defense_bonus or 0)
+    end
+    if class_def then
+        base_hp = base_hp + (class_def.hp_bonus or 0)
+        base_atk = base_atk + (class_def.attack_bonus or 0)
+        base_def = base_def + (class_def.defense_bonus or 0)
+    end
 
-hooks.on_input(function(session_id, line)
-    local state = login_state[session_id]
-    if not state then return end
+    -- Build skills list
+    local skills = {}
+    if class_def and class_def.starting_skills then
+        for _, s in ipairs(class_def.starting_skills) do
+            table.insert(skills, s)
+        end
+    end
+    if race_def and race_def.racial_skill then
+        local already = false
+        for _, s in ipairs(skills) do
+            if s == race_def.racial_skill then already = true; break end
+        end
+        if not already then
+            table.insert(skills, race_def.racial_skill)
+        end
+    end
 
-    -- Trim whitespace
-    line = line:match("^%s*(.-)%s*$") or ""
-    if #line == 0 then return end
+    local char_defaults = {
+        Health = {current = base_hp, max = base_hp},
+        Attack = base_atk,
+        Defense = base_def,
+        Race = race_id,
+        Class = class_id,
+        Level = 1,
+        Skills = {learned = skills},
+    }
+
+    local ok, result = pcall(function()
+        return auth:create_character(state.account.id, state.new_char_name, char_defaults)
+    end)
 
-    if state.step == "name" then
-        if auth then
-            -- Auth mode: check if account exists
-            local ok, existing = pcall(function()
-                return auth:check_account(line)
-            end)
-            if not ok then
-                output:send(session_id, colors.red .. "오류: " .. tostring(existing) .. colors.reset)
-                return
-            end
+    if ok then
+        output:send(session_id, colors.green .. "캐릭터 '" .. state.new_char_name .. "'이(가) 생성되었습니다! (" .. race_id .. "/" .. class_id .. ")" .. colors.reset)
+        spawn_character(session_id, result, state.account)
+        login_state[session_id] = nil
+    else
+        output:send(session_id, colors.red .. "캐릭터 생성 실패: " .. tostring(result) .. colors.reset)
+    end
+end
 
-            state.username = line
-            if existing then
-                state.step = "password"
-                output:send(session_id, "비밀번호를 입력하세요: ")
-            else
-                state.step = "password_new"
-                output:send(session_id, "새 계정을 만듭니다. 비밀번호를 입력하세요: ")
-            end
-        else
+STEPS.name = {
+    enter = function(session_id)
+        output:send(session_id, "이름을 입력하세요: ")
+    end,
+    input = function(session_id, line, state)
+        if not auth then
             -- Quick-play mode: name -> race selection
             state.player_name = line
-            state.step = "race_select"
-            show_race_selection(session_id)
+            return "race_select"
+        end
+
+        -- Auth mode: check if account exists
+        local ok, existing = pcall(function()
+            return auth:check_account(line)
+        end)
+        if not ok then
+            output:send(session_id, colors.red .. "오류: " .. tostring(existing) .. colors.reset)
+            return
+        end
+
+        state.username = line
+        if existing then
+            return "password"
         end
+        return "password_new"
+    end,
+}
 
-    elseif state.step == "password" then
+STEPS.password = {
+    enter = function(session_id)
+        output:send(session_id, "비밀번호를 입력하세요: ")
+    end,
+    input = function(session_id, line, state)
         local ok, result = pcall(function()
             return auth:authenticate(state.username, line)
         end)
         if ok then
             state.account = result
-            enter_character_selection(session_id, state)
-        else
-            output:send(session_id, colors.red .. "비밀번호가 틀렸습니다." .. colors.reset .. " 다시 입력하세요: ")
+            return "character_select"
         end
+        output:send(session_id, colors.red .. "비밀번호가 틀렸습니다." .. colors.reset .. " 다시 입력하세요: ")
+    end,
+}
 
-    elseif state.step == "password_new" then
+STEPS.password_new = {
+    enter = function(session_id)
+        output:send(session_id, "새 계정을 만듭니다. 비밀번호를 입력하세요: ")
+    end,
+    input = function(_session_id, line, state)
         state.password = line
-        state.step = "password_confirm"
-        output:send(session_id, "비밀번호를 한번 더 입력하세요: ")
+        return "password_confirm"
+    end,
+}
 
-    elseif state.step == "password_confirm" then
-        if line == state.password then
-            local ok, result = pcall(function()
-                return auth:create_account(state.username, line)
-            end)
-            if ok then
-                state.account = result
-                state.password = nil
-                enter_character_selection(session_id, state)
-            else
-                output:send(session_id, colors.red .. "계정 생성 실패: " .. tostring(result) .. colors.reset)
-                state.step = "name"
-                output:send(session_id, "이름을 입력하세요: ")
-            end
-        else
+STEPS.password_confirm = {
+    enter = function(session_id)
+        output:send(session_id, "비밀번호를 한번 더 입력하세요: ")
+    end,
+    input = function(session_id, line, state)
+        if line ~= state.password then
             output:send(session_id, colors.red .. "비밀번호가 일치하지 않습니다." .. colors.reset)
-            state.step = "password_new"
-            output:send(session_id, "비밀번호를 입력하세요: ")
+            return "password_new"
+        end
+        local ok, result = pcall(function()
+            return auth:create_account(state.username, line)
+        end)
+        if ok then
+            state.account = result
+            state.password = nil
+            return "character_select"
         end
+        output:send(session_id, colors.red .. "계정 생성 실패: " .. tostring(result) .. colors.reset)
+        return "name"
+    end,
+}
 
-    elseif state.step == "character_select" then
-        handle_character_selection(session_id, line, state)
+STEPS.character_select = {
+    enter = enter_character_selection,
+    input = handle_character_selection,
+}
 
-    elseif state.step == "race_select" then
+STEPS.race_select = {
+    enter = show_race_selection,
+    input = function(session_id, line, state)
         local num = tonumber(line)
         if num and num >= 1 and num <= #RACE_LIST then
             state.selected_race = RACE_LIST[math.floor(num)]
-            state.step = "class_select"
-            show_class_selection(session_id)
-        else
-            output:send(session_id, "1~" .. tostring(#RACE_LIST) .. " 사이의 번호를 입력하세요.")
+            return "class_select"
         end
+        output:send(session_id, "1~" .. tostring(#RACE_LIST) .. " 사이의 번호를 입력하세요.")
+    end,
+}
 
-    elseif state.step == "class_select" then
+STEPS.class_select = {
+    enter = show_class_selection,
+    input = function(session_id, line, state)
         local num = tonumber(line)
         if num and num >= 1 and num <= #CLASS_LIST then
-            local selected_class = CLASS_LIST[math.floor(num)]
-
-            if auth then
-                -- Auth mode: create character with race/class stats
-                local race_id = state.selected_race
-                local class_id = selected_class
-
-                -- Calculate stats for DB storage
-                local base_hp = 100
-                local base_atk = 10
-                local base_def = 5
-                local race_def = get_race_def(race_id)
-                local class_def = get_class_def(class_id)
-                if race_def then
-                    base_hp = base_hp + (race_def.hp_bonus or 0)
-                    base_atk = base_atk + (race_def.attack_bonus or 0)
-                    base_def = base_def + (race_def.defense_bonus or 0)
-                end
-                if class_def then
-                    base_hp = base_hp + (class_def.hp_bonus or 0)
-                    base_atk = base_atk + (class_def.attack_bonus or 0)
-                    base_def = base_def + (class_def.defense_bonus or 0)
-                end
-
-                -- Build skills list
-                local skills = {}
-                if class_def and class_def.starting_skills then
-                    for _, s in ipairs(class_def.starting_skills) do
-                        table.insert(skills, s)
-                    end
-                end
-                if race_def and race_def.racial_skill then
-                    local already = false
-                    for _, s in ipairs(skills) do
-                        if s == race_def.racial_skill then already = true; break end
-                    end
-                    if not already then
-                        table.insert(skills, race_def.racial_skill)
-                    end
-                end
-
-                local char_defaults = {
-                    Health = {current = base_hp, max = base_hp},
-                    Attack = base_atk,
-                    Defense = base_def,
-                    Race = race_id,
-                    Class = class_id,
-                    Level = {level = 1, exp = 0, exp_next = 100},
-                    Skills = {learned = skills},
-                }
-
-                local ok, result = pcall(function()
-                    return auth:create_character(state.account.id, state.new_char_name, char_defaults)
-                end)
-
-                if ok then
-                    output:send(session_id, colors.green .. "캐릭터 '" .. state.new_char_name .. "'이(가) 생성되었습니다! (" .. race_id .. "/" .. class_id .. ")" .. colors.reset)
-                    spawn_character(session_id, result, state.account)
-                    login_state[session_id] = nil
-                else
-                    output:send(session_id, colors.red .. "캐릭터 생성 실패: " .. tostring(result) .. colors.reset)
-                end
-            else
-                -- Quick-play mode: spawn with race/class
-                spawn_quick_play(session_id, state.player_name, state.selected_race, selected_class)
-                login_state[session_id] = nil
-            end
-        else
-            output:send(session_id, "1~" .. tostring(#CLASS_LIST) .. " 사이의 번호를 입력하세요.")
+            finish_class_selection(session_id, state, CLASS_LIST[math.floor(num)])
+            return
         end
+        output:send(session_id, "1~" .. tostring(#CLASS_LIST) .. " 사이의 번호를 입력하세요.")
+    end,
+}
+
+-- Welcome banner (the name prompt follows as the first step)
+local WELCOME_MSG = colors.bold .. colors.cyan
+    .. "========================================\n"
+    .. "     환영합니다, 모험가여!\n"
+    .. "========================================"
+    .. colors.reset
+
+-------------------------------------------------------
+-- Hook registrations
+-------------------------------------------------------
+
+hooks.on_tick(function(tick)
+    current_tick = tick
+end)
+
+hooks.on_connect(function(session_id)
+    local state = {}
+    login_state[session_id] = state
+    output:send(session_id, WELCOME_MSG)
+    goto_step(session_id, state, "name")
+end)
+
+hooks.on_input(function(session_id, line)
+    local state = login_state[session_id]
+    if not state then return end
+
+    -- Trim whitespace
+    line = line:match("^%s*(.-)%s*$") or ""
+    if #line == 0 then return end
+
+    local step = STEPS[state.step]
+    if not step or not step.input then return end
+    local next_step = step.input(session_id, line, state)
+    -- The step may have finished the login (state cleared) or chosen to stay
+    if next_step and login_state[session_id] == state then
+        goto_step(session_id, state, next_step)
     end
 end)
 
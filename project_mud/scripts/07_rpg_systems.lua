@@ -283,8 +283,7 @@ hooks.on_action("use_skill", function(ctx)
                 -- Award gold from loot_table
                 local gold_earned = calc_gold_drop(target)
                 if gold_earned > 0 then
-                    local current_gold = ecs:get(entity, "Gold") or 0
-                    ecs:set(entity, "Gold", current_gold + gold_earned)
+                    ecs:add_money(entity, gold_earned)
                     output:send(sid, colors.yellow .. "골드 +" .. tostring(gold_earned) .. colors.reset)
                 end
             end
@@ -65,13 +65,12 @@ hooks.on_action("buy", function(ctx)
         output:send(sid, "'" .. item_name .. "'은(는) 이 상점에서 판매하지 않습니다.")
         return true
     end
-    local gold = ecs:get(entity, "Gold") or 0
-    if gold < shop_item.price then
+    -- Deduct gold
+    local paid, gold = ecs:spend_money(entity, shop_item.price)
+    if not paid then
         output:send(sid, "골드가 부족합니다. (보유: " .. gold .. ", 필요: " .. shop_item.price .. ")")
         return true
     end
-    -- Deduct gold
-    ecs:set(entity, "Gold", gold - shop_item.price)
     -- Create item entity
     local new_item = ecs:spawn()
     ecs:set(new_item, "Name", shop_item.name)
@@ -128,8 +127,7 @@ hooks.on_action("sell", function(ctx)
     ecs:set(entity, "Inventory", {items = new_items})
     ecs:despawn(item_ent)
     -- Add gold
-    local gold = ecs:get(entity, "Gold") or 0
-    ecs:set(entity, "Gold", gold + sell_price)
+    ecs:add_money(entity, sell_price)
     output:send(sid, item_name .. "을(를) 판매했습니다. (+" .. sell_price .. " 골드)")
     return true
 end)
@@ -58,8 +58,7 @@ local function execute_action(entity, sid, action_str)
         output:send(sid, colors.green .. arg .. "을(를) 받았습니다." .. colors.reset)
     elseif cmd == "give_gold" then
         local amount = tonumber(arg) or 0
-        local gold = ecs:get(entity, "Gold") or 0
-        ecs:set(entity, "Gold", gold + amount)
+        ecs:add_money(entity, amount)
         output:send(sid, colors.green .. amount .. " 골드를 받았습니다." .. colors.reset)
     elseif cmd == "heal" then
         local hp = ecs:get(entity, "Health")
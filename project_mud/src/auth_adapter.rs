@@ -1,3 +1,4 @@
+use mud::persistence_setup::{migrate_legacy_gold, retain_character_tags};
 use player_db::PlayerDb;
 use scripting::auth::{
     AuthAccountInfo, AuthCharacterDetail, AuthCharacterSummary, AuthError, AuthProvider,
@@ -87,6 +88,7 @@ impl AuthProvider for PlayerDbAuthProvider<'_> {
             account_id: c.account_id,
             name: c.name,
             components: c.components,
+            script_data: c.script_data,
             room_id: c.room_id,
             position_x: c.position_x,
             position_y: c.position_y,
@@ -94,12 +96,15 @@ impl AuthProvider for PlayerDbAuthProvider<'_> {
     }
 
     fn load_character(&self, character_id: i64) -> Result<AuthCharacterDetail, AuthError> {
-        let c = self.db.character().load(character_id).map_err(map_err)?;
+        let mut c = self.db.character().load(character_id).map_err(map_err)?;
+        retain_character_tags(&mut c.components);
+        migrate_legacy_gold(&mut c.components);
         Ok(AuthCharacterDetail {
             id: c.id,
             account_id: c.account_id,
             name: c.name,
             components: c.components,
+            script_data: c.script_data,
             room_id: c.room_id,
             position_x: c.position_x,
             position_y: c.position_y,
@@ -118,4 +123,15 @@ impl AuthProvider for PlayerDbAuthProvider<'_> {
             .save_state(character_id, components, room_id, position)
             .map_err(map_err)
     }
+
+    fn save_script_data(
+        &self,
+        character_id: i64,
+        script_data: &serde_json::Value,
+    ) -> Result<(), AuthError> {
+        self.db
+            .character()
+            .save_script_data(character_id, script_data)
+            .map_err(map_err)
+    }
 }
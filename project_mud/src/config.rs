@@ -1,8 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::Deserialize;
 
 use engine_core::tick::TickConfig;
+use net::ip_filter::{IpFilter, IpFilterError};
+use player_db::{HashParams, PlayerDbError};
 use scripting::ScriptConfig;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -10,6 +13,10 @@ use scripting::ScriptConfig;
 pub struct NetConfig {
     pub telnet_addr: String,
     pub max_connections: usize,
+    /// Splits one input line into several commands run in order
+    /// (`get sword; look`). Only the first character is used; empty disables
+    /// chaining.
+    pub command_separator: String,
 }
 
 impl Default for NetConfig {
@@ -17,19 +24,36 @@ impl Default for NetConfig {
         Self {
             telnet_addr: "0.0.0.0:4000".to_string(),
             max_connections: 1000,
+            command_separator: ";".to_string(),
         }
     }
 }
 
+impl NetConfig {
+    /// The chaining separator, or None if chaining is disabled.
+    pub fn command_separator(&self) -> Option<char> {
+        self.command_separator.chars().next()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct TickSection {
     pub tps: u32,
+    /// Master seed for plugin and script randomness. Set it to make a run
+    /// reproducible; unset keeps Lua's `math.random` unseeded.
+    pub seed: Option<u64>,
+    /// Stop cleanly once this tick is reached, as on shutdown (0 = run
+    /// forever). For bounded load-test and CI runs.
+    pub max_ticks: u64,
+    /// Pace ticks to `tps` in wall time. False steps back-to-back (offline
+    /// simulation, tests).
+    pub realtime: bool,
 }
 
 impl Default for TickSection {
     fn default() -> Self {
-        Self { tps: 10 }
+        Self { tps: 10, seed: None, max_ticks: 0, realtime: true }
     }
 }
 
@@ -56,6 +80,12 @@ pub struct ScriptSection {
     pub content_dir: String,
     pub memory_limit_kb: usize,
     pub instruction_limit: u32,
+    /// Budget for the whole `on_init` pass (world setup).
+    pub init_instruction_limit: u32,
+    pub hook_error_threshold: u32,
+    pub hook_error_window_ticks: u64,
+    /// Directory for `require` modules (default: `<scripts_dir>/lib`).
+    pub lib_dir: Option<String>,
 }
 
 impl Default for ScriptSection {
@@ -65,6 +95,10 @@ impl Default for ScriptSection {
             content_dir: "content".to_string(),
             memory_limit_kb: 16384,       // 16 MB
             instruction_limit: 1_000_000,
+            init_instruction_limit: 100_000_000,
+            hook_error_threshold: 10,
+            hook_error_window_ticks: 100,
+            lib_dir: None,
         }
     }
 }
@@ -74,13 +108,26 @@ impl Default for ScriptSection {
 pub struct DatabaseSection {
     pub path: String,
     pub auth_required: bool,
+    /// Argon2 memory cost for new password hashes, in KiB.
+    pub hash_memory_kib: u32,
+    /// Argon2 passes; ignored when `hash_target_ms` is set.
+    pub hash_iterations: u32,
+    pub hash_parallelism: u32,
+    /// Pick `hash_iterations` at startup so one hash takes at most this long
+    /// on this host (0 = use `hash_iterations` as configured).
+    pub hash_target_ms: u64,
 }
 
 impl Default for DatabaseSection {
     fn default() -> Self {
+        let hash = HashParams::default();
         Self {
             path: "data/player.db".to_string(),
             auth_required: false,
+            hash_memory_kib: hash.memory_kib,
+            hash_iterations: hash.iterations,
+            hash_parallelism: hash.parallelism,
+            hash_target_ms: 0,
         }
     }
 }
@@ -91,7 +138,22 @@ pub struct SecuritySection {
     pub max_connections_total: usize,
     pub max_connections_per_ip: usize,
     pub max_commands_per_second: u32,
+    /// Longest accepted telnet input line, in bytes.
     pub max_input_length: usize,
+    /// `"truncate"` keeps the first `max_input_length` bytes of a longer
+    /// line; `"disconnect"` closes the connection.
+    pub overlong_input: net::telnet::OverlongLine,
+    pub ip_allow: Vec<String>,
+    pub ip_deny: Vec<String>,
+}
+
+impl SecuritySection {
+    pub fn line_limit(&self) -> net::telnet::LineLimit {
+        net::telnet::LineLimit {
+            max_len: self.max_input_length,
+            on_overflow: self.overlong_input,
+        }
+    }
 }
 
 impl Default for SecuritySection {
@@ -100,7 +162,10 @@ impl Default for SecuritySection {
             max_connections_total: 1000,
             max_connections_per_ip: 5,
             max_commands_per_second: 20,
-            max_input_length: 4096,
+            max_input_length: net::telnet::DEFAULT_MAX_LINE_LEN,
+            overlong_input: net::telnet::OverlongLine::Truncate,
+            ip_allow: Vec::new(),
+            ip_deny: Vec::new(),
         }
     }
 }
@@ -110,6 +175,9 @@ impl Default for SecuritySection {
 pub struct CharacterSection {
     pub save_interval: u64,
     pub linger_timeout_secs: u64,
+    /// Lingering entities kept in memory before the oldest are hibernated
+    /// to the DB (0 = no limit).
+    pub max_resident_lingering: usize,
 }
 
 impl Default for CharacterSection {
@@ -117,12 +185,13 @@ impl Default for CharacterSection {
         Self {
             save_interval: 600,       // 600 ticks = 60 seconds at 10 TPS
             linger_timeout_secs: 60,
+            max_resident_lingering: 0,
         }
     }
 }
 
 /// Top-level MUD server configuration.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
 pub struct ServerConfig {
     pub net: NetConfig,
@@ -134,20 +203,6 @@ pub struct ServerConfig {
     pub character: CharacterSection,
 }
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
-            net: NetConfig::default(),
-            tick: TickSection::default(),
-            persistence: PersistSection::default(),
-            scripting: ScriptSection::default(),
-            database: DatabaseSection::default(),
-            security: SecuritySection::default(),
-            character: CharacterSection::default(),
-        }
-    }
-}
-
 impl ServerConfig {
     /// Load configuration from an optional TOML file path.
     pub fn load(config_path: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
@@ -165,22 +220,63 @@ impl ServerConfig {
     pub fn to_tick_config(&self) -> TickConfig {
         TickConfig {
             tps: self.tick.tps,
-            max_ticks: 0,
+            max_ticks: self.tick.max_ticks,
+            realtime: self.tick.realtime,
+            ..TickConfig::default()
         }
     }
 
+    /// Settings exposed to Lua as the read-only `config` global.
+    pub fn to_script_globals(&self) -> serde_json::Value {
+        serde_json::json!({
+            "mode": "mud",
+            "tps": self.tick.tps,
+            "snapshot_interval": self.persistence.snapshot_interval,
+            "character_save_interval": self.character.save_interval,
+            "linger_timeout_secs": self.character.linger_timeout_secs,
+        })
+    }
+
+    /// Password hash parameters from the database section, calibrated on
+    /// this host when `hash_target_ms` is set.
+    pub fn to_hash_params(&self) -> Result<HashParams, PlayerDbError> {
+        let db = &self.database;
+        if db.hash_target_ms > 0 {
+            HashParams::calibrate(
+                Duration::from_millis(db.hash_target_ms),
+                db.hash_memory_kib,
+                db.hash_parallelism,
+            )
+        } else {
+            HashParams::new(db.hash_memory_kib, db.hash_iterations, db.hash_parallelism)
+        }
+    }
+
+    /// Build the accept-time IP filter from the security section.
+    pub fn to_ip_filter(&self) -> Result<IpFilter, IpFilterError> {
+        IpFilter::from_lists(&self.security.ip_allow, &self.security.ip_deny)
+    }
+
     /// Convert scripting section to scripting crate's ScriptConfig.
     pub fn to_script_config(&self) -> ScriptConfig {
         ScriptConfig {
             memory_limit: self.scripting.memory_limit_kb * 1024,
             instruction_limit: self.scripting.instruction_limit,
+            init_instruction_limit: self.scripting.init_instruction_limit,
+            hook_error_threshold: self.scripting.hook_error_threshold,
+            hook_error_window_ticks: self.scripting.hook_error_window_ticks,
+            lib_dir: Some(match &self.scripting.lib_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => Path::new(&self.scripting.scripts_dir).join("lib"),
+            }),
         }
     }
 }
 
 /// Parse CLI arguments and load config.
 /// Supports: --config <path>
-pub fn parse_cli_args() -> ServerConfig {
+/// Returns the config and the path it was loaded from (for hot reload).
+pub fn parse_cli_args() -> (ServerConfig, Option<String>) {
     let args: Vec<String> = std::env::args().collect();
     let mut config_path: Option<&str> = None;
 
@@ -204,7 +300,7 @@ pub fn parse_cli_args() -> ServerConfig {
     }
 
     match ServerConfig::load(config_path) {
-        Ok(c) => c,
+        Ok(c) => (c, config_path.map(str::to_string)),
         Err(e) => {
             eprintln!("Failed to load config: {}", e);
             std::process::exit(1);
@@ -222,6 +318,7 @@ mod tests {
     fn default_config_matches_hardcoded_values() {
         let config = ServerConfig::default();
         assert_eq!(config.net.telnet_addr, "0.0.0.0:4000");
+        assert_eq!(config.net.command_separator(), Some(';'));
         assert_eq!(config.tick.tps, 10);
         assert_eq!(config.persistence.snapshot_interval, 300);
         assert_eq!(config.persistence.save_dir, "data/snapshots");
@@ -229,6 +326,25 @@ mod tests {
         assert_eq!(config.scripting.content_dir, "content");
         assert_eq!(config.security.max_connections_per_ip, 5);
         assert_eq!(config.security.max_commands_per_second, 20);
+        assert_eq!(config.security.line_limit(), net::telnet::LineLimit::default());
+        assert_eq!(config.to_hash_params().unwrap(), HashParams::default());
+    }
+
+    #[test]
+    fn hash_params_from_toml() {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, r#"
+[database]
+hash_memory_kib = 64
+hash_iterations = 3
+"#).unwrap();
+        let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
+        assert_eq!(config.to_hash_params().unwrap(), HashParams::new(64, 3, 1).unwrap());
+
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, "[database]\nhash_iterations = 0\n").unwrap();
+        let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
+        assert!(config.to_hash_params().is_err());
     }
 
     #[test]
@@ -239,12 +355,23 @@ mod tests {
         assert_eq!(tc.max_ticks, 0);
     }
 
+    #[test]
+    fn script_globals_include_timing() {
+        let config = ServerConfig::default();
+        let globals = config.to_script_globals();
+        assert_eq!(globals["mode"], "mud");
+        assert_eq!(globals["tps"], config.tick.tps);
+        assert_eq!(globals["snapshot_interval"], config.persistence.snapshot_interval);
+    }
+
     #[test]
     fn to_script_config() {
         let config = ServerConfig::default();
         let sc = config.to_script_config();
         assert_eq!(sc.memory_limit, 16384 * 1024);
         assert_eq!(sc.instruction_limit, 1_000_000);
+        assert_eq!(sc.init_instruction_limit, 100_000_000);
+        assert_eq!(sc.lib_dir, Some(PathBuf::from("scripts/lib")));
     }
 
     #[test]
@@ -265,10 +392,47 @@ mod tests {
         write!(f, r#"
 [tick]
 tps = 20
+seed = 42
 "#).unwrap();
 
         let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
         assert_eq!(config.tick.tps, 20);
+        assert_eq!(config.tick.seed, Some(42));
         assert_eq!(config.net.telnet_addr, "0.0.0.0:4000");
     }
+
+    #[test]
+    fn security_ip_lists_build_filter() {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, r#"
+[security]
+ip_allow = ["10.0.0.0/8"]
+ip_deny = ["10.9.0.0/16"]
+"#).unwrap();
+
+        let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
+        let filter = config.to_ip_filter().unwrap();
+        assert!(filter.check("10.1.2.3".parse().unwrap()).is_ok());
+        assert!(filter.check("10.9.2.3".parse().unwrap()).is_err());
+        assert!(filter.check("192.168.1.1".parse().unwrap()).is_err());
+
+        let mut bad = ServerConfig::default();
+        bad.security.ip_deny = vec!["not-an-ip".to_string()];
+        assert!(bad.to_ip_filter().is_err());
+    }
+
+    #[test]
+    fn security_line_limit_from_toml() {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, r#"
+[security]
+max_input_length = 512
+overlong_input = "disconnect"
+"#).unwrap();
+
+        let config = ServerConfig::load(Some(f.path().to_str().unwrap())).unwrap();
+        let limit = config.security.line_limit();
+        assert_eq!(limit.max_len, 512);
+        assert_eq!(limit.on_overflow, net::telnet::OverlongLine::Disconnect);
+    }
 }
@@ -2,23 +2,30 @@ mod auth_adapter;
 mod config;
 mod shutdown;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use ecs_adapter::EcsAdapter;
-use engine_core::tick::TickLoop;
-use mud::components::*;
-use mud::parser::{parse_input, PlayerAction};
-use mud::persistence_setup::register_mud_components;
-use mud::script_setup::register_mud_script_components;
+use engine_core::despawn::despawn_with_space_and;
+use engine_core::tick::{TickLoop, TickPacer};
+use mud::parser::{parse_input_with_socials, split_commands, PlayerAction, SocialTable};
+use mud::persistence_setup::{export_character_json, register_mud_components};
+use mud::output::MessageTemplates;
+use mud::script_setup::{
+    register_follow_api, register_message_api, register_mud_script_components, register_party_api,
+};
+use mud::systems::status::PlayerStatus;
 use mud::systems::{GameContext, PlayerInput};
 use net::channels::{NetToTick, OutputTx, PlayerRx};
+use net::ip_filter::IpFilterHandle;
+use net::protocol::{ItemWire, ServerMessage};
+use observability::{TickPhase, TickProfiler};
 use persistence::manager::SnapshotManager;
 use persistence::registry::PersistenceRegistry;
 use persistence::snapshot;
 use scripting::engine::{ScriptContext, ScriptEngine};
 use scripting::ContentRegistry;
-use session::{SessionId, SessionManager, SessionOutput, SessionState};
+use session::{ConnectionInfo, PermissionLevel, SessionId, SessionManager, SessionOutput, SessionState};
 use space::RoomGraphSpace;
 use space::SpaceModel;
 
@@ -26,20 +33,33 @@ use crate::auth_adapter::PlayerDbAuthProvider;
 use crate::config::{parse_cli_args, ServerConfig};
 use crate::shutdown::{shutdown_channel, ShutdownRx};
 
-use player_db::PlayerDb;
+use player_db::{HashParams, PlayerDb};
 
 #[tokio::main]
 async fn main() {
     observability::init_logging();
 
-    let config = parse_cli_args();
+    let (config, config_path) = parse_cli_args();
+    let ip_filter = IpFilterHandle::start(
+        &config.security.ip_allow,
+        &config.security.ip_deny,
+        config_path.map(PathBuf::from),
+        |p| {
+            let config = ServerConfig::load(p.to_str()).map_err(|e| e.to_string())?;
+            config.to_ip_filter().map_err(|e| e.to_string())
+        },
+    )
+    .unwrap_or_else(|e| {
+        tracing::error!("Invalid IP filter config: {}", e);
+        std::process::exit(1);
+    });
     tracing::info!("MUD Server starting...");
 
     let (shutdown_tx, shutdown_rx) = shutdown_channel();
 
     let config_clone = config.clone();
     let server_future = async move {
-        run_mud_server(config_clone, shutdown_rx).await;
+        run_mud_server(config_clone, shutdown_rx, ip_filter).await;
     };
 
     tokio::select! {
@@ -54,7 +74,7 @@ async fn main() {
     tracing::info!("Server stopped.");
 }
 
-async fn run_mud_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
+async fn run_mud_server(config: ServerConfig, shutdown_rx: ShutdownRx, ip_filter: IpFilterHandle) {
     // Channels between async and tick thread
     let (player_tx, player_rx) = tokio::sync::mpsc::unbounded_channel();
     let (output_tx, output_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -73,6 +93,7 @@ async fn run_mud_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
     let register_tx_clone = register_tx.clone();
     let unregister_tx_clone = unregister_tx.clone();
     let tcp_shutdown = shutdown_rx.clone();
+    let line_limit = config.security.line_limit();
     tokio::spawn(async move {
         if let Err(e) = net::server::run_tcp_server_with_shutdown(
             listen_addr.clone(),
@@ -80,6 +101,8 @@ async fn run_mud_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
             register_tx_clone,
             unregister_tx_clone,
             tcp_shutdown.into_inner(),
+            Some(ip_filter),
+            line_limit,
         )
         .await
         {
@@ -102,13 +125,24 @@ async fn run_mud_server(config: ServerConfig, shutdown_rx: ShutdownRx) {
 fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: ServerConfig, shutdown_rx: ShutdownRx) {
     let tick_config = config.to_tick_config();
     let mut tick_loop = TickLoop::new(tick_config, RoomGraphSpace::new());
+    if let Some(seed) = config.tick.seed {
+        tick_loop.set_master_seed(seed);
+    }
     let mut sessions = SessionManager::new();
     let snapshot_mgr = SnapshotManager::new(&config.persistence.save_dir);
     let auth_required = config.database.auth_required;
 
     // Open player DB if auth is required
     let player_db = if auth_required {
-        match PlayerDb::open(&config.database.path) {
+        let hash_params = match config.to_hash_params() {
+            Ok(params) => params,
+            Err(e) => {
+                tracing::error!("Invalid password hash parameters: {}", e);
+                std::process::exit(1);
+            }
+        };
+        check_hash_latency(hash_params, config.database.hash_target_ms);
+        match PlayerDb::open(&config.database.path, hash_params) {
             Ok(db) => {
                 tracing::info!(path = %config.database.path, "Player database opened");
                 Some(db)
@@ -134,11 +168,26 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             std::process::exit(1);
         }
     };
+    if let Err(e) = script_engine.set_config_globals(&config.to_script_globals()) {
+        tracing::warn!("Failed to expose config to Lua: {}", e);
+    }
 
     // Register MUD components with the script engine
     register_mud_script_components(script_engine.component_registry_mut());
+    if let Err(e) = register_party_api(script_engine.lua()) {
+        tracing::error!("Failed to register party API: {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = register_follow_api(script_engine.lua()) {
+        tracing::error!("Failed to register follow API: {}", e);
+        std::process::exit(1);
+    }
+
+    let command_separator = config.net.command_separator();
 
     // Load content from content/ directory if it exists
+    let mut socials = SocialTable::new();
+    let mut templates = MessageTemplates::new();
     let content_path = Path::new(&config.scripting.content_dir);
     if content_path.is_dir() {
         match ContentRegistry::load_dir(content_path) {
@@ -151,10 +200,16 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
                 if let Err(e) = script_engine.register_content(&registry) {
                     tracing::warn!("Failed to register content in Lua: {}", e);
                 }
+                socials = SocialTable::from_content(&registry);
+                templates = MessageTemplates::from_content(&registry);
             }
             Err(e) => tracing::warn!("Failed to load content: {}", e),
         }
     }
+    if let Err(e) = register_message_api(script_engine.lua(), templates) {
+        tracing::error!("Failed to register message API: {}", e);
+        std::process::exit(1);
+    }
 
     // Load scripts from scripts/ directory if it exists
     let scripts_path = Path::new(&config.scripting.scripts_dir);
@@ -177,11 +232,22 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
     // Try to restore from snapshot
     if snapshot_mgr.has_latest() {
         match snapshot_mgr.load_latest() {
-            Ok(snap) => {
+            Ok(mut snap) => {
+                let lingering = std::mem::take(&mut snap.lingering);
                 match snapshot::restore(snap, &mut tick_loop.ecs, &mut tick_loop.space, &registry) {
                     Ok(tick) => {
                         tick_loop.current_tick = tick;
-                        tracing::info!(tick, "Restored from snapshot");
+                        // Reattach lingering players to their restored entities
+                        let allocator = tick_loop.ecs.allocator();
+                        let (alive, dropped): (Vec<_>, Vec<_>) = lingering
+                            .into_iter()
+                            .partition(|l| allocator.is_alive(l.entity));
+                        if !dropped.is_empty() {
+                            tracing::warn!(count = dropped.len(), "Dropped lingering records without entities");
+                        }
+                        let lingering_count = alive.len();
+                        sessions.import_lingering(alive);
+                        tracing::info!(tick, lingering = lingering_count, "Restored from snapshot");
                     }
                     Err(e) => {
                         tracing::warn!("Failed to restore snapshot: {}", e);
@@ -205,11 +271,12 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
         match script_engine.run_on_init(&mut script_ctx) {
             Ok(init_outputs) => {
                 for out in init_outputs {
-                    let _ = output_tx.send(out);
+                    send_output(&output_tx, &sessions, out);
                 }
             }
             Err(e) => {
                 tracing::error!("Lua on_init error: {}", e);
+                std::process::exit(1);
             }
         }
     }
@@ -218,18 +285,30 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
     let snapshot_interval = config.persistence.snapshot_interval;
     let character_save_interval = config.character.save_interval;
     let linger_timeout_ticks = config.character.linger_timeout_secs * config.tick.tps as u64;
+    let max_resident_lingering = config.character.max_resident_lingering;
+
+    let mut pacer = TickPacer::new(&tick_loop.config);
 
     loop {
-        if shutdown_rx.is_shutdown() {
-            tracing::info!("MUD tick loop: shutdown signal received");
+        // Waits out the rest of the tick, or returns at once while catching up
+        let paced = pacer.next_tick();
+        let max_ticks = tick_loop.config.max_ticks;
+        let max_ticks_reached = max_ticks > 0 && tick_loop.current_tick >= max_ticks;
+        if shutdown_rx.is_shutdown() || max_ticks_reached {
+            if max_ticks_reached {
+                tracing::info!(max_ticks, "MUD tick loop: max_ticks reached");
+            } else {
+                tracing::info!("MUD tick loop: shutdown signal received");
+            }
             // Save all characters to DB before shutdown
             if let Some(ref db) = player_db {
-                auto_save_characters(&tick_loop.ecs, &tick_loop.space, &sessions, db);
+                auto_save_characters(&tick_loop.ecs, &tick_loop.space, &registry, &sessions, db);
                 // Also save lingering entities
                 for linger in sessions.lingering_entities() {
                     save_character_state(
                         &tick_loop.ecs,
                         &tick_loop.space,
+                        &registry,
                         linger.entity,
                         linger.character_id,
                         db,
@@ -238,18 +317,20 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             }
             // Send shutdown message to all connected sessions
             for session in sessions.playing_sessions() {
-                let _ = output_tx.send(SessionOutput::with_disconnect(
-                    session.session_id,
-                    "서버가 종료됩니다. 안녕히 가세요!",
-                ));
+                send_output(
+                    &output_tx,
+                    &sessions,
+                    SessionOutput::with_disconnect(session.session_id, "서버가 종료됩니다. 안녕히 가세요!"),
+                );
             }
             // Final snapshot save
-            let snap = snapshot::capture(
+            let mut snap = snapshot::capture(
                 &tick_loop.ecs,
                 &tick_loop.space,
                 tick_loop.current_tick,
                 &registry,
             );
+            snap.lingering = sessions.export_lingering();
             if let Err(e) = snapshot_mgr.save_to_disk(&snap) {
                 tracing::error!("Failed to save final snapshot: {}", e);
             } else {
@@ -258,41 +339,49 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             break;
         }
 
-        let tick_start = std::time::Instant::now();
+        let mut profiler = TickProfiler::start();
+
+        if let Some(seed) = tick_loop.tick_seed() {
+            if let Err(e) = script_engine.set_tick_seed(seed) {
+                tracing::warn!("Failed to seed script RNG: {}", e);
+            }
+        }
 
         // Build auth provider for this tick (if auth is enabled)
-        let auth_provider = player_db.as_ref().map(|db| PlayerDbAuthProvider::new(db));
+        let auth_provider = player_db.as_ref().map(PlayerDbAuthProvider::new);
 
         // 1. Process network messages
         let mut inputs = Vec::new();
         while let Ok(msg) = player_rx.try_recv() {
             match msg {
-                NetToTick::NewConnection { session_id } => {
+                NetToTick::NewConnection { session_id, connection } => {
                     handle_new_connection(
                         &mut tick_loop.ecs,
                         &mut tick_loop.space,
                         &mut sessions,
                         &output_tx,
                         session_id,
+                        connection,
                         &script_engine,
                         tick_loop.current_tick,
                         auth_provider.as_ref().map(|p| p as &dyn scripting::AuthProvider),
                     );
                 }
                 NetToTick::PlayerInput { session_id, line } => {
-                    if let Some(input) = handle_player_input(
+                    sessions.record_input(session_id, line.len());
+                    inputs.extend(handle_player_input(
                         &mut tick_loop.ecs,
                         &mut tick_loop.space,
                         &mut sessions,
                         &output_tx,
                         session_id,
                         &line,
+                        &socials,
+                        command_separator,
                         &script_engine,
                         tick_loop.current_tick,
                         auth_provider.as_ref().map(|p| p as &dyn scripting::AuthProvider),
-                    ) {
-                        inputs.push(input);
-                    }
+                    ));
                 }
                 NetToTick::Disconnected { session_id } => {
                     handle_disconnect(
@@ -309,19 +398,15 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             }
         }
 
-        // 2. Run engine tick (WASM plugins, command stream)
-        let _metrics = tick_loop.step();
+        profiler.lap(TickPhase::Network);
 
-        // 3. Separate admin commands from normal inputs
-        let mut normal_inputs = Vec::new();
-        let mut admin_inputs = Vec::new();
-        for input in inputs {
-            if let PlayerAction::Admin { ref command, ref args } = input.action {
-                admin_inputs.push((input.session_id, input.entity, command.clone(), args.clone()));
-            } else {
-                normal_inputs.push(input);
-            }
-        }
+        // 2. Run engine tick (WASM plugins, command stream). While paused the
+        // world is frozen: network I/O and admin commands still go through.
+        let paused = tick_loop.step_if_running().is_none();
+        profiler.lap(TickPhase::EngineStep);
+
+        // 3. Separate admin commands and quits from normal inputs
+        let (normal_inputs, admin_inputs, quitting) = partition_inputs(inputs);
 
         // 3a. Run game systems — on_action hooks handle player input
         let mut ctx = GameContext {
@@ -330,23 +415,38 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             sessions: &mut sessions,
             tick: tick_loop.current_tick,
         };
-        let action_outputs = mud::systems::run_game_systems(&mut ctx, normal_inputs, Some(&script_engine));
-        for output in action_outputs {
-            let _ = output_tx.send(output);
-        }
+        let action_outputs = if paused {
+            normal_inputs
+                .iter()
+                .map(|input| SessionOutput::new(input.session_id, "세계가 잠시 멈춰 있습니다. 잠시 후 다시 시도하세요."))
+                .collect()
+        } else {
+            mud::systems::run_game_systems_with(&mut ctx, normal_inputs, Some(&script_engine), |input| {
+                mud::systems::unhandled_message(input, &socials)
+            })
+        };
+        let mut kicked = forward_outputs(&output_tx, &sessions, action_outputs);
+        profiler.lap(TickPhase::Systems);
 
         // 3b. Run admin commands via on_admin hooks
         for (admin_sid, admin_entity, admin_cmd, admin_args) in admin_inputs {
+            if admin_cmd == REENABLE_HOOKS_COMMAND {
+                let reply = reenable_hooks_command(&script_engine, &sessions, admin_sid);
+                send_output(&output_tx, &sessions, reply);
+                continue;
+            }
             let permission = sessions
+                .session_permission(admin_sid)
+                .unwrap_or_default();
+            let is_builder = sessions
                 .get_session(admin_sid)
-                .map(|s| s.permission.as_i32())
-                .unwrap_or(0);
+                .is_some_and(|s| s.can(PermissionLevel::Builder));
             let admin_info = scripting::engine::AdminInfo {
                 command: admin_cmd.clone(),
                 args: admin_args,
                 session_id: admin_sid,
                 entity: admin_entity,
-                permission,
+                permission: permission.as_i32(),
             };
             let mut script_ctx = ScriptContext {
                 ecs: &mut tick_loop.ecs,
@@ -356,35 +456,53 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             };
             match script_engine.run_on_admin(&mut script_ctx, &admin_info) {
                 Ok((admin_outputs, handled)) => {
-                    for out in admin_outputs {
-                        let _ = output_tx.send(out);
-                    }
+                    kicked.extend(forward_outputs(&output_tx, &sessions, admin_outputs));
                     if !handled {
-                        if permission < 1 {
-                            let _ = output_tx.send(SessionOutput::new(
-                                admin_sid,
-                                "관리자 명령어를 사용할 권한이 없습니다.",
-                            ));
+                        if !is_builder {
+                            send_output(
+                                &output_tx,
+                                &sessions,
+                                SessionOutput::new(admin_sid, "관리자 명령어를 사용할 권한이 없습니다."),
+                            );
                         } else {
-                            let _ = output_tx.send(SessionOutput::new(
-                                admin_sid,
-                                format!("알 수 없는 관리자 명령어: /{}", admin_cmd),
-                            ));
+                            send_output(
+                                &output_tx,
+                                &sessions,
+                                SessionOutput::new(admin_sid, format!("알 수 없는 관리자 명령어: /{}", admin_cmd)),
+                            );
                         }
                     }
                 }
                 Err(e) => {
                     tracing::warn!("Admin command error: {}", e);
-                    let _ = output_tx.send(SessionOutput::new(
-                        admin_sid,
-                        format!("관리자 명령어 오류: {}", e),
-                    ));
+                    send_output(
+                        &output_tx,
+                        &sessions,
+                        SessionOutput::new(admin_sid, format!("관리자 명령어 오류: {}", e)),
+                    );
                 }
             }
         }
 
+        // 3c. Quit once the commands chained before it have run
+        for session_id in quitting {
+            send_output(&output_tx, &sessions, SessionOutput::with_disconnect(session_id, "안녕히 가세요!"));
+            handle_disconnect(
+                &mut tick_loop.ecs,
+                &mut tick_loop.space,
+                &mut sessions,
+                &output_tx,
+                session_id,
+                &script_engine,
+                tick_loop.current_tick,
+                auth_provider.as_ref().map(|p| p as &dyn scripting::AuthProvider),
+            );
+        }
+
+        profiler.lap(TickPhase::Admin);
+
         // 4. Run Lua on_tick hooks (combat resolution, periodic systems)
-        {
+        if !paused {
             let mut script_ctx = ScriptContext {
                 ecs: &mut tick_loop.ecs,
                 space: &mut tick_loop.space,
@@ -393,9 +511,7 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             };
             match script_engine.run_on_tick(&mut script_ctx) {
                 Ok(script_outputs) => {
-                    for output in script_outputs {
-                        let _ = output_tx.send(output);
-                    }
+                    kicked.extend(forward_outputs(&output_tx, &sessions, script_outputs));
                 }
                 Err(e) => {
                     tracing::warn!("Lua on_tick error: {}", e);
@@ -403,10 +519,43 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
             }
         }
 
+        profiler.lap(TickPhase::ScriptTick);
+
+        // 4a. Close sessions that scripts asked to disconnect
+        kicked.sort();
+        kicked.dedup();
+        for session_id in kicked {
+            handle_disconnect(
+                &mut tick_loop.ecs,
+                &mut tick_loop.space,
+                &mut sessions,
+                &output_tx,
+                session_id,
+                &script_engine,
+                tick_loop.current_tick,
+                auth_provider.as_ref().map(|p| p as &dyn scripting::AuthProvider),
+            );
+        }
+
+        profiler.lap(TickPhase::Disconnect);
+
+        // Surface the hook errors queued by this tick's hook passes
+        report_hook_errors(&script_engine);
+
+        // 4b. Push vitals/inventory to clients with structured output
+        for (session_id, status) in mud::systems::status::status_updates(&tick_loop.ecs, &sessions) {
+            let msg = status_update_message(status);
+            send_output(&output_tx, &sessions, SessionOutput::structured(session_id, msg.to_json()));
+        }
+        tick_loop.ecs.clear_change_tracking();
+
+        profiler.lap(TickPhase::Broadcast);
+
         // 5. Periodic snapshot
-        if tick_loop.current_tick > 0 && tick_loop.current_tick % snapshot_interval == 0 {
-            let snap =
+        if !paused && tick_loop.current_tick > 0 && tick_loop.current_tick.is_multiple_of(snapshot_interval) {
+            let mut snap =
                 snapshot::capture(&tick_loop.ecs, &tick_loop.space, tick_loop.current_tick, &registry);
+            snap.lingering = sessions.export_lingering();
             if let Err(e) = snapshot_mgr.save_to_disk(&snap) {
                 tracing::error!("Failed to save snapshot: {}", e);
             }
@@ -414,47 +563,94 @@ fn run_mud_tick_thread(mut player_rx: PlayerRx, output_tx: OutputTx, config: Ser
 
         // 6. Character auto-save (only in auth mode)
         if let Some(ref db) = player_db {
-            if character_save_interval > 0
+            if !paused
+                && character_save_interval > 0
                 && tick_loop.current_tick > 0
-                && tick_loop.current_tick % character_save_interval == 0
+                && tick_loop.current_tick.is_multiple_of(character_save_interval)
             {
-                auto_save_characters(&tick_loop.ecs, &tick_loop.space, &sessions, db);
+                auto_save_characters(&tick_loop.ecs, &tick_loop.space, &registry, &sessions, db);
+            }
+
+            // 7. Hibernate lingering entities beyond the resident limit
+            if max_resident_lingering > 0 {
+                hibernate_excess_lingering(
+                    &mut tick_loop.ecs,
+                    &mut tick_loop.space,
+                    &mut sessions,
+                    &script_engine,
+                    max_resident_lingering,
+                    &registry,
+                    db,
+                );
             }
 
-            // 7. Clean up expired lingering entities
+            // 8. Clean up expired lingering entities
             if linger_timeout_ticks > 0 {
                 cleanup_expired_lingering(
                     &mut tick_loop.ecs,
                     &mut tick_loop.space,
                     &mut sessions,
+                    &script_engine,
                     tick_loop.current_tick,
                     linger_timeout_ticks,
+                    &registry,
                     Some(db),
                 );
             }
         }
 
-        // Sleep for remainder of tick
-        let elapsed = tick_start.elapsed();
-        if elapsed < tick_duration {
-            std::thread::sleep(tick_duration - elapsed);
-        }
+        profiler.lap(TickPhase::Persistence);
+        let mut profile = profiler.finish(tick_loop.current_tick);
+        profile.skipped_ticks = paced.skipped;
+        profile.log(tick_duration);
+        observability::slow_tick_sampler().finish_tick(&profile, tick_duration);
     }
 
     tracing::info!("MUD tick loop stopped");
 }
 
+/// Time one password hash with `params` and log it; warn when it exceeds
+/// the configured login latency target, since every login pays this cost.
+fn check_hash_latency(params: HashParams, target_ms: u64) {
+    let elapsed = match params.measure() {
+        Ok(elapsed) => elapsed,
+        Err(e) => {
+            tracing::error!("Password hashing failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+    tracing::info!(
+        memory_kib = params.memory_kib,
+        iterations = params.iterations,
+        parallelism = params.parallelism,
+        hash_ms = elapsed.as_millis() as u64,
+        "Password hash parameters"
+    );
+    if target_ms > 0 && elapsed > std::time::Duration::from_millis(target_ms) {
+        tracing::warn!(
+            hash_ms = elapsed.as_millis() as u64,
+            target_ms,
+            "Password hashing exceeds hash_target_ms; lower hash_memory_kib"
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_new_connection(
     ecs: &mut EcsAdapter,
     space: &mut RoomGraphSpace,
     sessions: &mut SessionManager,
     output_tx: &OutputTx,
     session_id: SessionId,
+    connection: ConnectionInfo,
     script_engine: &ScriptEngine,
     tick: u64,
     auth: Option<&dyn scripting::AuthProvider>,
 ) {
-    sessions.create_session_with_id(session_id);
+    sessions.create_session_with_connection(session_id, connection);
+    if let Some(session) = sessions.get_session_mut(session_id) {
+        session.connected_tick = tick;
+    }
 
     // Fire on_connect hooks (Lua sends welcome message)
     let mut script_ctx = ScriptContext {
@@ -465,8 +661,8 @@ fn handle_new_connection(
     };
     match script_engine.run_on_connect(&mut script_ctx, session_id) {
         Ok(connect_outputs) => {
-            for out in connect_outputs {
-                let _ = output_tx.send(out);
+            for kicked in forward_outputs(output_tx, sessions, connect_outputs) {
+                handle_disconnect(ecs, space, sessions, output_tx, kicked, script_engine, tick, auth);
             }
         }
         Err(e) => {
@@ -474,11 +670,37 @@ fn handle_new_connection(
         }
     }
 
-    // If on_connect didn't send anything (no hooks registered), send a default
-    // This is only for backwards compatibility when no login script exists
-    let _ = auth; // auth not needed here; on_connect doesn't require it
 }
 
+/// An admin command: (session, entity, command, args).
+type AdminInput = (SessionId, ecs_adapter::EntityId, String, String);
+
+/// Split this tick's inputs into game actions, admin commands and sessions
+/// that quit, keeping order. Inputs a session sends after its quit are dropped.
+fn partition_inputs(inputs: Vec<PlayerInput>) -> (Vec<PlayerInput>, Vec<AdminInput>, Vec<SessionId>) {
+    let mut normal_inputs = Vec::new();
+    let mut admin_inputs = Vec::new();
+    let mut quitting = Vec::new();
+    for input in inputs {
+        if quitting.contains(&input.session_id) {
+            continue;
+        }
+        match input.action {
+            PlayerAction::Admin { command, args } => {
+                admin_inputs.push((input.session_id, input.entity, command, args));
+            }
+            PlayerAction::Quit => quitting.push(input.session_id),
+            _ => normal_inputs.push(input),
+        }
+    }
+    (normal_inputs, admin_inputs, quitting)
+}
+
+/// Turn one input line into this tick's player inputs. While playing, a line
+/// chained with `separator` yields one input per command, in order; a `quit`
+/// ends the chain and drops the commands after it. The tick loop disconnects
+/// on `Quit` only after the commands before it have run.
+#[allow(clippy::too_many_arguments)]
 fn handle_player_input(
     ecs: &mut EcsAdapter,
     space: &mut RoomGraphSpace,
@@ -486,11 +708,15 @@ fn handle_player_input(
     output_tx: &OutputTx,
     session_id: SessionId,
     line: &str,
+    socials: &SocialTable,
+    separator: Option<char>,
     script_engine: &ScriptEngine,
     current_tick: u64,
     auth: Option<&dyn scripting::AuthProvider>,
-) -> Option<PlayerInput> {
-    let session = sessions.get_session(session_id)?;
+) -> Vec<PlayerInput> {
+    let Some(session) = sessions.get_session(session_id) else {
+        return Vec::new();
+    };
     let state = session.state.clone();
 
     match state {
@@ -504,8 +730,8 @@ fn handle_player_input(
             };
             match script_engine.run_on_input(&mut script_ctx, session_id, line, auth) {
                 Ok(input_outputs) => {
-                    for out in input_outputs {
-                        let _ = output_tx.send(out);
+                    for kicked in forward_outputs(output_tx, sessions, input_outputs) {
+                        handle_disconnect(ecs, space, sessions, output_tx, kicked, script_engine, current_tick, auth);
                     }
                 }
                 Err(e) => {
@@ -518,37 +744,84 @@ fn handle_player_input(
                 if session.state == SessionState::Playing {
                     if let Some(entity) = session.entity {
                         // Auto-look after login
-                        return Some(PlayerInput {
+                        return vec![PlayerInput {
                             session_id,
                             entity,
                             action: PlayerAction::Look,
-                        });
+                        }];
                     }
                 }
             }
 
-            None
+            Vec::new()
         }
         SessionState::Playing => {
-            let entity = session.entity?;
-            let action = parse_input(line);
+            let Some(entity) = session.entity else {
+                return Vec::new();
+            };
+            let commands = match separator {
+                Some(sep) => split_commands(line, sep),
+                None => vec![line],
+            };
 
-            if action == PlayerAction::Quit {
-                let _ = output_tx.send(SessionOutput::with_disconnect(session_id, "안녕히 가세요!"));
-                handle_disconnect(ecs, space, sessions, output_tx, session_id, script_engine, current_tick, auth);
-                return None;
+            let mut inputs = Vec::new();
+            for command in commands {
+                let action = parse_input_with_socials(command, socials);
+                let quit = action == PlayerAction::Quit;
+                inputs.push(PlayerInput {
+                    session_id,
+                    entity,
+                    action,
+                });
+                if quit {
+                    break;
+                }
             }
+            inputs
+        }
+        SessionState::Disconnected => Vec::new(),
+    }
+}
 
-            Some(PlayerInput {
-                session_id,
-                entity,
-                action,
-            })
+/// Word-wrap script outputs to each session's width, send them to the router
+/// and return the sessions that asked to be disconnected (via
+/// `output:send_and_disconnect`), in first-seen order.
+fn forward_outputs(
+    output_tx: &OutputTx,
+    sessions: &SessionManager,
+    outputs: Vec<SessionOutput>,
+) -> Vec<SessionId> {
+    let mut kicked = Vec::new();
+    for out in outputs {
+        if out.disconnect && !kicked.contains(&out.session_id) {
+            kicked.push(out.session_id);
         }
-        SessionState::Disconnected => None,
+        send_output(output_tx, sessions, out);
+    }
+    kicked
+}
+
+/// Wire form of a player's status. Transports drop it for clients without
+/// structured output, so it is sent unconditionally alongside the text.
+fn status_update_message(status: PlayerStatus) -> ServerMessage {
+    ServerMessage::StatusUpdate {
+        health: status.health,
+        max_health: status.max_health,
+        inventory: status
+            .inventory
+            .into_iter()
+            .map(|item| ItemWire { id: item.id, name: item.name })
+            .collect(),
     }
 }
 
+/// Word-wrap one output to its session's width and send it to the router.
+/// Every player-facing message goes through here so none skips wrapping.
+fn send_output(output_tx: &OutputTx, sessions: &SessionManager, out: SessionOutput) {
+    let _ = output_tx.send(mud::output::format_for_session(sessions, out));
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_disconnect(
     ecs: &mut EcsAdapter,
     space: &mut RoomGraphSpace,
@@ -569,7 +842,7 @@ fn handle_disconnect(
     match script_engine.run_on_disconnect(&mut script_ctx, session_id, auth) {
         Ok(disconnect_outputs) => {
             for out in disconnect_outputs {
-                let _ = output_tx.send(out);
+                send_output(output_tx, script_ctx.sessions, out);
             }
         }
         Err(e) => {
@@ -581,83 +854,50 @@ fn handle_disconnect(
     // This ensures resources are freed even if there's no on_disconnect hook.
     if sessions.get_session(session_id).is_some() {
         if let Some(entity) = sessions.disconnect(session_id) {
-            let _ = space.remove_entity(entity);
-            let _ = ecs.despawn_entity(entity);
+            despawn_player(ecs, space, script_engine, entity);
         }
         sessions.remove_session(session_id);
     }
 }
 
 /// Save a single character's ECS state to the database.
+/// Registered components are saved keyed by tag, except runtime-only ones
+/// (see `CHARACTER_RUNTIME_TAGS`). Returns false (after logging) if the save failed.
 fn save_character_state(
     ecs: &EcsAdapter,
     space: &RoomGraphSpace,
+    registry: &PersistenceRegistry,
     entity: ecs_adapter::EntityId,
     character_id: i64,
     db: &PlayerDb,
-) {
-    let mut components = serde_json::Map::new();
-
-    if let Ok(health) = ecs.get_component::<Health>(entity) {
-        components.insert(
-            "Health".to_string(),
-            serde_json::json!({"current": health.current, "max": health.max}),
-        );
-    }
-    if let Ok(attack) = ecs.get_component::<Attack>(entity) {
-        components.insert("Attack".to_string(), serde_json::json!(attack.0));
-    }
-    if let Ok(defense) = ecs.get_component::<Defense>(entity) {
-        components.insert("Defense".to_string(), serde_json::json!(defense.0));
-    }
-    if let Ok(race) = ecs.get_component::<Race>(entity) {
-        components.insert("Race".to_string(), serde_json::json!(race.0));
-    }
-    if let Ok(class) = ecs.get_component::<Class>(entity) {
-        components.insert("Class".to_string(), serde_json::json!(class.0));
-    }
-    if let Ok(level) = ecs.get_component::<Level>(entity) {
-        components.insert("Level".to_string(), serde_json::json!(level.0));
-    }
-    if let Ok(exp) = ecs.get_component::<Experience>(entity) {
-        components.insert("Experience".to_string(), serde_json::json!(exp.0));
-    }
-    if let Ok(mana) = ecs.get_component::<Mana>(entity) {
-        components.insert(
-            "Mana".to_string(),
-            serde_json::json!({"current": mana.current, "max": mana.max}),
-        );
-    }
-    if let Ok(skills) = ecs.get_component::<Skills>(entity) {
-        components.insert(
-            "Skills".to_string(),
-            serde_json::json!({"learned": skills.learned}),
-        );
-    }
-
+) -> bool {
+    let components = export_character_json(registry, ecs, entity);
     let room_id = space.entity_room(entity).map(|r| r.to_u64());
 
     if let Err(e) = db.character().save_state(
         character_id,
-        &serde_json::Value::Object(components),
+        &components,
         room_id,
         None,
     ) {
         tracing::warn!(character_id, "Failed to save character state: {}", e);
+        return false;
     }
+    true
 }
 
 /// Auto-save all playing characters to DB.
 fn auto_save_characters(
     ecs: &EcsAdapter,
     space: &RoomGraphSpace,
+    registry: &PersistenceRegistry,
     sessions: &SessionManager,
     db: &PlayerDb,
 ) {
     let mut count = 0u32;
     for session in sessions.playing_sessions() {
         if let (Some(entity), Some(character_id)) = (session.entity, session.character_id) {
-            save_character_state(ecs, space, entity, character_id, db);
+            save_character_state(ecs, space, registry, entity, character_id, db);
             count += 1;
         }
     }
@@ -666,13 +906,22 @@ fn auto_save_characters(
     }
 }
 
+/// Remove a player entity from the world and drop it from the Lua-side
+/// party and follow registries.
+fn despawn_player(ecs: &mut EcsAdapter, space: &mut RoomGraphSpace, script_engine: &ScriptEngine, entity: ecs_adapter::EntityId) {
+    despawn_with_space_and(ecs, space, entity, |e| mud::script_setup::forget_entity(script_engine.lua(), e));
+}
+
 /// Clean up expired lingering entities.
+#[allow(clippy::too_many_arguments)]
 fn cleanup_expired_lingering(
     ecs: &mut EcsAdapter,
     space: &mut RoomGraphSpace,
     sessions: &mut SessionManager,
+    script_engine: &ScriptEngine,
     current_tick: u64,
     timeout_ticks: u64,
+    registry: &PersistenceRegistry,
     db: Option<&PlayerDb>,
 ) {
     let expired = sessions.expired_lingering(current_tick, timeout_ticks);
@@ -680,11 +929,390 @@ fn cleanup_expired_lingering(
         if let Some(linger) = sessions.remove_lingering(character_id) {
             // Save final state to DB before despawning
             if let Some(db) = db {
-                save_character_state(ecs, space, linger.entity, linger.character_id, db);
+                save_character_state(ecs, space, registry, linger.entity, linger.character_id, db);
             }
-            let _ = space.remove_entity(linger.entity);
-            let _ = ecs.despawn_entity(linger.entity);
+            despawn_player(ecs, space, script_engine, linger.entity);
             tracing::info!(character_id, ?linger.entity, "Lingering entity expired, despawned");
         }
     }
+    // Hibernated characters are already saved; just forget them.
+    for character_id in sessions.expired_hibernated(current_tick, timeout_ticks) {
+        sessions.wake_hibernated(character_id);
+        tracing::info!(character_id, "Hibernated character expired, purged");
+    }
+}
+
+/// Save and despawn the oldest lingering entities until at most
+/// `max_resident` remain in memory. Their characters are rebuilt from the
+/// DB if they reconnect before the linger timeout.
+fn hibernate_excess_lingering(
+    ecs: &mut EcsAdapter,
+    space: &mut RoomGraphSpace,
+    sessions: &mut SessionManager,
+    script_engine: &ScriptEngine,
+    max_resident: usize,
+    registry: &PersistenceRegistry,
+    db: &PlayerDb,
+) {
+    for character_id in sessions.hibernation_candidates(max_resident) {
+        let Some(entity) = sessions.find_lingering(character_id).map(|l| l.entity) else {
+            continue;
+        };
+        // Keep the entity resident if its state could not be saved.
+        if !save_character_state(ecs, space, registry, entity, character_id, db) {
+            continue;
+        }
+        sessions.hibernate_lingering(character_id);
+        despawn_player(ecs, space, script_engine, entity);
+        tracing::info!(character_id, ?entity, "Lingering entity hibernated to DB");
+    }
+}
+
+/// Admin command that turns hooks disabled for repeated errors back on.
+/// Handled in Rust so it still works when the admin scripts' own hooks are
+/// the ones that were disabled.
+const REENABLE_HOOKS_COMMAND: &str = "reenable_hooks";
+
+/// Run `/reenable_hooks` for `session_id` (Admin+) and build the reply.
+fn reenable_hooks_command(script_engine: &ScriptEngine, sessions: &SessionManager, session_id: SessionId) -> SessionOutput {
+    let is_admin = sessions
+        .get_session(session_id)
+        .is_some_and(|s| s.can(PermissionLevel::Admin));
+    if !is_admin {
+        return SessionOutput::new(session_id, "관리자 명령어를 사용할 권한이 없습니다.");
+    }
+    let count = script_engine.reenable_hooks();
+    tracing::info!(?session_id, count, "Hooks re-enabled by admin command");
+    SessionOutput::new(session_id, format!("비활성화된 훅 {}개를 다시 활성화했습니다.", count))
+}
+
+/// Log and count the Lua hook errors queued since the last call.
+fn report_hook_errors(script_engine: &ScriptEngine) {
+    for err in script_engine.take_hook_errors() {
+        tracing::error!(source = %err.source, hook = %err.hook, "Lua hook error: {}", err.message);
+        observability::metrics::record_script_error(&err.hook);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shutdown::shutdown_channel;
+
+    #[test]
+    fn tick_thread_stops_cleanly_at_max_ticks() {
+        let dir = std::env::temp_dir().join("mud_test_max_ticks");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut config = ServerConfig::default();
+        config.tick.tps = 100;
+        config.tick.max_ticks = 5;
+        config.tick.realtime = false;
+        config.persistence.save_dir = dir.join("snapshots").to_string_lossy().into_owned();
+        config.scripting.scripts_dir = dir.join("scripts").to_string_lossy().into_owned();
+        config.scripting.content_dir = dir.join("content").to_string_lossy().into_owned();
+
+        let (_player_tx, player_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (output_tx, _output_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (_shutdown_tx, shutdown_rx) = shutdown_channel();
+        // Returns on its own without a shutdown signal.
+        run_mud_tick_thread(player_rx, output_tx, config.clone(), shutdown_rx);
+
+        // The shutdown path ran: a final snapshot at exactly tick 5.
+        let snap = SnapshotManager::new(&config.persistence.save_dir).load_latest().unwrap();
+        assert_eq!(snap.tick, 5);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Script engine running the real login flow, plus a world with one room.
+    fn login_world() -> (ScriptEngine, EcsAdapter, RoomGraphSpace) {
+        let mut engine = ScriptEngine::new(scripting::ScriptConfig::default()).unwrap();
+        register_mud_script_components(engine.component_registry_mut());
+        let scripts = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/scripts"));
+        for name in ["00_utils", "05_login"] {
+            let src = std::fs::read_to_string(scripts.join(format!("{}.lua", name))).unwrap();
+            engine.load_script(name, &src).unwrap();
+        }
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let room = ecs.spawn_entity();
+        space.register_room(room, Default::default());
+        (engine, ecs, space)
+    }
+
+    /// Log `username` in through 05_login.lua and pick their first character.
+    fn login_first_character(
+        engine: &ScriptEngine,
+        ecs: &mut EcsAdapter,
+        space: &mut RoomGraphSpace,
+        sessions: &mut SessionManager,
+        db: &PlayerDb,
+        username: &str,
+        password: &str,
+    ) -> ecs_adapter::EntityId {
+        let auth = PlayerDbAuthProvider::new(db);
+        let sid = sessions.create_session();
+        let mut ctx = ScriptContext { ecs, space, sessions, tick: 0 };
+        engine.run_on_connect(&mut ctx, sid).unwrap();
+        for line in [username, password, "1"] {
+            engine.run_on_input(&mut ctx, sid, line, Some(&auth)).unwrap();
+        }
+        let session = sessions.get_session(sid).unwrap();
+        assert_eq!(session.state, SessionState::Playing);
+        session.entity.unwrap()
+    }
+
+    #[test]
+    fn hibernated_character_logs_back_in_with_every_component() {
+        use mud::components::*;
+
+        let mut registry = PersistenceRegistry::new();
+        register_mud_components(&mut registry);
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("sleeper", "password123").unwrap();
+        let character = db
+            .character()
+            .create(account.id, "잠꾸러기", &serde_json::json!({}))
+            .unwrap();
+        let (engine, mut ecs, mut space) = login_world();
+        let mut sessions = SessionManager::new();
+
+        let eid = login_first_character(
+            &engine, &mut ecs, &mut space, &mut sessions, &db, "sleeper", "password123",
+        );
+        ecs.set_component(eid, Health { current: 40, max: 120 }).unwrap();
+        ecs.set_component(eid, Level(4)).unwrap();
+        ecs.set_component(eid, Experience(900)).unwrap();
+        ecs.set_component(eid, Mana { current: 12, max: 30 }).unwrap();
+        ecs.set_component(eid, Wallet { copper: 1250 }).unwrap();
+        ecs.set_component(eid, Description("졸린 얼굴".to_string())).unwrap();
+        ecs.set_component(eid, GameData(serde_json::json!({"quest": {"stage": 2}}))).unwrap();
+        ecs.add_tag(eid, "veteran").unwrap();
+        let before = export_character_json(&registry, &ecs, eid);
+
+        // Disconnect leaves the entity lingering; memory pressure hibernates it.
+        let sid = sessions.session_id_for_entity(eid).unwrap();
+        let auth = PlayerDbAuthProvider::new(&db);
+        let mut ctx = ScriptContext { ecs: &mut ecs, space: &mut space, sessions: &mut sessions, tick: 0 };
+        engine.run_on_disconnect(&mut ctx, sid, Some(&auth)).unwrap();
+        assert!(sessions.find_lingering(character.id).is_some());
+        hibernate_excess_lingering(&mut ecs, &mut space, &mut sessions, &engine, 0, &registry, &db);
+        assert!(!ecs.is_alive(eid));
+        assert_eq!(sessions.hibernated_count(), 1);
+
+        let woke = login_first_character(
+            &engine, &mut ecs, &mut space, &mut sessions, &db, "sleeper", "password123",
+        );
+        assert_eq!(sessions.hibernated_count(), 0);
+        assert_eq!(export_character_json(&registry, &ecs, woke), before);
+        for tag in ["Wallet", "Experience", "Mana", "GameData", "Tags", "Description"] {
+            assert!(before.get(tag).is_some(), "missing {}", tag);
+        }
+        assert!(ecs.has_component::<PlayerTag>(woke));
+        assert!(space.entity_room(woke).is_some());
+    }
+
+    #[test]
+    fn wallet_survives_save_and_login() {
+        use mud::components::Wallet;
+
+        let mut registry = PersistenceRegistry::new();
+        register_mud_components(&mut registry);
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("saver", "password123").unwrap();
+        db.character().create(account.id, "저축왕", &serde_json::json!({})).unwrap();
+        let (engine, mut ecs, mut space) = login_world();
+        let mut sessions = SessionManager::new();
+
+        let eid = login_first_character(
+            &engine, &mut ecs, &mut space, &mut sessions, &db, "saver", "password123",
+        );
+        ecs.set_component(eid, Wallet { copper: 340 }).unwrap();
+
+        // Disconnect, then the linger timeout saves and despawns the entity.
+        let sid = sessions.session_id_for_entity(eid).unwrap();
+        let auth = PlayerDbAuthProvider::new(&db);
+        let mut ctx = ScriptContext { ecs: &mut ecs, space: &mut space, sessions: &mut sessions, tick: 0 };
+        engine.run_on_disconnect(&mut ctx, sid, Some(&auth)).unwrap();
+        cleanup_expired_lingering(&mut ecs, &mut space, &mut sessions, &engine, 100, 10, &registry, Some(&db));
+        assert!(!ecs.is_alive(eid));
+
+        let again = login_first_character(
+            &engine, &mut ecs, &mut space, &mut sessions, &db, "saver", "password123",
+        );
+        assert_eq!(ecs.get_component::<Wallet>(again).unwrap(), &Wallet { copper: 340 });
+    }
+
+    #[test]
+    fn legacy_gold_is_folded_into_wallet_at_login() {
+        use mud::components::Wallet;
+
+        let db = PlayerDb::open_memory().unwrap();
+        let account = db.account().create("oldtimer", "password123").unwrap();
+        db.character()
+            .create(
+                account.id,
+                "고참",
+                &serde_json::json!({"Gold": 30, "Wallet": {"copper": 5}}),
+            )
+            .unwrap();
+        let (engine, mut ecs, mut space) = login_world();
+        let mut sessions = SessionManager::new();
+
+        let eid = login_first_character(
+            &engine, &mut ecs, &mut space, &mut sessions, &db, "oldtimer", "password123",
+        );
+        assert_eq!(ecs.get_component::<Wallet>(eid).unwrap(), &Wallet { copper: 35 });
+    }
+
+    #[test]
+    fn look_then_quit_defers_disconnect_until_after_look() {
+        let (engine, mut ecs, mut space) = login_world();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        let entity = ecs.spawn_entity();
+        sessions.bind_entity(sid, entity);
+        let (output_tx, _output_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let inputs = handle_player_input(
+            &mut ecs, &mut space, &mut sessions, &output_tx, sid, "look; quit; say bye",
+            &SocialTable::new(), Some(';'), &engine, 0, None,
+        );
+        // Nothing is torn down while parsing: look still has its session.
+        assert!(sessions.get_session(sid).is_some());
+        let actions: Vec<_> = inputs.iter().map(|i| i.action.clone()).collect();
+        assert_eq!(actions, vec![PlayerAction::Look, PlayerAction::Quit]);
+
+        let mut inputs = inputs;
+        inputs.push(PlayerInput { session_id: sid, entity, action: PlayerAction::InventoryList });
+        let (normal, admin, quitting) = partition_inputs(inputs);
+        assert_eq!(normal.len(), 1);
+        assert_eq!(normal[0].action, PlayerAction::Look);
+        assert!(admin.is_empty());
+        assert_eq!(quitting, vec![sid]);
+    }
+
+    #[test]
+    fn disconnect_outputs_are_wrapped_to_the_session_width() {
+        let mut engine = ScriptEngine::new(scripting::ScriptConfig::default()).unwrap();
+        engine
+            .load_script(
+                "bye",
+                r#"hooks.on_disconnect(function(session_id)
+                    output:send(session_id + 1, "one two three four five six")
+                end)"#,
+            )
+            .unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let mut sessions = SessionManager::new();
+        let leaving = sessions.create_session();
+        let watcher = sessions.create_session();
+        assert_eq!(watcher.0, leaving.0 + 1);
+        sessions.get_session_mut(watcher).unwrap().line_width = 10;
+        let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        handle_disconnect(&mut ecs, &mut space, &mut sessions, &output_tx, leaving, &engine, 0, None);
+
+        let out = output_rx.try_recv().unwrap();
+        assert_eq!(out.session_id, watcher);
+        assert!(out.text.lines().all(|line| line.chars().count() <= 10), "{:?}", out.text);
+        assert!(out.text.contains('\n'));
+    }
+
+    #[test]
+    fn hook_errors_are_drained_and_counted() {
+        use observability::metrics::{counters, SCRIPT_ERRORS_TOTAL};
+
+        let mut engine = ScriptEngine::new(scripting::ScriptConfig::default()).unwrap();
+        engine
+            .load_script("broken", r#"hooks.on_disconnect(function() error("boom") end)"#)
+            .unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        let (output_tx, _output_rx) = tokio::sync::mpsc::unbounded_channel();
+        handle_disconnect(&mut ecs, &mut space, &mut sessions, &output_tx, sid, &engine, 0, None);
+
+        let before = counters().get(SCRIPT_ERRORS_TOTAL, "on_disconnect");
+        report_hook_errors(&engine);
+        assert_eq!(counters().get(SCRIPT_ERRORS_TOTAL, "on_disconnect"), before + 1);
+        assert!(engine.take_hook_errors().is_empty());
+    }
+
+    #[test]
+    fn reenable_hooks_command_requires_admin_and_reports_count() {
+        let config = scripting::ScriptConfig { hook_error_threshold: 1, ..Default::default() };
+        let mut engine = ScriptEngine::new(config).unwrap();
+        engine
+            .load_script("broken", r#"hooks.on_disconnect(function() error("boom") end)"#)
+            .unwrap();
+        let mut ecs = EcsAdapter::new();
+        let mut space = RoomGraphSpace::new();
+        let mut sessions = SessionManager::new();
+        let (output_tx, _output_rx) = tokio::sync::mpsc::unbounded_channel();
+        for _ in 0..2 {
+            let sid = sessions.create_session();
+            handle_disconnect(&mut ecs, &mut space, &mut sessions, &output_tx, sid, &engine, 0, None);
+        }
+
+        let builder = sessions.create_session();
+        sessions.get_session_mut(builder).unwrap().permission = PermissionLevel::Builder;
+        let admin = sessions.create_session();
+        sessions.get_session_mut(admin).unwrap().permission = PermissionLevel::Admin;
+
+        let denied = reenable_hooks_command(&engine, &sessions, builder);
+        assert_eq!(denied.text, "관리자 명령어를 사용할 권한이 없습니다.");
+        let reply = reenable_hooks_command(&engine, &sessions, admin);
+        assert_eq!(reply.session_id, admin);
+        assert!(reply.text.contains("1개"), "{}", reply.text);
+        let again = reenable_hooks_command(&engine, &sessions, admin);
+        assert!(again.text.contains("0개"), "{}", again.text);
+    }
+
+    #[test]
+    fn status_update_message_carries_vitals_and_items() {
+        let status = PlayerStatus {
+            health: 4,
+            max_health: 10,
+            inventory: vec![mud::systems::status::StatusItem { id: 7, name: "물약".to_string() }],
+        };
+        let json: serde_json::Value = serde_json::from_str(&status_update_message(status).to_json()).unwrap();
+        assert_eq!(json["type"], "status_update");
+        assert_eq!(json["health"], 4);
+        assert_eq!(json["max_health"], 10);
+        assert_eq!(json["inventory"][0]["id"], 7);
+        assert_eq!(json["inventory"][0]["name"], "물약");
+    }
+
+    #[test]
+    fn despawned_player_leaves_party_and_follow_registries() {
+        let (engine, mut ecs, mut space) = login_world();
+        mud::script_setup::register_party_api(engine.lua()).unwrap();
+        mud::script_setup::register_follow_api(engine.lua()).unwrap();
+        let mut sessions = SessionManager::new();
+        let sid = sessions.create_session();
+        let entity = ecs.spawn_entity();
+        let leader = ecs.spawn_entity();
+        sessions.bind_entity(sid, entity);
+        engine
+            .lua()
+            .load(format!("party.join(party.create({1}), {0}); follow.start({0}, {1})", entity.to_u64(), leader.to_u64()))
+            .exec()
+            .unwrap();
+        let (output_tx, _output_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        handle_disconnect(&mut ecs, &mut space, &mut sessions, &output_tx, sid, &engine, 0, None);
+
+        assert!(!ecs.is_alive(entity));
+        let (party, follows): (Vec<u64>, Option<u64>) = engine
+            .lua()
+            .load(format!("return party.members({1}), follow.leader({0})", entity.to_u64(), leader.to_u64()))
+            .eval()
+            .unwrap();
+        assert_eq!(party, vec![leader.to_u64()]);
+        assert_eq!(follows, None);
+    }
 }
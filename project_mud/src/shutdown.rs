@@ -23,6 +23,7 @@ impl ShutdownTx {
 
 impl ShutdownRx {
     /// Async wait until shutdown is signaled.
+    #[allow(dead_code)]
     pub async fn wait(&mut self) {
         while !*self.0.borrow() {
             if self.0.changed().await.is_err() {
@@ -22,8 +22,7 @@ fn run_simulation(fuel_limit: u64, ticks: u64) -> Vec<Vec<plugin_abi::WasmComman
             plugin_id: "test_movement".into(),
             wasm_path: fixture_path("test_movement.wasm"),
             priority: 1,
-            fuel_limit: None,
-            enabled: true,
+            ..Default::default()
         })
         .unwrap();
 
@@ -4,11 +4,11 @@ use std::path::Path;
 
 use ecs_adapter::{EcsAdapter, EntityId};
 use mud::components::*;
-use mud::output::SessionId;
-use mud::parser::{Direction, PlayerAction};
-use mud::script_setup::register_mud_script_components;
+use mud::output::{MessageTemplates, SessionId};
+use mud::parser::{parse_input, Direction, PlayerAction};
+use mud::script_setup::{register_follow_api, register_message_api, register_mud_script_components};
 use mud::session::SessionManager;
-use mud::systems::{GameContext, PlayerInput};
+use mud::systems::{GameContext, PlayerInput, SystemOutcome};
 use scripting::engine::{ScriptContext, ScriptEngine};
 use scripting::{ContentRegistry, ScriptConfig};
 use space::{RoomGraphSpace, SpaceModel};
@@ -30,12 +30,16 @@ fn setup() -> (EcsAdapter, RoomGraphSpace, SessionManager, ScriptEngine) {
     register_mud_script_components(engine.component_registry_mut());
 
     // Load content before scripts (so Lua scripts can access content.*)
+    let mut templates = MessageTemplates::new();
     let cdir = content_dir();
     if cdir.is_dir() {
         if let Ok(registry) = ContentRegistry::load_dir(cdir) {
             let _ = engine.register_content(&registry);
+            templates = MessageTemplates::from_content(&registry);
         }
     }
+    register_message_api(engine.lua(), templates).unwrap();
+    register_follow_api(engine.lua()).unwrap();
 
     engine.load_directory(scripts_dir()).unwrap();
 
@@ -117,6 +121,40 @@ fn look_shows_room_description() {
     assert!(text.contains("환영합니다, 모험가여"), "Expected description, got: {}", text);
 }
 
+#[test]
+fn look_at_target_shows_its_description() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let market = find_entity_by_name(&ecs, "시장 광장").unwrap();
+    let (sid, entity) = spawn_player(&mut ecs, &mut space, &mut sessions, "Hero", market);
+
+    let inputs = vec![
+        PlayerInput {
+            session_id: sid,
+            entity,
+            action: parse_input("물약 보기"),
+        },
+        PlayerInput {
+            session_id: sid,
+            entity,
+            action: parse_input("고블린 보기"),
+        },
+    ];
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    let outputs = mud::systems::run_game_systems(&mut ctx, inputs, Some(&engine));
+
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs[0].text.contains("치유 물약"), "got: {}", outputs[0].text);
+    assert!(outputs[0].text.contains("빨간 액체"), "got: {}", outputs[0].text);
+    assert!(!outputs[0].text.contains("출구"), "got: {}", outputs[0].text);
+    // The goblin is in another room
+    assert!(outputs[1].text.contains("보이지 않습니다"), "got: {}", outputs[1].text);
+}
+
 #[test]
 fn move_east_to_market_square() {
     let (mut ecs, mut space, mut sessions, engine) = setup();
@@ -430,6 +468,75 @@ fn move_broadcasts_to_others() {
             "Bob departure msg: {}", bob_msgs[0].text);
 }
 
+fn act(
+    ecs: &mut EcsAdapter,
+    space: &mut RoomGraphSpace,
+    sessions: &mut SessionManager,
+    engine: &ScriptEngine,
+    session_id: SessionId,
+    entity: EntityId,
+    action: PlayerAction,
+) -> Vec<mud::output::SessionOutput> {
+    let mut ctx = GameContext {
+        ecs,
+        space,
+        sessions,
+        tick: 0,
+    };
+    let inputs = vec![PlayerInput { session_id, entity, action }];
+    mud::systems::run_game_systems(&mut ctx, inputs, Some(engine))
+}
+
+#[test]
+fn follower_trails_leader_until_unfollow() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let room = spawn_room(&ecs);
+    let market = find_entity_by_name(&ecs, "시장 광장").expect("시장 광장 not found");
+    let alley = find_entity_by_name(&ecs, "어두운 골목").expect("어두운 골목 not found");
+    let (lsid, leader) = spawn_player(&mut ecs, &mut space, &mut sessions, "Alice", room);
+    let (fsid, follower) = spawn_player(&mut ecs, &mut space, &mut sessions, "Bob", room);
+    let mut run = |sid, eid, action| act(&mut ecs, &mut space, &mut sessions, &engine, sid, eid, action);
+
+    let out = run(fsid, follower, parse_input("alice follow"));
+    assert!(out.iter().any(|o| o.session_id == lsid && o.text.contains("Bob님이 당신을 따라가기")));
+
+    let out = run(lsid, leader, PlayerAction::Move(Direction::East));
+    let bob: Vec<_> = out.iter().filter(|o| o.session_id == fsid).map(|o| o.text.as_str()).collect();
+    assert!(bob.iter().any(|t| t.contains("Alice님을 따라 동쪽으로")), "{:?}", bob);
+    run(lsid, leader, PlayerAction::Move(Direction::East));
+    assert_eq!(space.entity_room(leader), Some(alley));
+    assert_eq!(space.entity_room(follower), Some(alley));
+
+    let mut run = |sid, eid, action| act(&mut ecs, &mut space, &mut sessions, &engine, sid, eid, action);
+    let out = run(fsid, follower, PlayerAction::Unfollow);
+    assert!(out.iter().any(|o| o.session_id == fsid && o.text.contains("그만 따라갑니다")));
+    run(lsid, leader, PlayerAction::Move(Direction::West));
+    assert_eq!(space.entity_room(leader), Some(market));
+    assert_eq!(space.entity_room(follower), Some(alley));
+}
+
+#[test]
+fn follower_stays_behind_when_room_is_full() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let room = spawn_room(&ecs);
+    let market = find_entity_by_name(&ecs, "시장 광장").expect("시장 광장 not found");
+    let (lsid, leader) = spawn_player(&mut ecs, &mut space, &mut sessions, "Alice", room);
+    let (fsid, follower) = spawn_player(&mut ecs, &mut space, &mut sessions, "Bob", room);
+    let occupied = space
+        .room_occupants(market)
+        .into_iter()
+        .filter(|&e| ecs.has_component::<PlayerTag>(e) || ecs.has_component::<NpcTag>(e))
+        .count() as u32;
+    ecs.set_component(market, RoomCapacity(occupied + 1)).unwrap();
+
+    act(&mut ecs, &mut space, &mut sessions, &engine, fsid, follower, parse_input("alice follow"));
+    let out = act(&mut ecs, &mut space, &mut sessions, &engine, lsid, leader, PlayerAction::Move(Direction::East));
+
+    assert_eq!(space.entity_room(leader), Some(market));
+    assert_eq!(space.entity_room(follower), Some(room));
+    assert!(out.iter().any(|o| o.session_id == fsid && o.text.contains("가득 찼습니다")));
+}
+
 #[test]
 fn status_command_shows_character_info() {
     let (mut ecs, mut space, mut sessions, engine) = setup();
@@ -486,3 +593,259 @@ fn skill_list_command() {
     assert!(text.contains("강타"), "Should list skills, got: {}", text);
     assert!(text.contains("보유 스킬") || text.contains("사용 가능"), "Should show header, got: {}", text);
 }
+
+#[test]
+fn unknown_command_suggests_nearest() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let room = spawn_room(&ecs);
+    let (sid, entity) = spawn_player(&mut ecs, &mut space, &mut sessions, "Hero", room);
+
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    let inputs = ["lok", "xyzzyplugh"]
+        .into_iter()
+        .map(|line| PlayerInput {
+            session_id: sid,
+            entity,
+            action: parse_input(line),
+        })
+        .collect();
+    let outputs = mud::systems::run_game_systems(&mut ctx, inputs, Some(&engine));
+
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].text, "알 수 없는 명령어: lok (혹시 'look'?)");
+    assert_eq!(outputs[1].text, "알 수 없는 명령어: xyzzyplugh");
+}
+
+#[test]
+fn unconsumed_action_is_unhandled() {
+    let (mut ecs, mut space, mut sessions, _) = setup();
+    let room = spawn_room(&ecs);
+    let (sid, entity) = spawn_player(&mut ecs, &mut space, &mut sessions, "Hero", room);
+
+    // Engine whose only hook declines the action
+    let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    engine
+        .load_script("decline", r#"hooks.on_action("look", function(ctx) return false end)"#)
+        .unwrap();
+
+    let input = PlayerInput {
+        session_id: sid,
+        entity,
+        action: PlayerAction::Look,
+    };
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    let (_, outcome) = mud::systems::dispatch_action(&mut ctx, &input, Some(&engine));
+    assert_eq!(outcome, SystemOutcome::Unhandled);
+
+    // Caller-provided fallback replaces the default message
+    let outputs = mud::systems::run_game_systems_with(&mut ctx, vec![input], Some(&engine), |input| {
+        Some(format!("Unknown command: {:?}", input.action))
+    });
+    assert_eq!(outputs.len(), 1);
+    assert!(outputs[0].text.starts_with("Unknown command:"), "Got: {}", outputs[0].text);
+}
+
+#[test]
+fn consumed_action_gets_no_fallback() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let room = spawn_room(&ecs);
+    let (sid, entity) = spawn_player(&mut ecs, &mut space, &mut sessions, "Hero", room);
+
+    let input = PlayerInput {
+        session_id: sid,
+        entity,
+        action: PlayerAction::Look,
+    };
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    let (_, outcome) = mud::systems::dispatch_action(&mut ctx, &input, Some(&engine));
+    assert_eq!(outcome, SystemOutcome::Consumed);
+
+    let mut fallback_calls = 0;
+    let outputs = mud::systems::run_game_systems_with(&mut ctx, vec![input], Some(&engine), |_| {
+        fallback_calls += 1;
+        Some("fallback".to_string())
+    });
+    assert_eq!(fallback_calls, 0);
+    assert!(outputs.iter().all(|o| o.text != "fallback"));
+}
+
+#[test]
+fn equipment_script_roundtrip() {
+    let (mut ecs, _, _, engine) = setup();
+    let player = ecs.spawn_entity();
+    let sword = ecs.spawn_entity();
+    let mut eq = Equipment::new();
+    eq.slots.insert("weapon".to_string(), sword);
+    ecs.set_component(player, eq.clone()).unwrap();
+
+    let handler = engine.component_registry().get("Equipment").unwrap();
+    let value = handler.get_as_lua(&ecs, player, engine.lua()).unwrap().unwrap();
+    let slots: scripting::mlua::Table = value.as_table().unwrap().get("slots").unwrap();
+    assert_eq!(slots.get::<u64>("weapon").unwrap(), sword.to_u64());
+
+    ecs.remove_component::<Equipment>(player).unwrap();
+    handler.set_from_lua(&mut ecs, player, value, engine.lua()).unwrap();
+    assert_eq!(ecs.get_component::<Equipment>(player).unwrap(), &eq);
+}
+
+#[test]
+fn equip_and_unequip_apply_item_bonuses() {
+    let (mut ecs, mut space, mut sessions, _) = setup();
+    let room = spawn_room(&ecs);
+    let (sid, entity) = spawn_player(&mut ecs, &mut space, &mut sessions, "Hero", room);
+    let sword = ecs.spawn_entity();
+    ecs.set_component(sword, Name("녹슨 검".to_string())).unwrap();
+
+    // Utility helpers, a minimal item table, and a hook that toggles the weapon slot on "look"
+    let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    register_mud_script_components(engine.component_registry_mut());
+    let utils = std::fs::read_to_string(scripts_dir().join("00_utils.lua")).unwrap();
+    engine.load_script("00_utils", &utils).unwrap();
+    engine
+        .load_script(
+            "equip_toggle",
+            &format!(
+                r#"content = {{ items = {{ {{ id = "녹슨_검", name = "녹슨 검", attack_bonus = 3 }} }} }}
+                hooks.on_action("look", function(ctx)
+                    local eq = ecs:get(ctx.entity, "Equipment")
+                    if eq and eq.slots.weapon then
+                        unequip_item(ctx.entity, "weapon")
+                    else
+                        equip_item(ctx.entity, "weapon", {})
+                    end
+                    return true
+                end)"#,
+                sword.to_u64()
+            ),
+        )
+        .unwrap();
+
+    let input = PlayerInput {
+        session_id: sid,
+        entity,
+        action: PlayerAction::Look,
+    };
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    mud::systems::dispatch_action(&mut ctx, &input, Some(&engine));
+    assert_eq!(ecs.get_component::<Attack>(entity).unwrap().0, 13);
+    assert_eq!(
+        ecs.get_component::<Equipment>(entity).unwrap().slots.get("weapon"),
+        Some(&sword)
+    );
+
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 1,
+    };
+    mud::systems::dispatch_action(&mut ctx, &input, Some(&engine));
+    assert_eq!(ecs.get_component::<Attack>(entity).unwrap().0, 10);
+    assert!(ecs.get_component::<Equipment>(entity).unwrap().slots.is_empty());
+}
+
+#[test]
+fn emote_broadcasts_freeform_text() {
+    let (mut ecs, mut space, mut sessions, engine) = setup();
+    let room = spawn_room(&ecs);
+    let (sid1, entity1) = spawn_player(&mut ecs, &mut space, &mut sessions, "Alice", room);
+    let (sid2, _entity2) = spawn_player(&mut ecs, &mut space, &mut sessions, "Bob", room);
+
+    let inputs = vec![PlayerInput {
+        session_id: sid1,
+        entity: entity1,
+        action: mud::parser::parse_input("emote waves cheerfully"),
+    }];
+    let mut ctx = GameContext {
+        ecs: &mut ecs,
+        space: &mut space,
+        sessions: &mut sessions,
+        tick: 0,
+    };
+    let outputs = mud::systems::run_game_systems(&mut ctx, inputs, Some(&engine));
+
+    for sid in [sid1, sid2] {
+        let msg = outputs.iter().find(|o| o.session_id == sid);
+        assert_eq!(msg.unwrap().text, "Alice waves cheerfully", "outputs: {:?}", outputs);
+    }
+}
+
+#[test]
+fn social_from_content_with_and_without_target() {
+    let (mut ecs, mut space, mut sessions, _) = setup();
+    let room = spawn_room(&ecs);
+    let (sid1, entity1) = spawn_player(&mut ecs, &mut space, &mut sessions, "Alice", room);
+    let (sid2, _entity2) = spawn_player(&mut ecs, &mut space, &mut sessions, "Bob", room);
+    let (sid3, _entity3) = spawn_player(&mut ecs, &mut space, &mut sessions, "Carol", room);
+
+    // Only the socials collection (the full content dir is not loadable here)
+    let dir = std::env::temp_dir().join(format!("mud_socials_it_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(content_dir().join("socials.json"), dir.join("socials.json")).unwrap();
+    let registry = ContentRegistry::load_dir(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    let socials = mud::parser::SocialTable::from_content(&registry);
+
+    let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+    register_mud_script_components(engine.component_registry_mut());
+    register_message_api(engine.lua(), MessageTemplates::new()).unwrap();
+    engine.register_content(&registry).unwrap();
+    for name in ["00_utils", "02_commands"] {
+        let src = std::fs::read_to_string(scripts_dir().join(format!("{}.lua", name))).unwrap();
+        engine.load_script(name, &src).unwrap();
+    }
+
+    let mut run = |line: &str| {
+        let inputs = vec![PlayerInput {
+            session_id: sid1,
+            entity: entity1,
+            action: mud::parser::parse_input_with_socials(line, &socials),
+        }];
+        let mut ctx = GameContext {
+            ecs: &mut ecs,
+            space: &mut space,
+            sessions: &mut sessions,
+            tick: 0,
+        };
+        mud::systems::run_game_systems(&mut ctx, inputs, Some(&engine))
+    };
+    let text_for = |outputs: &[mud::output::SessionOutput], sid| {
+        outputs
+            .iter()
+            .find(|o| o.session_id == sid)
+            .map(|o| o.text.clone())
+            .unwrap_or_default()
+    };
+
+    let outputs = run("미소");
+    assert_eq!(text_for(&outputs, sid1), "당신은 미소를 짓습니다.");
+    assert_eq!(text_for(&outputs, sid2), "Alice님이 미소를 짓습니다.");
+
+    let outputs = run("bob 인사");
+    assert_eq!(text_for(&outputs, sid1), "당신은 Bob에게 정중하게 인사합니다.");
+    assert_eq!(text_for(&outputs, sid2), "Alice님이 당신에게 정중하게 인사합니다.");
+    assert_eq!(text_for(&outputs, sid3), "Alice님이 Bob에게 정중하게 인사합니다.");
+
+    let outputs = run("nobody bow");
+    assert!(text_for(&outputs, sid1).contains("찾을 수 없습니다"), "{:?}", outputs);
+}
@@ -0,0 +1,251 @@
+//! Integration tests for the scripted login flow (05_login.lua) and the
+//! `login_flow` extension points other scripts use to add steps.
+
+use std::path::Path;
+
+use ecs_adapter::EcsAdapter;
+use scripting::engine::{ScriptContext, ScriptEngine};
+use scripting::{
+    AuthAccountInfo, AuthCharacterDetail, AuthCharacterSummary, AuthError, AuthProvider,
+    ScriptConfig,
+};
+use session::{SessionId, SessionManager, SessionState};
+use space::RoomGraphSpace;
+
+fn scripts_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/scripts"))
+}
+
+/// Inserts a MOTD step between entering the name and the password prompt,
+/// and records every requested transition.
+const MOTD_SCRIPT: &str = r#"
+TRANSITIONS = {}
+login_flow.on_transition(function(session_id, from, to)
+    table.insert(TRANSITIONS, tostring(from) .. ">" .. to)
+end)
+
+login_flow.register_step("motd", {
+    enter = function(session_id)
+        output:send(session_id, "[공지] 오늘 밤 점검이 있습니다. 계속하려면 아무 글자나 입력하세요.")
+    end,
+    input = function(session_id, line, state)
+        return "password"
+    end,
+})
+
+login_flow.on_transition(function(session_id, from, to)
+    if from == "name" and to == "password" then
+        return "motd"
+    end
+end)
+"#;
+
+/// One existing account ("alice" / "secret") with no characters. With
+/// `list_fails` the character list cannot be read.
+#[derive(Default)]
+struct OneAccountAuth {
+    list_fails: bool,
+}
+
+impl AuthProvider for OneAccountAuth {
+    fn check_account(&self, username: &str) -> Result<Option<AuthAccountInfo>, AuthError> {
+        Ok((username == "alice").then(|| AuthAccountInfo {
+            id: 1,
+            username: "alice".into(),
+            permission: 0,
+        }))
+    }
+
+    fn authenticate(&self, username: &str, password: &str) -> Result<AuthAccountInfo, AuthError> {
+        if username != "alice" {
+            return Err(AuthError::AccountNotFound(username.to_string()));
+        }
+        if password != "secret" {
+            return Err(AuthError::InvalidPassword);
+        }
+        Ok(AuthAccountInfo {
+            id: 1,
+            username: "alice".into(),
+            permission: 0,
+        })
+    }
+
+    fn create_account(&self, username: &str, _password: &str) -> Result<AuthAccountInfo, AuthError> {
+        Err(AuthError::AccountExists(username.to_string()))
+    }
+
+    fn list_characters(&self, _account_id: i64) -> Result<Vec<AuthCharacterSummary>, AuthError> {
+        if self.list_fails {
+            return Err(AuthError::Internal("db locked".into()));
+        }
+        Ok(Vec::new())
+    }
+
+    fn create_character(
+        &self,
+        _account_id: i64,
+        name: &str,
+        _defaults: &serde_json::Value,
+    ) -> Result<AuthCharacterDetail, AuthError> {
+        Err(AuthError::CharacterNameTaken(name.to_string()))
+    }
+
+    fn load_character(&self, character_id: i64) -> Result<AuthCharacterDetail, AuthError> {
+        Err(AuthError::CharacterNotFound(character_id))
+    }
+
+    fn save_character(
+        &self,
+        _character_id: i64,
+        _components: &serde_json::Value,
+        _room_id: Option<u64>,
+        _position: Option<(i32, i32)>,
+    ) -> Result<(), AuthError> {
+        Ok(())
+    }
+
+    fn save_script_data(&self, _character_id: i64, _script_data: &serde_json::Value) -> Result<(), AuthError> {
+        Ok(())
+    }
+}
+
+struct LoginHarness {
+    engine: ScriptEngine,
+    ecs: EcsAdapter,
+    space: RoomGraphSpace,
+    sessions: SessionManager,
+}
+
+impl LoginHarness {
+    fn new(extra_script: &str) -> Self {
+        let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
+        mud::script_setup::register_mud_script_components(engine.component_registry_mut());
+        for name in ["00_utils", "05_login"] {
+            let src = std::fs::read_to_string(scripts_dir().join(format!("{}.lua", name))).unwrap();
+            engine.load_script(name, &src).unwrap();
+        }
+        engine.load_script("99_motd", extra_script).unwrap();
+
+        let mut space = RoomGraphSpace::new();
+        let mut ecs = EcsAdapter::new();
+        let room = ecs.spawn_entity();
+        space.register_room(room, Default::default());
+
+        Self {
+            engine,
+            ecs,
+            space,
+            sessions: SessionManager::new(),
+        }
+    }
+
+    fn ctx(&mut self) -> (&ScriptEngine, ScriptContext<'_, RoomGraphSpace>) {
+        let ctx = ScriptContext {
+            ecs: &mut self.ecs,
+            space: &mut self.space,
+            sessions: &mut self.sessions,
+            tick: 0,
+        };
+        (&self.engine, ctx)
+    }
+
+    fn connect(&mut self) -> (SessionId, String) {
+        let sid = self.sessions.create_session();
+        let (engine, mut ctx) = self.ctx();
+        (sid, join(engine.run_on_connect(&mut ctx, sid).unwrap()))
+    }
+
+    fn input(&mut self, sid: SessionId, line: &str, auth: Option<&dyn AuthProvider>) -> String {
+        let (engine, mut ctx) = self.ctx();
+        join(engine.run_on_input(&mut ctx, sid, line, auth).unwrap())
+    }
+
+    fn step_of(&self, sid: SessionId) -> Option<String> {
+        self.engine
+            .lua()
+            .load(format!("return login_flow.step_of({})", sid.0))
+            .eval()
+            .unwrap()
+    }
+
+    fn transitions(&self) -> String {
+        self.engine
+            .lua()
+            .load("return table.concat(TRANSITIONS, ',')")
+            .eval()
+            .unwrap()
+    }
+}
+
+fn join(outputs: Vec<session::SessionOutput>) -> String {
+    outputs.into_iter().map(|o| o.text).collect::<Vec<_>>().join("\n")
+}
+
+#[test]
+fn custom_motd_step_runs_between_name_and_password() {
+    let auth = OneAccountAuth::default();
+    let mut h = LoginHarness::new(MOTD_SCRIPT);
+
+    let (sid, out) = h.connect();
+    assert!(out.contains("이름을 입력하세요"), "{}", out);
+    assert_eq!(h.step_of(sid).as_deref(), Some("name"));
+
+    let out = h.input(sid, "alice", Some(&auth));
+    assert!(out.contains("[공지]"), "{}", out);
+    assert!(!out.contains("비밀번호"), "{}", out);
+    assert_eq!(h.step_of(sid).as_deref(), Some("motd"));
+
+    let out = h.input(sid, "ok", Some(&auth));
+    assert!(out.contains("비밀번호를 입력하세요"), "{}", out);
+    assert_eq!(h.step_of(sid).as_deref(), Some("password"));
+
+    let out = h.input(sid, "secret", Some(&auth));
+    assert!(out.contains("캐릭터가 없습니다"), "{}", out);
+    assert_eq!(h.step_of(sid).as_deref(), Some("character_select"));
+
+    assert_eq!(
+        h.transitions(),
+        "nil>name,name>password,motd>password,password>character_select"
+    );
+}
+
+#[test]
+fn quick_play_flow_is_unchanged_by_unrelated_hooks() {
+    let mut h = LoginHarness::new(MOTD_SCRIPT);
+
+    let (sid, _) = h.connect();
+    let out = h.input(sid, "Hero", None);
+    assert!(out.contains("종족 선택"), "{}", out);
+    h.input(sid, "1", None);
+    assert_eq!(h.step_of(sid).as_deref(), Some("class_select"));
+    h.input(sid, "1", None);
+
+    assert_eq!(h.step_of(sid), None);
+    assert_eq!(h.sessions.get_session(sid).unwrap().state, SessionState::Playing);
+    assert_eq!(
+        h.transitions(),
+        "nil>name,name>race_select,race_select>class_select"
+    );
+}
+
+#[test]
+fn failed_character_list_falls_back_to_the_name_step() {
+    let auth = OneAccountAuth { list_fails: true };
+    let mut h = LoginHarness::new(MOTD_SCRIPT);
+
+    let (sid, _) = h.connect();
+    h.input(sid, "alice", Some(&auth));
+    h.input(sid, "ok", Some(&auth));
+    let out = h.input(sid, "secret", Some(&auth));
+    assert!(out.contains("캐릭터 목록 조회 실패"), "{}", out);
+    assert!(out.contains("이름을 입력하세요"), "{}", out);
+    assert_eq!(h.step_of(sid).as_deref(), Some("name"));
+
+    // The session is not stuck: logging in again reaches the selection.
+    let out = h.input(sid, "alice", Some(&OneAccountAuth::default()));
+    assert!(out.contains("[공지]"), "{}", out);
+    assert_eq!(
+        h.transitions(),
+        "nil>name,name>password,motd>password,password>character_select,password>name,name>password"
+    );
+}
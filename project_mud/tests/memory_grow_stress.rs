@@ -14,10 +14,8 @@ fn memory_grow_10000_times() {
 
     for i in 0..10_000 {
         // Grow by 1 page (64KB)
-        let old_pages = memory.grow(&mut store, 1).expect(&format!(
-            "memory.grow() failed at iteration {}",
-            i
-        ));
+        let old_pages = memory.grow(&mut store, 1).unwrap_or_else(|_| panic!("memory.grow() failed at iteration {}",
+            i));
         assert!(old_pages >= 1);
 
         // Write to the beginning of the newly grown page
@@ -8,9 +8,9 @@ use tokio::sync::mpsc;
 
 use engine_core::tick::{TickConfig, TickLoop};
 use mud::components::*;
-use mud::output::SessionOutput;
+use mud::output::{MessageTemplates, SessionOutput};
 use mud::parser::{parse_input, PlayerAction};
-use mud::script_setup::register_mud_script_components;
+use mud::script_setup::{register_follow_api, register_message_api, register_mud_script_components};
 use mud::session::{SessionManager, SessionState};
 use mud::systems::{GameContext, PlayerInput};
 use net::channels::{NetToTick, OutputTx, PlayerRx};
@@ -36,8 +36,8 @@ fn run_test_ticks(
         let mut inputs = Vec::new();
         while let Ok(msg) = player_rx.try_recv() {
             match msg {
-                NetToTick::NewConnection { session_id } => {
-                    sessions.create_session_with_id(session_id);
+                NetToTick::NewConnection { session_id, connection } => {
+                    sessions.create_session_with_connection(session_id, connection);
                     let _ = output_tx.send(SessionOutput::new(
                         session_id,
                         "Rust MUD에 오신 것을 환영합니다!\n이름을 입력하세요:",
@@ -170,12 +170,14 @@ async fn tcp_login_and_move() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     // Setup game world via scripts
-    let config = TickConfig { tps: 10, max_ticks: 0 };
+    let config = TickConfig { tps: 10, max_ticks: 0, ..Default::default() };
     let mut tick_loop = TickLoop::new(config, space::RoomGraphSpace::new());
     let mut sessions = SessionManager::new();
 
     let mut script_engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
     register_mud_script_components(script_engine.component_registry_mut());
+    register_message_api(script_engine.lua(), MessageTemplates::new()).unwrap();
+    register_follow_api(script_engine.lua()).unwrap();
     script_engine.load_directory(scripts_dir()).unwrap();
 
     // Run on_init to create world
@@ -219,6 +221,12 @@ async fn tcp_login_and_move() {
     );
     tokio::time::sleep(Duration::from_millis(100)).await;
 
+    // The session records where the client connected from
+    let sid = sessions.all_session_ids()[0];
+    let connection = sessions.get_session(sid).unwrap().connection.unwrap();
+    assert_eq!(connection.peer_addr, stream.local_addr().unwrap());
+    assert_eq!(sessions.sessions_from_ip(connection.peer_addr.ip()), vec![sid]);
+
     // Read welcome message
     let mut buf = [0u8; 4096];
     let n = stream.read(&mut buf).await.unwrap();
@@ -3,8 +3,9 @@ use std::path::Path;
 
 use ecs_adapter::EcsAdapter;
 use mud::components::*;
-use mud::persistence_setup::register_mud_components;
-use mud::script_setup::register_mud_script_components;
+use mud::output::MessageTemplates;
+use mud::persistence_setup::{export_character_json, register_mud_components, retain_character_tags};
+use mud::script_setup::{register_follow_api, register_message_api, register_mud_script_components};
 use mud::session::SessionManager;
 use persistence::manager::SnapshotManager;
 use persistence::registry::PersistenceRegistry;
@@ -27,6 +28,8 @@ fn test_registry() -> PersistenceRegistry {
 fn create_world_via_lua(ecs: &mut EcsAdapter, space: &mut RoomGraphSpace) -> ScriptEngine {
     let mut engine = ScriptEngine::new(ScriptConfig::default()).unwrap();
     register_mud_script_components(engine.component_registry_mut());
+    register_message_api(engine.lua(), MessageTemplates::new()).unwrap();
+    register_follow_api(engine.lua()).unwrap();
     engine.load_directory(scripts_dir()).unwrap();
 
     let mut sessions = SessionManager::new();
@@ -199,3 +202,81 @@ fn snapshot_version_mismatch() {
     let result = snapshot::restore(snap, &mut ecs2, &mut space2, &registry);
     assert!(result.is_err());
 }
+
+#[test]
+fn export_entity_json_includes_every_registered_component() {
+    let registry = test_registry();
+    let mut ecs = EcsAdapter::new();
+    let room = ecs.spawn_entity();
+    let item = ecs.spawn_entity();
+    let eid = ecs.spawn_entity();
+
+    ecs.set_component(eid, Name("영웅".to_string())).unwrap();
+    ecs.set_component(eid, Description("용감한 모험가".to_string())).unwrap();
+    ecs.set_component(eid, Health { current: 85, max: 100 }).unwrap();
+    ecs.set_component(eid, Attack(15)).unwrap();
+    ecs.set_component(eid, Defense(7)).unwrap();
+    ecs.set_component(eid, Inventory { items: vec![item] }).unwrap();
+    let mut equipment = Equipment::new();
+    equipment.slots.insert("weapon".to_string(), item);
+    ecs.set_component(eid, equipment).unwrap();
+    ecs.set_component(eid, PlayerTag).unwrap();
+    ecs.set_component(eid, NpcTag).unwrap();
+    ecs.set_component(eid, ItemTag).unwrap();
+    ecs.set_component(eid, InRoom(room)).unwrap();
+    ecs.set_component(eid, CombatTarget(item)).unwrap();
+    ecs.set_component(eid, Dead).unwrap();
+    ecs.set_component(eid, Race("human".to_string())).unwrap();
+    ecs.set_component(eid, Class("warrior".to_string())).unwrap();
+    ecs.set_component(eid, Level(3)).unwrap();
+    ecs.set_component(eid, Mana { current: 20, max: 30 }).unwrap();
+    ecs.set_component(eid, Experience(250)).unwrap();
+    ecs.set_component(eid, CharacterPosition::Resting).unwrap();
+    ecs.set_component(eid, Skills { learned: vec!["slash".to_string()] }).unwrap();
+    ecs.set_component(eid, RoomCapacity(4)).unwrap();
+    ecs.set_component(eid, Wallet { copper: 1250 }).unwrap();
+    ecs.set_component(eid, GameData(serde_json::json!({"quest": 2}))).unwrap();
+    ecs.add_tag(eid, "quest_giver").unwrap();
+
+    let exported = registry.export_entity_json(&ecs, eid);
+    let object = exported.as_object().unwrap();
+    for handler in registry.components() {
+        assert!(object.contains_key(handler.tag()), "missing {}", handler.tag());
+    }
+    assert_eq!(object.len(), registry.components().len());
+    assert_eq!(exported["Health"], serde_json::json!({"current": 85, "max": 100}));
+    assert_eq!(exported["Attack"], serde_json::json!(15));
+    assert_eq!(exported["GameData"], serde_json::json!({"quest": 2}));
+    assert_eq!(exported["Wallet"], serde_json::json!({"copper": 1250}));
+    assert_eq!(exported["Tags"], serde_json::json!(["quest_giver"]));
+
+    // Only components the entity actually has are exported
+    assert_eq!(registry.export_entity_json(&ecs, room), serde_json::json!({}));
+}
+
+#[test]
+fn character_export_skips_runtime_components() {
+    let registry = test_registry();
+    let mut ecs = EcsAdapter::new();
+    let room = ecs.spawn_entity();
+    let item = ecs.spawn_entity();
+    let eid = ecs.spawn_entity();
+    ecs.set_component(eid, Name("Hero".to_string())).unwrap();
+    ecs.set_component(eid, Health { current: 85, max: 100 }).unwrap();
+    ecs.set_component(eid, Inventory { items: vec![item] }).unwrap();
+    ecs.set_component(eid, PlayerTag).unwrap();
+    ecs.set_component(eid, InRoom(room)).unwrap();
+    ecs.set_component(eid, CombatTarget(item)).unwrap();
+    ecs.set_component(eid, Experience(250)).unwrap();
+
+    let exported = export_character_json(&registry, &ecs, eid);
+    assert_eq!(
+        exported,
+        serde_json::json!({"Health": {"current": 85, "max": 100}, "Experience": 250})
+    );
+
+    // Older saves carry every component; loading drops the runtime ones.
+    let mut legacy = registry.export_entity_json(&ecs, eid);
+    retain_character_tags(&mut legacy);
+    assert_eq!(legacy, exported);
+}
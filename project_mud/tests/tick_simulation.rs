@@ -31,6 +31,7 @@ fn simulation_100_entities_5_rooms_300_ticks() {
     let config = TickConfig {
         tps: 30,
         max_ticks: 300,
+        ..Default::default()
     };
     let mut tick_loop = TickLoop::new(config, RoomGraphSpace::new());
 
@@ -53,7 +54,7 @@ fn simulation_100_entities_5_rooms_300_ticks() {
     for tick in 0..300u64 {
         // Every tick, move some entities to the next room
         for (i, &entity) in entities.iter().enumerate() {
-            if (tick as usize + i) % 7 == 0 {
+            if (tick as usize + i).is_multiple_of(7) {
                 // Only move if entity is still in a room
                 if let Some(current_room) = tick_loop.space.entity_room(entity) {
                     let neighbors = tick_loop.space.neighbors(current_room).unwrap();
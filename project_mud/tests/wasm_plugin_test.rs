@@ -25,8 +25,7 @@ fn load_and_run_movement_plugin() {
         plugin_id: "test_movement".into(),
         wasm_path: fixture_path("test_movement.wasm"),
         priority: 1,
-        fuel_limit: None,
-        enabled: true,
+        ..Default::default()
     };
     runtime.load_plugin(&config).unwrap();
     assert_eq!(runtime.plugin_count(), 1);
@@ -43,6 +42,57 @@ fn load_and_run_movement_plugin() {
     assert_eq!(total_commands, 10, "expected 10 commands over 30 ticks");
 }
 
+#[test]
+fn plugin_memory_restored_from_world_snapshot() {
+    use ecs_adapter::EcsAdapter;
+    use persistence::manager::SnapshotManager;
+    use persistence::registry::PersistenceRegistry;
+    use space::RoomGraphSpace;
+
+    let config = PluginConfig {
+        plugin_id: "test_movement".into(),
+        wasm_path: fixture_path("test_movement.wasm"),
+        priority: 1,
+        ..Default::default()
+    };
+    let mut original = PluginRuntime::new(default_fuel()).unwrap();
+    original.load_plugin(&config).unwrap();
+    for tick in 0..10 {
+        original.run_tick(tick);
+    }
+
+    // Save through the engine snapshot, as a server restart would.
+    let dir = std::env::temp_dir().join(format!("wasm_plugin_memory_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut snap = persistence::snapshot::capture(
+        &EcsAdapter::new(),
+        &RoomGraphSpace::new(),
+        10,
+        &PersistenceRegistry::new(),
+    );
+    snap.plugin_memory = original.export_memory().unwrap();
+    let mgr = SnapshotManager::new(&dir);
+    mgr.save_to_disk(&snap).unwrap();
+    let loaded = mgr.load_latest().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut restored = PluginRuntime::new(default_fuel()).unwrap();
+    restored.load_plugin(&config).unwrap();
+    let mut fresh = PluginRuntime::new(default_fuel()).unwrap();
+    fresh.load_plugin(&config).unwrap();
+    restored.import_memory(&loaded.plugin_memory).unwrap();
+
+    for tick in 10..30 {
+        assert_eq!(restored.run_tick(tick), original.run_tick(tick));
+        fresh.run_tick(tick);
+    }
+    // The bump allocator's position carried over; without the restore it
+    // did not.
+    let original_memory = original.export_memory().unwrap();
+    assert_eq!(restored.export_memory().unwrap(), original_memory);
+    assert_ne!(fresh.export_memory().unwrap(), original_memory);
+}
+
 #[test]
 fn fuel_exhaustion_stops_infinite_loop() {
     let fuel_config = FuelConfig {
@@ -54,8 +104,7 @@ fn fuel_exhaustion_stops_infinite_loop() {
         plugin_id: "infinite_loop".into(),
         wasm_path: fixture_path("test_infinite_loop.wasm"),
         priority: 1,
-        fuel_limit: None,
-        enabled: true,
+        ..Default::default()
     };
     runtime.load_plugin(&config).unwrap();
 
@@ -78,8 +127,7 @@ fn panic_plugin_quarantined_after_3_failures() {
         plugin_id: "panicker".into(),
         wasm_path: fixture_path("test_panic.wasm"),
         priority: 1,
-        fuel_limit: None,
-        enabled: true,
+        ..Default::default()
     };
     runtime.load_plugin(&config).unwrap();
 
@@ -111,8 +159,7 @@ fn infinite_loop_quarantined_after_3_failures() {
         plugin_id: "looper".into(),
         wasm_path: fixture_path("test_infinite_loop.wasm"),
         priority: 1,
-        fuel_limit: None,
-        enabled: true,
+        ..Default::default()
     };
     runtime.load_plugin(&config).unwrap();
 
@@ -133,8 +180,7 @@ fn multiple_plugins_priority_order() {
             plugin_id: "mover_b".into(),
             wasm_path: fixture_path("test_movement.wasm"),
             priority: 10,
-            fuel_limit: None,
-            enabled: true,
+            ..Default::default()
         })
         .unwrap();
 
@@ -144,8 +190,7 @@ fn multiple_plugins_priority_order() {
             plugin_id: "mover_a".into(),
             wasm_path: fixture_path("test_movement.wasm"),
             priority: 1,
-            fuel_limit: None,
-            enabled: true,
+            ..Default::default()
         })
         .unwrap();
 
@@ -165,8 +210,7 @@ fn unload_plugin() {
             plugin_id: "temp".into(),
             wasm_path: fixture_path("test_movement.wasm"),
             priority: 1,
-            fuel_limit: None,
-            enabled: true,
+            ..Default::default()
         })
         .unwrap();
     assert_eq!(runtime.plugin_count(), 1);
@@ -179,8 +179,7 @@ fn generate_lua(dialogues: &[Dialogue]) -> String {
     lua.push_str("        output:send(sid, colors.green .. arg .. \"을(를) 받았습니다.\" .. colors.reset)\n");
     lua.push_str("    elseif cmd == \"give_gold\" then\n");
     lua.push_str("        local amount = tonumber(arg) or 0\n");
-    lua.push_str("        local gold = ecs:get(entity, \"Gold\") or 0\n");
-    lua.push_str("        ecs:set(entity, \"Gold\", gold + amount)\n");
+    lua.push_str("        ecs:add_money(entity, amount)\n");
     lua.push_str("        output:send(sid, colors.green .. amount .. \" 골드를 받았습니다.\" .. colors.reset)\n");
     lua.push_str("    elseif cmd == \"heal\" then\n");
     lua.push_str("        local hp = ecs:get(entity, \"Health\")\n");
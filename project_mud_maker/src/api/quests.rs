@@ -26,7 +26,7 @@ fn default_count() -> u32 {
     1
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct QuestRewards {
     #[serde(default)]
     pub gold: u32,
@@ -52,16 +52,6 @@ pub struct Quest {
     pub rewards: QuestRewards,
 }
 
-impl Default for QuestRewards {
-    fn default() -> Self {
-        Self {
-            gold: 0,
-            exp: 0,
-            items: vec![],
-        }
-    }
-}
-
 /// Called by generate_all.
 pub fn generate_quests_lua_inner(state: &AppState) -> Result<String, String> {
     let content_dir = state.config.content_dir();
@@ -184,8 +174,7 @@ fn generate_lua(quests: &[Quest]) -> String {
     lua.push_str("    if not quest then return end\n");
     lua.push_str("    local r = quest.rewards\n");
     lua.push_str("    if r.gold > 0 then\n");
-    lua.push_str("        local gold = ecs:get(entity, \"Gold\") or 0\n");
-    lua.push_str("        ecs:set(entity, \"Gold\", gold + r.gold)\n");
+    lua.push_str("        ecs:add_money(entity, r.gold)\n");
     lua.push_str("    end\n");
     lua.push_str("    if r.exp > 0 then\n");
     lua.push_str("        local lvl = ecs:get(entity, \"Level\")\n");
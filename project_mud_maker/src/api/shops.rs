@@ -209,13 +209,12 @@ fn generate_lua(shops: &[Shop], all_items: &[serde_json::Value]) -> String {
     lua.push_str("        output:send(sid, \"'\" .. item_name .. \"'은(는) 이 상점에서 판매하지 않습니다.\")\n");
     lua.push_str("        return true\n");
     lua.push_str("    end\n");
-    lua.push_str("    local gold = ecs:get(entity, \"Gold\") or 0\n");
-    lua.push_str("    if gold < shop_item.price then\n");
+    lua.push_str("    -- Deduct gold\n");
+    lua.push_str("    local paid, gold = ecs:spend_money(entity, shop_item.price)\n");
+    lua.push_str("    if not paid then\n");
     lua.push_str("        output:send(sid, \"골드가 부족합니다. (보유: \" .. gold .. \", 필요: \" .. shop_item.price .. \")\")\n");
     lua.push_str("        return true\n");
     lua.push_str("    end\n");
-    lua.push_str("    -- Deduct gold\n");
-    lua.push_str("    ecs:set(entity, \"Gold\", gold - shop_item.price)\n");
     lua.push_str("    -- Create item entity\n");
     lua.push_str("    local new_item = ecs:spawn()\n");
     lua.push_str("    ecs:set(new_item, \"Name\", shop_item.name)\n");
@@ -273,8 +272,7 @@ fn generate_lua(shops: &[Shop], all_items: &[serde_json::Value]) -> String {
     lua.push_str("    ecs:set(entity, \"Inventory\", {items = new_items})\n");
     lua.push_str("    ecs:despawn(item_ent)\n");
     lua.push_str("    -- Add gold\n");
-    lua.push_str("    local gold = ecs:get(entity, \"Gold\") or 0\n");
-    lua.push_str("    ecs:set(entity, \"Gold\", gold + sell_price)\n");
+    lua.push_str("    ecs:add_money(entity, sell_price)\n");
     lua.push_str("    output:send(sid, item_name .. \"을(를) 판매했습니다. (+\" .. sell_price .. \" 골드)\")\n");
     lua.push_str("    return true\n");
     lua.push_str("end)\n");
@@ -401,9 +401,7 @@ fn generate_triggers_lua(data: &TriggerData, state: &AppState) -> String {
                 lua.push_str("    local dead_entities = ecs:query(\"Dead\")\n");
                 lua.push_str("    for _, dead_ent in ipairs(dead_entities) do\n");
                 if !content_id.is_empty() {
-                    lua.push_str(&format!(
-                        "        local name = ecs:get(dead_ent, \"Name\") or \"\"\n"
-                    ));
+                    lua.push_str("        local name = ecs:get(dead_ent, \"Name\") or \"\"\n");
                     lua.push_str(&format!(
                         "        if name == \"{}\" then\n",
                         escape_lua(content_id)
@@ -445,7 +443,7 @@ fn gen_condition_expr(cond: &TriggerCondition, entity_var: &str) -> String {
             )
         }
         TriggerCondition::MinGold { amount } => {
-            format!("(ecs:get({}, \"Gold\") or 0) >= {}", entity_var, amount)
+            format!("ecs:money({}) >= {}", entity_var, amount)
         }
         TriggerCondition::MinLevel { level } => {
             format!(
@@ -675,7 +673,7 @@ fn sanitize_var(id: &str) -> String {
         .chars()
         .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
         .collect();
-    if s.is_empty() || s.chars().next().map_or(true, |c| c.is_ascii_digit()) {
+    if s.is_empty() || s.chars().next().is_none_or(|c| c.is_ascii_digit()) {
         format!("v_{s}")
     } else {
         s
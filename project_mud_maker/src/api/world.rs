@@ -491,7 +491,7 @@ fn generate_world_lua(world: &WorldData, state: &AppState) -> String {
                             get_override_or_content_i64(&placed.overrides, c, "gold")
                         {
                             lua.push_str(&format!(
-                                "    ecs:set({ent_var}, \"Gold\", {gold})\n"
+                                "    ecs:add_money({ent_var}, {gold})\n"
                             ));
                         }
                         // Level + Experience
@@ -704,7 +704,7 @@ fn generate_content_data(lua: &mut String, state: &AppState) {
     };
     let mut collections: Vec<_> = entries
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map_or(false, |ext| ext == "json"))
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
         .collect();
     collections.sort_by_key(|e| e.file_name());
 